@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use nova_snark::{
+  provider::{PallasEngine, Secp256k1Engine},
+  traits::{commitment::CommitmentEngineTrait, Engine},
+};
+use rand::{rngs::OsRng, RngCore};
+
+criterion_group! {
+name = commit;
+config = Criterion::default().warm_up_time(Duration::from_millis(3000)).sample_size(10);
+targets = bench_commit_pallas, bench_commit_secp256k1, bench_commit_tables_pallas, bench_commit_tables_secp256k1
+}
+
+criterion_main!(commit);
+
+fn bench_commit_pallas(c: &mut Criterion) {
+  bench_commit::<PallasEngine>(c, "PallasEngine");
+}
+
+fn bench_commit_secp256k1(c: &mut Criterion) {
+  bench_commit::<Secp256k1Engine>(c, "Secp256k1Engine");
+}
+
+fn bench_commit_tables_pallas(c: &mut Criterion) {
+  bench_commit_tables::<PallasEngine>(c, "PallasEngine");
+}
+
+fn bench_commit_tables_secp256k1(c: &mut Criterion) {
+  bench_commit_tables::<Secp256k1Engine>(c, "Secp256k1Engine");
+}
+
+// Compares the commitment to vectors of bits, bytes and u64s, as field elements and as small
+// scalars, with the commitment to a vector of random field elements
+fn bench_commit<E: Engine>(c: &mut Criterion, name: &str) {
+  let mut group = c.benchmark_group(format!("commit/{name}"));
+  for log_n in [14, 18] {
+    let n = 1 << log_n;
+    let ck = E::CE::setup(b"bench", n);
+
+    let scalars = (0..n).map(|_| E::Scalar::random(OsRng)).collect::<Vec<_>>();
+    group.bench_function(BenchmarkId::new("random", log_n), |b| {
+      b.iter(|| E::CE::commit(black_box(&ck), black_box(&scalars)))
+    });
+
+    for num_bits in [1, 8, 64] {
+      let small = (0..n)
+        .map(|_| OsRng.next_u64() >> (64 - num_bits))
+        .collect::<Vec<_>>();
+      let scalars = small
+        .iter()
+        .map(|&s| E::Scalar::from(s))
+        .collect::<Vec<_>>();
+
+      group.bench_function(BenchmarkId::new(format!("u{num_bits}"), log_n), |b| {
+        b.iter(|| E::CE::commit(black_box(&ck), black_box(&scalars)))
+      });
+      group.bench_function(BenchmarkId::new(format!("u{num_bits}_small"), log_n), |b| {
+        b.iter(|| E::CE::commit_small(black_box(&ck), black_box(&small)))
+      });
+    }
+  }
+  group.finish();
+}
+
+// Compares the commitment to a vector of random field elements without fixed-base tables with
+// the commitment with tables for each window size, along with the time to precompute them, to
+// pick the window size passed to `precompute_tables`. Windows of at most 10 bits are summed in a
+// single pass, larger ones in segments with doublings between them
+fn bench_commit_tables<E: Engine>(c: &mut Criterion, name: &str) {
+  let mut group = c.benchmark_group(format!("commit_tables/{name}"));
+  for log_n in [10, 14, 18] {
+    let n = 1 << log_n;
+    let ck = E::CE::setup(b"bench", n);
+    let scalars = (0..n).map(|_| E::Scalar::random(OsRng)).collect::<Vec<_>>();
+
+    group.bench_function(BenchmarkId::new("without_tables", log_n), |b| {
+      b.iter(|| E::CE::commit(black_box(&ck), black_box(&scalars)))
+    });
+
+    for window_size in [4, 6, 8, 10, 12, 16] {
+      let mut ck_tables = ck.clone();
+      E::CE::precompute_tables(&mut ck_tables, window_size).unwrap();
+      assert_eq!(
+        E::CE::commit(&ck, &scalars),
+        E::CE::commit(&ck_tables, &scalars)
+      );
+
+      group.bench_function(
+        BenchmarkId::new(format!("with_tables_{window_size}"), log_n),
+        |b| b.iter(|| E::CE::commit(black_box(&ck_tables), black_box(&scalars))),
+      );
+      group.bench_function(
+        BenchmarkId::new(format!("precompute_tables_{window_size}"), log_n),
+        |b| {
+          b.iter(|| {
+            let mut ck_tables = ck.clone();
+            E::CE::precompute_tables(black_box(&mut ck_tables), window_size).unwrap();
+            ck_tables
+          })
+        },
+      );
+    }
+  }
+  group.finish();
+}
@@ -0,0 +1,168 @@
+use std::{
+  alloc::{GlobalAlloc, Layout, System},
+  sync::atomic::{AtomicUsize, Ordering},
+  time::Duration,
+};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use itertools::Itertools as _;
+use nova_snark::{provider::PallasEngine, spartan::polys::multilinear::MultilinearPolynomial};
+use rand::rngs::OsRng;
+
+type E = PallasEngine;
+type Scalar = <E as nova_snark::traits::Engine>::Scalar;
+
+criterion_group! {
+name = multilinear;
+config = Criterion::default().warm_up_time(Duration::from_millis(3000)).sample_size(10);
+targets = bench_bind, bench_evaluate, bench_memory
+}
+
+criterion_main!(multilinear);
+
+/// An allocator that tracks the peak number of bytes allocated on the heap
+struct PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc(layout);
+    if !ptr.is_null() {
+      let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+      PEAK.fetch_max(allocated, Ordering::SeqCst);
+    }
+    ptr
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout);
+    ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+  }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Returns the peak number of bytes `f` allocates on the heap beyond what was allocated before
+fn peak_heap<T>(f: impl FnOnce() -> T) -> usize {
+  let allocated = ALLOCATED.load(Ordering::SeqCst);
+  PEAK.store(allocated, Ordering::SeqCst);
+  black_box(f());
+  PEAK.load(Ordering::SeqCst) - allocated
+}
+
+/// Evaluates a polynomial with the full table of eq(r, ·), as `evaluate` used to
+fn evaluate_with_full_table(Z: &[Scalar], r: &[Scalar]) -> Scalar {
+  let mut chis = vec![Scalar::ONE];
+  for r_i in r {
+    chis = chis
+      .iter()
+      .flat_map(|chi| [*chi * (Scalar::ONE - r_i), *chi * r_i])
+      .collect();
+  }
+  chis.iter().zip_eq(Z).map(|(chi, Z_i)| *chi * Z_i).sum()
+}
+
+fn bench_bind(c: &mut Criterion) {
+  let mut group = c.benchmark_group("MultilinearPolynomial::bind");
+  for num_vars in [16, 20] {
+    let poly = MultilinearPolynomial::<Scalar>::random(num_vars, &mut OsRng);
+    let r = Scalar::random(OsRng);
+
+    group.bench_with_input(BenchmarkId::new("top", num_vars), &poly, |b, poly| {
+      b.iter_batched(
+        || poly.clone(),
+        |mut poly| {
+          while poly.get_num_vars() > 0 {
+            poly.bind_poly_var_top(black_box(&r));
+          }
+          poly
+        },
+        criterion::BatchSize::LargeInput,
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("bot", num_vars), &poly, |b, poly| {
+      b.iter_batched(
+        || poly.clone(),
+        |mut poly| {
+          while poly.get_num_vars() > 0 {
+            poly.bind_poly_var_bot(black_box(&r));
+          }
+          poly
+        },
+        criterion::BatchSize::LargeInput,
+      )
+    });
+  }
+  group.finish();
+}
+
+fn bench_evaluate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("MultilinearPolynomial::evaluate");
+  for num_vars in [16, 20] {
+    let polys = (0..8)
+      .map(|_| MultilinearPolynomial::<Scalar>::random(num_vars, &mut OsRng))
+      .collect::<Vec<_>>();
+    let evals_vec = polys.iter().map(|p| p.evaluations()).collect::<Vec<_>>();
+    let r = (0..num_vars)
+      .map(|_| Scalar::random(OsRng))
+      .collect::<Vec<_>>();
+
+    group.bench_function(BenchmarkId::new("one_by_one", num_vars), |b| {
+      b.iter(|| {
+        evals_vec
+          .iter()
+          .map(|evals| MultilinearPolynomial::evaluate_with(black_box(evals), black_box(&r)))
+          .collect::<Vec<_>>()
+      })
+    });
+    group.bench_function(BenchmarkId::new("batch", num_vars), |b| {
+      b.iter(|| MultilinearPolynomial::evaluate_batch(black_box(&evals_vec), black_box(&r)))
+    });
+  }
+  group.finish();
+}
+
+/// Reports, rather than times, the peak heap memory of binding and evaluating polynomials
+fn bench_memory(_c: &mut Criterion) {
+  for num_vars in [16, 20] {
+    let poly = MultilinearPolynomial::<Scalar>::random(num_vars, &mut OsRng);
+    let r = (0..num_vars)
+      .map(|_| Scalar::random(OsRng))
+      .collect::<Vec<_>>();
+    let report = |name: &str, bytes: usize| {
+      println!("MultilinearPolynomial::memory/{name}/{num_vars}: peak heap {bytes} bytes");
+    };
+
+    report(
+      "polynomial",
+      poly.evaluations().len() * std::mem::size_of::<Scalar>(),
+    );
+    type Bind = fn(&mut MultilinearPolynomial<Scalar>, &Scalar);
+    for (name, bind) in [
+      ("bind_top", MultilinearPolynomial::bind_poly_var_top as Bind),
+      ("bind_bot", MultilinearPolynomial::bind_poly_var_bot),
+    ] {
+      let mut poly = poly.clone();
+      report(
+        name,
+        peak_heap(|| {
+          for r_i in &r {
+            bind(&mut poly, r_i);
+          }
+        }),
+      );
+    }
+    report(
+      "evaluate_full_table",
+      peak_heap(|| evaluate_with_full_table(poly.evaluations(), &r)),
+    );
+    report(
+      "evaluate_with",
+      peak_heap(|| MultilinearPolynomial::evaluate_with(poly.evaluations(), &r)),
+    );
+  }
+}
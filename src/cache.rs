@@ -0,0 +1,33 @@
+//! This module provides a `SetupCache` that persists the preprocessed data of setups on disk.
+use crate::{errors::NovaError, traits::snark::SetupCache};
+use std::{fs, path::PathBuf};
+
+/// A `SetupCache` that stores each entry in a file of a directory, named after its key
+#[derive(Clone, Debug)]
+pub struct FileSetupCache {
+  dir: PathBuf,
+}
+
+impl FileSetupCache {
+  /// Creates a cache in the directory `dir`, which is created if it does not exist
+  pub fn new(dir: impl Into<PathBuf>) -> Result<Self, NovaError> {
+    let dir = dir.into();
+    fs::create_dir_all(&dir).map_err(|_| NovaError::SetupCacheError)?;
+    Ok(Self { dir })
+  }
+}
+
+impl SetupCache for FileSetupCache {
+  fn load(&self, key: &str) -> Option<Vec<u8>> {
+    fs::read(self.dir.join(key)).ok()
+  }
+
+  fn store(&self, key: &str, bytes: &[u8]) -> Result<(), NovaError> {
+    // write to a temporary file first, so that a concurrent load never reads a partial entry
+    let path = self.dir.join(key);
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, bytes)
+      .and_then(|_| fs::rename(&tmp_path, &path))
+      .map_err(|_| NovaError::SetupCacheError)
+  }
+}
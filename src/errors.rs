@@ -51,12 +51,26 @@ pub enum NovaError {
   /// returned when the transcript engine encounters an overflow of the round number
   #[error("InternalTranscriptError")]
   InternalTranscriptError,
+  /// returned when a random oracle is squeezed before absorbing anything, or for more bits
+  /// than its output holds
+  #[error("InvalidROSqueeze")]
+  InvalidROSqueeze,
+  /// returned when fixed-base tables are requested for a commitment key with an unsupported
+  /// window size
+  #[error("InvalidTableWindowSize")]
+  InvalidTableWindowSize,
   /// returned when the multiset check fails
   #[error("InvalidMultisetProof")]
   InvalidMultisetProof,
   /// returned when the product proof check fails
   #[error("InvalidProductProof")]
   InvalidProductProof,
+  /// returned when a looked-up value is not an entry of the lookup table
+  #[error("InvalidLookup")]
+  InvalidLookup,
+  /// returned when a SNARK does not support the lookups declared by an R1CS shape
+  #[error("UnsupportedLookups")]
+  UnsupportedLookups,
   /// returned when the consistency with public IO and assignment used fails
   #[error("IncorrectWitness")]
   IncorrectWitness,
@@ -69,6 +83,32 @@ pub enum NovaError {
   /// returned when the prover cannot prove the provided statement due to completeness error
   #[error("InternalError")]
   InternalError,
+  /// returned when the preprocessed data of a setup cannot be stored in a `SetupCache`,
+  /// or does not match the shape and commitment key of the setup
+  #[error("SetupCacheError")]
+  SetupCacheError,
+  /// returned when a proof of a batch fails verification
+  #[error("BatchVerifyError")]
+  BatchVerifyError {
+    /// index of the failing proof in the batch
+    index: usize,
+    /// the error returned by the verification of the failing proof
+    error: Box<NovaError>,
+  },
+  /// returned when a batch of proofs fails verification, although each half of it passes
+  #[error("BatchRangeVerifyError")]
+  BatchRangeVerifyError {
+    /// index of the first proof of the failing range of the batch
+    start: usize,
+    /// index past the last proof of the failing range of the batch
+    end: usize,
+    /// the error returned by the verification of the range
+    error: Box<NovaError>,
+  },
+  /// returned when a memory budget is smaller than the memory the prover needs in its
+  /// low-memory mode
+  #[error("InsufficientMemoryBudget")]
+  InsufficientMemoryBudget,
 }
 
 /// Errors specific to the Polynomial commitment scheme
@@ -83,4 +123,10 @@ pub enum PCSError {
   /// returned when a length check fails in a PCS
   #[error("LengthError")]
   LengthError,
+  /// returned when a structured reference string cannot be read or fails its consistency checks
+  #[error("InvalidSRS")]
+  InvalidSRS,
+  /// returned when an invalid Basefold evaluation argument is provided
+  #[error("InvalidBasefold")]
+  InvalidBasefold,
 }
@@ -0,0 +1,966 @@
+//! This module implements gadgets that verify, inside a circuit over the scalar field of a
+//! Spartan proof, the sum-check reductions performed by Spartan's verifier. They are building
+//! blocks for verifying Spartan proofs in-circuit, e.g. to compose a `CompressedSNARK` with
+//! another proof system.
+//!
+//! `AllocatedRelaxedR1CSSNARK` verifies the part of a `spartan::snark::RelaxedR1CSSNARK` proof
+//! that lives in the scalar field, for engines whose transcript is `PoseidonTranscript`: it
+//! replays the verifier's transcript with a `PoseidonTranscriptCircuit`, absorbing the instance
+//! and deriving every challenge in the circuit, and it verifies the outer and inner sum-checks,
+//! the checks of their final claims, the evaluation of the R1CS matrices and of the public IO,
+//! and the sum-check that batches the claims about the witness and error vectors.
+//!
+//! The gadgets do not verify a whole proof. The evaluation argument of the polynomial commitment
+//! scheme, whose group operations are over the base field, is not verified: the gadget returns
+//! the claim about the evaluation of a combination of the committed vectors that it must
+//! establish, along with the transcript to verify it with. Shapes with lookups are not
+//! supported.
+use crate::{
+  gadgets::utils::{alloc_one, alloc_zero},
+  provider::poseidon::{PoseidonTranscript, PoseidonTranscriptCircuit},
+  r1cs::{R1CSShape, RelaxedR1CSInstance},
+  spartan::{snark::RelaxedR1CSSNARK, sumcheck::SumcheckProof},
+  traits::{evaluation::EvaluationEngineTrait, Engine, TranscriptReprTrait},
+  Commitment,
+};
+use bellpepper::gadgets::Assignment;
+use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+use ff::PrimeField;
+use itertools::Itertools as _;
+
+/// A sum-check proof allocated in a circuit: for each round, the coefficients of the prover's
+/// univariate polynomial except its linear term, which the verifier recovers from the claim
+#[derive(Clone)]
+pub struct AllocatedSumcheckProof<F: PrimeField> {
+  compressed_polys: Vec<Vec<AllocatedNum<F>>>,
+}
+
+impl<F: PrimeField> AllocatedSumcheckProof<F> {
+  /// Allocates a proof with `num_rounds` univariate polynomials of degree `degree`, using the
+  /// coefficients of `proof` if it is provided, and zeros otherwise
+  pub fn alloc<E, CS>(
+    mut cs: CS,
+    proof: Option<&SumcheckProof<E>>,
+    num_rounds: usize,
+    degree: usize,
+  ) -> Result<Self, SynthesisError>
+  where
+    E: Engine<Scalar = F>,
+    CS: ConstraintSystem<F>,
+  {
+    if let Some(proof) = proof {
+      if proof.compressed_polys().len() != num_rounds
+        || proof
+          .compressed_polys()
+          .iter()
+          .any(|poly| poly.coeffs_except_linear_term().len() != degree)
+      {
+        return Err(SynthesisError::Unsatisfiable);
+      }
+    }
+
+    let mut compressed_polys = Vec::with_capacity(num_rounds);
+    for i in 0..num_rounds {
+      let mut coeffs = Vec::with_capacity(degree);
+      for j in 0..degree {
+        coeffs.push(AllocatedNum::alloc(
+          cs.namespace(|| format!("round {i} coeff {j}")),
+          || {
+            Ok(proof.map_or(F::ZERO, |p| {
+              p.compressed_polys()[i].coeffs_except_linear_term()[j]
+            }))
+          },
+        )?);
+      }
+      compressed_polys.push(coeffs);
+    }
+
+    Ok(Self { compressed_polys })
+  }
+
+  /// Verifies the proof of `claim`, deriving the challenge of each round from `transcript` the
+  /// way `SumcheckProof::verify` does.
+  ///
+  /// Returns the claim reduced to the point `r` of the challenges, which the caller must then
+  /// check, along with `r`.
+  pub fn verify<CS: ConstraintSystem<F>>(
+    &self,
+    mut cs: CS,
+    claim: &AllocatedNum<F>,
+    transcript: &mut PoseidonTranscriptCircuit<F>,
+  ) -> Result<(AllocatedNum<F>, Vec<AllocatedNum<F>>), SynthesisError> {
+    self.verify_rounds(&mut cs, "", claim, transcript)
+  }
+
+  // verifies the proof like `verify`, with the namespaces of the rounds prefixed with `name`
+  fn verify_rounds<CS: ConstraintSystem<F>>(
+    &self,
+    cs: &mut CS,
+    name: &str,
+    claim: &AllocatedNum<F>,
+    transcript: &mut PoseidonTranscriptCircuit<F>,
+  ) -> Result<(AllocatedNum<F>, Vec<AllocatedNum<F>>), SynthesisError> {
+    let mut e = claim.clone();
+    let mut r = Vec::with_capacity(self.compressed_polys.len());
+
+    for (i, coeffs) in self.compressed_polys.iter().enumerate() {
+      transcript.absorb(b"p", coeffs);
+      let r_i = transcript.squeeze(cs.namespace(|| format!("{name}challenge {i}")), b"c")?;
+      e = eval_round_poly(
+        cs.namespace(|| format!("{name}round {i}")),
+        coeffs,
+        &e,
+        &r_i,
+      )?;
+      r.push(r_i);
+    }
+
+    Ok((e, r))
+  }
+}
+
+/// A relaxed R1CS instance allocated in a circuit over its scalar field, with its commitments
+/// given by their representation as scalars in transcripts, which the caller must bind to the
+/// commitments it knows the instance by
+#[derive(Clone)]
+pub struct AllocatedSpartanInstance<F: PrimeField> {
+  /// the representation of the commitment to the witness vector
+  pub comm_W: Vec<AllocatedNum<F>>,
+  /// the representation of the commitment to the error vector
+  pub comm_E: Vec<AllocatedNum<F>>,
+  /// the scalar of the instance
+  pub u: AllocatedNum<F>,
+  /// the public IO of the instance
+  pub X: Vec<AllocatedNum<F>>,
+}
+
+impl<F: PrimeField> AllocatedSpartanInstance<F> {
+  /// Allocates an instance with `num_io` public inputs, using `U` if it is provided, and the
+  /// default commitments and zeros otherwise
+  pub fn alloc<E, CS>(
+    mut cs: CS,
+    U: Option<&RelaxedR1CSInstance<E>>,
+    num_io: usize,
+  ) -> Result<Self, SynthesisError>
+  where
+    E: Engine<Scalar = F>,
+    CS: ConstraintSystem<F>,
+  {
+    if U.is_some_and(|U| U.X.len() != num_io) {
+      return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let mut alloc_vec = |name: &str, values: Vec<F>| {
+      values
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| AllocatedNum::alloc(cs.namespace(|| format!("{name} {i}")), || Ok(v)))
+        .collect::<Result<Vec<_>, _>>()
+    };
+    let comm_scalars = |comm: Option<&Commitment<E>>| {
+      TranscriptReprTrait::<E::GE>::to_transcript_scalars(
+        comm.unwrap_or(&Commitment::<E>::default()),
+      )
+    };
+    let comm_W = alloc_vec("comm_W", comm_scalars(U.map(|U| &U.comm_W)))?;
+    let comm_E = alloc_vec("comm_E", comm_scalars(U.map(|U| &U.comm_E)))?;
+    let u = alloc_vec("u", vec![U.map_or(F::ZERO, |U| U.u)])?.remove(0);
+    let X = alloc_vec("X", U.map_or(vec![F::ZERO; num_io], |U| U.X.clone()))?;
+
+    Ok(Self {
+      comm_W,
+      comm_E,
+      u,
+      X,
+    })
+  }
+}
+
+/// The scalars of a `spartan::snark::RelaxedR1CSSNARK` proof that its sum-check reductions are
+/// made of, allocated in a circuit
+#[derive(Clone)]
+pub struct AllocatedRelaxedR1CSSNARK<F: PrimeField> {
+  sc_proof_outer: AllocatedSumcheckProof<F>,
+  claims_outer: [AllocatedNum<F>; 3],
+  eval_E: AllocatedNum<F>,
+  sc_proof_inner: AllocatedSumcheckProof<F>,
+  eval_W: AllocatedNum<F>,
+  sc_proof_batch: AllocatedSumcheckProof<F>,
+  evals_batch: Option<[AllocatedNum<F>; 2]>, // none when the claims are reduced with a unified opening
+}
+
+/// The claim a Spartan proof reduces to, about the evaluation of a combination of the witness and
+/// error vectors of the instance, which the caller must check with the evaluation argument of the
+/// proof against the same combination of the commitments of the instance
+pub struct SpartanEvalClaim<F: PrimeField> {
+  /// the coefficients of the witness and error vectors, in that order, in the combination
+  pub coeffs: [AllocatedNum<F>; 2],
+  /// the point at which the combination is evaluated
+  pub point: Vec<AllocatedNum<F>>,
+  /// the claimed evaluation of the combination at `point`
+  pub eval: AllocatedNum<F>,
+}
+
+impl<F: PrimeField> AllocatedRelaxedR1CSSNARK<F> {
+  /// Allocates a proof for the shape `S`, which must be padded, using the scalars of `proof` if
+  /// it is provided, and zeros otherwise. `unified_opening` is whether the proof reduces its
+  /// evaluation claims with a unified opening, see `ProverKey::set_unified_opening`.
+  pub fn alloc<E, EE, CS>(
+    mut cs: CS,
+    proof: Option<&RelaxedR1CSSNARK<E, EE>>,
+    S: &R1CSShape<E>,
+    unified_opening: bool,
+  ) -> Result<Self, SynthesisError>
+  where
+    E: Engine<Scalar = F, TE = PoseidonTranscript<E>>,
+    EE: EvaluationEngineTrait<E>,
+    CS: ConstraintSystem<F>,
+  {
+    if !S.is_regular_shape() || !S.lookups.is_empty() {
+      return Err(SynthesisError::Unsatisfiable);
+    }
+    if proof.is_some_and(|p| p.evals_batch.len() != if unified_opening { 0 } else { 2 }) {
+      return Err(SynthesisError::Unsatisfiable);
+    }
+    let (num_rounds_x, num_rounds_y) = num_rounds(S);
+
+    let sc_proof_outer = AllocatedSumcheckProof::alloc(
+      cs.namespace(|| "sc_proof_outer"),
+      proof.map(|p| &p.sc_proof_outer),
+      num_rounds_x,
+      3,
+    )?;
+    let sc_proof_inner = AllocatedSumcheckProof::alloc(
+      cs.namespace(|| "sc_proof_inner"),
+      proof.map(|p| &p.sc_proof_inner),
+      num_rounds_y,
+      2,
+    )?;
+    let sc_proof_batch = AllocatedSumcheckProof::alloc(
+      cs.namespace(|| "sc_proof_batch"),
+      proof.map(|p| &p.sc_proof_batch),
+      num_rounds_x.max(num_rounds_y - 1),
+      2,
+    )?;
+    let mut alloc_scalar = |name: &'static str, value: Option<F>| {
+      AllocatedNum::alloc(cs.namespace(|| name), || Ok(value.unwrap_or(F::ZERO)))
+    };
+    let claims_outer = [
+      alloc_scalar("claim_Az", proof.map(|p| p.claims_outer.0))?,
+      alloc_scalar("claim_Bz", proof.map(|p| p.claims_outer.1))?,
+      alloc_scalar("claim_Cz", proof.map(|p| p.claims_outer.2))?,
+    ];
+    let eval_E = alloc_scalar("eval_E", proof.map(|p| p.eval_E))?;
+    let eval_W = alloc_scalar("eval_W", proof.map(|p| p.eval_W))?;
+    let evals_batch = if unified_opening {
+      None
+    } else {
+      Some([
+        alloc_scalar("eval_W_batch", proof.map(|p| p.evals_batch[0]))?,
+        alloc_scalar("eval_E_batch", proof.map(|p| p.evals_batch[1]))?,
+      ])
+    };
+
+    Ok(Self {
+      sc_proof_outer,
+      claims_outer,
+      eval_E,
+      sc_proof_inner,
+      eval_W,
+      sc_proof_batch,
+      evals_batch,
+    })
+  }
+
+  /// Verifies the sum-check reductions of the proof for the shape `S`, which must be padded, the
+  /// digest `vk_digest` of the verifier key and the instance `U`, deriving the verifier's
+  /// challenges with a transcript that replays the native verifier's.
+  ///
+  /// Returns the claim the proof reduces to, which the caller must check with the evaluation
+  /// argument of the proof, along with the transcript to verify it with.
+  pub fn verify<E, CS>(
+    &self,
+    mut cs: CS,
+    S: &R1CSShape<E>,
+    vk_digest: F,
+    U: &AllocatedSpartanInstance<F>,
+  ) -> Result<(SpartanEvalClaim<F>, PoseidonTranscriptCircuit<F>), SynthesisError>
+  where
+    E: Engine<Scalar = F, TE = PoseidonTranscript<E>>,
+    CS: ConstraintSystem<F>,
+  {
+    if U.X.len() != S.num_io {
+      return Err(SynthesisError::Unsatisfiable);
+    }
+    let (num_rounds_x, _) = num_rounds(S);
+    let [claim_Az, claim_Bz, claim_Cz] = &self.claims_outer;
+
+    let mut transcript = PoseidonTranscriptCircuit::new(b"RelaxedR1CSSNARK");
+    transcript.absorb_constants(b"vk", &[vk_digest]);
+    transcript.absorb(
+      b"U",
+      &[
+        U.comm_W.as_slice(),
+        U.comm_E.as_slice(),
+        &[U.u.clone()],
+        U.X.as_slice(),
+      ]
+      .concat(),
+    );
+
+    // outer sum-check
+    let tau = (0..num_rounds_x)
+      .map(|i| transcript.squeeze(cs.namespace(|| format!("tau {i}")), b"t"))
+      .collect::<Result<Vec<_>, _>>()?;
+    let zero = alloc_zero(cs.namespace(|| "zero"));
+    let (claim_outer_final, r_x) =
+      self
+        .sc_proof_outer
+        .verify_rounds(&mut cs, "outer ", &zero, &mut transcript)?;
+
+    // check claim_outer_final = eq(tau, r_x) * (Az * Bz - u * Cz - E)
+    let taus_bound_rx = eval_eq(cs.namespace(|| "taus_bound_rx"), &tau, &r_x)?;
+    let AzBz = claim_Az.mul(cs.namespace(|| "Az * Bz"), claim_Bz)?;
+    let uCz = U.u.mul(cs.namespace(|| "u * Cz"), claim_Cz)?;
+    cs.enforce(
+      || "claim_outer_final",
+      |lc| lc + taus_bound_rx.get_variable(),
+      |lc| lc + AzBz.get_variable() - uCz.get_variable() - self.eval_E.get_variable(),
+      |lc| lc + claim_outer_final.get_variable(),
+    );
+
+    // inner sum-check
+    transcript.absorb(
+      b"claims_outer",
+      &[
+        claim_Az.clone(),
+        claim_Bz.clone(),
+        claim_Cz.clone(),
+        self.eval_E.clone(),
+      ],
+    );
+    let r = transcript.squeeze(cs.namespace(|| "r"), b"r")?;
+    let r_sq = r.square(cs.namespace(|| "r^2"))?;
+    let rBz = r.mul(cs.namespace(|| "r * Bz"), claim_Bz)?;
+    let r_sqCz = r_sq.mul(cs.namespace(|| "r^2 * Cz"), claim_Cz)?;
+    let claim_inner_joint = alloc_sum(
+      cs.namespace(|| "claim_inner_joint"),
+      &[(F::ONE, claim_Az), (F::ONE, &rBz), (F::ONE, &r_sqCz)],
+    )?;
+    let (claim_inner_final, r_y) =
+      self
+        .sc_proof_inner
+        .verify_rounds(&mut cs, "inner ", &claim_inner_joint, &mut transcript)?;
+
+    // eval_Z = (1 - r_y[0]) * eval_W + r_y[0] * eval_X, where X is prefixed with u
+    let eval_X = {
+      let mut terms = Vec::with_capacity(U.X.len() + 1);
+      for (i, x_i) in [&U.u].into_iter().chain(&U.X).enumerate() {
+        let chi = eval_chi(cs.namespace(|| format!("chi {i}")), i, &r_y[1..])?;
+        terms.push(chi.mul(cs.namespace(|| format!("io term {i}")), x_i)?);
+      }
+      alloc_sum(
+        cs.namespace(|| "eval_X"),
+        &terms.iter().map(|t| (F::ONE, t)).collect::<Vec<_>>(),
+      )?
+    };
+    let eval_Z = AllocatedNum::alloc(cs.namespace(|| "eval_Z"), || {
+      let (r_y_0, eval_W, eval_X) = (
+        *r_y[0].get_value().get()?,
+        *self.eval_W.get_value().get()?,
+        *eval_X.get_value().get()?,
+      );
+      Ok(eval_W + r_y_0 * (eval_X - eval_W))
+    })?;
+    cs.enforce(
+      || "eval_Z",
+      |lc| lc + r_y[0].get_variable(),
+      |lc| lc + eval_X.get_variable() - self.eval_W.get_variable(),
+      |lc| lc + eval_Z.get_variable() - self.eval_W.get_variable(),
+    );
+
+    // evaluate the R1CS matrices at (r_x, r_y)
+    let T_x = eval_eq_table(cs.namespace(|| "T_x"), &r_x)?;
+    let T_y = eval_eq_table(cs.namespace(|| "T_y"), &r_y)?;
+    let mut evals = Vec::with_capacity(3);
+    for (name, M) in [("A", &S.A), ("B", &S.B), ("C", &S.C)] {
+      let mut cs = cs.namespace(|| format!("eval {name}"));
+      let products = M
+        .iter()
+        .enumerate()
+        .map(|(k, (row, col, val))| {
+          let product = T_x[row].mul(cs.namespace(|| format!("entry {k}")), &T_y[col])?;
+          Ok((val, product))
+        })
+        .collect::<Result<Vec<_>, SynthesisError>>()?;
+      evals.push(alloc_sum(
+        cs.namespace(|| "sum"),
+        &products
+          .iter()
+          .map(|(val, p)| (*val, p))
+          .collect::<Vec<_>>(),
+      )?);
+    }
+    let rB = r.mul(cs.namespace(|| "r * eval_B"), &evals[1])?;
+    let r_sqC = r_sq.mul(cs.namespace(|| "r^2 * eval_C"), &evals[2])?;
+
+    // check claim_inner_final = (eval_A + r * eval_B + r^2 * eval_C) * eval_Z
+    cs.enforce(
+      || "claim_inner_final",
+      |lc| lc + evals[0].get_variable() + rB.get_variable() + r_sqC.get_variable(),
+      |lc| lc + eval_Z.get_variable(),
+      |lc| lc + claim_inner_final.get_variable(),
+    );
+
+    // batch the claims about W at r_y[1..] and E at r_x
+    let claim = self.verify_batch(
+      cs.namespace(|| "batch"),
+      [(&r_y[1..], &self.eval_W), (r_x.as_slice(), &self.eval_E)],
+      &mut transcript,
+    )?;
+
+    Ok((claim, transcript))
+  }
+
+  // verifies the sum-check that reduces the claims `u_vec` about the evaluations of W and E, as
+  // `batch_eval_verify` or `batch_eval_verify_unified` do depending on the proof
+  fn verify_batch<CS: ConstraintSystem<F>>(
+    &self,
+    mut cs: CS,
+    u_vec: [(&[AllocatedNum<F>], &AllocatedNum<F>); 2],
+    transcript: &mut PoseidonTranscriptCircuit<F>,
+  ) -> Result<SpartanEvalClaim<F>, SynthesisError> {
+    let rho = transcript.squeeze(cs.namespace(|| "rho"), b"r")?;
+
+    // claim = ∑ᵢ ρⁱ⋅2^{n-nᵢ}⋅eᵢ, where n = maxᵢ{nᵢ}
+    let num_rounds_max = u_vec[0].0.len().max(u_vec[1].0.len());
+    let scaling = |x: &[AllocatedNum<F>]| F::from(1u64 << (num_rounds_max - x.len()));
+    let rho_e = rho.mul(cs.namespace(|| "rho * e_1"), u_vec[1].1)?;
+    let claim = alloc_sum(
+      cs.namespace(|| "claim"),
+      &[
+        (scaling(u_vec[0].0), u_vec[0].1),
+        (scaling(u_vec[1].0), &rho_e),
+      ],
+    )?;
+    let (claim_batch_final, r) = self
+      .sc_proof_batch
+      .verify_rounds(&mut cs, "batch ", &claim, transcript)?;
+
+    // for each claim, eq(xᵢ, r_hi) and L₀(r_lo), where r_hi are the last nᵢ coordinates of r
+    let mut eq_evals = Vec::with_capacity(2);
+    let mut lagrange_evals = Vec::with_capacity(2);
+    for (i, (x, _)) in u_vec.iter().enumerate() {
+      let (r_lo, r_hi) = r.split_at(num_rounds_max - x.len());
+      eq_evals.push(eval_eq(cs.namespace(|| format!("eq {i}")), r_hi, x)?);
+      // L₀(r_lo) = ∏ⱼ (1 - r_lo[j]) is the evaluation at r_lo of the polynomial that is one at 0
+      let lagrange_eval = eval_chi(cs.namespace(|| format!("lagrange {i}")), 0, r_lo)?;
+      lagrange_evals.push(lagrange_eval);
+    }
+    let rho_eq = rho.mul(cs.namespace(|| "rho * eq 1"), &eq_evals[1])?;
+
+    match &self.evals_batch {
+      None => {
+        // the weights wᵢ = ρⁱ⋅eq(xᵢ, r_hi)/L₀(r_lo), which require L₀(r_lo) ≠ 0
+        let mut weight = |i: usize, numerator: &AllocatedNum<F>| {
+          let inv = alloc_inverse(
+            cs.namespace(|| format!("lagrange {i} inverse")),
+            &lagrange_evals[i],
+          )?;
+          numerator.mul(cs.namespace(|| format!("weight {i}")), &inv)
+        };
+        let coeffs = [weight(0, &eq_evals[0])?, weight(1, &rho_eq)?];
+
+        Ok(SpartanEvalClaim {
+          coeffs,
+          point: r,
+          eval: claim_batch_final,
+        })
+      }
+      Some(evals_batch) => {
+        // check claim_batch_final = ∑ᵢ ρⁱ⋅eq(xᵢ, r_hi)⋅evalᵢ
+        let term_W = eq_evals[0].mul(cs.namespace(|| "eq * eval 0"), &evals_batch[0])?;
+        let term_E = rho_eq.mul(cs.namespace(|| "rho * eq * eval 1"), &evals_batch[1])?;
+        cs.enforce(
+          || "claim_batch_final",
+          |lc| lc + term_W.get_variable() + term_E.get_variable(),
+          |lc| lc + CS::one(),
+          |lc| lc + claim_batch_final.get_variable(),
+        );
+
+        // combine the claims at r with the powers of γ, rescaling the evaluations by L₀(r_lo)
+        transcript.absorb(b"l", evals_batch);
+        let gamma = transcript.squeeze(cs.namespace(|| "gamma"), b"g")?;
+        let scaled_W = lagrange_evals[0].mul(cs.namespace(|| "scaled eval 0"), &evals_batch[0])?;
+        let scaled_E = lagrange_evals[1].mul(cs.namespace(|| "scaled eval 1"), &evals_batch[1])?;
+        let gamma_E = gamma.mul(cs.namespace(|| "gamma * scaled eval 1"), &scaled_E)?;
+        let eval = alloc_sum(
+          cs.namespace(|| "eval"),
+          &[(F::ONE, &scaled_W), (F::ONE, &gamma_E)],
+        )?;
+
+        Ok(SpartanEvalClaim {
+          coeffs: [alloc_one(cs.namespace(|| "gamma^0")), gamma],
+          point: r,
+          eval,
+        })
+      }
+    }
+  }
+}
+
+// allocates the inverse of `x`, which is unsatisfiable if `x` is zero
+fn alloc_inverse<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  x: &AllocatedNum<F>,
+) -> Result<AllocatedNum<F>, SynthesisError> {
+  let inv = AllocatedNum::alloc(cs.namespace(|| "inverse"), || {
+    Option::from(x.get_value().get()?.invert()).ok_or(SynthesisError::DivisionByZero)
+  })?;
+  cs.enforce(
+    || "x * inverse = 1",
+    |lc| lc + x.get_variable(),
+    |lc| lc + inv.get_variable(),
+    |lc| lc + CS::one(),
+  );
+  Ok(inv)
+}
+
+// the number of rounds of the outer and inner sum-checks for the padded shape `S`
+fn num_rounds<E: Engine>(S: &R1CSShape<E>) -> (usize, usize) {
+  (
+    usize::try_from(S.num_cons.ilog2()).unwrap(),
+    usize::try_from(S.num_vars.ilog2()).unwrap() + 1,
+  )
+}
+
+// allocates the sum of the variables in `terms`, each scaled by a constant
+fn alloc_sum<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  terms: &[(F, &AllocatedNum<F>)],
+) -> Result<AllocatedNum<F>, SynthesisError> {
+  let sum = AllocatedNum::alloc(cs.namespace(|| "sum"), || {
+    terms
+      .iter()
+      .try_fold(F::ZERO, |acc, (c, v)| Ok(acc + *c * *v.get_value().get()?))
+  })?;
+  cs.enforce(
+    || "sum of terms",
+    |lc| {
+      terms
+        .iter()
+        .fold(lc, |lc, (c, v)| lc + (*c, v.get_variable()))
+    },
+    |lc| lc + CS::one(),
+    |lc| lc + sum.get_variable(),
+  );
+  Ok(sum)
+}
+
+/// Evaluates at `r` the univariate polynomial p whose coefficients are `coeffs` except for the
+/// linear term, which is determined by `hint` = p(0) + p(1)
+fn eval_round_poly<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  coeffs: &[AllocatedNum<F>],
+  hint: &AllocatedNum<F>,
+  r: &AllocatedNum<F>,
+) -> Result<AllocatedNum<F>, SynthesisError> {
+  // linear_term = hint - 2 * constant_term - (the remaining coefficients)
+  let linear_term = AllocatedNum::alloc(cs.namespace(|| "linear term"), || {
+    let mut linear_term = *hint.get_value().get()? - *coeffs[0].get_value().get()?;
+    for coeff in coeffs {
+      linear_term -= *coeff.get_value().get()?;
+    }
+    Ok(linear_term)
+  })?;
+  cs.enforce(
+    || "linear term",
+    |lc| {
+      coeffs.iter().fold(
+        lc + hint.get_variable() - coeffs[0].get_variable(),
+        |lc, coeff| lc - coeff.get_variable(),
+      )
+    },
+    |lc| lc + CS::one(),
+    |lc| lc + linear_term.get_variable(),
+  );
+
+  // the coefficients of p from the highest degree down, evaluated with Horner's rule
+  let mut coeffs_desc = coeffs[1..].iter().rev().chain([&linear_term, &coeffs[0]]);
+  let mut eval = coeffs_desc.next().unwrap().clone();
+  for (k, coeff) in coeffs_desc.enumerate() {
+    let next = AllocatedNum::alloc(cs.namespace(|| format!("horner step {k}")), || {
+      Ok(*eval.get_value().get()? * *r.get_value().get()? + *coeff.get_value().get()?)
+    })?;
+    cs.enforce(
+      || format!("horner step {k} is eval * r + coeff"),
+      |lc| lc + eval.get_variable(),
+      |lc| lc + r.get_variable(),
+      |lc| lc + next.get_variable() - coeff.get_variable(),
+    );
+    eval = next;
+  }
+
+  Ok(eval)
+}
+
+/// Evaluates eq(x, y) = ∏ᵢ (xᵢ⋅yᵢ + (1 - xᵢ)⋅(1 - yᵢ)), the multilinear extension of the
+/// equality function, e.g. to check the final claim of Spartan's outer sum-check
+pub fn eval_eq<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  x: &[AllocatedNum<F>],
+  y: &[AllocatedNum<F>],
+) -> Result<AllocatedNum<F>, SynthesisError> {
+  if x.len() != y.len() {
+    return Err(SynthesisError::Unsatisfiable);
+  }
+
+  let mut eval = alloc_one(cs.namespace(|| "one"));
+  for (i, (x_i, y_i)) in x.iter().zip_eq(y).enumerate() {
+    // t = 1 - x - y + 2⋅x⋅y
+    let t = AllocatedNum::alloc(cs.namespace(|| format!("term {i}")), || {
+      let (x_i, y_i) = (*x_i.get_value().get()?, *y_i.get_value().get()?);
+      Ok(F::ONE - x_i - y_i + (x_i * y_i).double())
+    })?;
+    cs.enforce(
+      || format!("term {i} is 1 - x - y + 2xy"),
+      |lc| lc + x_i.get_variable() + x_i.get_variable(),
+      |lc| lc + y_i.get_variable(),
+      |lc| lc + t.get_variable() - CS::one() + x_i.get_variable() + y_i.get_variable(),
+    );
+    eval = eval.mul(cs.namespace(|| format!("product {i}")), &t)?;
+  }
+
+  Ok(eval)
+}
+
+/// Evaluates at `r` the multilinear polynomial that is one at the point of the hypercube whose
+/// bits, from the most significant one, are those of `index`, and zero elsewhere
+fn eval_chi<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  index: usize,
+  r: &[AllocatedNum<F>],
+) -> Result<AllocatedNum<F>, SynthesisError> {
+  let mut eval = alloc_one(cs.namespace(|| "one"));
+  for (j, r_j) in r.iter().enumerate() {
+    let bit = (index >> (r.len() - 1 - j)) & 1 == 1;
+    let next = AllocatedNum::alloc(cs.namespace(|| format!("factor {j}")), || {
+      let (eval, r_j) = (*eval.get_value().get()?, *r_j.get_value().get()?);
+      Ok(if bit { eval * r_j } else { eval - eval * r_j })
+    })?;
+    cs.enforce(
+      || format!("factor {j} is r_j or 1 - r_j"),
+      |lc| lc + eval.get_variable(),
+      |lc| {
+        if bit {
+          lc + r_j.get_variable()
+        } else {
+          lc + CS::one() - r_j.get_variable()
+        }
+      },
+      |lc| lc + next.get_variable(),
+    );
+    eval = next;
+  }
+
+  Ok(eval)
+}
+
+/// Computes the evaluations of eq(r, x) over the hypercube, in the order of
+/// `EqPolynomial::evals_from_points`
+fn eval_eq_table<F: PrimeField, CS: ConstraintSystem<F>>(
+  mut cs: CS,
+  r: &[AllocatedNum<F>],
+) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
+  let mut evals = vec![alloc_one(cs.namespace(|| "one"))];
+  for (j, r_j) in r.iter().enumerate().rev() {
+    let mut right = Vec::with_capacity(evals.len());
+    for (k, left) in evals.iter_mut().enumerate() {
+      // right = left * r_j, and left becomes left - right
+      let y = left.mul(cs.namespace(|| format!("var {j} entry {k} right")), r_j)?;
+      let x = AllocatedNum::alloc(cs.namespace(|| format!("var {j} entry {k} left")), || {
+        Ok(*left.get_value().get()? - *y.get_value().get()?)
+      })?;
+      cs.enforce(
+        || format!("var {j} entry {k} left is the difference"),
+        |lc| lc + left.get_variable() - y.get_variable(),
+        |lc| lc + CS::one(),
+        |lc| lc + x.get_variable(),
+      );
+      *left = x;
+      right.push(y);
+    }
+    evals.extend(right);
+  }
+
+  Ok(evals)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    bellpepper::{
+      r1cs::{NovaShape, NovaWitness},
+      shape_cs::ShapeCS,
+      solver::SatisfyingAssignment,
+    },
+    provider::{ipa_pc::EvaluationEngine, PallasPoseidonEngine},
+    r1cs::{commitment_key, RelaxedR1CSWitness},
+    spartan::polys::{eq::EqPolynomial, multilinear::MultilinearPolynomial},
+    traits::{
+      snark::{DigestHelperTrait, RelaxedR1CSSNARKTrait},
+      TranscriptEngineTrait,
+    },
+  };
+  use bellpepper_core::{test_cs::TestConstraintSystem, Circuit};
+  use ff::Field;
+  use rand::rngs::OsRng;
+
+  type E = PallasPoseidonEngine;
+  type F = <E as Engine>::Scalar;
+  type SNARK = RelaxedR1CSSNARK<E, EvaluationEngine<E>>;
+
+  // proves knowledge of y = x^3 + x + 5, with x and y public
+  struct CubicCircuit {
+    x: F,
+  }
+
+  impl Circuit<F> for CubicCircuit {
+    fn synthesize<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+      let x = AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(self.x))?;
+      let x_sq = x.square(cs.namespace(|| "x_sq"))?;
+      let x_cu = x_sq.mul(cs.namespace(|| "x_cu"), &x)?;
+      let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+        Ok(*x_cu.get_value().get()? + self.x + F::from(5u64))
+      })?;
+      cs.enforce(
+        || "y = x^3 + x + 5",
+        |lc| lc + x_cu.get_variable() + x.get_variable() + (F::from(5u64), CS::one()),
+        |lc| lc + CS::one(),
+        |lc| lc + y.get_variable(),
+      );
+      x.inputize(cs.namespace(|| "input x"))?;
+      y.inputize(cs.namespace(|| "input y"))?;
+      Ok(())
+    }
+  }
+
+  // the values of a claim returned by the gadget, and a challenge squeezed from its transcript
+  type ClaimValues = (Vec<F>, Vec<F>, F, F);
+
+  // verifies `proof` with the gadget, returning whether the circuit is satisfied along with the
+  // values of the claim it reduces to
+  fn verify_spartan_gadget(
+    proof: &SNARK,
+    S: &R1CSShape<E>,
+    vk_digest: F,
+    U: &RelaxedR1CSInstance<E>,
+    unified_opening: bool,
+  ) -> (bool, ClaimValues) {
+    let mut cs = TestConstraintSystem::<F>::new();
+    let proof_var =
+      AllocatedRelaxedR1CSSNARK::alloc(cs.namespace(|| "proof"), Some(proof), S, unified_opening)
+        .unwrap();
+    let U_var = AllocatedSpartanInstance::alloc(cs.namespace(|| "U"), Some(U), S.num_io).unwrap();
+    let (claim, mut transcript) = proof_var
+      .verify(cs.namespace(|| "verify"), S, vk_digest, &U_var)
+      .unwrap();
+    let c = transcript
+      .squeeze(cs.namespace(|| "next challenge"), b"c")
+      .unwrap();
+
+    let values =
+      |v: &[AllocatedNum<F>]| -> Vec<F> { v.iter().map(|v| v.get_value().unwrap()).collect() };
+    (
+      cs.is_satisfied(),
+      (
+        values(&claim.coeffs),
+        values(&claim.point),
+        claim.eval.get_value().unwrap(),
+        c.get_value().unwrap(),
+      ),
+    )
+  }
+
+  fn alloc_vec<CS: ConstraintSystem<F>>(mut cs: CS, v: &[F]) -> Vec<AllocatedNum<F>> {
+    v.iter()
+      .enumerate()
+      .map(|(i, v_i)| AllocatedNum::alloc(cs.namespace(|| format!("{i}")), || Ok(*v_i)).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_sumcheck_gadget() {
+    let num_rounds = 4;
+    let poly_A = (0..1 << num_rounds)
+      .map(|_| F::random(OsRng))
+      .collect::<Vec<_>>();
+    let poly_B = (0..1 << num_rounds)
+      .map(|_| F::random(OsRng))
+      .collect::<Vec<_>>();
+    let claim = poly_A
+      .iter()
+      .zip_eq(poly_B.iter())
+      .map(|(a, b)| *a * b)
+      .sum::<F>();
+
+    let comb_func = |comps: &[F]| -> F { comps[0] * comps[1] };
+    let (proof, r, evals) = SumcheckProof::<E>::prove(
+      &claim,
+      num_rounds,
+      vec![
+        MultilinearPolynomial::new(poly_A),
+        MultilinearPolynomial::new(poly_B),
+      ],
+      2,
+      comb_func,
+      &mut <E as Engine>::TE::new(b"test"),
+    )
+    .unwrap();
+
+    let mut cs = TestConstraintSystem::<F>::new();
+    let claim_var = AllocatedNum::alloc(cs.namespace(|| "claim"), || Ok(claim)).unwrap();
+    let proof_var =
+      AllocatedSumcheckProof::alloc(cs.namespace(|| "proof"), Some(&proof), num_rounds, 2).unwrap();
+
+    // the gadget derives the same challenges as the prover
+    let (e, r_out) = proof_var
+      .verify(
+        cs.namespace(|| "verify"),
+        &claim_var,
+        &mut PoseidonTranscriptCircuit::new(b"test"),
+      )
+      .unwrap();
+
+    assert!(cs.is_satisfied());
+    assert_eq!(
+      r_out
+        .iter()
+        .map(|r| r.get_value().unwrap())
+        .collect::<Vec<_>>(),
+      r
+    );
+    assert_eq!(e.get_value(), Some(evals[0] * evals[1]));
+
+    // a proof of a different claim reduces to a different final claim
+    let wrong_claim =
+      AllocatedNum::alloc(cs.namespace(|| "wrong claim"), || Ok(claim + F::ONE)).unwrap();
+    let (e, _) = proof_var
+      .verify(
+        cs.namespace(|| "verify wrong"),
+        &wrong_claim,
+        &mut PoseidonTranscriptCircuit::new(b"test"),
+      )
+      .unwrap();
+    assert_ne!(e.get_value(), Some(evals[0] * evals[1]));
+
+    // proofs of the wrong shape are rejected
+    assert!(AllocatedSumcheckProof::alloc(
+      cs.namespace(|| "short"),
+      Some(&proof),
+      num_rounds - 1,
+      2
+    )
+    .is_err());
+  }
+
+  fn test_spartan_gadget_with(unified_opening: bool) {
+    let circuit = || CubicCircuit { x: F::from(3u64) };
+
+    let mut cs: ShapeCS<E> = ShapeCS::new();
+    circuit().synthesize(&mut cs).unwrap();
+    let S = cs.r1cs_shape();
+    let ck = commitment_key(&S, &*SNARK::ck_floor());
+
+    let mut cs = SatisfyingAssignment::<E>::new();
+    circuit().synthesize(&mut cs).unwrap();
+    let (u, w) = cs.r1cs_instance_and_witness(&S, &ck).unwrap();
+    let U = RelaxedR1CSInstance::from_r1cs_instance_unchecked(&u.comm_W, &u.X);
+    let W = RelaxedR1CSWitness::from_r1cs_witness(&S, w);
+
+    let (mut pk, vk) = SNARK::setup(&ck, &S).unwrap();
+    pk.set_unified_opening(unified_opening);
+    let proof = SNARK::prove(&ck, &pk, &S, &U, &W).unwrap();
+    assert!(proof.verify(&vk, &U).is_ok());
+
+    // the gadget accepts the proof, and reduces it to the claim the native verifier does, with
+    // its transcript in the same state
+    let S = S.pad();
+    let (is_satisfied, (coeffs, point, eval, c)) =
+      verify_spartan_gadget(&proof, &S, vk.digest(), &U, unified_opening);
+    assert!(is_satisfied);
+    let (mut transcript, u_native) = proof.verify_reduce(&vk, &U).unwrap();
+    assert_eq!(U.comm_W * coeffs[0] + U.comm_E * coeffs[1], u_native.c);
+    assert_eq!(point, u_native.x);
+    assert_eq!(eval, u_native.e);
+    assert_eq!(c, transcript.squeeze(b"c").unwrap());
+
+    // it rejects proofs with a wrong round polynomial in the outer or inner sum-check, or a wrong
+    // claim
+    let tamper = |sc_proof: &SumcheckProof<E>| {
+      let mut polys = sc_proof.compressed_polys().to_vec();
+      let mut poly = polys[0].decompress(&F::ZERO);
+      poly.coeffs[0] += F::ONE;
+      polys[0] = poly.compress();
+      SumcheckProof::new(polys)
+    };
+    let mut bad_outer = proof.clone();
+    bad_outer.sc_proof_outer = tamper(&proof.sc_proof_outer);
+    let mut bad_inner = proof.clone();
+    bad_inner.sc_proof_inner = tamper(&proof.sc_proof_inner);
+    let mut bad_claim = proof.clone();
+    bad_claim.claims_outer.0 += F::ONE;
+    let mut bad_eval = proof.clone();
+    bad_eval.eval_W += F::ONE;
+    let mut bad_proofs = vec![bad_outer, bad_inner, bad_claim, bad_eval];
+
+    // a wrong polynomial in the batching sum-check is rejected right away when the evaluations at
+    // its end are sent, and otherwise changes the claim left to the evaluation argument
+    let mut bad_batch = proof.clone();
+    bad_batch.sc_proof_batch = tamper(&proof.sc_proof_batch);
+    if unified_opening {
+      let (is_satisfied, (_, _, bad_eval, _)) =
+        verify_spartan_gadget(&bad_batch, &S, vk.digest(), &U, unified_opening);
+      assert!(is_satisfied);
+      assert_ne!(bad_eval, eval);
+    } else {
+      let mut bad_evals_batch = proof.clone();
+      bad_evals_batch.evals_batch[0] += F::ONE;
+      bad_proofs.extend([bad_batch, bad_evals_batch]);
+    }
+
+    for bad_proof in bad_proofs {
+      assert!(!verify_spartan_gadget(&bad_proof, &S, vk.digest(), &U, unified_opening).0);
+    }
+
+    // and proofs that do not reduce their claims the way it expects
+    assert!(AllocatedRelaxedR1CSSNARK::alloc(
+      TestConstraintSystem::<F>::new(),
+      Some(&proof),
+      &S,
+      !unified_opening
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_spartan_gadget() {
+    test_spartan_gadget_with(false);
+    test_spartan_gadget_with(true);
+  }
+
+  #[test]
+  fn test_eq_gadget() {
+    let x = (0..5).map(|_| F::random(OsRng)).collect::<Vec<_>>();
+    let y = (0..5).map(|_| F::random(OsRng)).collect::<Vec<_>>();
+
+    let mut cs = TestConstraintSystem::<F>::new();
+    let x_var = alloc_vec(cs.namespace(|| "x"), &x);
+    let y_var = alloc_vec(cs.namespace(|| "y"), &y);
+    let eval = eval_eq(cs.namespace(|| "eq"), &x_var, &y_var).unwrap();
+
+    assert!(cs.is_satisfied());
+    assert_eq!(eval.get_value(), Some(EqPolynomial::new(x).evaluate(&y)));
+  }
+}
@@ -17,6 +17,7 @@ mod digest;
 mod nifs;
 
 // public modules
+pub mod cache;
 pub mod constants;
 pub mod errors;
 pub mod gadgets;
@@ -28,6 +29,7 @@ pub mod traits;
 pub mod supernova;
 
 use once_cell::sync::OnceCell;
+use rayon::prelude::*;
 
 use crate::digest::{DigestComputer, SimpleDigestible};
 use crate::{
@@ -42,20 +44,21 @@ use abomonation::Abomonation;
 use abomonation_derive::Abomonation;
 use bellpepper_core::ConstraintSystem;
 use circuit::{NovaAugmentedCircuit, NovaAugmentedCircuitInputs, NovaAugmentedCircuitParams};
-use constants::{BN_LIMB_WIDTH, BN_N_LIMBS, NUM_FE_WITHOUT_IO_FOR_CRHF, NUM_HASH_BITS};
+use constants::{BN_LIMB_WIDTH, BN_N_LIMBS, NUM_HASH_BITS};
 use core::marker::PhantomData;
 use errors::NovaError;
 use ff::{Field, PrimeField};
 use gadgets::utils::scalar_as_base;
 use nifs::NIFS;
 use r1cs::{
-  CommitmentKeyHint, R1CSInstance, R1CSShape, R1CSWitness, RelaxedR1CSInstance, RelaxedR1CSWitness,
+  commitment_key, commitment_key_size, CommitmentKeyHint, R1CSInstance, R1CSShape, R1CSWitness,
+  RelaxedR1CSInstance, RelaxedR1CSWitness,
 };
 use serde::{Deserialize, Serialize};
 use traits::{
   circuit::StepCircuit,
-  commitment::{CommitmentEngineTrait, CommitmentTrait},
-  snark::RelaxedR1CSSNARKTrait,
+  commitment::{CommitmentEngineTrait, CommitmentTrait, Len},
+  snark::{RelaxedR1CSSNARKTrait, SetupCache},
   AbsorbInROTrait, Engine, ROConstants, ROConstantsCircuit, ROTrait,
 };
 
@@ -188,6 +191,55 @@ where
     ck_hint1: &CommitmentKeyHint<E1>,
     ck_hint2: &CommitmentKeyHint<E2>,
   ) -> Self {
+    Self::setup_with(
+      c_primary,
+      c_secondary,
+      |S| Ok(commitment_key(S, ck_hint1)),
+      |S| Ok(commitment_key(S, ck_hint2)),
+    )
+    .expect("commitment keys are generated for the shapes")
+  }
+
+  /// Set up `PublicParams` for a pair of circuits `C1` and `C2`, like [`PublicParams::setup`],
+  /// but with given commitment keys instead of generated ones, e.g. KZG parameters loaded from
+  /// the output of a trusted setup with [`provider::UVUniversalKZGParam::from_ptau`].
+  ///
+  /// Returns `NovaError::InvalidCommitmentKeyLength` if a key is shorter than what the
+  /// corresponding circuit and hint require.
+  pub fn setup_with_commitment_keys(
+    c_primary: &C1,
+    c_secondary: &C2,
+    ck_hint1: &CommitmentKeyHint<E1>,
+    ck_hint2: &CommitmentKeyHint<E2>,
+    ck_primary: CommitmentKey<E1>,
+    ck_secondary: CommitmentKey<E2>,
+  ) -> Result<Self, NovaError> {
+    Self::setup_with(
+      c_primary,
+      c_secondary,
+      |S| {
+        if ck_primary.length() < commitment_key_size(S, ck_hint1) {
+          return Err(NovaError::InvalidCommitmentKeyLength);
+        }
+        Ok(ck_primary)
+      },
+      |S| {
+        if ck_secondary.length() < commitment_key_size(S, ck_hint2) {
+          return Err(NovaError::InvalidCommitmentKeyLength);
+        }
+        Ok(ck_secondary)
+      },
+    )
+  }
+
+  /// Synthesizes the shapes of the augmented circuits, and obtains the commitment key for each of
+  /// them from `ck_primary` and `ck_secondary`
+  fn setup_with(
+    c_primary: &C1,
+    c_secondary: &C2,
+    ck_primary: impl FnOnce(&R1CSShape<E1>) -> Result<CommitmentKey<E1>, NovaError>,
+    ck_secondary: impl FnOnce(&R1CSShape<E2>) -> Result<CommitmentKey<E2>, NovaError>,
+  ) -> Result<Self, NovaError> {
     let augmented_circuit_params_primary =
       NovaAugmentedCircuitParams::new(BN_LIMB_WIDTH, BN_N_LIMBS, true);
     let augmented_circuit_params_secondary =
@@ -212,7 +264,8 @@ where
     );
     let mut cs: ShapeCS<E1> = ShapeCS::new();
     let _ = circuit_primary.synthesize(&mut cs);
-    let (r1cs_shape_primary, ck_primary) = cs.r1cs_shape_and_key(ck_hint1);
+    let r1cs_shape_primary = cs.r1cs_shape();
+    let ck_primary = ck_primary(&r1cs_shape_primary)?;
     let circuit_shape_primary = CircuitShape::new(r1cs_shape_primary, F_arity_primary);
 
     // Initialize ck for the secondary
@@ -224,10 +277,11 @@ where
     );
     let mut cs: ShapeCS<E2> = ShapeCS::new();
     let _ = circuit_secondary.synthesize(&mut cs);
-    let (r1cs_shape_secondary, ck_secondary) = cs.r1cs_shape_and_key(ck_hint2);
+    let r1cs_shape_secondary = cs.r1cs_shape();
+    let ck_secondary = ck_secondary(&r1cs_shape_secondary)?;
     let circuit_shape_secondary = CircuitShape::new(r1cs_shape_secondary, F_arity_secondary);
 
-    PublicParams {
+    Ok(PublicParams {
       F_arity_primary,
       F_arity_secondary,
       ro_consts_primary,
@@ -242,7 +296,7 @@ where
       augmented_circuit_params_secondary,
       digest: OnceCell::new(),
       _p: Default::default(),
-    }
+    })
   }
 
   /// Retrieve the digest of the public parameters.
@@ -254,6 +308,19 @@ where
       .expect("Failure in retrieving digest")
   }
 
+  /// Precomputes fixed-base tables in the commitment keys of the primary and secondary circuits,
+  /// for windows of `window_size` bits, which make proving faster at the cost of memory, see
+  /// [`CommitmentEngineTrait::precompute_tables`]
+  ///
+  /// The tables do not change the digest of the parameters, nor their proofs. With Pedersen
+  /// commitments, they are 16 to 32 times as large as the keys, and whether they pay off depends
+  /// on the machine and the size of the keys: the `commit` benchmark compares commitments with
+  /// and without them.
+  pub fn precompute_commitment_tables(&mut self, window_size: usize) -> Result<(), NovaError> {
+    E1::CE::precompute_tables(&mut self.ck_primary, window_size)?;
+    E2::CE::precompute_tables(&mut self.ck_secondary, window_size)
+  }
+
   /// Returns the number of constraints in the primary and secondary circuits
   pub const fn num_constraints(&self) -> (usize, usize) {
     (
@@ -619,10 +686,7 @@ where
 
     // check if the output hashes in R1CS instances point to the right running instances
     let (hash_primary, hash_secondary) = {
-      let mut hasher = <E2 as Engine>::RO::new(
-        pp.ro_consts_secondary.clone(),
-        NUM_FE_WITHOUT_IO_FOR_CRHF + 2 * pp.F_arity_primary,
-      );
+      let mut hasher = <E2 as Engine>::RO::new(pp.ro_consts_secondary.clone());
       hasher.absorb(pp.digest());
       hasher.absorb(E1::Scalar::from(num_steps as u64));
       for e in z0_primary {
@@ -633,10 +697,7 @@ where
       }
       self.r_U_secondary.absorb_in_ro(&mut hasher);
 
-      let mut hasher2 = <E1 as Engine>::RO::new(
-        pp.ro_consts_primary.clone(),
-        NUM_FE_WITHOUT_IO_FOR_CRHF + 2 * pp.F_arity_secondary,
-      );
+      let mut hasher2 = <E1 as Engine>::RO::new(pp.ro_consts_primary.clone());
       hasher2.absorb(scalar_as_base::<E1>(pp.digest()));
       hasher2.absorb(E2::Scalar::from(num_steps as u64));
       for e in z0_secondary {
@@ -648,8 +709,8 @@ where
       self.r_U_primary.absorb_in_ro(&mut hasher2);
 
       (
-        hasher.squeeze(NUM_HASH_BITS),
-        hasher2.squeeze(NUM_HASH_BITS),
+        hasher.squeeze(NUM_HASH_BITS)?,
+        hasher2.squeeze(NUM_HASH_BITS)?,
       )
     };
 
@@ -797,6 +858,50 @@ where
     let (pk_secondary, vk_secondary) =
       S2::setup(&pp.ck_secondary, &pp.circuit_shape_secondary.r1cs_shape)?;
 
+    Ok(Self::keys(
+      pp,
+      (pk_primary, vk_primary),
+      (pk_secondary, vk_secondary),
+    ))
+  }
+
+  /// Creates prover and verifier keys for `CompressedSNARK`, reusing the preprocessed data
+  /// that an earlier setup with the same public parameters stored in `cache`
+  pub fn setup_with_cache(
+    pp: &PublicParams<E1, E2, C1, C2>,
+    cache: &dyn SetupCache,
+  ) -> Result<
+    (
+      ProverKey<E1, E2, C1, C2, S1, S2>,
+      VerifierKey<E1, E2, C1, C2, S1, S2>,
+    ),
+    NovaError,
+  > {
+    let (pk_primary, vk_primary) =
+      S1::setup_with_cache(&pp.ck_primary, &pp.circuit_shape_primary.r1cs_shape, cache)?;
+    let (pk_secondary, vk_secondary) = S2::setup_with_cache(
+      &pp.ck_secondary,
+      &pp.circuit_shape_secondary.r1cs_shape,
+      cache,
+    )?;
+
+    Ok(Self::keys(
+      pp,
+      (pk_primary, vk_primary),
+      (pk_secondary, vk_secondary),
+    ))
+  }
+
+  // Assembles the keys for `CompressedSNARK` from those of the SNARKs of both circuits
+  #[allow(clippy::type_complexity)]
+  fn keys(
+    pp: &PublicParams<E1, E2, C1, C2>,
+    (pk_primary, vk_primary): (S1::ProverKey, S1::VerifierKey),
+    (pk_secondary, vk_secondary): (S2::ProverKey, S2::VerifierKey),
+  ) -> (
+    ProverKey<E1, E2, C1, C2, S1, S2>,
+    VerifierKey<E1, E2, C1, C2, S1, S2>,
+  ) {
     let pk = ProverKey {
       pk_primary,
       pk_secondary,
@@ -814,7 +919,7 @@ where
       _p: Default::default(),
     };
 
-    Ok((pk, vk))
+    (pk, vk)
   }
 
   /// Create a new `CompressedSNARK`
@@ -881,11 +986,125 @@ where
     z0_primary: &[E1::Scalar],
     z0_secondary: &[E2::Scalar],
   ) -> Result<(Vec<E1::Scalar>, Vec<E2::Scalar>), NovaError> {
+    let f_U_secondary = self.verify_instances(vk, num_steps, z0_primary, z0_secondary)?;
+
+    // check the satisfiability of the folded instances using
+    // SNARKs proving the knowledge of their satisfying witnesses
+    let (res_primary, res_secondary) = rayon::join(
+      || {
+        self
+          .r_W_snark_primary
+          .verify(&vk.vk_primary, &self.r_U_primary)
+      },
+      || {
+        self
+          .f_W_snark_secondary
+          .verify(&vk.vk_secondary, &f_U_secondary)
+      },
+    );
+
+    res_primary?;
+    res_secondary?;
+
+    Ok((self.zn_primary.clone(), self.zn_secondary.clone()))
+  }
+
+  /// Verify the correctness of a batch of `CompressedSNARK`s sharing the verifier key `vk`,
+  /// where `snarks[i]` is checked against `num_steps[i]`, `z0_primary[i]`, and `z0_secondary[i]`.
+  /// The evaluation arguments of all SNARKs are checked at once. If a SNARK does not verify,
+  /// returns `NovaError::BatchVerifyError` with its index.
+  pub fn batch_verify(
+    snarks: &[Self],
+    vk: &VerifierKey<E1, E2, C1, C2, S1, S2>,
+    num_steps: &[usize],
+    z0_primary: &[Vec<E1::Scalar>],
+    z0_secondary: &[Vec<E2::Scalar>],
+  ) -> Result<Vec<(Vec<E1::Scalar>, Vec<E2::Scalar>)>, NovaError> {
+    if num_steps.len() != snarks.len()
+      || z0_primary.len() != snarks.len()
+      || z0_secondary.len() != snarks.len()
+    {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    let f_U_secondary = snarks
+      .par_iter()
+      .enumerate()
+      .map(|(index, snark)| {
+        snark
+          .verify_instances(
+            vk,
+            num_steps[index],
+            &z0_primary[index],
+            &z0_secondary[index],
+          )
+          .map_err(|error| NovaError::BatchVerifyError {
+            index,
+            error: Box::new(error),
+          })
+      })
+      .collect::<Result<Vec<_>, NovaError>>()?;
+
+    // check the satisfiability of the folded instances using
+    // SNARKs proving the knowledge of their satisfying witnesses
+    let (res_primary, res_secondary) = rayon::join(
+      || {
+        let r_W_snarks = snarks
+          .iter()
+          .map(|snark| &snark.r_W_snark_primary)
+          .collect::<Vec<_>>();
+        let r_U_primary = snarks
+          .iter()
+          .map(|snark| snark.r_U_primary.clone())
+          .collect::<Vec<_>>();
+        S1::batch_verify(&r_W_snarks, &vk.vk_primary, &r_U_primary)
+      },
+      || {
+        let f_W_snarks = snarks
+          .iter()
+          .map(|snark| &snark.f_W_snark_secondary)
+          .collect::<Vec<_>>();
+        S2::batch_verify(&f_W_snarks, &vk.vk_secondary, &f_U_secondary)
+      },
+    );
+
+    res_primary?;
+    res_secondary?;
+
+    Ok(
+      snarks
+        .iter()
+        .map(|snark| (snark.zn_primary.clone(), snark.zn_secondary.clone()))
+        .collect(),
+    )
+  }
+
+  // Checks the public outputs of the SNARK against the inputs, and returns the instance of the
+  // secondary circuit obtained by folding its last instance into its running instance
+  fn verify_instances(
+    &self,
+    vk: &VerifierKey<E1, E2, C1, C2, S1, S2>,
+    num_steps: usize,
+    z0_primary: &[E1::Scalar],
+    z0_secondary: &[E2::Scalar],
+  ) -> Result<RelaxedR1CSInstance<E2>, NovaError> {
     // the number of steps cannot be zero
     if num_steps == 0 {
       return Err(NovaError::ProofVerifyError);
     }
 
+    // the initial inputs must match the arity of the step circuits
+    if z0_primary.len() != vk.F_arity_primary || z0_secondary.len() != vk.F_arity_secondary {
+      return Err(NovaError::InvalidInitialInputLength);
+    }
+
+    // and so must the claimed outputs
+    if self.zn_primary.len() != vk.F_arity_primary
+      || self.zn_secondary.len() != vk.F_arity_secondary
+    {
+      return Err(NovaError::ProofVerifyError);
+    }
+
     // check if the (relaxed) R1CS instances have two public outputs
     if self.l_u_secondary.X.len() != 2
       || self.r_U_primary.X.len() != 2
@@ -896,10 +1115,7 @@ where
 
     // check if the output hashes in R1CS instances point to the right running instances
     let (hash_primary, hash_secondary) = {
-      let mut hasher = <E2 as Engine>::RO::new(
-        vk.ro_consts_secondary.clone(),
-        NUM_FE_WITHOUT_IO_FOR_CRHF + 2 * vk.F_arity_primary,
-      );
+      let mut hasher = <E2 as Engine>::RO::new(vk.ro_consts_secondary.clone());
       hasher.absorb(vk.pp_digest);
       hasher.absorb(E1::Scalar::from(num_steps as u64));
       for e in z0_primary {
@@ -910,10 +1126,7 @@ where
       }
       self.r_U_secondary.absorb_in_ro(&mut hasher);
 
-      let mut hasher2 = <E1 as Engine>::RO::new(
-        vk.ro_consts_primary.clone(),
-        NUM_FE_WITHOUT_IO_FOR_CRHF + 2 * vk.F_arity_secondary,
-      );
+      let mut hasher2 = <E1 as Engine>::RO::new(vk.ro_consts_primary.clone());
       hasher2.absorb(scalar_as_base::<E1>(vk.pp_digest));
       hasher2.absorb(E2::Scalar::from(num_steps as u64));
       for e in z0_secondary {
@@ -925,8 +1138,8 @@ where
       self.r_U_primary.absorb_in_ro(&mut hasher2);
 
       (
-        hasher.squeeze(NUM_HASH_BITS),
-        hasher2.squeeze(NUM_HASH_BITS),
+        hasher.squeeze(NUM_HASH_BITS)?,
+        hasher2.squeeze(NUM_HASH_BITS)?,
       )
     };
 
@@ -937,32 +1150,12 @@ where
     }
 
     // fold the secondary's running instance with the last instance to get a folded instance
-    let f_U_secondary = self.nifs_secondary.verify(
+    self.nifs_secondary.verify(
       &vk.ro_consts_secondary,
       &scalar_as_base::<E1>(vk.pp_digest),
       &self.r_U_secondary,
       &self.l_u_secondary,
-    )?;
-
-    // check the satisfiability of the folded instances using
-    // SNARKs proving the knowledge of their satisfying witnesses
-    let (res_primary, res_secondary) = rayon::join(
-      || {
-        self
-          .r_W_snark_primary
-          .verify(&vk.vk_primary, &self.r_U_primary)
-      },
-      || {
-        self
-          .f_W_snark_secondary
-          .verify(&vk.vk_secondary, &f_U_secondary)
-      },
-    );
-
-    res_primary?;
-    res_secondary?;
-
-    Ok((self.zn_primary.clone(), self.zn_secondary.clone()))
+    )
   }
 }
 
@@ -1000,10 +1193,14 @@ mod tests {
   use super::*;
   use crate::{
     provider::{
-      non_hiding_zeromorph::ZMPCS, traits::DlogGroup, Bn256Engine, Bn256EngineZM, GrumpkinEngine,
-      PallasEngine, Secp256k1Engine, Secq256k1Engine, VestaEngine,
+      hyperkzg::HyperKZGPCS, non_hiding_zeromorph::ZMPCS, pedersen::MAX_TABLE_WINDOW_SIZE,
+      traits::DlogGroup, Bn256Engine, Bn256EngineZM, GrumpkinEngine, PallasEngine,
+      PallasPoseidonEngine, Secp256k1Engine, Secq256k1Engine, VestaEngine, VestaPoseidonEngine,
+    },
+    traits::{
+      evaluation::EvaluationEngineTrait,
+      snark::{default_ck_hint, DigestHelperTrait},
     },
-    traits::{evaluation::EvaluationEngineTrait, snark::default_ck_hint},
   };
   use ::bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
   use core::{fmt::Write, marker::PhantomData};
@@ -1063,6 +1260,67 @@ mod tests {
     }
   }
 
+  type CubicPublicParams<E1, E2> = PublicParams<
+    E1,
+    E2,
+    TrivialCircuit<<E1 as Engine>::Scalar>,
+    CubicCircuit<<E2 as Engine>::Scalar>,
+  >;
+
+  type CubicRecursiveSNARK<E1, E2> = RecursiveSNARK<
+    E1,
+    E2,
+    TrivialCircuit<<E1 as Engine>::Scalar>,
+    CubicCircuit<<E2 as Engine>::Scalar>,
+  >;
+
+  // Produces public parameters for the trivial circuit on the primary curve and the cubic circuit
+  // on the secondary one, for the commitment key hints `ck_hint1` and `ck_hint2`
+  fn cubic_pp<E1, E2>(
+    ck_hint1: &CommitmentKeyHint<E1>,
+    ck_hint2: &CommitmentKeyHint<E2>,
+  ) -> CubicPublicParams<E1, E2>
+  where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+  {
+    PublicParams::setup(
+      &TrivialCircuit::default(),
+      &CubicCircuit::default(),
+      ck_hint1,
+      ck_hint2,
+    )
+  }
+
+  // Produces a recursive SNARK of `num_steps` steps of the circuits of `pp`, from the inputs
+  // `[1]` and `[0]`
+  fn cubic_recursive_snark<E1, E2>(
+    pp: &CubicPublicParams<E1, E2>,
+    num_steps: usize,
+  ) -> CubicRecursiveSNARK<E1, E2>
+  where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+  {
+    let circuit_primary = TrivialCircuit::default();
+    let circuit_secondary = CubicCircuit::default();
+
+    let mut recursive_snark = RecursiveSNARK::new(
+      pp,
+      &circuit_primary,
+      &circuit_secondary,
+      &[<E1 as Engine>::Scalar::ONE],
+      &[<E2 as Engine>::Scalar::ZERO],
+    )
+    .unwrap();
+    for _i in 0..num_steps {
+      recursive_snark
+        .prove_step(pp, &circuit_primary, &circuit_secondary)
+        .unwrap();
+    }
+    recursive_snark
+  }
+
   fn test_pp_digest_with<E1, E2, T1, T2, EE1, EE2>(circuit1: &T1, circuit2: &T2, expected: &str)
   where
     E1: Engine<Base = <E2 as Engine>::Scalar>,
@@ -1225,6 +1483,48 @@ mod tests {
     test_ivc_trivial_with::<Secp256k1Engine, Secq256k1Engine>();
   }
 
+  fn test_ivc_commitment_tables_with<E1, E2>()
+  where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+  {
+    // produce public parameters, with and without tables
+    let pp = cubic_pp::<E1, E2>(&*default_ck_hint(), &*default_ck_hint());
+    let mut pp_tables = cubic_pp::<E1, E2>(&*default_ck_hint(), &*default_ck_hint());
+    for window_size in [0, MAX_TABLE_WINDOW_SIZE + 1] {
+      assert_eq!(
+        pp_tables.precompute_commitment_tables(window_size),
+        Err(NovaError::InvalidTableWindowSize)
+      );
+    }
+
+    // windows of at most 10 bits are summed in a single pass by the small MSM, and larger ones
+    // in segments
+    for window_size in [4, 8, 12, MAX_TABLE_WINDOW_SIZE] {
+      pp_tables.precompute_commitment_tables(window_size).unwrap();
+
+      // the tables change neither the digest of the parameters nor the proofs
+      assert_eq!(pp.digest(), pp_tables.digest());
+
+      let num_steps = 3;
+      let recursive_snark = cubic_recursive_snark(&pp_tables, num_steps);
+      let res = recursive_snark.verify(
+        &pp,
+        num_steps,
+        &[<E1 as Engine>::Scalar::ONE],
+        &[<E2 as Engine>::Scalar::ZERO],
+      );
+      assert!(res.is_ok());
+    }
+  }
+
+  #[test]
+  fn test_ivc_commitment_tables() {
+    test_ivc_commitment_tables_with::<PallasEngine, VestaEngine>();
+    test_ivc_commitment_tables_with::<Bn256Engine, GrumpkinEngine>();
+    test_ivc_commitment_tables_with::<Secp256k1Engine, Secq256k1Engine>();
+  }
+
   fn test_ivc_nontrivial_with<E1, E2>()
   where
     E1: Engine<Base = <E2 as Engine>::Scalar>,
@@ -1305,6 +1605,59 @@ mod tests {
     test_ivc_nontrivial_with::<Secp256k1Engine, Secq256k1Engine>();
   }
 
+  #[test]
+  fn test_setup_with_commitment_keys() {
+    type E1 = Bn256EngineZM;
+    type E2 = GrumpkinEngine;
+    type C1 = TrivialCircuit<<E1 as Engine>::Scalar>;
+    type C2 = CubicCircuit<<E2 as Engine>::Scalar>;
+
+    let circuit_primary = TrivialCircuit::default();
+    let circuit_secondary = CubicCircuit::default();
+
+    let pp = PublicParams::<E1, E2, C1, C2>::setup(
+      &circuit_primary,
+      &circuit_secondary,
+      &*default_ck_hint(),
+      &*default_ck_hint(),
+    );
+
+    // the same keys, loaded as the powers of a trusted setup, give the same parameters
+    let srs = provider::UVUniversalKZGParam::<Bn256>::from_powers(
+      pp.ck_primary.powers_of_g.clone(),
+      pp.ck_primary.powers_of_h.clone(),
+    )
+    .unwrap();
+    let pp_loaded = PublicParams::<E1, E2, C1, C2>::setup_with_commitment_keys(
+      &circuit_primary,
+      &circuit_secondary,
+      &*default_ck_hint(),
+      &*default_ck_hint(),
+      srs,
+      pp.ck_secondary.clone(),
+    )
+    .unwrap();
+    assert_eq!(pp.digest(), pp_loaded.digest());
+
+    // keys that are too short for the circuits are rejected
+    let srs = provider::UVUniversalKZGParam::<Bn256>::from_powers(
+      pp.ck_primary.powers_of_g[..2].to_vec(),
+      pp.ck_primary.powers_of_h.clone(),
+    )
+    .unwrap();
+    assert!(matches!(
+      PublicParams::<E1, E2, C1, C2>::setup_with_commitment_keys(
+        &circuit_primary,
+        &circuit_secondary,
+        &*default_ck_hint(),
+        &*default_ck_hint(),
+        srs,
+        pp.ck_secondary.clone(),
+      ),
+      Err(NovaError::InvalidCommitmentKeyLength)
+    ));
+  }
+
   fn test_ivc_nontrivial_with_compression_with<E1, E2, EE1, EE2>()
   where
     E1: Engine<Base = <E2 as Engine>::Scalar>,
@@ -1398,6 +1751,157 @@ mod tests {
     test_ivc_nontrivial_with_compression_with::<Bn256Engine, GrumpkinEngine, EE<_>, EE<_>>();
     test_ivc_nontrivial_with_compression_with::<Secp256k1Engine, Secq256k1Engine, EE<_>, EE<_>>();
     test_ivc_nontrivial_with_compression_with::<
+      PallasPoseidonEngine,
+      VestaPoseidonEngine,
+      EE<_>,
+      EE<_>,
+    >();
+    test_ivc_nontrivial_with_compression_with::<
+      Bn256EngineZM,
+      GrumpkinEngine,
+      ZMPCS<Bn256, _>,
+      EE<_>,
+    >();
+    test_ivc_nontrivial_with_compression_with::<
+      Bn256EngineZM,
+      GrumpkinEngine,
+      HyperKZGPCS<Bn256, _>,
+      EE<_>,
+    >();
+  }
+
+  fn test_ivc_nontrivial_with_batch_compression_with<E1, E2, EE1, EE2>()
+  where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+    EE1: EvaluationEngineTrait<E1>,
+    EE2: EvaluationEngineTrait<E2>,
+    // this is due to the reliance on Abomonation
+    <E1::Scalar as PrimeField>::Repr: Abomonation,
+    <E2::Scalar as PrimeField>::Repr: Abomonation,
+  {
+    // produce public parameters
+    let pp = cubic_pp::<E1, E2>(&*default_ck_hint(), &*default_ck_hint());
+
+    // produce the prover and verifier keys for compressed snark
+    let (pk, vk) = CompressedSNARK::<_, _, _, _, S<E1, EE1>, S<E2, EE2>>::setup(&pp).unwrap();
+
+    // produce compressed SNARKs for computations with different numbers of steps
+    let num_steps = vec![1, 2, 3, 4];
+    let z0_primary = vec![vec![<E1 as Engine>::Scalar::ONE]; num_steps.len()];
+    let z0_secondary = vec![vec![<E2 as Engine>::Scalar::ZERO]; num_steps.len()];
+
+    let compressed_snarks = num_steps
+      .iter()
+      .map(|num_steps| {
+        let recursive_snark = cubic_recursive_snark(&pp, *num_steps);
+        CompressedSNARK::<_, _, _, _, S<E1, EE1>, S<E2, EE2>>::prove(&pp, &pk, &recursive_snark)
+          .unwrap()
+      })
+      .collect::<Vec<_>>();
+
+    // verify the compressed SNARKs in a batch
+    let res = CompressedSNARK::batch_verify(
+      &compressed_snarks,
+      &vk,
+      &num_steps,
+      &z0_primary,
+      &z0_secondary,
+    );
+    assert!(res.is_ok());
+
+    // the outputs are those of the SNARKs verified one by one
+    for (i, outputs) in res.unwrap().into_iter().enumerate() {
+      let res = compressed_snarks[i].verify(&vk, num_steps[i], &z0_primary[i], &z0_secondary[i]);
+      assert_eq!(res, Ok(outputs));
+    }
+
+    // the batch reports the SNARK that does not verify
+    let mut wrong_num_steps = num_steps.clone();
+    wrong_num_steps[2] += 1;
+    let res = CompressedSNARK::batch_verify(
+      &compressed_snarks,
+      &vk,
+      &wrong_num_steps,
+      &z0_primary,
+      &z0_secondary,
+    );
+    assert!(matches!(
+      res,
+      Err(NovaError::BatchVerifyError { index: 2, .. })
+    ));
+  }
+
+  #[test]
+  fn test_ivc_nontrivial_with_batch_compression() {
+    test_ivc_nontrivial_with_batch_compression_with::<PallasEngine, VestaEngine, EE<_>, EE<_>>();
+    test_ivc_nontrivial_with_batch_compression_with::<
+      Bn256EngineZM,
+      GrumpkinEngine,
+      ZMPCS<Bn256, _>,
+      EE<_>,
+    >();
+  }
+
+  fn test_ivc_nontrivial_with_unified_opening_compression_with<E1, E2, EE1, EE2>()
+  where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+    EE1: EvaluationEngineTrait<E1>,
+    EE2: EvaluationEngineTrait<E2>,
+    // this is due to the reliance on Abomonation
+    <E1::Scalar as PrimeField>::Repr: Abomonation,
+    <E2::Scalar as PrimeField>::Repr: Abomonation,
+  {
+    let pp = cubic_pp::<E1, E2>(&*default_ck_hint(), &*default_ck_hint());
+
+    let num_steps = 3;
+    let z0_primary = [<E1 as Engine>::Scalar::ONE];
+    let z0_secondary = [<E2 as Engine>::Scalar::ZERO];
+    let recursive_snark = cubic_recursive_snark(&pp, num_steps);
+
+    let (mut pk, vk) = CompressedSNARK::<_, _, _, _, S<E1, EE1>, S<E2, EE2>>::setup(&pp).unwrap();
+    let compressed_snark =
+      CompressedSNARK::<_, _, _, _, S<E1, EE1>, S<E2, EE2>>::prove(&pp, &pk, &recursive_snark)
+        .unwrap();
+
+    pk.pk_primary.set_unified_opening(true);
+    pk.pk_secondary.set_unified_opening(true);
+    let compressed_snark_unified =
+      CompressedSNARK::<_, _, _, _, S<E1, EE1>, S<E2, EE2>>::prove(&pp, &pk, &recursive_snark)
+        .unwrap();
+
+    // the unified opening leaves out the evaluations of the batched claims
+    assert!(
+      bincode::serialize(&compressed_snark_unified).unwrap().len()
+        < bincode::serialize(&compressed_snark).unwrap().len()
+    );
+
+    // the verifier accepts proofs produced either way, including in a batch
+    let snarks = [compressed_snark, compressed_snark_unified];
+    for snark in &snarks {
+      let res = snark.verify(&vk, num_steps, &z0_primary, &z0_secondary);
+      assert!(res.is_ok());
+    }
+    let res = CompressedSNARK::batch_verify(
+      &snarks,
+      &vk,
+      &[num_steps; 2],
+      &[z0_primary.to_vec(), z0_primary.to_vec()],
+      &[z0_secondary.to_vec(), z0_secondary.to_vec()],
+    );
+    assert!(res.is_ok());
+  }
+
+  #[test]
+  fn test_ivc_nontrivial_with_unified_opening_compression() {
+    test_ivc_nontrivial_with_unified_opening_compression_with::<
+      PallasEngine,
+      VestaEngine,
+      EE<_>,
+      EE<_>,
+    >();
+    test_ivc_nontrivial_with_unified_opening_compression_with::<
       Bn256EngineZM,
       GrumpkinEngine,
       ZMPCS<Bn256, _>,
@@ -1511,6 +2015,136 @@ mod tests {
     >();
   }
 
+  #[test]
+  fn test_ivc_nontrivial_with_spark_compression_cache() {
+    type E1 = PallasEngine;
+    type E2 = VestaEngine;
+    type S1 = SPrime<E1, EE<E1>>;
+    type S2 = SPrime<E2, EE<E2>>;
+
+    let pp = cubic_pp::<E1, E2>(&*S1::ck_floor(), &*S2::ck_floor());
+
+    let num_steps = 3;
+    let z0_primary = [<E1 as Engine>::Scalar::ONE];
+    let z0_secondary = [<E2 as Engine>::Scalar::ZERO];
+    let recursive_snark = cubic_recursive_snark(&pp, num_steps);
+
+    let dir = std::env::temp_dir().join(format!("nova-setup-cache-{}", std::process::id()));
+    let cache = cache::FileSetupCache::new(&dir).unwrap();
+
+    // the first setup stores the Spark representations of both shapes, and the second loads them
+    let (_, vk) = CompressedSNARK::<_, _, _, _, S1, S2>::setup(&pp).unwrap();
+    let (_, vk_stored) =
+      CompressedSNARK::<_, _, _, _, S1, S2>::setup_with_cache(&pp, &cache).unwrap();
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    let (pk_loaded, vk_loaded) =
+      CompressedSNARK::<_, _, _, _, S1, S2>::setup_with_cache(&pp, &cache).unwrap();
+
+    for vk_cached in [&vk_stored, &vk_loaded] {
+      assert_eq!(vk_cached.vk_primary.digest(), vk.vk_primary.digest());
+      assert_eq!(vk_cached.vk_secondary.digest(), vk.vk_secondary.digest());
+    }
+
+    let compressed_snark =
+      CompressedSNARK::<_, _, _, _, S1, S2>::prove(&pp, &pk_loaded, &recursive_snark).unwrap();
+    assert!(compressed_snark
+      .verify(&vk, num_steps, &z0_primary, &z0_secondary)
+      .is_ok());
+
+    // corrupted entries are detected on load, and replaced
+    for entry in std::fs::read_dir(&dir).unwrap() {
+      std::fs::write(entry.unwrap().path(), b"corrupted").unwrap();
+    }
+    let (pk_recomputed, _) =
+      CompressedSNARK::<_, _, _, _, S1, S2>::setup_with_cache(&pp, &cache).unwrap();
+    let compressed_snark =
+      CompressedSNARK::<_, _, _, _, S1, S2>::prove(&pp, &pk_recomputed, &recursive_snark).unwrap();
+    assert!(compressed_snark
+      .verify(&vk, num_steps, &z0_primary, &z0_secondary)
+      .is_ok());
+    for entry in std::fs::read_dir(&dir).unwrap() {
+      assert_ne!(std::fs::read(entry.unwrap().path()).unwrap(), b"corrupted");
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn test_ivc_nontrivial_with_spark_compression_memory_budget() {
+    type E1 = PallasEngine;
+    type E2 = VestaEngine;
+    type S1 = SPrime<E1, EE<E1>>;
+    type S2 = SPrime<E2, EE<E2>>;
+
+    let pp = cubic_pp::<E1, E2>(&*S1::ck_floor(), &*S2::ck_floor());
+
+    let num_steps = 3;
+    let z0_primary = [<E1 as Engine>::Scalar::ONE];
+    let z0_secondary = [<E2 as Engine>::Scalar::ZERO];
+    let recursive_snark = cubic_recursive_snark(&pp, num_steps);
+
+    let (mut pk, vk) = CompressedSNARK::<_, _, _, _, S1, S2>::setup(&pp).unwrap();
+    let compressed_snark =
+      CompressedSNARK::<_, _, _, _, S1, S2>::prove(&pp, &pk, &recursive_snark).unwrap();
+
+    // a budget too small even for the prover that recomputes vectors is rejected
+    let min_budget = pk.pk_primary.min_memory_budget();
+    assert!(matches!(
+      pk.pk_primary.set_memory_budget(Some(min_budget - 1)),
+      Err(NovaError::InsufficientMemoryBudget)
+    ));
+    assert_eq!(pk.pk_primary.memory_budget(), None);
+
+    // a budget too small for the default prover makes it recompute vectors instead of keeping them
+    pk.pk_primary.set_memory_budget(Some(min_budget)).unwrap();
+    let min_budget = pk.pk_secondary.min_memory_budget();
+    pk.pk_secondary.set_memory_budget(Some(min_budget)).unwrap();
+    let compressed_snark_bounded =
+      CompressedSNARK::<_, _, _, _, S1, S2>::prove(&pp, &pk, &recursive_snark).unwrap();
+
+    assert_eq!(
+      bincode::serialize(&compressed_snark).unwrap(),
+      bincode::serialize(&compressed_snark_bounded).unwrap()
+    );
+    assert!(compressed_snark_bounded
+      .verify(&vk, num_steps, &z0_primary, &z0_secondary)
+      .is_ok());
+  }
+
+  #[test]
+  fn test_ivc_nontrivial_with_zk_compression() {
+    type E1 = PallasEngine;
+    type E2 = VestaEngine;
+    type S1 = spartan::zksnark::RelaxedR1CSSNARK<E1>;
+    type S2 = spartan::zksnark::RelaxedR1CSSNARK<E2>;
+
+    // produce public parameters and a recursive SNARK
+    let pp = cubic_pp::<E1, E2>(&*S1::ck_floor(), &*S2::ck_floor());
+    let num_steps = 3;
+    let recursive_snark = cubic_recursive_snark(&pp, num_steps);
+
+    // produce the prover and verifier keys for the zero-knowledge compressed snark
+    let (pk, vk) = CompressedSNARK::<_, _, _, _, S1, S2>::setup(&pp).unwrap();
+
+    // produce a compressed SNARK
+    let res = CompressedSNARK::<_, _, _, _, S1, S2>::prove(&pp, &pk, &recursive_snark);
+    assert!(res.is_ok());
+    let compressed_snark = res.unwrap();
+
+    // verify the compressed SNARK
+    let res = compressed_snark.verify(
+      &vk,
+      num_steps,
+      &[<E1 as Engine>::Scalar::ONE],
+      &[<E2 as Engine>::Scalar::ZERO],
+    );
+    assert!(res.is_ok());
+    assert_eq!(
+      res.unwrap().1,
+      vec![<E2 as Engine>::Scalar::from(2460515u64)]
+    );
+  }
+
   fn test_ivc_nondet_with_compression_with<E1, E2, EE1, EE2>()
   where
     E1: Engine<Base = <E2 as Engine>::Scalar>,
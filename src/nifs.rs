@@ -2,7 +2,7 @@
 #![allow(non_snake_case)]
 
 use crate::{
-  constants::{NUM_CHALLENGE_BITS, NUM_FE_FOR_RO},
+  constants::NUM_CHALLENGE_BITS,
   errors::NovaError,
   r1cs::{
     R1CSInstance, R1CSResult, R1CSShape, R1CSWitness, RelaxedR1CSInstance, RelaxedR1CSWitness,
@@ -44,7 +44,7 @@ impl<E: Engine> NIFS<E> {
     W2: &R1CSWitness<E>,
   ) -> Result<(NIFS<E>, (RelaxedR1CSInstance<E>, RelaxedR1CSWitness<E>)), NovaError> {
     // initialize a new RO
-    let mut ro = E::RO::new(ro_consts.clone(), NUM_FE_FOR_RO);
+    let mut ro = E::RO::new(ro_consts.clone());
 
     // append the digest of pp to the transcript
     ro.absorb(scalar_as_base::<E>(*pp_digest));
@@ -60,7 +60,7 @@ impl<E: Engine> NIFS<E> {
     comm_T.absorb_in_ro(&mut ro);
 
     // compute a challenge from the RO
-    let r = ro.squeeze(NUM_CHALLENGE_BITS);
+    let r = ro.squeeze(NUM_CHALLENGE_BITS)?;
 
     // fold the instance using `r` and `comm_T`
     let U = U1.fold(U2, &comm_T, &r);
@@ -98,7 +98,7 @@ impl<E: Engine> NIFS<E> {
     ABC_Z_2: &mut R1CSResult<E>,
   ) -> Result<NIFS<E>, NovaError> {
     // initialize a new RO
-    let mut ro = E::RO::new(ro_consts.clone(), NUM_FE_FOR_RO);
+    let mut ro = E::RO::new(ro_consts.clone());
 
     // append the digest of pp to the transcript
     ro.absorb(scalar_as_base::<E>(*pp_digest));
@@ -114,7 +114,7 @@ impl<E: Engine> NIFS<E> {
     comm_T.absorb_in_ro(&mut ro);
 
     // compute a challenge from the RO
-    let r = ro.squeeze(NUM_CHALLENGE_BITS);
+    let r = ro.squeeze(NUM_CHALLENGE_BITS)?;
 
     // fold the instance using `r` and `comm_T`
     U1.fold_mut(U2, &comm_T, &r);
@@ -141,7 +141,7 @@ impl<E: Engine> NIFS<E> {
     U2: &R1CSInstance<E>,
   ) -> Result<RelaxedR1CSInstance<E>, NovaError> {
     // initialize a new RO
-    let mut ro = E::RO::new(ro_consts.clone(), NUM_FE_FOR_RO);
+    let mut ro = E::RO::new(ro_consts.clone());
 
     // append the digest of pp to the transcript
     ro.absorb(scalar_as_base::<E>(*pp_digest));
@@ -155,7 +155,7 @@ impl<E: Engine> NIFS<E> {
     comm_T.absorb_in_ro(&mut ro);
 
     // compute a challenge from the RO
-    let r = ro.squeeze(NUM_CHALLENGE_BITS);
+    let r = ro.squeeze(NUM_CHALLENGE_BITS)?;
 
     // fold the instance using `r` and `comm_T`
     let U = U1.fold(U2, &comm_T, &r);
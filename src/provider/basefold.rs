@@ -0,0 +1,566 @@
+//! This module implements `PCSEngineTrait` with Basefold, a polynomial commitment scheme that
+//! commits to a multilinear polynomial with the Merkle root of a Reed-Solomon codeword of its
+//! coefficients, and proves an evaluation by interleaving a sum-check with the FRI folding of that
+//! codeword. Its commitments are hashes, so they cannot be folded: it serves SNARKs that prove
+//! circuits directly, such as `spartan::pcs_snark::R1CSSNARK`.
+//!
+//! Codewords are evaluations over a multiplicative subgroup of the scalar field, which must have a
+//! large enough two-adic subgroup: this is the case for the scalar fields of Pallas, Vesta and
+//! BN254, but not for those of Grumpkin, secp256k1 and secq256k1.
+use crate::{
+  errors::{NovaError, PCSError},
+  spartan::polys::{
+    eq::EqPolynomial,
+    multilinear::MultilinearPolynomial,
+    univariate::{CompressedUniPoly, UniPoly},
+  },
+  traits::{pcs::PCSEngineTrait, Engine, Group, TranscriptEngineTrait, TranscriptReprTrait},
+};
+use core::marker::PhantomData;
+use ff::{Field, PrimeField};
+use itertools::Itertools as _;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Keccak256};
+
+/// The logarithm of the inverse of the rate of the code
+const LOG_BLOWUP: usize = 2;
+
+/// The number of positions at which the folding of codewords is checked, which gives about 128
+/// bits of security with a rate of 1/4 under the usual conjecture on the proximity gaps of
+/// Reed-Solomon codes
+const NUM_QUERIES: usize = 64;
+
+type Digest = [u8; 32];
+
+/// A type that holds the commitment key; Basefold needs no trusted setup, so it only bounds the
+/// number of variables of the committed polynomials
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommitmentKey {
+  max_num_vars: usize,
+}
+
+/// A type that holds the verifier key
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifierKey {
+  max_num_vars: usize,
+}
+
+/// A commitment to a polynomial, i.e. the Merkle root of the codeword of its coefficients
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitment {
+  root: Digest,
+}
+
+impl<G: Group> TranscriptReprTrait<G> for Commitment {
+  fn to_transcript_bytes(&self) -> Vec<u8> {
+    self.root.to_vec()
+  }
+}
+
+/// The entries of a codeword at a queried position and at its opposite in the domain, along with
+/// the Merkle path of the leaf that holds them
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+struct LayerOpening<E: Engine> {
+  pair: [E::Scalar; 2],
+  path: Vec<Digest>,
+}
+
+/// An evaluation argument
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EvaluationArgument<E: Engine> {
+  comms: Vec<Commitment>,
+  sc_polys: Vec<CompressedUniPoly<E::Scalar>>,
+  final_value: E::Scalar,
+  queries: Vec<Vec<LayerOpening<E>>>,
+}
+
+/// Provides an implementation of a polynomial commitment scheme using Basefold
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BasefoldEngine<E: Engine> {
+  _p: PhantomData<E>,
+}
+
+/// A Merkle tree whose leaves each hold the entries of a codeword at a position `j` of the first
+/// half of the domain and at its opposite `j + N/2`, which are the entries folded together
+struct MerkleTree {
+  layers: Vec<Vec<Digest>>,
+}
+
+fn hash_leaf<F: PrimeField>(a: &F, b: &F) -> Digest {
+  let mut hasher = Keccak256::new();
+  hasher.update(a.to_repr().as_ref());
+  hasher.update(b.to_repr().as_ref());
+  hasher.finalize().into()
+}
+
+fn hash_node(left: &Digest, right: &Digest) -> Digest {
+  let mut hasher = Keccak256::new();
+  hasher.update(left);
+  hasher.update(right);
+  hasher.finalize().into()
+}
+
+impl MerkleTree {
+  fn new<F: PrimeField>(codeword: &[F]) -> Self {
+    let half = codeword.len() / 2;
+    let mut layers = vec![(0..half)
+      .into_par_iter()
+      .map(|j| hash_leaf(&codeword[j], &codeword[j + half]))
+      .collect::<Vec<_>>()];
+    while layers.last().unwrap().len() > 1 {
+      let layer = layers
+        .last()
+        .unwrap()
+        .par_chunks(2)
+        .map(|pair| hash_node(&pair[0], &pair[1]))
+        .collect();
+      layers.push(layer);
+    }
+    MerkleTree { layers }
+  }
+
+  fn root(&self) -> Digest {
+    self.layers.last().unwrap()[0]
+  }
+
+  /// Returns the siblings of the path from the leaf `j` to the root
+  fn open(&self, mut j: usize) -> Vec<Digest> {
+    let mut path = Vec::with_capacity(self.layers.len() - 1);
+    for layer in &self.layers[..self.layers.len() - 1] {
+      path.push(layer[j ^ 1]);
+      j /= 2;
+    }
+    path
+  }
+}
+
+fn verify_path(root: &Digest, leaf: Digest, mut j: usize, path: &[Digest]) -> bool {
+  let node = path.iter().fold(leaf, |node, sibling| {
+    let parent = if j % 2 == 0 {
+      hash_node(&node, sibling)
+    } else {
+      hash_node(sibling, &node)
+    };
+    j /= 2;
+    parent
+  });
+  node == *root
+}
+
+fn bit_reverse(k: usize, log_n: usize) -> usize {
+  if log_n == 0 {
+    0
+  } else {
+    k.reverse_bits() >> (usize::BITS as usize - log_n)
+  }
+}
+
+/// Returns a generator of the subgroup of order `2^log_n`
+fn root_of_unity<F: PrimeField>(log_n: usize) -> Result<F, NovaError> {
+  let s = F::S as usize;
+  if log_n > s {
+    return Err(PCSError::LengthError.into());
+  }
+  Ok((log_n..s).fold(F::ROOT_OF_UNITY, |omega, _| omega.square()))
+}
+
+/// Evaluates in place the polynomial with coefficients `a` at the powers of `omega`, whose order
+/// must be the length of `a`
+fn ntt<F: PrimeField>(a: &mut [F], omega: &F) {
+  let n = a.len();
+  let log_n = n.trailing_zeros() as usize;
+  for k in 0..n {
+    let rk = bit_reverse(k, log_n);
+    if k < rk {
+      a.swap(k, rk);
+    }
+  }
+
+  let mut m = 1;
+  while m < n {
+    let omega_m = omega.pow_vartime([(n / (2 * m)) as u64]);
+    a.par_chunks_mut(2 * m).for_each(|chunk| {
+      let (lo, hi) = chunk.split_at_mut(m);
+      let mut w = F::ONE;
+      for (u, v) in lo.iter_mut().zip_eq(hi.iter_mut()) {
+        let t = w * *v;
+        *v = *u - t;
+        *u += t;
+        w *= omega_m;
+      }
+    });
+    m *= 2;
+  }
+}
+
+/// Returns the codeword of a multilinear polynomial given by its evaluations.
+///
+/// The polynomial's coefficients in the monomial basis are those of a univariate polynomial
+/// `P(Y) = Σᵢ cᵢ Yⁱ`, where bit `j` of `i` stands for the variable `x_j`, so that splitting `P`
+/// into its even and odd parts splits the polynomial on `x_0`, the variable bound first by the
+/// sum-check. The codeword is the evaluation of `P` over a domain `2^LOG_BLOWUP` times larger than
+/// its number of coefficients.
+fn encode<F: PrimeField>(poly: &[F]) -> Result<Vec<F>, NovaError> {
+  let n = poly.len().trailing_zeros() as usize;
+
+  // the Möbius transform turns evaluations into coefficients, with x_0 the most significant bit
+  // of their index, which we then reverse
+  let mut coeffs = poly.to_vec();
+  for b in 0..n {
+    let step = 1 << b;
+    coeffs.par_chunks_mut(2 * step).for_each(|chunk| {
+      let (lo, hi) = chunk.split_at_mut(step);
+      for (l, h) in lo.iter().zip_eq(hi.iter_mut()) {
+        *h -= l;
+      }
+    });
+  }
+  let mut codeword = (0..poly.len())
+    .map(|i| coeffs[bit_reverse(i, n)])
+    .collect::<Vec<_>>();
+
+  codeword.resize(poly.len() << LOG_BLOWUP, F::ZERO);
+  ntt(&mut codeword, &root_of_unity(n + LOG_BLOWUP)?);
+  Ok(codeword)
+}
+
+/// Folds the entries `a = P(w)` and `b = P(-w)` of a codeword into the evaluation at `w²` of
+/// `P_even + r·P_odd`, given `w_inv = 1/w`
+fn fold_pair<F: PrimeField>(a: &F, b: &F, r: &F, w_inv: &F) -> F {
+  (*a + b + *r * (*a - b) * w_inv) * F::TWO_INV
+}
+
+/// Folds a codeword over the domain generated by `1/omega_inv` into a codeword of half its length
+fn fold<F: PrimeField>(codeword: &[F], r: &F, omega_inv: &F) -> Vec<F> {
+  let half = codeword.len() / 2;
+  let powers = (0..half)
+    .scan(F::ONE, |w, _| {
+      let w_j = *w;
+      *w *= omega_inv;
+      Some(w_j)
+    })
+    .collect::<Vec<_>>();
+  (0..half)
+    .into_par_iter()
+    .map(|j| fold_pair(&codeword[j], &codeword[j + half], r, &powers[j]))
+    .collect()
+}
+
+/// Derives the leaves of the first codeword at which the folding is checked
+fn squeeze_queries<E: Engine>(
+  transcript: &mut E::TE,
+  num_leaves: usize,
+) -> Result<Vec<usize>, NovaError> {
+  (0..NUM_QUERIES)
+    .map(|_| {
+      let c = transcript.squeeze(b"q")?;
+      let bytes = c.to_repr();
+      let q = u64::from_le_bytes(bytes.as_ref()[..8].try_into().unwrap());
+      Ok((q % num_leaves as u64) as usize)
+    })
+    .collect()
+}
+
+fn check_num_vars(num_vars: usize, max_num_vars: usize) -> Result<(), NovaError> {
+  if num_vars == 0 || num_vars > max_num_vars {
+    return Err(PCSError::LengthError.into());
+  }
+  Ok(())
+}
+
+impl<E: Engine> PCSEngineTrait<E> for BasefoldEngine<E> {
+  type CommitmentKey = CommitmentKey;
+  type VerifierKey = VerifierKey;
+  type Commitment = Commitment;
+  type EvaluationArgument = EvaluationArgument<E>;
+
+  fn setup(
+    _label: &'static [u8],
+    n: usize,
+  ) -> Result<(Self::CommitmentKey, Self::VerifierKey), NovaError> {
+    let max_num_vars = n.next_power_of_two().trailing_zeros() as usize;
+    // the field must hold the domain of the largest codeword
+    root_of_unity::<E::Scalar>(max_num_vars + LOG_BLOWUP)?;
+
+    Ok((CommitmentKey { max_num_vars }, VerifierKey { max_num_vars }))
+  }
+
+  fn commit(ck: &Self::CommitmentKey, poly: &[E::Scalar]) -> Result<Self::Commitment, NovaError> {
+    if !poly.len().is_power_of_two() {
+      return Err(PCSError::LengthError.into());
+    }
+    check_num_vars(poly.len().trailing_zeros() as usize, ck.max_num_vars)?;
+
+    let codeword = encode(poly)?;
+    Ok(Commitment {
+      root: MerkleTree::new(&codeword).root(),
+    })
+  }
+
+  fn prove(
+    ck: &Self::CommitmentKey,
+    transcript: &mut E::TE,
+    _comm: &Self::Commitment,
+    poly: &[E::Scalar],
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+  ) -> Result<Self::EvaluationArgument, NovaError> {
+    let num_vars = point.len();
+    check_num_vars(num_vars, ck.max_num_vars)?;
+    if poly.len() != 1 << num_vars {
+      return Err(PCSError::LengthError.into());
+    }
+
+    // the codewords and Merkle trees of the first and the folded polynomials
+    let codeword = encode(poly)?;
+    let tree = MerkleTree::new(&codeword);
+    let mut layers = vec![(codeword, tree)];
+    let mut omega_inv = root_of_unity::<E::Scalar>(num_vars + LOG_BLOWUP)?
+      .invert()
+      .unwrap();
+
+    // a sum-check of poly(x) * eq(point, x), whose challenges fold the codeword
+    let mut poly = MultilinearPolynomial::new(poly.to_vec());
+    let mut eq = MultilinearPolynomial::new(EqPolynomial::evals_from_points(point));
+    let mut claim = *eval;
+    let mut comms = Vec::with_capacity(num_vars - 1);
+    let mut sc_polys = Vec::with_capacity(num_vars);
+    for k in 0..num_vars {
+      let half = poly.len() / 2;
+      let (eval_0, eval_2) = (0..half)
+        .into_par_iter()
+        .map(|j| {
+          let (p_lo, p_hi, eq_lo, eq_hi) = (poly[j], poly[j + half], eq[j], eq[j + half]);
+          (
+            p_lo * eq_lo,
+            (p_hi.double() - p_lo) * (eq_hi.double() - eq_lo),
+          )
+        })
+        .reduce(
+          || (E::Scalar::ZERO, E::Scalar::ZERO),
+          |a, b| (a.0 + b.0, a.1 + b.1),
+        );
+      let sc_poly = UniPoly::from_evals(&[eval_0, claim - eval_0, eval_2]);
+
+      transcript.absorb(b"p", &sc_poly);
+      let r_k = transcript.squeeze(b"c")?;
+      claim = sc_poly.evaluate(&r_k);
+      sc_polys.push(sc_poly.compress());
+
+      rayon::join(
+        || poly.bind_poly_var_top(&r_k),
+        || eq.bind_poly_var_top(&r_k),
+      );
+      let folded = fold(&layers.last().unwrap().0, &r_k, &omega_inv);
+      omega_inv = omega_inv.square();
+
+      // the last folded codeword is constant, and is sent as a value instead
+      if k + 1 < num_vars {
+        let tree = MerkleTree::new(&folded);
+        let comm = Commitment { root: tree.root() };
+        transcript.absorb(b"r", &comm);
+        comms.push(comm);
+        layers.push((folded, tree));
+      }
+    }
+
+    let final_value = poly[0];
+    transcript.absorb(b"f", &final_value);
+
+    // open the codewords at the queried positions
+    let queries = squeeze_queries::<E>(transcript, layers[0].0.len() / 2)?
+      .into_iter()
+      .map(|q| {
+        layers
+          .iter()
+          .map(|(codeword, tree)| {
+            let half = codeword.len() / 2;
+            let j = q % half;
+            LayerOpening {
+              pair: [codeword[j], codeword[j + half]],
+              path: tree.open(j),
+            }
+          })
+          .collect()
+      })
+      .collect();
+
+    Ok(EvaluationArgument {
+      comms,
+      sc_polys,
+      final_value,
+      queries,
+    })
+  }
+
+  fn verify(
+    vk: &Self::VerifierKey,
+    transcript: &mut E::TE,
+    comm: &Self::Commitment,
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+    arg: &Self::EvaluationArgument,
+  ) -> Result<(), NovaError> {
+    let num_vars = point.len();
+    check_num_vars(num_vars, vk.max_num_vars)?;
+    if arg.comms.len() != num_vars - 1
+      || arg.sc_polys.len() != num_vars
+      || arg.queries.len() != NUM_QUERIES
+    {
+      return Err(PCSError::LengthError.into());
+    }
+
+    // replay the sum-check, and check its final claim against the value of the last folding
+    let mut claim = *eval;
+    let mut r = Vec::with_capacity(num_vars);
+    for (k, sc_poly) in arg.sc_polys.iter().enumerate() {
+      let sc_poly = sc_poly.decompress(&claim);
+      if sc_poly.degree() != 2 {
+        return Err(PCSError::InvalidBasefold.into());
+      }
+
+      transcript.absorb(b"p", &sc_poly);
+      let r_k = transcript.squeeze(b"c")?;
+      claim = sc_poly.evaluate(&r_k);
+      r.push(r_k);
+
+      if k + 1 < num_vars {
+        transcript.absorb(b"r", &arg.comms[k]);
+      }
+    }
+    transcript.absorb(b"f", &arg.final_value);
+
+    if claim != arg.final_value * EqPolynomial::new(point.to_vec()).evaluate(&r) {
+      return Err(PCSError::InvalidBasefold.into());
+    }
+
+    // check that the openings are in the committed codewords, and fold into one another
+    let roots = [comm].into_iter().chain(&arg.comms).collect::<Vec<_>>();
+    let omegas_inv = (0..num_vars)
+      .scan(
+        root_of_unity::<E::Scalar>(num_vars + LOG_BLOWUP)?
+          .invert()
+          .unwrap(),
+        |omega_inv, _| {
+          let omega_k = *omega_inv;
+          *omega_inv = omega_inv.square();
+          Some(omega_k)
+        },
+      )
+      .collect::<Vec<_>>();
+
+    let num_leaves = 1 << (num_vars + LOG_BLOWUP - 1);
+    let queries = squeeze_queries::<E>(transcript, num_leaves)?;
+    for (q, openings) in queries.into_iter().zip_eq(&arg.queries) {
+      if openings.len() != num_vars {
+        return Err(PCSError::LengthError.into());
+      }
+
+      for (k, opening) in openings.iter().enumerate() {
+        let half = num_leaves >> k;
+        let j = q % half;
+        let LayerOpening { pair: [a, b], path } = opening;
+        if path.len() != half.trailing_zeros() as usize
+          || !verify_path(&roots[k].root, hash_leaf(a, b), j, path)
+        {
+          return Err(PCSError::InvalidBasefold.into());
+        }
+
+        // the folded entry is at position j of the next codeword, whose leaves hold its halves
+        let folded = fold_pair(a, b, &r[k], &omegas_inv[k].pow_vartime([j as u64]));
+        let expected = if k + 1 < num_vars {
+          openings[k + 1].pair[usize::from(j >= half / 2)]
+        } else {
+          arg.final_value
+        };
+        if folded != expected {
+          return Err(PCSError::InvalidBasefold.into());
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::provider::{Bn256Engine, PallasEngine, Secp256k1Engine};
+  use rand::rngs::OsRng;
+
+  fn test_basefold_with<E: Engine>(num_vars: usize) {
+    let n = 1 << num_vars;
+    let poly = (0..n).map(|_| E::Scalar::random(OsRng)).collect::<Vec<_>>();
+    let point = (0..num_vars)
+      .map(|_| E::Scalar::random(OsRng))
+      .collect::<Vec<_>>();
+    let eval = MultilinearPolynomial::evaluate_with(&poly, &point);
+
+    let (ck, vk) = BasefoldEngine::<E>::setup(b"test", n).unwrap();
+    let comm = BasefoldEngine::<E>::commit(&ck, &poly).unwrap();
+    let arg =
+      BasefoldEngine::<E>::prove(&ck, &mut E::TE::new(b"test"), &comm, &poly, &point, &eval)
+        .unwrap();
+
+    let verify = |comm: &Commitment, eval: &E::Scalar, arg: &EvaluationArgument<E>| {
+      BasefoldEngine::<E>::verify(&vk, &mut E::TE::new(b"test"), comm, &point, eval, arg)
+    };
+    assert!(verify(&comm, &eval, &arg).is_ok());
+
+    // a wrong evaluation is rejected
+    assert!(verify(&comm, &(eval + E::Scalar::ONE), &arg).is_err());
+
+    // as is the evaluation of another polynomial
+    let mut other = poly.clone();
+    other[0] += E::Scalar::ONE;
+    let other_comm = BasefoldEngine::<E>::commit(&ck, &other).unwrap();
+    assert!(verify(&other_comm, &eval, &arg).is_err());
+
+    // or an argument with a tampered final value or opening
+    let mut bad_final = arg.clone();
+    bad_final.final_value += E::Scalar::ONE;
+    assert!(verify(&comm, &eval, &bad_final).is_err());
+
+    let mut bad_opening = arg.clone();
+    bad_opening.queries[0][num_vars - 1].pair[0] += E::Scalar::ONE;
+    assert!(verify(&comm, &eval, &bad_opening).is_err());
+  }
+
+  #[test]
+  fn test_basefold() {
+    for num_vars in [1, 2, 5, 8] {
+      test_basefold_with::<PallasEngine>(num_vars);
+      test_basefold_with::<Bn256Engine>(num_vars);
+    }
+  }
+
+  #[test]
+  fn test_encode() {
+    type F = <PallasEngine as Engine>::Scalar;
+
+    // the codeword holds the evaluations of P(Y) = Σᵢ cᵢ Yⁱ, with bit j of i standing for x_j,
+    // and x_0 the most significant bit of the index of the evaluations
+    let poly = (0..8).map(|_| F::random(OsRng)).collect::<Vec<_>>();
+    let codeword = encode(&poly).unwrap();
+    let omega = root_of_unity::<F>(3 + LOG_BLOWUP).unwrap();
+    for (k, entry) in codeword.iter().enumerate() {
+      let y = omega.pow_vartime([k as u64]);
+      let point = [y, y.square(), y.square().square()];
+      assert_eq!(
+        *entry,
+        MultilinearPolynomial::evaluate_with(&poly, &point),
+        "entry {k}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_basefold_unsupported_field() {
+    // the scalar field of secp256k1 has a two-adic subgroup of order 2^6 only
+    assert!(BasefoldEngine::<Secp256k1Engine>::setup(b"test", 1 << 10).is_err());
+  }
+}
@@ -2,7 +2,7 @@
 use crate::{
   impl_traits,
   provider::{
-    msm::cpu_best_msm,
+    msm::{cpu_best_msm, cpu_best_msm_small},
     traits::{CompressedGroup, DlogGroup},
   },
   traits::{Group, PrimeFieldExt, TranscriptReprTrait},
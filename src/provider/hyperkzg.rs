@@ -0,0 +1,550 @@
+//! Non-hiding HyperKZG scheme for multilinear polynomials.
+//!
+//! A multilinear polynomial is committed as the KZG commitment of the univariate polynomial whose
+//! coefficients are its evaluations over the hypercube, exactly as with Zeromorph. To open it at
+//! a point, the prover folds it one variable at a time, as in Gemini, commits to the folded
+//! polynomials, and proves their evaluations at r, -r and r² with a batched univariate KZG
+//! opening. The verifier checks these evaluations are consistent with the folding, which leaves
+//! it with two pairings, whatever the number of variables.
+use crate::{
+  errors::{NovaError, PCSError},
+  provider::{
+    kzg_commitment::KZGCommitmentEngine,
+    non_hiding_kzg::{
+      UVKZGCommitment, UVKZGEvaluation, UVKZGPoly, UVKZGProof, UVKZGProverKey, UVKZGVerifierKey,
+      UVUniversalKZGParam, UVKZGPCS,
+    },
+    traits::DlogGroup,
+  },
+  spartan::{polys::multilinear::MultilinearPolynomial, BatchEvalArgument},
+  traits::{
+    commitment::Len, evaluation::EvaluationEngineTrait, Engine as NovaEngine, Group,
+    TranscriptEngineTrait, TranscriptReprTrait,
+  },
+  Commitment,
+};
+use ff::{Field, PrimeFieldBits};
+use group::Curve;
+use itertools::Itertools as _;
+use pairing::{Engine, MultiMillerLoop};
+use rayon::prelude::*;
+use ref_cast::RefCast;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{iter, marker::PhantomData, slice};
+
+/// Proofs
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+  serialize = "E::G1Affine: Serialize, E::Fr: Serialize",
+  deserialize = "E::G1Affine: Deserialize<'de>, E::Fr: Deserialize<'de>"
+))]
+pub struct HyperKZGProof<E: Engine> {
+  /// Commitments to the polynomials obtained by folding the committed one
+  pub com: Vec<UVKZGCommitment<E>>,
+  /// Opening proofs of the batched polynomial at r, -r and r²
+  pub w: Vec<E::G1Affine>,
+  /// Evaluations of the committed and folded polynomials at r, -r and r²
+  pub v: Vec<Vec<E::Fr>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+/// HyperKZG Polynomial Commitment Scheme on multilinear polynomials.
+/// Note: this is non-hiding, which is why we will implement the EvaluationEngineTrait on this token struct,
+/// as we will have several impls for the trait pegged on the same instance of a pairing::Engine.
+#[allow(clippy::upper_case_acronyms)]
+pub struct HyperKZGPCS<E, NE> {
+  #[doc(hidden)]
+  phantom: PhantomData<(E, NE)>,
+}
+
+impl<E: MultiMillerLoop, NE: NovaEngine<GE = E::G1, Scalar = E::Fr>> HyperKZGPCS<E, NE>
+where
+  E::G1: DlogGroup<PreprocessedGroupElement = E::G1Affine, Scalar = E::Fr>,
+  // Note: due to the move of the bound TranscriptReprTrait<G> on G::Base from Group to Engine
+  <E::G1 as Group>::Base: TranscriptReprTrait<E::G1>,
+{
+  const fn protocol_name() -> &'static [u8] {
+    b"HyperKZG"
+  }
+
+  /// Generate a commitment for a polynomial
+  /// Note that the scheme is not hidding
+  pub fn commit(
+    pk: &UVKZGProverKey<E>,
+    poly: &MultilinearPolynomial<E::Fr>,
+  ) -> Result<UVKZGCommitment<E>, NovaError> {
+    if pk.powers_of_g.len() < poly.Z.len() {
+      return Err(PCSError::LengthError.into());
+    }
+    UVKZGPCS::commit(pk, UVKZGPoly::ref_cast(&poly.Z))
+  }
+
+  /// On input a polynomial `poly` and a point `point`, outputs a proof of its evaluation at
+  /// `point`.
+  pub fn open(
+    pk: &UVKZGProverKey<E>,
+    poly: &MultilinearPolynomial<E::Fr>,
+    point: &[E::Fr],
+    transcript: &mut impl TranscriptEngineTrait<NE>,
+  ) -> Result<HyperKZGProof<E>, NovaError> {
+    transcript.dom_sep(Self::protocol_name());
+
+    let ell = point.len();
+    if ell == 0 || poly.Z.len() != 1 << ell || pk.powers_of_g.len() < poly.Z.len() {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    // P_0 is the committed polynomial, and P_{i+1} is P_i with its bottom variable bound to the
+    // matching coordinate of the point, so that P_{ell} would be the evaluation
+    let mut polys = Vec::with_capacity(ell);
+    let mut folded = poly.clone();
+    polys.push(poly.Z.clone());
+    for x in point[1..].iter().rev() {
+      folded.bind_poly_var_bot(x);
+      polys.push(folded.Z.clone());
+    }
+    drop(folded);
+
+    // Compute and absorb the commitments to P_1, ..., P_{ell-1}
+    let com = polys[1..]
+      .par_iter()
+      .map(|p| UVKZGPCS::commit(pk, UVKZGPoly::ref_cast(p)))
+      .collect::<Result<Vec<_>, _>>()?;
+    com.iter().for_each(|c| transcript.absorb(b"c", c));
+
+    // Get challenge r, and evaluate each P_i at r, -r and r²
+    let r = transcript.squeeze(b"r")?;
+    let u = [r, -r, r.square()];
+    let v = u
+      .iter()
+      .map(|u_j| {
+        polys
+          .par_iter()
+          .map(|p| UVKZGPoly::ref_cast(p).evaluate(u_j))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+    v.iter()
+      .for_each(|v_j| transcript.absorb(b"v", &v_j.as_slice()));
+
+    // Get challenge q, and batch the polynomials as B = Σᵢ qⁱ⋅P_i
+    let q = transcript.squeeze(b"q")?;
+    let mut B = vec![E::Fr::ZERO; poly.Z.len()];
+    for (p, q_i) in polys
+      .iter()
+      .zip_eq(iter::successors(Some(E::Fr::ONE), |q_i| Some(*q_i * q)))
+    {
+      B[..p.len()]
+        .par_iter_mut()
+        .zip_eq(p.par_iter())
+        .for_each(|(b, p)| *b += q_i * p);
+    }
+    drop(polys);
+    let B = UVKZGPoly::new(B);
+
+    // Compute and absorb the opening proofs of B at r, -r and r²
+    let w = u
+      .par_iter()
+      .map(|u_j| UVKZGPCS::open(pk, &B, u_j).map(|(proof, _)| proof.proof))
+      .collect::<Result<Vec<_>, _>>()?;
+    w.iter()
+      .for_each(|w_j| transcript.absorb(b"w", &UVKZGCommitment::<E>(*w_j)));
+
+    Ok(HyperKZGProof { com, w, v })
+  }
+
+  /// Verifies that `evaluation` is the evaluation at `point` of the polynomial committed inside
+  /// `comm`.
+  pub fn verify(
+    vk: &UVKZGVerifierKey<E>,
+    transcript: &mut impl TranscriptEngineTrait<NE>,
+    comm: &UVKZGCommitment<E>,
+    point: &[E::Fr],
+    evaluation: &E::Fr,
+    proof: &HyperKZGProof<E>,
+  ) -> Result<bool, NovaError> {
+    Self::batch_verify(
+      vk,
+      slice::from_mut(transcript),
+      slice::from_ref(comm),
+      &[point.to_vec()],
+      slice::from_ref(evaluation),
+      &[proof],
+    )
+  }
+
+  /// Verifies several evaluation proofs, each with its own transcript, by checking all their
+  /// batched KZG openings at once, which costs two pairings in total.
+  pub fn batch_verify(
+    vk: &UVKZGVerifierKey<E>,
+    transcripts: &mut [impl TranscriptEngineTrait<NE>],
+    comms: &[UVKZGCommitment<E>],
+    points: &[Vec<E::Fr>],
+    evaluations: &[E::Fr],
+    proofs: &[&HyperKZGProof<E>],
+  ) -> Result<bool, NovaError> {
+    let n = transcripts.len();
+    if comms.len() != n || points.len() != n || evaluations.len() != n || proofs.len() != n {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    let mut kzg_comms = Vec::with_capacity(3 * n);
+    let mut kzg_points = Vec::with_capacity(3 * n);
+    let mut kzg_proofs = Vec::with_capacity(3 * n);
+    let mut kzg_evaluations = Vec::with_capacity(3 * n);
+    for (i, transcript) in transcripts.iter_mut().enumerate() {
+      let Some((comm_B, u, evals_B)) = Self::verify_reduce(
+        transcript,
+        &comms[i],
+        &points[i],
+        &evaluations[i],
+        proofs[i],
+      )?
+      else {
+        return Ok(false);
+      };
+      for ((u_j, w_j), eval_j) in u.into_iter().zip_eq(&proofs[i].w).zip_eq(evals_B) {
+        kzg_comms.push(UVKZGCommitment(comm_B));
+        kzg_points.push(u_j);
+        kzg_proofs.push(UVKZGProof { proof: *w_j });
+        kzg_evaluations.push(UVKZGEvaluation(eval_j));
+      }
+    }
+
+    UVKZGPCS::<E>::batch_verify(vk, &kzg_comms, &kzg_points, &kzg_proofs, &kzg_evaluations)
+  }
+
+  /// Replays the transcript of an evaluation proof and checks that the evaluations of the folded
+  /// polynomials are consistent with each other and with `evaluation`. Returns the commitment to
+  /// the batched polynomial B, the points r, -r and r², and the evaluations of B at them, which
+  /// remain to be checked with KZG openings, or `None` if the evaluations are inconsistent.
+  fn verify_reduce(
+    transcript: &mut impl TranscriptEngineTrait<NE>,
+    comm: &UVKZGCommitment<E>,
+    point: &[E::Fr],
+    evaluation: &E::Fr,
+    proof: &HyperKZGProof<E>,
+  ) -> Result<Option<(E::G1Affine, [E::Fr; 3], [E::Fr; 3])>, NovaError> {
+    transcript.dom_sep(Self::protocol_name());
+
+    let ell = point.len();
+    if ell == 0
+      || proof.com.len() != ell - 1
+      || proof.w.len() != 3
+      || proof.v.len() != 3
+      || proof.v.iter().any(|v_j| v_j.len() != ell)
+    {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    // Receive the commitments to P_1, ..., P_{ell-1}, and get challenge r
+    proof.com.iter().for_each(|c| transcript.absorb(b"c", c));
+    let r = transcript.squeeze(b"r")?;
+    if r == E::Fr::ZERO {
+      return Ok(None);
+    }
+    let u = [r, -r, r.square()];
+
+    // Receive the evaluations, get challenge q, and receive the opening proofs
+    proof
+      .v
+      .iter()
+      .for_each(|v_j| transcript.absorb(b"v", &v_j.as_slice()));
+    let q = transcript.squeeze(b"q")?;
+    proof
+      .w
+      .iter()
+      .for_each(|w_j| transcript.absorb(b"w", &UVKZGCommitment::<E>(*w_j)));
+
+    // P_{i+1}(r²) = (1 - x)⋅(P_i(r) + P_i(-r)) / 2 + x⋅(P_i(r) - P_i(-r)) / 2r, where x is the
+    // coordinate of the point bound in P_{i+1}, and P_{ell} is the evaluation
+    let (v_r, v_minus_r, v_r2) = (&proof.v[0], &proof.v[1], &proof.v[2]);
+    let two_r = r.double();
+    let consistent = (0..ell).all(|i| {
+      let x = point[ell - i - 1];
+      let next = if i + 1 < ell {
+        v_r2[i + 1]
+      } else {
+        *evaluation
+      };
+      two_r * next == r * (E::Fr::ONE - x) * (v_r[i] + v_minus_r[i]) + x * (v_r[i] - v_minus_r[i])
+    });
+    if !consistent {
+      return Ok(None);
+    }
+
+    // B = Σᵢ qⁱ⋅P_i, hence its commitment and its evaluations are the same combinations
+    let powers_of_q = iter::successors(Some(E::Fr::ONE), |q_i| Some(*q_i * q))
+      .take(ell)
+      .collect::<Vec<_>>();
+    let bases = iter::once(comm.0)
+      .chain(proof.com.iter().map(|c| c.0))
+      .collect::<Vec<_>>();
+    let comm_B = <E::G1 as DlogGroup>::vartime_multiscalar_mul(&powers_of_q, &bases).to_affine();
+    let evals_B = [v_r, v_minus_r, v_r2].map(|v_j| {
+      v_j
+        .iter()
+        .zip_eq(powers_of_q.iter())
+        .map(|(v, q_i)| *v * q_i)
+        .sum::<E::Fr>()
+    });
+
+    Ok(Some((comm_B, u, evals_B)))
+  }
+}
+
+impl<E: MultiMillerLoop, NE: NovaEngine<GE = E::G1, Scalar = E::Fr, CE = KZGCommitmentEngine<E>>>
+  EvaluationEngineTrait<NE> for HyperKZGPCS<E, NE>
+where
+  E::G1: DlogGroup<PreprocessedGroupElement = E::G1Affine, Scalar = E::Fr>,
+  E::G1Affine: Serialize + DeserializeOwned,
+  E::G2Affine: Serialize + DeserializeOwned,
+  <E::G1 as Group>::Base: TranscriptReprTrait<E::G1>, // Note: due to the move of the bound TranscriptReprTrait<G> on G::Base from Group to Engine
+  E::Fr: PrimeFieldBits, // TODO due to use of gen_srs_for_testing, make optional
+{
+  type ProverKey = UVKZGProverKey<E>;
+  type VerifierKey = UVKZGVerifierKey<E>;
+
+  type EvaluationArgument = HyperKZGProof<E>;
+  type BatchEvaluationArgument = BatchEvalArgument<NE, HyperKZGProof<E>>;
+
+  fn setup(ck: &UVUniversalKZGParam<E>) -> (Self::ProverKey, Self::VerifierKey) {
+    ck.trim(ck.length() - 1)
+  }
+
+  fn prove(
+    _ck: &UVUniversalKZGParam<E>,
+    pk: &Self::ProverKey,
+    transcript: &mut NE::TE,
+    _comm: &Commitment<NE>,
+    poly: &[NE::Scalar],
+    point: &[NE::Scalar],
+    _eval: &NE::Scalar,
+  ) -> Result<Self::EvaluationArgument, NovaError> {
+    let polynomial = MultilinearPolynomial::new(poly.to_vec());
+
+    Self::open(pk, &polynomial, point, transcript)
+  }
+
+  fn verify(
+    vk: &Self::VerifierKey,
+    transcript: &mut NE::TE,
+    comm: &Commitment<NE>,
+    point: &[NE::Scalar],
+    eval: &NE::Scalar,
+    arg: &Self::EvaluationArgument,
+  ) -> Result<(), NovaError> {
+    let commitment = UVKZGCommitment::from(*comm);
+
+    if !Self::verify(vk, transcript, &commitment, point, eval, arg)? {
+      return Err(NovaError::UnSat);
+    }
+    Ok(())
+  }
+
+  fn prove_batch(
+    ck: &UVUniversalKZGParam<E>,
+    pk: &Self::ProverKey,
+    transcript: &mut NE::TE,
+    comms: &[Commitment<NE>],
+    polys: &[Vec<NE::Scalar>],
+    points: &[Vec<NE::Scalar>],
+    evals: &[NE::Scalar],
+  ) -> Result<Self::BatchEvaluationArgument, NovaError> {
+    BatchEvalArgument::prove::<Self>(ck, pk, transcript, comms, polys, points, evals)
+  }
+
+  fn verify_batch(
+    vk: &Self::VerifierKey,
+    transcript: &mut NE::TE,
+    comms: &[Commitment<NE>],
+    points: &[Vec<NE::Scalar>],
+    evals: &[NE::Scalar],
+    arg: &Self::BatchEvaluationArgument,
+  ) -> Result<(), NovaError> {
+    arg.verify::<Self>(vk, transcript, comms, points, evals)
+  }
+
+  fn batch_verify(
+    vk: &Self::VerifierKey,
+    transcripts: &mut [NE::TE],
+    comms: &[Commitment<NE>],
+    points: &[Vec<NE::Scalar>],
+    evals: &[NE::Scalar],
+    args: &[&Self::EvaluationArgument],
+  ) -> Result<(), NovaError> {
+    let commitments = comms
+      .iter()
+      .map(|comm| UVKZGCommitment::from(*comm))
+      .collect::<Vec<_>>();
+
+    if !Self::batch_verify(vk, transcripts, &commitments, points, evals, args)? {
+      return Err(NovaError::UnSat);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    provider::{keccak::Keccak256Transcript, non_hiding_zeromorph::ZMPCS, Bn256EngineZM},
+    traits::commitment::CommitmentEngineTrait,
+  };
+  use halo2curves::bn256::Bn256;
+  use rand::thread_rng;
+
+  type E = Bn256;
+  type NE = Bn256EngineZM;
+  type Fr = <NE as NovaEngine>::Scalar;
+
+  #[test]
+  fn test_commit_open_verify() {
+    let mut rng = thread_rng();
+    let max_vars = 8;
+    let ck = <NE as NovaEngine>::CE::setup(b"test", 1 << max_vars);
+    let (pk, vk) = HyperKZGPCS::<E, NE>::setup(&ck);
+
+    for num_vars in 1..=max_vars {
+      let poly = MultilinearPolynomial::<Fr>::random(num_vars, &mut rng);
+      let comm = HyperKZGPCS::<E, NE>::commit(&pk, &poly).unwrap();
+      let point = (0..num_vars)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+      let eval = poly.evaluate(&point);
+
+      let mut transcript_prover = Keccak256Transcript::<NE>::new(b"test");
+      let proof = HyperKZGPCS::open(&pk, &poly, &point, &mut transcript_prover).unwrap();
+
+      let mut transcript_verifier = Keccak256Transcript::<NE>::new(b"test");
+      assert!(
+        HyperKZGPCS::verify(&vk, &mut transcript_verifier, &comm, &point, &eval, &proof).unwrap()
+      );
+
+      // check both random oracles are synced, as expected
+      assert_eq!(
+        transcript_prover.squeeze(b"test").unwrap(),
+        transcript_verifier.squeeze(b"test").unwrap()
+      );
+
+      // a wrong evaluation is rejected
+      let mut transcript_verifier = Keccak256Transcript::<NE>::new(b"test");
+      assert!(!HyperKZGPCS::verify(
+        &vk,
+        &mut transcript_verifier,
+        &comm,
+        &point,
+        &(eval + Fr::ONE),
+        &proof
+      )
+      .unwrap());
+    }
+  }
+
+  #[test]
+  fn test_batch_verify() {
+    let mut rng = thread_rng();
+    let num_vars = 6;
+    let num_proofs = 4;
+    let ck = <NE as NovaEngine>::CE::setup(b"test", 1 << num_vars);
+    let (pk, vk) = HyperKZGPCS::<E, NE>::setup(&ck);
+
+    let mut comms = Vec::new();
+    let mut points = Vec::new();
+    let mut evals = Vec::new();
+    let mut proofs = Vec::new();
+    for _ in 0..num_proofs {
+      let poly = MultilinearPolynomial::<Fr>::random(num_vars, &mut rng);
+      let point = (0..num_vars)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+      let mut transcript = Keccak256Transcript::<NE>::new(b"test");
+      proofs.push(HyperKZGPCS::open(&pk, &poly, &point, &mut transcript).unwrap());
+      comms.push(HyperKZGPCS::<E, NE>::commit(&pk, &poly).unwrap());
+      evals.push(poly.evaluate(&point));
+      points.push(point);
+    }
+    let proofs = proofs.iter().collect::<Vec<_>>();
+
+    let mut transcripts = vec![Keccak256Transcript::<NE>::new(b"test"); num_proofs];
+    assert!(
+      HyperKZGPCS::batch_verify(&vk, &mut transcripts, &comms, &points, &evals, &proofs).unwrap()
+    );
+
+    // a single wrong evaluation makes the batch fail
+    evals[1] += Fr::ONE;
+    let mut transcripts = vec![Keccak256Transcript::<NE>::new(b"test"); num_proofs];
+    assert!(
+      !HyperKZGPCS::batch_verify(&vk, &mut transcripts, &comms, &points, &evals, &proofs).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_against_zeromorph() {
+    type HyperKZG = HyperKZGPCS<E, NE>;
+    type Zeromorph = ZMPCS<E, NE>;
+
+    let mut rng = thread_rng();
+    let num_vars = 7;
+    let ck = <NE as NovaEngine>::CE::setup(b"test", 1 << num_vars);
+    let (pk_hkzg, vk_hkzg) = HyperKZG::setup(&ck);
+    let (pk_zm, vk_zm) = Zeromorph::setup(&ck);
+
+    for _ in 0..3 {
+      // both schemes open the same commitments
+      let poly = MultilinearPolynomial::<Fr>::random(num_vars, &mut rng);
+      let comm = <NE as NovaEngine>::CE::commit(&ck, &poly.Z);
+      assert_eq!(
+        HyperKZG::commit(&pk_hkzg, &poly).unwrap().0,
+        UVKZGCommitment::<E>::from(comm).0
+      );
+      let point = (0..num_vars)
+        .map(|_| Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+      let eval = poly.evaluate(&point);
+
+      let arg_hkzg = HyperKZG::prove(
+        &ck,
+        &pk_hkzg,
+        &mut Keccak256Transcript::new(b"test"),
+        &comm,
+        &poly.Z,
+        &point,
+        &eval,
+      )
+      .unwrap();
+      let arg_zm = Zeromorph::prove(
+        &ck,
+        &pk_zm,
+        &mut Keccak256Transcript::new(b"test"),
+        &comm,
+        &poly.Z,
+        &point,
+        &eval,
+      )
+      .unwrap();
+
+      for eval in [eval, eval + Fr::ONE] {
+        let res_hkzg = <HyperKZG as EvaluationEngineTrait<NE>>::verify(
+          &vk_hkzg,
+          &mut Keccak256Transcript::new(b"test"),
+          &comm,
+          &point,
+          &eval,
+          &arg_hkzg,
+        );
+        let res_zm = <Zeromorph as EvaluationEngineTrait<NE>>::verify(
+          &vk_zm,
+          &mut Keccak256Transcript::new(b"test"),
+          &comm,
+          &point,
+          &eval,
+          &arg_zm,
+        );
+        assert_eq!(res_hkzg.is_ok(), res_zm.is_ok());
+        assert_eq!(res_hkzg.is_ok(), eval == poly.evaluate(&point));
+      }
+    }
+  }
+}
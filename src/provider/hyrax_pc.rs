@@ -0,0 +1,240 @@
+//! This module implements `PCSEngineTrait` with Hyrax, a polynomial commitment scheme that lays out
+//! the `N` evaluations of a multilinear polynomial as a matrix of about `√N` rows and columns, and
+//! commits to each row with Pedersen. The commitments to the rows combine into a commitment to any
+//! linear combination of them, so an evaluation reduces to an inner product argument over a single
+//! row, and the verifier only needs `√N` generators, where `ipa_pc` needs `N`.
+use crate::{
+  errors::{NovaError, PCSError},
+  provider::{
+    ipa_pc::{InnerProductArgument, InnerProductInstance, InnerProductWitness},
+    pedersen::CommitmentKeyExtTrait,
+    traits::DlogGroup,
+  },
+  spartan::polys::eq::EqPolynomial,
+  traits::{
+    commitment::{CommitmentEngineTrait, Len},
+    pcs::PCSEngineTrait,
+    Engine, TranscriptReprTrait,
+  },
+  Commitment, CommitmentKey, CE,
+};
+use itertools::Itertools as _;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// A type that holds the commitment key, with the generators of a row
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct HyraxCommitmentKey<E: Engine> {
+  ck: CommitmentKey<E>,
+  ck_s: CommitmentKey<E>,
+}
+
+/// A type that holds the verifier key, which also only holds the generators of a row
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct HyraxVerifierKey<E: Engine> {
+  ck_v: CommitmentKey<E>,
+  ck_s: CommitmentKey<E>,
+}
+
+/// A commitment to a polynomial, made of the commitments to the rows of its evaluations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct HyraxCommitment<E: Engine> {
+  comms: Vec<Commitment<E>>,
+}
+
+impl<E: Engine> TranscriptReprTrait<E::GE> for HyraxCommitment<E> {
+  fn to_transcript_bytes(&self) -> Vec<u8> {
+    self
+      .comms
+      .iter()
+      .flat_map(|comm| comm.to_transcript_bytes())
+      .collect()
+  }
+
+  fn to_transcript_scalars(&self) -> Vec<E::Scalar> {
+    self
+      .comms
+      .iter()
+      .flat_map(|comm| comm.to_transcript_scalars())
+      .collect()
+  }
+}
+
+/// Provides an implementation of a polynomial commitment scheme using Hyrax
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HyraxEngine<E: Engine> {
+  _p: PhantomData<E>,
+}
+
+/// Returns the number of variables of the rows and of the columns of a polynomial in `num_vars`
+/// variables, the first of which select the row
+fn split_num_vars(num_vars: usize) -> (usize, usize) {
+  (num_vars / 2, num_vars - num_vars / 2)
+}
+
+impl<E> PCSEngineTrait<E> for HyraxEngine<E>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+  CommitmentKey<E>: CommitmentKeyExtTrait<E>,
+{
+  type CommitmentKey = HyraxCommitmentKey<E>;
+  type VerifierKey = HyraxVerifierKey<E>;
+  type Commitment = HyraxCommitment<E>;
+  type EvaluationArgument = InnerProductArgument<E>;
+
+  fn setup(
+    label: &'static [u8],
+    n: usize,
+  ) -> Result<(Self::CommitmentKey, Self::VerifierKey), NovaError> {
+    let num_vars = n.next_power_of_two().trailing_zeros() as usize;
+    let (_, num_vars_col) = split_num_vars(num_vars);
+
+    let ck = E::CE::setup(label, 1 << num_vars_col);
+    let ck_s = E::CE::setup(b"ipa", 1);
+    let vk = HyraxVerifierKey {
+      ck_v: ck.clone(),
+      ck_s: ck_s.clone(),
+    };
+
+    Ok((HyraxCommitmentKey { ck, ck_s }, vk))
+  }
+
+  fn commit(ck: &Self::CommitmentKey, poly: &[E::Scalar]) -> Result<Self::Commitment, NovaError> {
+    if !poly.len().is_power_of_two() {
+      return Err(PCSError::LengthError.into());
+    }
+    let (_, num_vars_col) = split_num_vars(poly.len().trailing_zeros() as usize);
+    if ck.ck.length() < 1 << num_vars_col {
+      return Err(PCSError::LengthError.into());
+    }
+
+    let comms = poly
+      .par_chunks(1 << num_vars_col)
+      .map(|row| CE::<E>::commit(&ck.ck, row))
+      .collect();
+    Ok(HyraxCommitment { comms })
+  }
+
+  fn prove(
+    ck: &Self::CommitmentKey,
+    transcript: &mut E::TE,
+    _comm: &Self::Commitment,
+    poly: &[E::Scalar],
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+  ) -> Result<Self::EvaluationArgument, NovaError> {
+    if poly.len() != 1 << point.len() {
+      return Err(PCSError::LengthError.into());
+    }
+    let (num_vars_row, _) = split_num_vars(point.len());
+    let (r_row, r_col) = point.split_at(num_vars_row);
+
+    // the combination of the rows with eq(r_row, ·), whose commitment is the combination of the
+    // commitments to the rows
+    let eq_row = EqPolynomial::evals_from_points(r_row);
+    let num_cols = poly.len() / eq_row.len();
+    let row = (0..num_cols)
+      .into_par_iter()
+      .map(|j| {
+        eq_row
+          .iter()
+          .enumerate()
+          .map(|(i, e)| *e * poly[i * num_cols + j])
+          .sum()
+      })
+      .collect::<Vec<E::Scalar>>();
+    let comm_row = CE::<E>::commit(&ck.ck, &row);
+
+    let u = InnerProductInstance::new(&comm_row, &EqPolynomial::evals_from_points(r_col), eval);
+    let w = InnerProductWitness::new(&row);
+
+    InnerProductArgument::prove(&ck.ck, &ck.ck_s, &u, &w, transcript)
+  }
+
+  fn verify(
+    vk: &Self::VerifierKey,
+    transcript: &mut E::TE,
+    comm: &Self::Commitment,
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+    arg: &Self::EvaluationArgument,
+  ) -> Result<(), NovaError> {
+    let (num_vars_row, num_vars_col) = split_num_vars(point.len());
+    if comm.comms.len() != 1 << num_vars_row {
+      return Err(PCSError::LengthError.into());
+    }
+    let (r_row, r_col) = point.split_at(num_vars_row);
+
+    // C = ∑ᵢ eq(r_row, i)⋅Cᵢ
+    let eq_row = EqPolynomial::evals_from_points(r_row);
+    let comm_row = comm
+      .comms
+      .iter()
+      .zip_eq(eq_row.iter())
+      .map(|(c, e)| *c * *e)
+      .fold(Commitment::<E>::default(), |acc, item| acc + item);
+
+    let u = InnerProductInstance::new(&comm_row, &EqPolynomial::evals_from_points(r_col), eval);
+
+    arg.verify(&vk.ck_v, &vk.ck_s, 1 << num_vars_col, &u, transcript)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    provider::{PallasEngine, Secp256k1Engine},
+    spartan::polys::multilinear::MultilinearPolynomial,
+    traits::TranscriptEngineTrait,
+  };
+  use ff::Field;
+  use rand::rngs::OsRng;
+
+  fn test_hyrax_with<E>(num_vars: usize)
+  where
+    E: Engine,
+    E::GE: DlogGroup,
+    CommitmentKey<E>: CommitmentKeyExtTrait<E>,
+  {
+    let n = 1 << num_vars;
+    let poly = (0..n).map(|_| E::Scalar::random(OsRng)).collect::<Vec<_>>();
+    let point = (0..num_vars)
+      .map(|_| E::Scalar::random(OsRng))
+      .collect::<Vec<_>>();
+    let eval = MultilinearPolynomial::evaluate_with(&poly, &point);
+
+    let (ck, vk) = HyraxEngine::<E>::setup(b"test", n).unwrap();
+    let comm = HyraxEngine::<E>::commit(&ck, &poly).unwrap();
+    let arg =
+      HyraxEngine::<E>::prove(&ck, &mut E::TE::new(b"test"), &comm, &poly, &point, &eval).unwrap();
+
+    let verify = |comm: &HyraxCommitment<E>, eval: &E::Scalar| {
+      HyraxEngine::<E>::verify(&vk, &mut E::TE::new(b"test"), comm, &point, eval, &arg)
+    };
+    assert!(verify(&comm, &eval).is_ok());
+
+    // the verifier key only holds the generators of a row
+    assert_eq!(vk.ck_v.length(), 1 << (num_vars - num_vars / 2));
+
+    // a wrong evaluation is rejected, as is the evaluation of another polynomial
+    assert!(verify(&comm, &(eval + E::Scalar::ONE)).is_err());
+    let mut other = poly.clone();
+    other[n - 1] += E::Scalar::ONE;
+    let other_comm = HyraxEngine::<E>::commit(&ck, &other).unwrap();
+    assert!(verify(&other_comm, &eval).is_err());
+  }
+
+  #[test]
+  fn test_hyrax() {
+    for num_vars in [1, 4, 7] {
+      test_hyrax_with::<PallasEngine>(num_vars);
+      test_hyrax_with::<Secp256k1Engine>(num_vars);
+    }
+  }
+}
@@ -1,10 +1,13 @@
 //! This module implements `EvaluationEngine` using an IPA-based polynomial commitment scheme
 use crate::{
   errors::{NovaError, PCSError},
-  provider::{pedersen::CommitmentKeyExtTrait, traits::DlogGroup},
-  spartan::polys::eq::EqPolynomial,
+  provider::{
+    pedersen::{CommitmentEngine, CommitmentKeyExtTrait},
+    traits::DlogGroup,
+  },
+  spartan::{polys::eq::EqPolynomial, BatchEvalArgument},
   traits::{
-    commitment::{CommitmentEngineTrait, CommitmentTrait},
+    commitment::{CommitmentEngineTrait, CommitmentTrait, HidingCommitmentEngineTrait},
     evaluation::EvaluationEngineTrait,
     Engine, TranscriptEngineTrait, TranscriptReprTrait,
   },
@@ -13,6 +16,7 @@ use crate::{
 use abomonation_derive::Abomonation;
 use core::iter;
 use ff::Field;
+use rand::rngs::OsRng;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -26,6 +30,10 @@ pub struct ProverKey<E: Engine> {
 }
 
 /// Provides an implementation of the verifier key
+///
+/// It holds the whole commitment key, since the last step of the IPA verifier is a multi-scalar
+/// multiplication over all of its generators. `hyrax_pc` needs a square-root sized key only, with
+/// a commitment made of one group element per row of the committed vector.
 #[derive(Clone, Debug, Serialize, Deserialize, Abomonation)]
 #[serde(bound = "")]
 #[abomonation_omit_bounds]
@@ -49,6 +57,7 @@ where
   type ProverKey = ProverKey<E>;
   type VerifierKey = VerifierKey<E>;
   type EvaluationArgument = InnerProductArgument<E>;
+  type BatchEvaluationArgument = BatchEvalArgument<E, InnerProductArgument<E>>;
 
   fn setup(
     ck: &<<E as Engine>::CE as CommitmentEngineTrait<E>>::CommitmentKey,
@@ -100,6 +109,55 @@ where
 
     Ok(())
   }
+
+  fn prove_batch(
+    ck: &CommitmentKey<E>,
+    pk: &Self::ProverKey,
+    transcript: &mut E::TE,
+    comms: &[Commitment<E>],
+    polys: &[Vec<E::Scalar>],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+  ) -> Result<Self::BatchEvaluationArgument, NovaError> {
+    BatchEvalArgument::prove::<Self>(ck, pk, transcript, comms, polys, points, evals)
+  }
+
+  fn verify_batch(
+    vk: &Self::VerifierKey,
+    transcript: &mut E::TE,
+    comms: &[Commitment<E>],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+    arg: &Self::BatchEvaluationArgument,
+  ) -> Result<(), NovaError> {
+    arg.verify::<Self>(vk, transcript, comms, points, evals)
+  }
+
+  /// A method to verify purported evaluations of several polynomials, by merging the final
+  /// multi-scalar multiplications of their inner product arguments
+  fn batch_verify(
+    vk: &Self::VerifierKey,
+    transcripts: &mut [E::TE],
+    comms: &[Commitment<E>],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+    args: &[&Self::EvaluationArgument],
+  ) -> Result<(), NovaError> {
+    if points.len() != comms.len() || evals.len() != comms.len() {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    let U = comms
+      .par_iter()
+      .zip_eq(points.par_iter())
+      .zip_eq(evals.par_iter())
+      .map(|((comm, point), eval)| {
+        InnerProductInstance::new(comm, &EqPolynomial::evals_from_points(point), eval)
+      })
+      .collect::<Vec<_>>();
+
+    InnerProductArgument::batch_verify(args, &vk.ck_v, &vk.ck_s, &U, transcripts)
+  }
 }
 
 fn inner_product<T: Field + Send + Sync>(a: &[T], b: &[T]) -> T {
@@ -123,7 +181,7 @@ where
   E: Engine,
   E::GE: DlogGroup,
 {
-  fn new(comm_a_vec: &Commitment<E>, b_vec: &[E::Scalar], c: &E::Scalar) -> Self {
+  pub(crate) fn new(comm_a_vec: &Commitment<E>, b_vec: &[E::Scalar], c: &E::Scalar) -> Self {
     InnerProductInstance {
       comm_a_vec: *comm_a_vec,
       b_vec: b_vec.to_vec(),
@@ -141,14 +199,22 @@ impl<E: Engine> TranscriptReprTrait<E::GE> for InnerProductInstance<E> {
     ]
     .concat()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<E::Scalar> {
+    [
+      self.comm_a_vec.to_transcript_scalars(),
+      self.c.to_transcript_scalars(),
+    ]
+    .concat()
+  }
 }
 
-struct InnerProductWitness<E: Engine> {
+pub(crate) struct InnerProductWitness<E: Engine> {
   a_vec: Vec<E::Scalar>,
 }
 
 impl<E: Engine> InnerProductWitness<E> {
-  fn new(a_vec: &[E::Scalar]) -> Self {
+  pub(crate) fn new(a_vec: &[E::Scalar]) -> Self {
     InnerProductWitness {
       a_vec: a_vec.to_vec(),
     }
@@ -174,7 +240,7 @@ where
     b"IPA"
   }
 
-  fn prove(
+  pub(crate) fn prove(
     ck: &CommitmentKey<E>,
     ck_c: &CommitmentKey<E>,
     U: &InnerProductInstance<E>,
@@ -286,7 +352,7 @@ where
     })
   }
 
-  fn verify(
+  pub(crate) fn verify(
     &self,
     ck: &CommitmentKey<E>,
     ck_c: &CommitmentKey<E>,
@@ -314,99 +380,453 @@ where
 
     let P = U.comm_a_vec + CE::<E>::commit(&ck_c, &[U.c]);
 
-    let batch_invert = |v: &[E::Scalar]| -> Result<Vec<E::Scalar>, NovaError> {
-      let mut products = vec![E::Scalar::ZERO; v.len()];
-      let mut acc = E::Scalar::ONE;
+    let (ck_hat, b_hat, P_hat) =
+      fold_verifier(&ck, n, &U.b_vec, &P, &self.L_vec, &self.R_vec, transcript)?;
 
-      for i in 0..v.len() {
-        products[i] = acc;
-        acc *= v[i];
-      }
+    if P_hat == CE::<E>::commit(&ck_hat.combine(&ck_c), &[self.a_hat, self.a_hat * b_hat]) {
+      Ok(())
+    } else {
+      Err(NovaError::PCSError(PCSError::InvalidIPA))
+    }
+  }
 
-      // return error if acc is zero
-      acc = match Option::from(acc.invert()) {
-        Some(inv) => inv,
-        None => return Err(NovaError::InternalError),
-      };
-
-      // compute the inverse once for all entries
-      let mut inv = vec![E::Scalar::ZERO; v.len()];
-      for i in (0..v.len()).rev() {
-        let tmp = acc * v[i];
-        inv[i] = products[i] * acc;
-        acc = tmp;
-      }
+  /// Verifies several inner product arguments, each with its own transcript. The final equation
+  /// of each argument is
+  ///   Σⱼ rⱼ²⋅Lⱼ + Σⱼ rⱼ⁻²⋅Rⱼ + C + (c - â⋅b̂)⋅r⋅H = â⋅⟨s, G⟩,
+  /// so we check a random linear combination of these equations with one multi-scalar
+  /// multiplication on each side, where the generators G are shared by all arguments.
+  fn batch_verify(
+    args: &[&Self],
+    ck: &CommitmentKey<E>,
+    ck_c: &CommitmentKey<E>,
+    U: &[InnerProductInstance<E>],
+    transcripts: &mut [E::TE],
+  ) -> Result<(), NovaError> {
+    if args.len() != U.len() || transcripts.len() != U.len() {
+      return Err(NovaError::InvalidInputLength);
+    }
+    if U.is_empty() {
+      return Ok(());
+    }
 
-      Ok(inv)
-    };
+    // replay the reductions of each argument to obtain the scalars of its final equation
+    let folded = transcripts
+      .par_iter_mut()
+      .zip_eq(args.par_iter())
+      .zip_eq(U.par_iter())
+      .map(|((transcript, arg), U)| {
+        transcript.dom_sep(Self::protocol_name());
+        let n = U.b_vec.len();
+        if n != (1 << arg.L_vec.len())
+          || arg.L_vec.len() != arg.R_vec.len()
+          || arg.L_vec.len() >= 32
+        {
+          return Err(NovaError::InvalidInputLength);
+        }
+
+        // absorb the instance in the transcript
+        transcript.absorb(b"U", U);
 
-    // compute a vector of public coins using self.L_vec and self.R_vec
-    let r = (0..self.L_vec.len())
-      .map(|i| {
-        transcript.absorb(b"L", &self.L_vec[i]);
-        transcript.absorb(b"R", &self.R_vec[i]);
-        transcript.squeeze(b"r")
+        // sample the scalar of the base for commiting to the inner product
+        let r = transcript.squeeze(b"r")?;
+
+        let (r_square, r_inverse_square, s) =
+          fold_scalars::<E>(n, &arg.L_vec, &arg.R_vec, transcript)?;
+        let b_hat = inner_product(&U.b_vec, &s);
+
+        Ok((r, r_square, r_inverse_square, s, b_hat))
       })
-      .collect::<Result<Vec<E::Scalar>, NovaError>>()?;
-
-    // precompute scalars necessary for verification
-    let r_square: Vec<E::Scalar> = (0..self.L_vec.len())
-      .into_par_iter()
-      .map(|i| r[i] * r[i])
-      .collect();
-    let r_inverse = batch_invert(&r)?;
-    let r_inverse_square: Vec<E::Scalar> = (0..self.L_vec.len())
-      .into_par_iter()
-      .map(|i| r_inverse[i] * r_inverse[i])
-      .collect();
-
-    // compute the vector with the tensor structure
-    let s = {
-      let mut s = vec![E::Scalar::ZERO; n];
-      s[0] = {
-        let mut v = E::Scalar::ONE;
-        for r_inverse_i in r_inverse {
-          v *= r_inverse_i;
-        }
-        v
-      };
-      for i in 1..n {
-        let pos_in_r = (31 - (i as u32).leading_zeros()) as usize;
-        s[i] = s[i - (1 << pos_in_r)] * r_square[(self.L_vec.len() - 1) - pos_in_r];
-      }
-      s
+      .collect::<Result<Vec<_>, NovaError>>()?;
+
+    // sample the weights of the random linear combination
+    let rho = (0..U.len())
+      .map(|_| E::Scalar::random(OsRng))
+      .collect::<Vec<_>>();
+
+    // the left side, over the messages and commitments of all arguments, and the base H
+    let lhs = {
+      let bases = args
+        .iter()
+        .zip_eq(U.iter())
+        .flat_map(|(arg, U)| {
+          arg
+            .L_vec
+            .iter()
+            .chain(arg.R_vec.iter())
+            .cloned()
+            .chain(iter::once(U.comm_a_vec.compress()))
+        })
+        .collect::<Vec<_>>();
+
+      let scalar_H = (0..U.len())
+        .map(|i| {
+          let (r, _, _, _, b_hat) = &folded[i];
+          rho[i] * r * (U[i].c - args[i].a_hat * b_hat)
+        })
+        .sum::<E::Scalar>();
+
+      let scalars = folded
+        .iter()
+        .zip_eq(rho.iter())
+        .flat_map(|((_, r_square, r_inverse_square, _, _), rho_i)| {
+          r_square
+            .iter()
+            .chain(r_inverse_square.iter())
+            .map(move |r_i| *rho_i * r_i)
+            .chain(iter::once(*rho_i))
+        })
+        .chain(iter::once(scalar_H))
+        .collect::<Vec<_>>();
+
+      let ck_lhs = CommitmentKey::<E>::reinterpret_commitments_as_ck(&bases)?.combine(ck_c);
+      CE::<E>::commit(&ck_lhs, &scalars)
     };
 
-    let ck_hat = {
-      let c = CE::<E>::commit(&ck, &s).compress();
-      CommitmentKey::<E>::reinterpret_commitments_as_ck(&[c])?
+    // the right side, over the generators G
+    let rhs = {
+      let n_max = U.iter().map(|U| U.b_vec.len()).max().unwrap();
+      let (ck, _) = ck.split_at(n_max);
+
+      let scalars = (0..n_max)
+        .into_par_iter()
+        .map(|k| {
+          folded
+            .iter()
+            .zip_eq(args.iter())
+            .zip_eq(rho.iter())
+            .filter(|(((_, _, _, s, _), _), _)| k < s.len())
+            .map(|(((_, _, _, s, _), arg), rho_i)| *rho_i * arg.a_hat * s[k])
+            .sum::<E::Scalar>()
+        })
+        .collect::<Vec<_>>();
+
+      CE::<E>::commit(&ck, &scalars)
     };
 
-    let b_hat = inner_product(&U.b_vec, &s);
+    if lhs == rhs {
+      Ok(())
+    } else {
+      Err(NovaError::PCSError(PCSError::InvalidIPA))
+    }
+  }
+}
+
+/// A zero-knowledge variant of [`InnerProductArgument`], which proves the evaluation of a
+/// multilinear polynomial under a hiding commitment, i.e., one produced with
+/// [`HidingCommitmentEngineTrait::commit_with_blind`], while revealing nothing else about the
+/// polynomial.
+///
+/// The messages of the recursive reductions are blinded, and instead of revealing the folded
+/// vector, the prover shows knowledge of it and of the accumulated blind with a Schnorr proof.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct HidingInnerProductArgument<E: Engine> {
+  L_vec: Vec<CompressedCommitment<E>>,
+  R_vec: Vec<CompressedCommitment<E>>,
+  comm_d: CompressedCommitment<E>,
+  z_a: E::Scalar,
+  z_blind: E::Scalar,
+}
+
+impl<E> HidingInnerProductArgument<E>
+where
+  E: Engine<CE = CommitmentEngine<E>>,
+  E::GE: DlogGroup,
+{
+  const fn protocol_name() -> &'static [u8] {
+    b"HidingIPA"
+  }
+
+  /// Proves that `eval` is the evaluation at `point` of the multilinear polynomial `poly`, whose
+  /// hiding commitment `comm` was produced with the blind `blind`
+  #[allow(clippy::too_many_arguments)]
+  pub fn prove(
+    ck: &CommitmentKey<E>,
+    pk: &ProverKey<E>,
+    transcript: &mut E::TE,
+    comm: &Commitment<E>,
+    poly: &[E::Scalar],
+    blind: &E::Scalar,
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+  ) -> Result<Self, NovaError> {
+    let U = InnerProductInstance::new(comm, &EqPolynomial::evals_from_points(point), eval);
+    let W = InnerProductWitness::new(poly);
+
+    transcript.dom_sep(Self::protocol_name());
 
-    let P_hat = {
-      let ck_folded = {
-        let ck_L = CommitmentKey::<E>::reinterpret_commitments_as_ck(&self.L_vec)?;
-        let ck_R = CommitmentKey::<E>::reinterpret_commitments_as_ck(&self.R_vec)?;
-        let ck_P = CommitmentKey::<E>::reinterpret_commitments_as_ck(&[P.compress()])?;
-        ck_L.combine(&ck_R).combine(&ck_P)
-      };
+    let (ck, _) = ck.split_at(U.b_vec.len());
 
-      CE::<E>::commit(
-        &ck_folded,
-        &r_square
+    if U.b_vec.len() != W.a_vec.len() {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    // absorb the instance in the transcript
+    transcript.absorb(b"U", &U);
+
+    // sample a random base for commiting to the inner product
+    let r = transcript.squeeze(b"r")?;
+    let ck_c = pk.ck_s.scale(&r);
+
+    // two vectors to hold the logarithmic number of group elements
+    let mut L_vec: Vec<CompressedCommitment<E>> = Vec::new();
+    let mut R_vec: Vec<CompressedCommitment<E>> = Vec::new();
+
+    // we create mutable copies of vectors, generators, and the blind
+    let mut a_vec = W.a_vec;
+    let mut b_vec = U.b_vec.clone();
+    let mut ck = ck;
+    let mut blind = *blind;
+    for _i in 0..usize::try_from(U.b_vec.len().ilog2()).unwrap() {
+      let n = a_vec.len();
+      let (ck_L, ck_R) = ck.split_at(n / 2);
+
+      let c_L = inner_product(&a_vec[0..n / 2], &b_vec[n / 2..n]);
+      let c_R = inner_product(&a_vec[n / 2..n], &b_vec[0..n / 2]);
+
+      let (blind_L, blind_R) = (E::Scalar::random(OsRng), E::Scalar::random(OsRng));
+      let L = CommitmentEngine::<E>::commit_with_blind(
+        &ck_R.combine(&ck_c),
+        &a_vec[0..n / 2]
           .iter()
-          .chain(r_inverse_square.iter())
-          .chain(iter::once(&E::Scalar::ONE))
+          .chain(iter::once(&c_L))
           .copied()
           .collect::<Vec<E::Scalar>>(),
+        &blind_L,
       )
-    };
+      .compress();
+      let R = CommitmentEngine::<E>::commit_with_blind(
+        &ck_L.combine(&ck_c),
+        &a_vec[n / 2..n]
+          .iter()
+          .chain(iter::once(&c_R))
+          .copied()
+          .collect::<Vec<E::Scalar>>(),
+        &blind_R,
+      )
+      .compress();
 
-    if P_hat == CE::<E>::commit(&ck_hat.combine(&ck_c), &[self.a_hat, self.a_hat * b_hat]) {
+      transcript.absorb(b"L", &L);
+      transcript.absorb(b"R", &R);
+
+      let r = transcript.squeeze(b"r")?;
+      let r_inverse = r.invert().unwrap();
+
+      // fold the left half and the right half, and accumulate the blinds of L and R
+      a_vec = a_vec[0..n / 2]
+        .par_iter()
+        .zip_eq(a_vec[n / 2..n].par_iter())
+        .map(|(a_L, a_R)| *a_L * r + r_inverse * *a_R)
+        .collect::<Vec<E::Scalar>>();
+      b_vec = b_vec[0..n / 2]
+        .par_iter()
+        .zip_eq(b_vec[n / 2..n].par_iter())
+        .map(|(b_L, b_R)| *b_L * r_inverse + r * *b_R)
+        .collect::<Vec<E::Scalar>>();
+      ck = ck.fold(&r_inverse, &r);
+      blind += r * r * blind_L + r_inverse * r_inverse * blind_R;
+
+      L_vec.push(L);
+      R_vec.push(R);
+    }
+
+    // prove knowledge of the folded vector a_hat and of the accumulated blind, given the
+    // folded commitment a_hat * (G_hat + b_hat * H) + blind * h
+    let (a_hat, b_hat) = (a_vec[0], b_vec[0]);
+    let (d, blind_d) = (E::Scalar::random(OsRng), E::Scalar::random(OsRng));
+    let comm_d =
+      CommitmentEngine::<E>::commit_with_blind(&ck.combine(&ck_c), &[d, d * b_hat], &blind_d)
+        .compress();
+    transcript.absorb(b"d", &comm_d);
+
+    let e = transcript.squeeze(b"e")?;
+
+    Ok(HidingInnerProductArgument {
+      L_vec,
+      R_vec,
+      comm_d,
+      z_a: d + e * a_hat,
+      z_blind: blind_d + e * blind,
+    })
+  }
+
+  /// Verifies that `eval` is the evaluation at `point` of the multilinear polynomial committed
+  /// to in the hiding commitment `comm`
+  pub fn verify(
+    &self,
+    vk: &VerifierKey<E>,
+    transcript: &mut E::TE,
+    comm: &Commitment<E>,
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+  ) -> Result<(), NovaError> {
+    let U = InnerProductInstance::new(comm, &EqPolynomial::evals_from_points(point), eval);
+    let n = (2_usize).pow(point.len() as u32);
+
+    let (ck, _) = vk.ck_v.split_at(U.b_vec.len());
+
+    transcript.dom_sep(Self::protocol_name());
+    if n != (1 << self.L_vec.len())
+      || self.L_vec.len() != self.R_vec.len()
+      || self.L_vec.len() >= 32
+    {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    // absorb the instance in the transcript
+    transcript.absorb(b"U", &U);
+
+    // sample a random base for commiting to the inner product
+    let r = transcript.squeeze(b"r")?;
+    let ck_c = vk.ck_s.scale(&r);
+
+    let P = U.comm_a_vec + CE::<E>::commit(&ck_c, &[U.c]);
+
+    let (ck_hat, b_hat, P_hat) =
+      fold_verifier(&ck, n, &U.b_vec, &P, &self.L_vec, &self.R_vec, transcript)?;
+
+    transcript.absorb(b"d", &self.comm_d);
+
+    let e = transcript.squeeze(b"e")?;
+
+    let comm_d = Commitment::<E>::decompress(&self.comm_d)?;
+    if comm_d + P_hat * e
+      == CommitmentEngine::<E>::commit_with_blind(
+        &ck_hat.combine(&ck_c),
+        &[self.z_a, self.z_a * b_hat],
+        &self.z_blind,
+      )
+    {
       Ok(())
     } else {
       Err(NovaError::PCSError(PCSError::InvalidIPA))
     }
   }
 }
+
+/// Replays the recursive reductions of an inner product argument whose messages are `L_vec` and
+/// `R_vec`, returning the folded commitment key, the folded vector `b_vec`, and the folded
+/// commitment, obtained from `P`, a commitment to both the vector and its inner product
+fn fold_verifier<E>(
+  ck: &CommitmentKey<E>,
+  n: usize,
+  b_vec: &[E::Scalar],
+  P: &Commitment<E>,
+  L_vec: &[CompressedCommitment<E>],
+  R_vec: &[CompressedCommitment<E>],
+  transcript: &mut E::TE,
+) -> Result<(CommitmentKey<E>, E::Scalar, Commitment<E>), NovaError>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+  CommitmentKey<E>: CommitmentKeyExtTrait<E>,
+{
+  let (r_square, r_inverse_square, s) = fold_scalars::<E>(n, L_vec, R_vec, transcript)?;
+
+  let ck_hat = {
+    let c = CE::<E>::commit(ck, &s).compress();
+    CommitmentKey::<E>::reinterpret_commitments_as_ck(&[c])?
+  };
+
+  let b_hat = inner_product(b_vec, &s);
+
+  let P_hat = {
+    let ck_folded = {
+      let ck_L = CommitmentKey::<E>::reinterpret_commitments_as_ck(L_vec)?;
+      let ck_R = CommitmentKey::<E>::reinterpret_commitments_as_ck(R_vec)?;
+      let ck_P = CommitmentKey::<E>::reinterpret_commitments_as_ck(&[P.compress()])?;
+      ck_L.combine(&ck_R).combine(&ck_P)
+    };
+
+    CE::<E>::commit(
+      &ck_folded,
+      &r_square
+        .iter()
+        .chain(r_inverse_square.iter())
+        .chain(iter::once(&E::Scalar::ONE))
+        .copied()
+        .collect::<Vec<E::Scalar>>(),
+    )
+  };
+
+  Ok((ck_hat, b_hat, P_hat))
+}
+
+/// Derives the challenges of the recursive reductions of an inner product argument whose
+/// messages are `L_vec` and `R_vec`, returning the weights of `L_vec` and `R_vec` in the folded
+/// commitment, and the weights `s` of the generators in the folded commitment key
+fn fold_scalars<E>(
+  n: usize,
+  L_vec: &[CompressedCommitment<E>],
+  R_vec: &[CompressedCommitment<E>],
+  transcript: &mut E::TE,
+) -> Result<(Vec<E::Scalar>, Vec<E::Scalar>, Vec<E::Scalar>), NovaError>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+{
+  let batch_invert = |v: &[E::Scalar]| -> Result<Vec<E::Scalar>, NovaError> {
+    let mut products = vec![E::Scalar::ZERO; v.len()];
+    let mut acc = E::Scalar::ONE;
+
+    for i in 0..v.len() {
+      products[i] = acc;
+      acc *= v[i];
+    }
+
+    // return error if acc is zero
+    acc = match Option::from(acc.invert()) {
+      Some(inv) => inv,
+      None => return Err(NovaError::InternalError),
+    };
+
+    // compute the inverse once for all entries
+    let mut inv = vec![E::Scalar::ZERO; v.len()];
+    for i in (0..v.len()).rev() {
+      let tmp = acc * v[i];
+      inv[i] = products[i] * acc;
+      acc = tmp;
+    }
+
+    Ok(inv)
+  };
+
+  // compute a vector of public coins using L_vec and R_vec
+  let r = (0..L_vec.len())
+    .map(|i| {
+      transcript.absorb(b"L", &L_vec[i]);
+      transcript.absorb(b"R", &R_vec[i]);
+      transcript.squeeze(b"r")
+    })
+    .collect::<Result<Vec<E::Scalar>, NovaError>>()?;
+
+  // precompute scalars necessary for verification
+  let r_square: Vec<E::Scalar> = (0..L_vec.len())
+    .into_par_iter()
+    .map(|i| r[i] * r[i])
+    .collect();
+  let r_inverse = batch_invert(&r)?;
+  let r_inverse_square: Vec<E::Scalar> = (0..L_vec.len())
+    .into_par_iter()
+    .map(|i| r_inverse[i] * r_inverse[i])
+    .collect();
+
+  // compute the vector with the tensor structure
+  let s = {
+    let mut s = vec![E::Scalar::ZERO; n];
+    s[0] = {
+      let mut v = E::Scalar::ONE;
+      for r_inverse_i in r_inverse {
+        v *= r_inverse_i;
+      }
+      v
+    };
+    for i in 1..n {
+      let pos_in_r = (31 - (i as u32).leading_zeros()) as usize;
+      s[i] = s[i - (1 << pos_in_r)] * r_square[(L_vec.len() - 1) - pos_in_r];
+    }
+    s
+  };
+
+  Ok((r_square, r_inverse_square, s))
+}
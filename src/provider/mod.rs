@@ -1,6 +1,9 @@
 //! This module implements Nova's traits using the following several different combinations
 
 // public modules to be used as an evaluation engine with Spartan
+pub mod basefold;
+pub mod hyperkzg;
+pub mod hyrax_pc;
 pub mod ipa_pc;
 pub mod non_hiding_zeromorph;
 
@@ -19,13 +22,14 @@ mod util;
 // crate-private modules
 mod keccak;
 mod msm;
+mod ptau;
 
 use crate::{
   provider::{
     bn256_grumpkin::{bn256, grumpkin},
     keccak::Keccak256Transcript,
     pedersen::CommitmentEngine as PedersenCommitmentEngine,
-    poseidon::{PoseidonRO, PoseidonROCircuit},
+    poseidon::{PoseidonRO, PoseidonROCircuit, PoseidonTranscript},
     secp_secq::{secp256k1, secq256k1},
   },
   traits::Engine,
@@ -35,6 +39,10 @@ use pasta_curves::{pallas, vesta};
 
 use self::kzg_commitment::KZGCommitmentEngine;
 
+/// The universal parameters of KZG commitments, which serve as the commitment key of engines
+/// using them, and can be loaded from the output of a trusted setup
+pub use self::non_hiding_kzg::UVUniversalKZGParam;
+
 /// An implementation of the Nova `Engine` trait with BN254 curve and Pedersen commitment scheme
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Bn256Engine;
@@ -133,6 +141,36 @@ impl Engine for VestaEngine {
   type CE = PedersenCommitmentEngine<Self>;
 }
 
+/// An implementation of the Nova `Engine` trait with Pallas curve, Pedersen commitment scheme and
+/// a Poseidon-based transcript, whose verifiers are cheaper to express in circuits
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PallasPoseidonEngine;
+
+/// An implementation of the Nova `Engine` trait with Vesta curve, Pedersen commitment scheme and
+/// a Poseidon-based transcript, whose verifiers are cheaper to express in circuits
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VestaPoseidonEngine;
+
+impl Engine for PallasPoseidonEngine {
+  type Base = pallas::Base;
+  type Scalar = pallas::Scalar;
+  type GE = pallas::Point;
+  type RO = PoseidonRO<Self::Base, Self::Scalar>;
+  type ROCircuit = PoseidonROCircuit<Self::Base>;
+  type TE = PoseidonTranscript<Self>;
+  type CE = PedersenCommitmentEngine<Self>;
+}
+
+impl Engine for VestaPoseidonEngine {
+  type Base = vesta::Base;
+  type Scalar = vesta::Scalar;
+  type GE = vesta::Point;
+  type RO = PoseidonRO<Self::Base, Self::Scalar>;
+  type ROCircuit = PoseidonROCircuit<Self::Base>;
+  type TE = PoseidonTranscript<Self>;
+  type CE = PedersenCommitmentEngine<Self>;
+}
+
 #[cfg(test)]
 mod tests {
   use crate::provider::{
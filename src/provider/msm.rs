@@ -1,18 +1,60 @@
-//! This module provides a multi-scalar multiplication routine
+//! This module provides multi-scalar multiplication routines
 /// Adapted from zcash/halo2
 use ff::PrimeField;
 use itertools::Itertools as _;
 use pasta_curves::{self, arithmetic::CurveAffine, group::Group as AnotherGroup};
 use rayon::{current_num_threads, prelude::*};
 
-fn cpu_msm_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C]) -> C::Curve {
-  let c = if bases.len() < 4 {
+#[derive(Clone, Copy)]
+enum Bucket<C: CurveAffine> {
+  None,
+  Affine(C),
+  Projective(C::Curve),
+}
+
+impl<C: CurveAffine> Bucket<C> {
+  fn add_assign(&mut self, other: &C) {
+    *self = match *self {
+      Bucket::None => Bucket::Affine(*other),
+      Bucket::Affine(a) => Bucket::Projective(a + *other),
+      Bucket::Projective(a) => Bucket::Projective(a + other),
+    }
+  }
+
+  fn add(self, other: C::Curve) -> C::Curve {
+    match self {
+      Bucket::None => other,
+      Bucket::Affine(a) => other + a,
+      Bucket::Projective(a) => other + a,
+    }
+  }
+}
+
+// Adds the sum of the buckets, each weighted by its index plus one, to `acc`
+fn add_buckets<C: CurveAffine>(acc: &mut C::Curve, buckets: Vec<Bucket<C>>) {
+  // Summation by parts
+  // e.g. 3a + 2b + 1c = a +
+  //                    (a) + b +
+  //                    ((a) + b) + c
+  let mut running_sum = C::Curve::identity();
+  for exp in buckets.into_iter().rev() {
+    running_sum = exp.add(running_sum);
+    *acc += &running_sum;
+  }
+}
+
+fn window_size(num_bases: usize) -> usize {
+  if num_bases < 4 {
     1
-  } else if bases.len() < 32 {
+  } else if num_bases < 32 {
     3
   } else {
-    (f64::from(bases.len() as u32)).ln().ceil() as usize
-  };
+    (f64::from(num_bases as u32)).ln().ceil() as usize
+  }
+}
+
+fn cpu_msm_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C]) -> C::Curve {
+  let c = window_size(bases.len());
 
   fn get_at<F: PrimeField>(segment: usize, c: usize, bytes: &F::Repr) -> usize {
     let skip_bits = segment * c;
@@ -42,31 +84,6 @@ fn cpu_msm_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C]) -> C::Curve
     .fold(C::Curve::identity(), |mut acc, segment| {
       (0..c).for_each(|_| acc = acc.double());
 
-      #[derive(Clone, Copy)]
-      enum Bucket<C: CurveAffine> {
-        None,
-        Affine(C),
-        Projective(C::Curve),
-      }
-
-      impl<C: CurveAffine> Bucket<C> {
-        fn add_assign(&mut self, other: &C) {
-          *self = match *self {
-            Bucket::None => Bucket::Affine(*other),
-            Bucket::Affine(a) => Bucket::Projective(a + *other),
-            Bucket::Projective(a) => Bucket::Projective(a + other),
-          }
-        }
-
-        fn add(self, other: C::Curve) -> C::Curve {
-          match self {
-            Bucket::None => other,
-            Bucket::Affine(a) => other + a,
-            Bucket::Projective(a) => other + a,
-          }
-        }
-      }
-
       let mut buckets = vec![Bucket::None; (1 << c) - 1];
 
       for (coeff, base) in coeffs.iter().zip_eq(bases.iter()) {
@@ -76,19 +93,51 @@ fn cpu_msm_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C]) -> C::Curve
         }
       }
 
-      // Summation by parts
-      // e.g. 3a + 2b + 1c = a +
-      //                    (a) + b +
-      //                    ((a) + b) + c
-      let mut running_sum = C::Curve::identity();
-      for exp in buckets.into_iter().rev() {
-        running_sum = exp.add(running_sum);
-        acc += &running_sum;
-      }
+      add_buckets(&mut acc, buckets);
       acc
     })
 }
 
+fn cpu_msm_small_serial<C: CurveAffine>(coeffs: &[u64], bases: &[C], num_bits: usize) -> C::Curve {
+  match num_bits {
+    0 => C::Curve::identity(),
+    // bits only select the bases to add up
+    1 => coeffs
+      .iter()
+      .zip_eq(bases.iter())
+      .filter(|(coeff, _)| **coeff == 1)
+      .fold(C::Curve::identity(), |acc, (_, base)| acc + base),
+    _ => {
+      // scalars of a few bits fit in a single window, and only windows covering the bits of the
+      // largest scalar are computed
+      let c = if num_bits <= 10 {
+        num_bits
+      } else {
+        window_size(bases.len())
+      };
+      let segments = (num_bits + c - 1) / c;
+
+      (0..segments)
+        .rev()
+        .fold(C::Curve::identity(), |mut acc, segment| {
+          (0..c).for_each(|_| acc = acc.double());
+
+          let mut buckets = vec![Bucket::None; (1 << c) - 1];
+
+          for (coeff, base) in coeffs.iter().zip_eq(bases.iter()) {
+            let coeff = ((coeff >> (segment * c)) & ((1 << c) - 1)) as usize;
+            if coeff != 0 {
+              buckets[coeff - 1].add_assign(base);
+            }
+          }
+
+          add_buckets(&mut acc, buckets);
+          acc
+        })
+    }
+  }
+}
+
 /// Performs a multi-scalar-multiplication operation without GPU acceleration.
 ///
 /// This function will panic if coeffs and bases have a different length.
@@ -111,9 +160,37 @@ pub(crate) fn cpu_best_msm<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C]) ->
   }
 }
 
+/// Performs a multi-scalar-multiplication operation with scalars that fit in a `u64`, without
+/// GPU acceleration.
+///
+/// This function will panic if coeffs and bases have a different length.
+///
+/// Its cost depends on the bit size of the largest scalar rather than on the size of the field:
+/// an MSM of bits is a sum of bases, and one of bytes a single window of buckets.
+pub(crate) fn cpu_best_msm_small<C: CurveAffine>(coeffs: &[u64], bases: &[C]) -> C::Curve {
+  assert_eq!(coeffs.len(), bases.len());
+
+  let num_bits = coeffs
+    .par_iter()
+    .max()
+    .map_or(0, |max| (u64::BITS - max.leading_zeros()) as usize);
+
+  let num_threads = current_num_threads();
+  if coeffs.len() > num_threads {
+    let chunk = coeffs.len() / num_threads;
+    coeffs
+      .par_chunks(chunk)
+      .zip_eq(bases.par_chunks(chunk))
+      .map(|(coeffs, bases)| cpu_msm_small_serial(coeffs, bases, num_bits))
+      .reduce(C::Curve::identity, |sum, evl| sum + evl)
+  } else {
+    cpu_msm_small_serial(coeffs, bases, num_bits)
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use super::cpu_best_msm;
+  use super::{cpu_best_msm, cpu_best_msm_small};
 
   use crate::provider::{
     bn256_grumpkin::{bn256, grumpkin},
@@ -123,7 +200,7 @@ mod tests {
   use halo2curves::CurveAffine;
   use itertools::Itertools as _;
   use pasta_curves::{pallas, vesta};
-  use rand_core::OsRng;
+  use rand_core::{OsRng, RngCore};
 
   fn test_msm_with<F: Field, A: CurveAffine<ScalarExt = F>>() {
     let n = 8;
@@ -142,6 +219,28 @@ mod tests {
     assert_eq!(naive, msm)
   }
 
+  fn test_msm_small_with<F: Field, A: CurveAffine<ScalarExt = F>>() {
+    let n = 100;
+    let bases = (0..n)
+      .map(|_| A::from(A::generator() * F::random(OsRng)))
+      .collect::<Vec<_>>();
+
+    // zeros, bits, bytes, and scalars of various sizes up to u64
+    for num_bits in [0, 1, 8, 20, 64] {
+      let coeffs = (0..n)
+        .map(|_| OsRng.next_u64().checked_shr(64 - num_bits).unwrap_or(0))
+        .collect::<Vec<_>>();
+      let naive = coeffs
+        .iter()
+        .zip_eq(bases.iter())
+        .fold(A::CurveExt::identity(), |acc, (coeff, base)| {
+          acc + *base * F::from(*coeff)
+        });
+
+      assert_eq!(naive, cpu_best_msm_small(&coeffs, &bases));
+    }
+  }
+
   #[test]
   fn test_msm() {
     test_msm_with::<pallas::Scalar, pallas::Affine>();
@@ -151,4 +250,14 @@ mod tests {
     test_msm_with::<secp256k1::Scalar, secp256k1::Affine>();
     test_msm_with::<secq256k1::Scalar, secq256k1::Affine>();
   }
+
+  #[test]
+  fn test_msm_small() {
+    test_msm_small_with::<pallas::Scalar, pallas::Affine>();
+    test_msm_small_with::<vesta::Scalar, vesta::Affine>();
+    test_msm_small_with::<bn256::Scalar, bn256::Affine>();
+    test_msm_small_with::<grumpkin::Scalar, grumpkin::Affine>();
+    test_msm_small_with::<secp256k1::Scalar, secp256k1::Affine>();
+    test_msm_small_with::<secq256k1::Scalar, secq256k1::Affine>();
+  }
 }
@@ -1,11 +1,13 @@
 //! Non-hiding variant of KZG10 scheme for univariate polynomials.
 use abomonation_derive::Abomonation;
 use ff::{Field, PrimeField, PrimeFieldBits};
-use group::{prime::PrimeCurveAffine, Curve, Group as _};
+use group::{prime::PrimeCurveAffine, Curve, Group as _, GroupEncoding};
+use itertools::Itertools as _;
 use pairing::{Engine, MillerLoopResult, MultiMillerLoop};
+use rand::rngs::OsRng;
 use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
-use std::{borrow::Borrow, marker::PhantomData, ops::Mul};
+use std::{borrow::Borrow, io::Read, iter, marker::PhantomData, ops::Mul};
 
 use crate::{
   errors::{NovaError, PCSError},
@@ -177,6 +179,114 @@ where
     }
   }
 }
+
+/// The number of random pairs of consecutive powers whose consistency is checked when loading
+/// universal parameters
+const SRS_SPOT_CHECKS: usize = 32;
+
+impl<E: MultiMillerLoop> UVUniversalKZGParam<E> {
+  /// Builds universal parameters from the powers `{ β^i G }` and `{ β^i H }` output by a trusted
+  /// setup, e.g. a powers-of-tau ceremony. At least two powers of each are required.
+  ///
+  /// Consecutive powers are checked to differ by the same factor β with pairings, at the last
+  /// index and at random ones, which catches corrupted or mismatched inputs. This is no
+  /// substitute for verifying the transcript of the ceremony.
+  pub fn from_powers(
+    powers_of_g: Vec<E::G1Affine>,
+    powers_of_h: Vec<E::G2Affine>,
+  ) -> Result<Self, NovaError> {
+    if powers_of_g.len() < 2
+      || powers_of_h.len() < 2
+      || bool::from(
+        powers_of_g[0].is_identity() | powers_of_h[0].is_identity() | powers_of_h[1].is_identity(),
+      )
+    {
+      return Err(NovaError::PCSError(PCSError::InvalidSRS));
+    }
+
+    // indices i of pairs of consecutive powers (i, i + 1) to check, always including the last one
+    let sample = |len: usize| {
+      let mut rng = OsRng;
+      iter::once(len - 2)
+        .chain((0..SRS_SPOT_CHECKS).map(move |_| (rng.next_u64() % (len as u64 - 1)) as usize))
+        .collect::<Vec<_>>()
+    };
+    let (indices_g, indices_h) = (sample(powers_of_g.len()), sample(powers_of_h.len()));
+    let rho = (0..=SRS_SPOT_CHECKS)
+      .map(|_| E::Fr::random(OsRng))
+      .collect::<Vec<_>>();
+
+    // Σⱼ ρⱼ⋅G_{iⱼ + offset} and Σⱼ ρⱼ⋅H_{iⱼ + offset}
+    let combine_g = |offset: usize| {
+      indices_g
+        .iter()
+        .zip_eq(&rho)
+        .map(|(i, rho)| powers_of_g[i + offset] * *rho)
+        .sum::<E::G1>()
+        .to_affine()
+    };
+    let combine_h = |offset: usize| {
+      indices_h
+        .iter()
+        .zip_eq(&rho)
+        .map(|(i, rho)| powers_of_h[i + offset] * *rho)
+        .sum::<E::G2>()
+        .to_affine()
+    };
+
+    // e(G_{i+1}, H) = e(G_i, βH) and e(βG, H_i) = e(G, H_{i+1}), each batched over the sample
+    let pairing_inputs: Vec<(E::G1Affine, E::G2Prepared)> = vec![
+      (combine_g(1), powers_of_h[0].into()),
+      (-combine_g(0), powers_of_h[1].into()),
+      (powers_of_g[1], combine_h(0).into()),
+      (-powers_of_g[0], combine_h(1).into()),
+    ];
+    let pairing_input_refs = pairing_inputs
+      .iter()
+      .map(|(a, b)| (a, b))
+      .collect::<Vec<_>>();
+    let pairing_result = E::multi_miller_loop(pairing_input_refs.as_slice()).final_exponentiation();
+    if !bool::from(pairing_result.is_identity()) {
+      return Err(NovaError::PCSError(PCSError::InvalidSRS));
+    }
+
+    Ok(Self {
+      powers_of_g,
+      powers_of_h,
+    })
+  }
+
+  /// Builds universal parameters from raw dumps of the powers `{ β^i G }` and `{ β^i H }`, each
+  /// stored back to back in their compressed encoding, reading only the first
+  /// `num_powers_of_g` and `num_powers_of_h` of them respectively.
+  ///
+  /// The powers are checked as in [`UVUniversalKZGParam::from_powers`].
+  pub fn from_raw_powers<R1: Read, R2: Read>(
+    g_reader: R1,
+    num_powers_of_g: usize,
+    h_reader: R2,
+    num_powers_of_h: usize,
+  ) -> Result<Self, NovaError> {
+    Self::from_powers(
+      read_points(g_reader, num_powers_of_g)?,
+      read_points(h_reader, num_powers_of_h)?,
+    )
+  }
+}
+
+/// Reads `n` points stored back to back in their compressed encoding
+fn read_points<A: GroupEncoding, R: Read>(mut reader: R, n: usize) -> Result<Vec<A>, NovaError> {
+  (0..n)
+    .map(|_| {
+      let mut repr = A::Repr::default();
+      reader
+        .read_exact(repr.as_mut())
+        .map_err(|_| NovaError::PCSError(PCSError::InvalidSRS))?;
+      Option::from(A::from_bytes(&repr)).ok_or(NovaError::PCSError(PCSError::InvalidSRS))
+    })
+    .collect()
+}
+
 /// Commitments
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
 #[serde(bound(
@@ -205,6 +315,21 @@ where
     ]
     .concat()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<<E::G1 as Group>::Scalar> {
+    let (x, y, is_infinity) = self.0.to_curve().to_coordinates();
+    let is_infinity_scalar = if is_infinity {
+      <E::G1 as Group>::Scalar::ZERO
+    } else {
+      <E::G1 as Group>::Scalar::ONE
+    };
+    [
+      x.to_transcript_scalars(),
+      y.to_transcript_scalars(),
+      vec![is_infinity_scalar],
+    ]
+    .concat()
+  }
 }
 
 /// Polynomial Evaluation
@@ -310,6 +435,66 @@ where
     let pairing_result = E::multi_miller_loop(pairing_input_refs.as_slice()).final_exponentiation();
     Ok(pairing_result.is_identity().into())
   }
+
+  /// Verifies that each `evaluations[i]` is the evaluation at `points[i]` of the polynomial
+  /// committed inside `commitments[i]`, by checking a random linear combination of the
+  /// pairing equations of all the proofs, which costs two pairings in total.
+  pub fn batch_verify(
+    verifier_param: impl Borrow<UVKZGVerifierKey<E>>,
+    commitments: &[UVKZGCommitment<E>],
+    points: &[E::Fr],
+    proofs: &[UVKZGProof<E>],
+    evaluations: &[UVKZGEvaluation<E>],
+  ) -> Result<bool, NovaError> {
+    let verifier_param = verifier_param.borrow();
+
+    let n = commitments.len();
+    if points.len() != n || proofs.len() != n || evaluations.len() != n {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    let rho = (0..n).map(|_| E::Fr::random(OsRng)).collect::<Vec<_>>();
+
+    // Σᵢ ρᵢ⋅(vᵢ⋅g - xᵢ⋅πᵢ - Cᵢ)
+    let lhs = {
+      let eval = rho
+        .iter()
+        .zip_eq(evaluations)
+        .map(|(rho_i, evaluation)| *rho_i * evaluation.0)
+        .sum::<E::Fr>();
+      let scalars = iter::once(eval)
+        .chain(
+          rho
+            .iter()
+            .zip_eq(points)
+            .map(|(rho_i, point)| -*rho_i * point),
+        )
+        .chain(rho.iter().map(|rho_i| -*rho_i))
+        .collect::<Vec<_>>();
+      let bases = iter::once(verifier_param.g)
+        .chain(proofs.iter().map(|proof| proof.proof))
+        .chain(commitments.iter().map(|commitment| commitment.0))
+        .collect::<Vec<_>>();
+      <E::G1 as DlogGroup>::vartime_multiscalar_mul(&scalars, &bases).to_affine()
+    };
+
+    // Σᵢ ρᵢ⋅πᵢ
+    let proof = {
+      let bases = proofs.iter().map(|proof| proof.proof).collect::<Vec<_>>();
+      <E::G1 as DlogGroup>::vartime_multiscalar_mul(&rho, &bases).to_affine()
+    };
+
+    let pairing_inputs: Vec<(E::G1Affine, E::G2Prepared)> = vec![
+      (lhs, verifier_param.h.into()),
+      (proof, verifier_param.beta_h.into()),
+    ];
+    let pairing_input_refs = pairing_inputs
+      .iter()
+      .map(|(a, b)| (a, b))
+      .collect::<Vec<_>>();
+    let pairing_result = E::multi_miller_loop(pairing_input_refs.as_slice()).final_exponentiation();
+    Ok(pairing_result.is_identity().into())
+  }
 }
 
 #[cfg(test)]
@@ -354,4 +539,92 @@ mod tests {
   fn end_to_end_test() {
     end_to_end_test_template::<halo2curves::bn256::Bn256>().expect("test failed for Bn256");
   }
+
+  fn batch_test_template<E>() -> Result<(), NovaError>
+  where
+    E: MultiMillerLoop,
+    E::G1: DlogGroup<PreprocessedGroupElement = E::G1Affine, Scalar = E::Fr>,
+    E::Fr: PrimeFieldBits,
+  {
+    let mut rng = &mut thread_rng();
+    let degree = 16;
+    let num_proofs = 10;
+
+    let pp = UVUniversalKZGParam::<E>::gen_srs_for_testing(&mut rng, degree);
+    let (ck, vk) = pp.trim(degree);
+
+    let mut comms = Vec::new();
+    let mut points = Vec::new();
+    let mut proofs = Vec::new();
+    let mut values = Vec::new();
+    for _ in 0..num_proofs {
+      let p = random(rng.gen_range(2..degree), rng);
+      let point = E::Fr::random(&mut rng);
+      let (proof, value) = UVKZGPCS::<E>::open(&ck, &p, &point)?;
+      comms.push(UVKZGPCS::<E>::commit(&ck, &p)?);
+      points.push(point);
+      proofs.push(proof);
+      values.push(value);
+    }
+    assert!(UVKZGPCS::<E>::batch_verify(
+      &vk, &comms, &points, &proofs, &values
+    )?);
+
+    // a single wrong evaluation makes the batch fail
+    values[3].0 += E::Fr::ONE;
+    assert!(!UVKZGPCS::<E>::batch_verify(
+      &vk, &comms, &points, &proofs, &values
+    )?);
+    Ok(())
+  }
+
+  #[test]
+  fn batch_test() {
+    batch_test_template::<halo2curves::bn256::Bn256>().expect("test failed for Bn256");
+  }
+
+  #[test]
+  fn test_load_srs() {
+    type E = halo2curves::bn256::Bn256;
+
+    let pp = UVUniversalKZGParam::<E>::gen_srs_for_testing(&mut thread_rng(), 15);
+    let loaded =
+      UVUniversalKZGParam::<E>::from_powers(pp.powers_of_g.clone(), pp.powers_of_h.clone())
+        .unwrap();
+    assert_eq!(loaded, pp);
+
+    // raw dumps can be longer than what is loaded
+    let g_bytes = pp
+      .powers_of_g
+      .iter()
+      .flat_map(|p| p.to_bytes().as_ref().to_vec())
+      .collect::<Vec<_>>();
+    let h_bytes = pp
+      .powers_of_h
+      .iter()
+      .flat_map(|p| p.to_bytes().as_ref().to_vec())
+      .collect::<Vec<_>>();
+    let loaded =
+      UVUniversalKZGParam::<E>::from_raw_powers(g_bytes.as_slice(), 8, h_bytes.as_slice(), 2)
+        .unwrap();
+    assert_eq!(loaded.powers_of_g, pp.powers_of_g[..8]);
+    assert_eq!(loaded.powers_of_h, pp.powers_of_h[..2]);
+    assert!(UVUniversalKZGParam::<E>::from_raw_powers(
+      g_bytes.as_slice(),
+      17,
+      h_bytes.as_slice(),
+      2
+    )
+    .is_err());
+
+    // the last power is always checked
+    let mut powers_of_g = pp.powers_of_g.clone();
+    powers_of_g[15] = (powers_of_g[15].to_curve() + powers_of_g[0]).to_affine();
+    assert!(UVUniversalKZGParam::<E>::from_powers(powers_of_g, pp.powers_of_h.clone()).is_err());
+
+    // so are consecutive powers in G2
+    let mut powers_of_h = pp.powers_of_h.clone();
+    powers_of_h[15] = (powers_of_h[15].to_curve() + powers_of_h[0]).to_affine();
+    assert!(UVUniversalKZGParam::<E>::from_powers(pp.powers_of_g.clone(), powers_of_h).is_err());
+  }
 }
@@ -23,6 +23,7 @@ use ff::{BatchInvert, Field, PrimeField, PrimeFieldBits};
 use group::{Curve, Group as _};
 use itertools::Itertools as _;
 use pairing::{Engine, MillerLoopResult, MultiMillerLoop};
+use rand::rngs::OsRng;
 use rayon::{
   iter::IntoParallelRefIterator,
   prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator},
@@ -133,6 +134,21 @@ pub struct ZMProof<E: Engine> {
   pub ck: Vec<UVKZGCommitment<E>>,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(bound(
+  serialize = "E::G1Affine: Serialize",
+  deserialize = "E::G1Affine: Deserialize<'de>"
+))]
+/// Proofs of the evaluations of several polynomials, each at its own point
+pub struct ZMBatchProof<E: Engine> {
+  /// proof
+  pub pi: E::G1Affine,
+  /// Polynomial commitment to the random combination of the qhat of each polynomial
+  pub cqhat: UVKZGCommitment<E>,
+  /// Polynomial commitments to the qk of each polynomial
+  pub ck: Vec<Vec<UVKZGCommitment<E>>>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 /// Zeromorph Polynomial Commitment Scheme on multilinear polynomials.
 /// Note: this is non-hiding, which is why we will implement the EvaluationEngineTrait on this token struct,
@@ -258,10 +274,308 @@ where
     point: &[E::Fr],
     evaluation: &ZMEvaluation<E>,
     proof: &ZMProof<E>,
+  ) -> Result<bool, NovaError> {
+    let vk = vk.borrow();
+
+    let (scalars, bases, x) = Self::verify_scalars(vk, transcript, comm, point, evaluation, proof)?;
+    let c = <E::G1 as DlogGroup>::vartime_multiscalar_mul(&scalars, &bases).to_affine();
+
+    Ok(Self::check_opening(vk, &c, &proof.pi, &x))
+  }
+
+  /// Checks the final pairing equation e(c, -[s]₂)⋅e(π, [β]₂ - x⋅[1]₂) = 1 of an evaluation proof,
+  /// where c is the commitment reduced from the transcript
+  fn check_opening(vk: &ZMVerifierKey<E>, c: &E::G1Affine, pi: &E::G1Affine, x: &E::Fr) -> bool {
+    let pairing_inputs = [
+      (c, &(-vk.s_offset_h).into()),
+      (
+        pi,
+        &(E::G2::from(vk.vp.beta_h) - (vk.vp.h * x))
+          .to_affine()
+          .into(),
+      ),
+    ];
+
+    let pairing_result = E::multi_miller_loop(&pairing_inputs).final_exponentiation();
+    pairing_result.is_identity().into()
+  }
+
+  /// On input several polynomials `polys`, and for each of them a point and its evaluation at
+  /// that point, outputs a single proof of all the evaluations.
+  ///
+  /// As in Shplonk, the openings at different points are batched into one: the claims share the
+  /// challenges of the protocol, the lifted-degree quotients of all the polynomials are combined
+  /// with a random challenge ρ before being committed, and so are the polynomials that vanish at
+  /// x, so that the proof ends with a single KZG opening. Only the multilinear quotients are
+  /// committed per polynomial, since they are relative to its own point.
+  pub fn multi_open(
+    pp: &impl Borrow<ZMProverKey<E>>,
+    polys: &[MultilinearPolynomial<E::Fr>],
+    points: &[Vec<E::Fr>],
+    evals: &[ZMEvaluation<E>],
+    transcript: &mut impl TranscriptEngineTrait<NE>,
+  ) -> Result<ZMBatchProof<E>, NovaError> {
+    transcript.dom_sep(Self::protocol_name());
+
+    let pp = pp.borrow();
+    let m = polys.len();
+    if m == 0
+      || points.len() != m
+      || evals.len() != m
+      || polys.iter().zip_eq(points).any(|(poly, point)| {
+        poly.get_num_vars() != point.len() || pp.commit_pp.powers_of_g.len() < poly.Z.len()
+      })
+    {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    // Compute the multilinear quotients q_{i,k} of each polynomial
+    let quotients_polys = polys
+      .par_iter()
+      .zip_eq(points.par_iter())
+      .zip_eq(evals.par_iter())
+      .map(|((poly, point), _eval)| {
+        let (quotients, remainder) = quotients(poly, point);
+        debug_assert_eq!(remainder, _eval.0);
+        quotients
+          .into_iter()
+          .map(UVKZGPoly::new)
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    // Compute and absorb commitments C_{q_{i,k}} = [q_{i,k}]
+    let q_comms = quotients_polys
+      .iter()
+      .map(|quotients_polys| {
+        quotients_polys
+          .par_iter()
+          .map(|q| UVKZGPCS::commit(&pp.commit_pp, q))
+          .collect::<Result<Vec<_>, _>>()
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    q_comms
+      .iter()
+      .flatten()
+      .for_each(|c| transcript.absorb(b"quo", c));
+
+    // Get challenges y and ρ
+    let y = transcript.squeeze(b"y")?;
+    let rho = transcript.squeeze(b"rho")?;
+    let powers_of_rho = iter::successors(Some(E::Fr::ONE), |acc| Some(*acc * rho))
+      .take(m)
+      .collect::<Vec<_>>();
+
+    // Compute and absorb the commitment to \hat{q} = ∑_i ρ^i * \hat{q}_i
+    let mut q_hat = UVKZGPoly::new(Vec::new());
+    for (quotients_polys, rho_i) in quotients_polys.iter().zip_eq(powers_of_rho.iter()) {
+      let mut q_hat_i = batched_lifted_degree_quotient(y, quotients_polys);
+      q_hat_i *= rho_i;
+      q_hat += &q_hat_i;
+    }
+    let q_hat_comm = UVKZGPCS::commit(&pp.commit_pp, &q_hat)?;
+    transcript.absorb(b"q_hat", &q_hat_comm);
+
+    // Get challenges x and z
+    let x = transcript.squeeze(b"x")?;
+    let z = transcript.squeeze(b"z")?;
+
+    // f = q_hat + ∑_i ρ^i * (z * poly_i.Z + (-z * Φ_{n_i}(x) * e_i) + ∑_k (q_scalars_{i,k} * q_{i,k}))
+    let mut f = q_hat;
+    let mut constant = E::Fr::ZERO;
+    for ((((poly, point), eval), quotients_polys), rho_i) in polys
+      .iter()
+      .zip_eq(points)
+      .zip_eq(evals)
+      .zip_eq(quotients_polys)
+      .zip_eq(powers_of_rho)
+    {
+      let (eval_scalar, (degree_check_q_scalars, zmpoly_q_scalars)) =
+        eval_and_quotient_scalars(y, x, z, point);
+      let mut f_i = UVKZGPoly::new(poly.Z.clone());
+      f_i *= &z;
+      quotients_polys
+        .into_iter()
+        .zip_eq(degree_check_q_scalars)
+        .zip_eq(zmpoly_q_scalars)
+        .for_each(|((mut q, degree_check_scalar), zm_poly_scalar)| {
+          q *= &(degree_check_scalar + zm_poly_scalar);
+          f_i += &q;
+        });
+      f_i *= &rho_i;
+      f += &f_i;
+      constant += rho_i * eval_scalar * eval.0;
+    }
+    f[0] += constant;
+    debug_assert_eq!(f.evaluate(&x), E::Fr::ZERO);
+
+    // Compute and send proof commitment pi
+    let (uvproof, _uveval) = UVKZGPCS::<E>::open(&pp.open_pp, &f, &x)?;
+
+    Ok(ZMBatchProof {
+      pi: uvproof.proof,
+      cqhat: q_hat_comm,
+      ck: q_comms,
+    })
+  }
+
+  /// Verifies that each `evaluations[i]` is the evaluation at `points[i]` of the polynomial
+  /// committed inside `comms[i]`, with a proof produced by `multi_open`
+  pub fn multi_verify(
+    vk: &impl Borrow<ZMVerifierKey<E>>,
+    transcript: &mut impl TranscriptEngineTrait<NE>,
+    comms: &[ZMCommitment<E>],
+    points: &[Vec<E::Fr>],
+    evaluations: &[ZMEvaluation<E>],
+    proof: &ZMBatchProof<E>,
   ) -> Result<bool, NovaError> {
     transcript.dom_sep(Self::protocol_name());
 
     let vk = vk.borrow();
+    let m = comms.len();
+    if m == 0
+      || points.len() != m
+      || evaluations.len() != m
+      || proof.ck.len() != m
+      || proof
+        .ck
+        .iter()
+        .zip_eq(points)
+        .any(|(ck, point)| ck.len() != point.len())
+    {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    // Receive commitments [q_{i,k}]
+    proof
+      .ck
+      .iter()
+      .flatten()
+      .for_each(|c| transcript.absorb(b"quo", c));
+
+    // Challenges y and ρ
+    let y = transcript.squeeze(b"y")?;
+    let rho = transcript.squeeze(b"rho")?;
+
+    // Receive commitment C_{q}
+    transcript.absorb(b"q_hat", &proof.cqhat);
+
+    // Challenges x, z
+    let x = transcript.squeeze(b"x")?;
+    let z = transcript.squeeze(b"z")?;
+
+    // c = C_q + ∑_i ρ^i * (z * C_i + (-z * Φ_{n_i}(x) * e_i) * g + ∑_k q_scalars_{i,k} * C_{q_{i,k}})
+    let mut scalars = vec![E::Fr::ONE, E::Fr::ZERO];
+    let mut bases = vec![proof.cqhat.0, vk.vp.g];
+    let powers_of_rho = iter::successors(Some(E::Fr::ONE), |acc| Some(*acc * rho)).take(m);
+    for ((((comm, point), evaluation), ck), rho_i) in comms
+      .iter()
+      .zip_eq(points)
+      .zip_eq(evaluations)
+      .zip_eq(&proof.ck)
+      .zip_eq(powers_of_rho)
+    {
+      let (eval_scalar, (q_scalars, zmpoly_q_scalars)) = eval_and_quotient_scalars(y, x, z, point);
+      scalars[1] += rho_i * eval_scalar * evaluation.0;
+      scalars.push(rho_i * z);
+      bases.push(comm.0);
+      scalars.extend(
+        q_scalars
+          .into_iter()
+          .zip_eq(zmpoly_q_scalars)
+          .map(|(q_scalar, zm_poly_scalar)| rho_i * (q_scalar + zm_poly_scalar)),
+      );
+      bases.extend(ck.iter().map(|c| c.0));
+    }
+    let c = <E::G1 as DlogGroup>::vartime_multiscalar_mul(&scalars, &bases).to_affine();
+
+    Ok(Self::check_opening(vk, &c, &proof.pi, &x))
+  }
+
+  /// Verifies several evaluation proofs, each with its own transcript, by checking a random
+  /// linear combination of their pairing equations. Since the equation of the i-th proof is
+  /// e(cᵢ, -[s]₂)⋅e(πᵢ, [β]₂ - xᵢ⋅[1]₂) = 1, the combination costs three pairings in total.
+  pub fn batch_verify(
+    vk: &impl Borrow<ZMVerifierKey<E>>,
+    transcripts: &mut [impl TranscriptEngineTrait<NE>],
+    comms: &[ZMCommitment<E>],
+    points: &[Vec<E::Fr>],
+    evaluations: &[ZMEvaluation<E>],
+    proofs: &[&ZMProof<E>],
+  ) -> Result<bool, NovaError> {
+    let vk = vk.borrow();
+
+    let n = transcripts.len();
+    if comms.len() != n || points.len() != n || evaluations.len() != n || proofs.len() != n {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    let reduced = transcripts
+      .iter_mut()
+      .enumerate()
+      .map(|(i, transcript)| {
+        Self::verify_scalars(
+          vk,
+          transcript,
+          &comms[i],
+          &points[i],
+          &evaluations[i],
+          proofs[i],
+        )
+      })
+      .collect::<Result<Vec<_>, NovaError>>()?;
+
+    let rho = (0..n).map(|_| E::Fr::random(OsRng)).collect::<Vec<_>>();
+
+    // Σᵢ ρᵢ⋅cᵢ
+    let c = {
+      let (scalars, bases): (Vec<_>, Vec<_>) = reduced
+        .iter()
+        .zip_eq(rho.iter())
+        .flat_map(|((scalars, bases, _), rho_i)| {
+          scalars
+            .iter()
+            .map(move |scalar| *rho_i * scalar)
+            .zip_eq(bases.iter().copied())
+        })
+        .unzip();
+      <E::G1 as DlogGroup>::vartime_multiscalar_mul(&scalars, &bases).to_affine()
+    };
+
+    // Σᵢ ρᵢ⋅πᵢ and -Σᵢ ρᵢ⋅xᵢ⋅πᵢ
+    let pis = proofs.iter().map(|proof| proof.pi).collect::<Vec<_>>();
+    let pi = <E::G1 as DlogGroup>::vartime_multiscalar_mul(&rho, &pis).to_affine();
+    let pi_x = {
+      let scalars = reduced
+        .iter()
+        .zip_eq(rho.iter())
+        .map(|((_, _, x), rho_i)| -*rho_i * x)
+        .collect::<Vec<_>>();
+      <E::G1 as DlogGroup>::vartime_multiscalar_mul(&scalars, &pis).to_affine()
+    };
+
+    let pairing_inputs = [
+      (&c, &(-vk.s_offset_h).into()),
+      (&pi, &vk.vp.beta_h.into()),
+      (&pi_x, &vk.vp.h.into()),
+    ];
+
+    let pairing_result = E::multi_miller_loop(&pairing_inputs).final_exponentiation();
+    Ok(pairing_result.is_identity().into())
+  }
+
+  /// Replays the transcript of an evaluation proof, returning the scalars and bases of the
+  /// multi-scalar multiplication of the commitment `c` checked by the final pairing equation,
+  /// together with the evaluation challenge `x`
+  fn verify_scalars(
+    vk: &ZMVerifierKey<E>,
+    transcript: &mut impl TranscriptEngineTrait<NE>,
+    comm: &ZMCommitment<E>,
+    point: &[E::Fr],
+    evaluation: &ZMEvaluation<E>,
+    proof: &ZMProof<E>,
+  ) -> Result<(Vec<E::Fr>, Vec<E::G1Affine>, E::Fr), NovaError> {
+    transcript.dom_sep(Self::protocol_name());
 
     // Receive commitments [q_k]
     proof.ck.iter().for_each(|c| transcript.absorb(b"quo", c));
@@ -290,22 +604,8 @@ where
       proof.ck.iter().map(|c| c.0).collect(),
     ]
     .concat();
-    let c = <E::G1 as DlogGroup>::vartime_multiscalar_mul(&scalars, &bases).to_affine();
 
-    let pi = proof.pi;
-
-    let pairing_inputs = [
-      (&c, &(-vk.s_offset_h).into()),
-      (
-        &pi,
-        &(E::G2::from(vk.vp.beta_h) - (vk.vp.h * x))
-          .to_affine()
-          .into(),
-      ),
-    ];
-
-    let pairing_result = E::multi_miller_loop(&pairing_inputs).final_exponentiation();
-    Ok(pairing_result.is_identity().into())
+    Ok((scalars, bases, x))
   }
 }
 
@@ -473,6 +773,7 @@ where
   type VerifierKey = ZMVerifierKey<E>;
 
   type EvaluationArgument = ZMProof<E>;
+  type BatchEvaluationArgument = ZMBatchProof<E>;
 
   fn setup(ck: &UVUniversalKZGParam<E>) -> (Self::ProverKey, Self::VerifierKey) {
     trim(ck, ck.length() - 1)
@@ -510,6 +811,75 @@ where
     }
     Ok(())
   }
+
+  /// Proves the evaluations natively with `ZMPCS::multi_open`, which needs no reduction of the
+  /// points to a common one
+  fn prove_batch(
+    _ck: &UVUniversalKZGParam<E>,
+    pk: &Self::ProverKey,
+    transcript: &mut NE::TE,
+    _comms: &[Commitment<NE>],
+    polys: &[Vec<NE::Scalar>],
+    points: &[Vec<NE::Scalar>],
+    evals: &[NE::Scalar],
+  ) -> Result<Self::BatchEvaluationArgument, NovaError> {
+    let polynomials = polys
+      .iter()
+      .map(|poly| MultilinearPolynomial::new(poly.clone()))
+      .collect::<Vec<_>>();
+    let evaluations = evals
+      .iter()
+      .map(|eval| ZMEvaluation(*eval))
+      .collect::<Vec<_>>();
+
+    ZMPCS::multi_open(pk, &polynomials, points, &evaluations, transcript)
+  }
+
+  fn verify_batch(
+    vk: &Self::VerifierKey,
+    transcript: &mut NE::TE,
+    comms: &[Commitment<NE>],
+    points: &[Vec<NE::Scalar>],
+    evals: &[NE::Scalar],
+    arg: &Self::BatchEvaluationArgument,
+  ) -> Result<(), NovaError> {
+    let commitments = comms
+      .iter()
+      .map(|comm| ZMCommitment::from(UVKZGCommitment::from(*comm)))
+      .collect::<Vec<_>>();
+    let evaluations = evals
+      .iter()
+      .map(|eval| ZMEvaluation(*eval))
+      .collect::<Vec<_>>();
+
+    if !ZMPCS::multi_verify(vk, transcript, &commitments, points, &evaluations, arg)? {
+      return Err(NovaError::UnSat);
+    }
+    Ok(())
+  }
+
+  fn batch_verify(
+    vk: &Self::VerifierKey,
+    transcripts: &mut [NE::TE],
+    comms: &[Commitment<NE>],
+    points: &[Vec<NE::Scalar>],
+    evals: &[NE::Scalar],
+    args: &[&Self::EvaluationArgument],
+  ) -> Result<(), NovaError> {
+    let commitments = comms
+      .iter()
+      .map(|comm| ZMCommitment::from(UVKZGCommitment::from(*comm)))
+      .collect::<Vec<_>>();
+    let evaluations = evals
+      .iter()
+      .map(|eval| ZMEvaluation(*eval))
+      .collect::<Vec<_>>();
+
+    if !ZMPCS::batch_verify(vk, transcripts, &commitments, points, &evaluations, args)? {
+      return Err(NovaError::UnSat);
+    }
+    Ok(())
+  }
 }
 
 #[cfg(test)]
@@ -534,10 +904,13 @@ mod test {
         batched_lifted_degree_quotient, eval_and_quotient_scalars, trim, ZMEvaluation, ZMPCS,
       },
       traits::DlogGroup,
-      Bn256Engine,
+      Bn256Engine, Bn256EngineZM,
     },
     spartan::polys::multilinear::MultilinearPolynomial,
-    traits::{Engine as NovaEngine, Group, TranscriptEngineTrait, TranscriptReprTrait},
+    traits::{
+      commitment::CommitmentEngineTrait, evaluation::EvaluationEngineTrait, Engine as NovaEngine,
+      Group, TranscriptEngineTrait, TranscriptReprTrait,
+    },
   };
 
   fn commit_open_verify_with<E: MultiMillerLoop, NE: NovaEngine<GE = E::G1, Scalar = E::Fr>>()
@@ -597,6 +970,161 @@ mod test {
     commit_open_verify_with::<Bn256, Bn256Engine>();
   }
 
+  fn batch_verify_with<E: MultiMillerLoop, NE: NovaEngine<GE = E::G1, Scalar = E::Fr>>()
+  where
+    E::G1: DlogGroup<PreprocessedGroupElement = E::G1Affine, Scalar = E::Fr>,
+    <E::G1 as Group>::Base: TranscriptReprTrait<E::G1>, // Note: due to the move of the bound TranscriptReprTrait<G> on G::Base from Group to Engine
+    E::Fr: PrimeFieldBits,
+  {
+    let num_vars = 8;
+    let num_proofs = 6;
+    let mut rng = thread_rng();
+    let universal_setup =
+      UVUniversalKZGParam::<E>::gen_srs_for_testing(&mut rng, 1 << (num_vars + 2));
+    let (pp, vk) = trim(&universal_setup, 1 << (num_vars + 1));
+
+    let mut comms = Vec::new();
+    let mut points = Vec::new();
+    let mut evals = Vec::new();
+    let mut proofs = Vec::new();
+    for _ in 0..num_proofs {
+      let poly = MultilinearPolynomial::<E::Fr>::random(num_vars, &mut rng);
+      let comm = ZMPCS::<E, NE>::commit(&pp, &poly).unwrap();
+      let point = (0..num_vars)
+        .map(|_| E::Fr::random(&mut rng))
+        .collect::<Vec<_>>();
+      let eval = ZMEvaluation(poly.evaluate(&point));
+
+      let mut transcript = Keccak256Transcript::<NE>::new(b"test");
+      let proof = ZMPCS::open(&pp, &comm, &poly, &point, &eval, &mut transcript).unwrap();
+
+      comms.push(comm);
+      points.push(point);
+      evals.push(eval);
+      proofs.push(proof);
+    }
+    let proofs = proofs.iter().collect::<Vec<_>>();
+
+    let mut transcripts = vec![Keccak256Transcript::<NE>::new(b"test"); num_proofs];
+    assert!(
+      ZMPCS::<E, NE>::batch_verify(&vk, &mut transcripts, &comms, &points, &evals, &proofs)
+        .unwrap()
+    );
+
+    // a single wrong evaluation makes the batch fail
+    evals[2].0 += E::Fr::ONE;
+    let mut transcripts = vec![Keccak256Transcript::<NE>::new(b"test"); num_proofs];
+    assert!(
+      !ZMPCS::<E, NE>::batch_verify(&vk, &mut transcripts, &comms, &points, &evals, &proofs)
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn test_batch_verify() {
+    batch_verify_with::<Bn256, Bn256Engine>();
+  }
+
+  #[test]
+  fn test_prove_verify_batch() {
+    type E = Bn256;
+    type NE = Bn256EngineZM;
+
+    let max_vars = 8;
+    let mut rng = thread_rng();
+    let ck = <NE as NovaEngine>::CE::setup(b"test", 1 << max_vars);
+    let (pk, vk) = ZMPCS::<E, NE>::setup(&ck);
+
+    // polynomials of different sizes
+    let polys = [max_vars, 3, max_vars, 5]
+      .iter()
+      .map(|num_vars| MultilinearPolynomial::<Scalar>::random(*num_vars, &mut rng))
+      .collect::<Vec<_>>();
+    let comms = polys
+      .iter()
+      .map(|poly| <NE as NovaEngine>::CE::commit(&ck, &poly.Z))
+      .collect::<Vec<_>>();
+    let polys_Z = polys.iter().map(|poly| poly.Z.clone()).collect::<Vec<_>>();
+
+    // each polynomial evaluated at its own point, and at suffixes of a common point
+    let common_point = (0..max_vars)
+      .map(|_| Scalar::random(&mut rng))
+      .collect::<Vec<_>>();
+    let points_sets = [
+      polys
+        .iter()
+        .map(|poly| {
+          (0..poly.get_num_vars())
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>(),
+      polys
+        .iter()
+        .map(|poly| common_point[max_vars - poly.get_num_vars()..].to_vec())
+        .collect::<Vec<_>>(),
+    ];
+
+    for points in points_sets {
+      let mut evals = polys
+        .iter()
+        .zip_eq(points.iter())
+        .map(|(poly, point)| poly.evaluate(point))
+        .collect::<Vec<_>>();
+
+      let mut transcript_prover = Keccak256Transcript::<NE>::new(b"test");
+      let arg = ZMPCS::<E, NE>::prove_batch(
+        &ck,
+        &pk,
+        &mut transcript_prover,
+        &comms,
+        &polys_Z,
+        &points,
+        &evals,
+      )
+      .unwrap();
+
+      // the proof holds the quotient commitments of each polynomial, and a single opening
+      assert_eq!(
+        arg.ck.iter().map(|ck| ck.len()).collect::<Vec<_>>(),
+        polys
+          .iter()
+          .map(|poly| poly.get_num_vars())
+          .collect::<Vec<_>>()
+      );
+
+      let mut transcript_verifier = Keccak256Transcript::<NE>::new(b"test");
+      assert!(ZMPCS::<E, NE>::verify_batch(
+        &vk,
+        &mut transcript_verifier,
+        &comms,
+        &points,
+        &evals,
+        &arg
+      )
+      .is_ok());
+
+      // check both random oracles are synced, as expected
+      assert_eq!(
+        transcript_prover.squeeze(b"test"),
+        transcript_verifier.squeeze(b"test")
+      );
+
+      // a single wrong evaluation makes the argument fail
+      evals[1] += Scalar::ONE;
+      let mut transcript_verifier = Keccak256Transcript::<NE>::new(b"test");
+      assert!(ZMPCS::<E, NE>::verify_batch(
+        &vk,
+        &mut transcript_verifier,
+        &comms,
+        &points,
+        &evals,
+        &arg
+      )
+      .is_err());
+    }
+  }
+
   #[test]
   fn test_quotients() {
     // Define size parameters
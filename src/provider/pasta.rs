@@ -1,7 +1,7 @@
 //! This module implements the Nova traits for `pallas::Point`, `pallas::Scalar`, `vesta::Point`, `vesta::Scalar`.
 use crate::{
   provider::{
-    msm::cpu_best_msm,
+    msm::{cpu_best_msm, cpu_best_msm_small},
     traits::{CompressedGroup, DlogGroup},
   },
   traits::{Group, PrimeFieldExt, TranscriptReprTrait},
@@ -93,6 +93,18 @@ macro_rules! impl_traits {
         cpu_best_msm(scalars, bases)
       }
 
+      #[tracing::instrument(
+        skip_all,
+        level = "trace",
+        name = "<_ as Group>::vartime_multiscalar_mul_small"
+      )]
+      fn vartime_multiscalar_mul_small(
+        scalars: &[u64],
+        bases: &[Self::PreprocessedGroupElement],
+      ) -> Self {
+        cpu_best_msm_small(scalars, bases)
+      }
+
       fn preprocessed(&self) -> Self::PreprocessedGroupElement {
         self.to_affine()
       }
@@ -186,6 +198,10 @@ macro_rules! impl_traits {
       fn to_transcript_bytes(&self) -> Vec<u8> {
         self.to_repr().to_vec()
       }
+
+      fn to_transcript_scalars(&self) -> Vec<G::Scalar> {
+        crate::traits::field_to_transcript_scalars(self)
+      }
     }
   };
 }
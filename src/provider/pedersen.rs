@@ -3,22 +3,36 @@ use crate::{
   errors::NovaError,
   provider::traits::{CompressedGroup, DlogGroup},
   traits::{
-    commitment::{CommitmentEngineTrait, CommitmentTrait, Len},
+    bytes_to_scalars,
+    commitment::{CommitmentEngineTrait, CommitmentTrait, HidingCommitmentEngineTrait, Len},
     AbsorbInROTrait, Engine, ROTrait, TranscriptReprTrait,
   },
 };
 use abomonation_derive::Abomonation;
+use bitvec::field::BitField as _;
 use core::{
   fmt::Debug,
   marker::PhantomData,
   ops::{Add, Mul, MulAssign},
 };
-use ff::Field;
+use ff::{Field, PrimeField, PrimeFieldBits};
+use itertools::Itertools as _;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// The largest window size, in bits, of the fixed-base tables of a [`CommitmentKey`]
+pub const MAX_TABLE_WINDOW_SIZE: usize = 16;
+
 /// A type that holds commitment generators
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Abomonation)]
+///
+/// A key can optionally hold fixed-base tables, precomputed with
+/// [`CommitmentEngineTrait::precompute_tables`], that make commitments faster at the cost of
+/// memory. They are persisted by Abomonation, but not by serde, so that they do not change the
+/// digest of the public parameters that hold the key, and they are ignored when comparing keys.
+/// The same goes for the generator that blinds hiding commitments, which is derived again when a
+/// key is deserialized. The `commit_tables` benchmark compares commitments with tables of each
+/// window size to commitments without them.
+#[derive(Debug, Serialize, Deserialize, Abomonation)]
 #[abomonation_omit_bounds]
 pub struct CommitmentKey<E>
 where
@@ -27,6 +41,29 @@ where
 {
   #[abomonate_with(Vec<[u64; 8]>)] // this is a hack; we just assume the size of the element.
   ck: Vec<<E::GE as DlogGroup>::PreprocessedGroupElement>,
+  // the window size of the fixed-base tables in bits, or zero if there are none
+  #[serde(skip)]
+  table_window: usize,
+  // the multiples 2^{j * table_window} * ck[i] of the generators for each window j, in the order
+  // of i and then j
+  #[serde(skip)]
+  #[abomonate_with(Vec<[u64; 8]>)] // this is a hack; we just assume the size of the element.
+  table: Vec<<E::GE as DlogGroup>::PreprocessedGroupElement>,
+  // the generator that blinds hiding commitments, sampled once rather than at each commitment
+  #[serde(skip, default = "blinding_generator::<E>")]
+  #[abomonate_with([u64; 8])] // this is a hack; we just assume the size of the element.
+  blinding: <E::GE as DlogGroup>::PreprocessedGroupElement,
+}
+
+/// Returns the generator used to blind hiding commitments, which is sampled from its own label so
+/// that it is independent of the generators of any commitment key. Keys hold a copy of it, which
+/// is left out of their serialization so that it does not change their digest
+fn blinding_generator<E>() -> <E::GE as DlogGroup>::PreprocessedGroupElement
+where
+  E: Engine,
+  E::GE: DlogGroup,
+{
+  E::GE::from_label(b"blinding", 1).pop().unwrap()
 }
 
 /// [CommitmentKey]s are often large, and this helps with cloning bottlenecks
@@ -38,8 +75,59 @@ where
   fn clone(&self) -> Self {
     Self {
       ck: self.ck.par_iter().cloned().collect(),
+      table_window: self.table_window,
+      table: self.table.par_iter().cloned().collect(),
+      blinding: self.blinding.clone(),
+    }
+  }
+}
+
+/// Keys are equal when their generators are, whether or not they hold tables
+impl<E> PartialEq for CommitmentKey<E>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+{
+  fn eq(&self, other: &Self) -> bool {
+    self.ck == other.ck
+  }
+}
+
+impl<E> Eq for CommitmentKey<E>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+{
+}
+
+impl<E> CommitmentKey<E>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+{
+  fn new(ck: Vec<<E::GE as DlogGroup>::PreprocessedGroupElement>) -> Self {
+    Self {
+      ck,
+      table_window: 0,
+      table: Vec::new(),
+      blinding: blinding_generator::<E>(),
     }
   }
+
+  // a key with the generators `ck`, without tables, that shares the blinding generator of `self`
+  fn derive(&self, ck: Vec<<E::GE as DlogGroup>::PreprocessedGroupElement>) -> Self {
+    Self {
+      ck,
+      table_window: 0,
+      table: Vec::new(),
+      blinding: self.blinding.clone(),
+    }
+  }
+
+  // the number of windows of `window_size` bits that cover a scalar
+  fn num_windows(window_size: usize) -> usize {
+    (E::Scalar::NUM_BITS as usize + window_size - 1) / window_size
+  }
 }
 
 impl<E> Len for CommitmentKey<E>
@@ -127,6 +215,21 @@ where
     ]
     .concat()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<E::Scalar> {
+    let (x, y, is_infinity) = self.comm.to_coordinates();
+    let is_infinity_scalar = if is_infinity {
+      E::Scalar::ZERO
+    } else {
+      E::Scalar::ONE
+    };
+    [
+      x.to_transcript_scalars(),
+      y.to_transcript_scalars(),
+      vec![is_infinity_scalar],
+    ]
+    .concat()
+  }
 }
 
 impl<E> AbsorbInROTrait<E> for Commitment<E>
@@ -154,6 +257,16 @@ where
   fn to_transcript_bytes(&self) -> Vec<u8> {
     self.comm.to_transcript_bytes()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<E::Scalar> {
+    // the coordinates of the point, as for the uncompressed commitment, so that algebraic
+    // transcripts absorb field elements rather than packed bytes; an encoding that is not a point
+    // is absorbed as packed bytes, and is rejected wherever it is decompressed
+    match Commitment::<E>::decompress(self) {
+      Ok(comm) => comm.to_transcript_scalars(),
+      Err(_) => bytes_to_scalars(&self.to_transcript_bytes()),
+    }
+  }
 }
 
 impl<E> MulAssign<E::Scalar> for Commitment<E>
@@ -224,15 +337,86 @@ where
   type Commitment = Commitment<E>;
 
   fn setup(label: &'static [u8], n: usize) -> Self::CommitmentKey {
-    Self::CommitmentKey {
-      ck: E::GE::from_label(label, n.next_power_of_two()),
-    }
+    CommitmentKey::new(E::GE::from_label(label, n.next_power_of_two()))
   }
 
   fn commit(ck: &Self::CommitmentKey, v: &[E::Scalar]) -> Self::Commitment {
     assert!(ck.ck.len() >= v.len());
+    if ck.table.is_empty() {
+      return Commitment {
+        comm: E::GE::vartime_multiscalar_mul(v, &ck.ck[..v.len()]),
+      };
+    }
+
+    // with tables, the commitment is an MSM of the windows of the scalars with the multiples of
+    // the generators. For windows of at most 10 bits, the small MSM sums them in a single pass of
+    // buckets, without doublings; larger windows are split into segments with doublings between
+    // them, as in a regular MSM with fewer bits per scalar
+    let window_size = ck.table_window;
+    let num_windows = CommitmentKey::<E>::num_windows(window_size);
+    let mut windows = vec![0u64; v.len() * num_windows];
+    windows
+      .par_chunks_mut(num_windows)
+      .enumerate()
+      .for_each(|(i, windows)| {
+        // the representation may have more bits than NUM_BITS, but these are zero
+        let bits = v[i].to_le_bits();
+        for (window, chunk) in windows
+          .iter_mut()
+          .zip_eq(bits.chunks(window_size).take(num_windows))
+        {
+          *window = chunk.load_le::<u64>();
+        }
+      });
+    Commitment {
+      comm: E::GE::vartime_multiscalar_mul_small(&windows, &ck.table[..windows.len()]),
+    }
+  }
+
+  fn commit_small(ck: &Self::CommitmentKey, v: &[u64]) -> Self::Commitment {
+    assert!(ck.ck.len() >= v.len());
+    Commitment {
+      comm: E::GE::vartime_multiscalar_mul_small(v, &ck.ck[..v.len()]),
+    }
+  }
+
+  fn precompute_tables(ck: &mut Self::CommitmentKey, window_size: usize) -> Result<(), NovaError> {
+    if window_size == 0 || window_size > MAX_TABLE_WINDOW_SIZE {
+      return Err(NovaError::InvalidTableWindowSize);
+    }
+
+    // the tables hold one multiple of each generator per window, so they are num_windows times
+    // as large as the key: for 255-bit scalars, 32 times for windows of 8 bits, and 16 times for
+    // windows of 16 bits
+    let num_windows = CommitmentKey::<E>::num_windows(window_size);
+    ck.table = ck
+      .ck
+      .par_iter()
+      .flat_map_iter(|g| {
+        let mut multiple = E::GE::vartime_multiscalar_mul_small(&[1], &[g.clone()]);
+        (0..num_windows).map(move |_| {
+          let entry = multiple.preprocessed();
+          for _ in 0..window_size {
+            multiple = multiple + multiple;
+          }
+          entry
+        })
+      })
+      .collect();
+    ck.table_window = window_size;
+    Ok(())
+  }
+}
+
+impl<E> HidingCommitmentEngineTrait<E> for CommitmentEngine<E>
+where
+  E: Engine,
+  E::GE: DlogGroup,
+{
+  fn commit_with_blind(ck: &CommitmentKey<E>, v: &[E::Scalar], r: &E::Scalar) -> Commitment<E> {
+    let comm = Self::commit(ck, v);
     Commitment {
-      comm: E::GE::vartime_multiscalar_mul(v, &ck.ck[..v.len()]),
+      comm: comm.comm + E::GE::vartime_multiscalar_mul(&[*r], &[ck.blinding.clone()]),
     }
   }
 }
@@ -272,12 +456,8 @@ where
 {
   fn split_at(&self, n: usize) -> (CommitmentKey<E>, CommitmentKey<E>) {
     (
-      CommitmentKey {
-        ck: self.ck[0..n].to_vec(),
-      },
-      CommitmentKey {
-        ck: self.ck[n..].to_vec(),
-      },
+      self.derive(self.ck[0..n].to_vec()),
+      self.derive(self.ck[n..].to_vec()),
     )
   }
 
@@ -287,7 +467,7 @@ where
       c.extend(other.ck.clone());
       c
     };
-    CommitmentKey { ck }
+    self.derive(ck)
   }
 
   // combines the left and right halves of `self` using `w1` and `w2` as the weights
@@ -303,7 +483,7 @@ where
       })
       .collect();
 
-    CommitmentKey { ck }
+    self.derive(ck)
   }
 
   /// Scales each element in `self` by `r`
@@ -315,7 +495,7 @@ where
       .map(|g| E::GE::vartime_multiscalar_mul(&[*r], &[g]).preprocessed())
       .collect();
 
-    CommitmentKey { ck: ck_scaled }
+    self.derive(ck_scaled)
   }
 
   /// reinterprets a vector of commitments as a set of generators
@@ -328,6 +508,6 @@ where
       .into_par_iter()
       .map(|i| d[i].comm.preprocessed())
       .collect();
-    Ok(CommitmentKey { ck })
+    Ok(CommitmentKey::new(ck))
   }
 }
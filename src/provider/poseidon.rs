@@ -1,5 +1,10 @@
-//! Poseidon Constants and Poseidon-based RO used in Nova
-use crate::traits::{ROCircuitTrait, ROTrait};
+//! Poseidon Constants, Poseidon-based RO and transcript used in Nova
+use crate::{
+  errors::NovaError,
+  traits::{
+    bytes_to_scalars, Engine, ROCircuitTrait, ROTrait, TranscriptEngineTrait, TranscriptReprTrait,
+  },
+};
 use abomonation::Abomonation;
 use abomonation_derive::Abomonation;
 use bellpepper_core::{
@@ -35,6 +40,11 @@ impl<Scalar: PrimeField> Default for PoseidonConstantsCircuit<Scalar> {
 }
 
 /// A Poseidon-based RO to use outside circuits
+///
+/// It is a chained hash rather than a duplex sponge: each squeeze hashes the elements absorbed
+/// since the previous squeeze, preceded by the output of that squeeze, if any, with a fresh
+/// sponge. Since the domain separation of the sponge depends on the number of elements it hashes,
+/// these are buffered until the squeeze.
 #[derive(Serialize, Deserialize, Abomonation)]
 #[abomonation_bounds(
   where
@@ -51,8 +61,6 @@ where
   #[abomonate_with(Vec<Base::Repr>)]
   state: Vec<Base>,
   constants: PoseidonConstantsCircuit<Base>,
-  num_absorbs: usize,
-  squeezed: bool,
   _p: PhantomData<Scalar>,
 }
 
@@ -65,40 +73,39 @@ where
   type CircuitRO = PoseidonROCircuit<Base>;
   type Constants = PoseidonConstantsCircuit<Base>;
 
-  fn new(constants: PoseidonConstantsCircuit<Base>, num_absorbs: usize) -> Self {
+  fn new(constants: PoseidonConstantsCircuit<Base>) -> Self {
     Self {
       state: Vec::new(),
       constants,
-      num_absorbs,
-      squeezed: false,
       _p: PhantomData,
     }
   }
 
   /// Absorb a new number into the state of the oracle
   fn absorb(&mut self, e: Base) {
-    assert!(!self.squeezed, "Cannot absorb after squeezing");
     self.state.push(e);
   }
 
   /// Compute a challenge by hashing the current state
-  fn squeeze(&mut self, num_bits: usize) -> Scalar {
-    // check if we have squeezed already
-    assert!(!self.squeezed, "Cannot squeeze again after squeezing");
-    self.squeezed = true;
+  fn squeeze(&mut self, num_bits: usize) -> Result<Scalar, NovaError> {
+    if self.state.is_empty() || num_bits > Base::CAPACITY.min(Scalar::CAPACITY) as usize {
+      return Err(NovaError::InvalidROSqueeze);
+    }
 
     let mut sponge = Sponge::new_with_constants(&self.constants.0, Simplex);
     let acc = &mut ();
-    let parameter = IOPattern(vec![
-      SpongeOp::Absorb(self.num_absorbs as u32),
-      SpongeOp::Squeeze(1u32),
-    ]);
+    let num_absorbs = self.state.len() as u32;
+    let parameter = IOPattern(vec![SpongeOp::Absorb(num_absorbs), SpongeOp::Squeeze(1u32)]);
 
     sponge.start(parameter, None, acc);
-    assert_eq!(self.num_absorbs, self.state.len());
-    SpongeAPI::absorb(&mut sponge, self.num_absorbs as u32, &self.state, acc);
+    SpongeAPI::absorb(&mut sponge, num_absorbs, &self.state, acc);
     let hash = SpongeAPI::squeeze(&mut sponge, 1, acc);
-    sponge.finish(acc).unwrap();
+    sponge
+      .finish(acc)
+      .map_err(|_| NovaError::InvalidROSqueeze)?;
+
+    // the hash is the first element hashed by the next squeeze
+    self.state = vec![hash[0]];
 
     // Only return `num_bits`
     let bits = hash[0].to_le_bits();
@@ -110,18 +117,17 @@ where
       }
       coeff += coeff;
     }
-    res
+    Ok(res)
   }
 }
 
-/// A Poseidon-based RO gadget to use inside the verifier circuit.
+/// A Poseidon-based RO gadget to use inside the verifier circuit, which computes the same chained
+/// hash as [`PoseidonRO`]
 #[derive(Serialize, Deserialize)]
 pub struct PoseidonROCircuit<Scalar: PrimeField> {
   // Internal state
   state: Vec<AllocatedNum<Scalar>>,
   constants: PoseidonConstantsCircuit<Scalar>,
-  num_absorbs: usize,
-  squeezed: bool,
 }
 
 impl<Scalar> ROCircuitTrait<Scalar> for PoseidonROCircuit<Scalar>
@@ -133,18 +139,15 @@ where
   type Constants = PoseidonConstantsCircuit<Scalar>;
 
   /// Initialize the internal state and set the poseidon constants
-  fn new(constants: PoseidonConstantsCircuit<Scalar>, num_absorbs: usize) -> Self {
+  fn new(constants: PoseidonConstantsCircuit<Scalar>) -> Self {
     Self {
       state: Vec::new(),
       constants,
-      num_absorbs,
-      squeezed: false,
     }
   }
 
   /// Absorb a new number into the state of the oracle
   fn absorb(&mut self, e: &AllocatedNum<Scalar>) {
-    assert!(!self.squeezed, "Cannot absorb after squeezing");
     self.state.push(e.clone());
   }
 
@@ -154,24 +157,22 @@ where
     mut cs: CS,
     num_bits: usize,
   ) -> Result<Vec<AllocatedBit>, SynthesisError> {
-    // check if we have squeezed already
-    assert!(!self.squeezed, "Cannot squeeze again after squeezing");
-    self.squeezed = true;
-    let parameter = IOPattern(vec![
-      SpongeOp::Absorb(self.num_absorbs as u32),
-      SpongeOp::Squeeze(1u32),
-    ]);
+    if self.state.is_empty() || num_bits > Scalar::CAPACITY as usize {
+      return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let num_absorbs = self.state.len() as u32;
+    let parameter = IOPattern(vec![SpongeOp::Absorb(num_absorbs), SpongeOp::Squeeze(1u32)]);
     let mut ns = cs.namespace(|| "ns");
 
     let hash = {
       let mut sponge = SpongeCircuit::new_with_constants(&self.constants.0, Simplex);
       let acc = &mut ns;
-      assert_eq!(self.num_absorbs, self.state.len());
 
       sponge.start(parameter, None, acc);
       neptune::sponge::api::SpongeAPI::absorb(
         &mut sponge,
-        self.num_absorbs as u32,
+        num_absorbs,
         &(0..self.state.len())
           .map(|i| Elt::Allocated(self.state[i].clone()))
           .collect::<Vec<Elt<Scalar>>>(),
@@ -179,12 +180,17 @@ where
       );
 
       let output = neptune::sponge::api::SpongeAPI::squeeze(&mut sponge, 1, acc);
-      sponge.finish(acc).unwrap();
+      sponge
+        .finish(acc)
+        .map_err(|_| SynthesisError::Unsatisfiable)?;
       output
     };
 
     let hash = Elt::ensure_allocated(&hash[0], &mut ns.namespace(|| "ensure allocated"), true)?;
 
+    // the hash is the first element hashed by the next squeeze
+    self.state = vec![hash.clone()];
+
     // return the hash as a vector of bits, truncated
     Ok(
       hash
@@ -200,16 +206,239 @@ where
   }
 }
 
+const PERSONA_TAG: &[u8] = b"NoTR";
+const DOM_SEP_TAG: &[u8] = b"NoDS";
+
+/// Provides an implementation of `TranscriptEngineTrait` using Poseidon over the scalar field
+///
+/// Objects are absorbed as their representation as scalars, given by
+/// [`TranscriptReprTrait::to_transcript_scalars`], and challenges are squeezed natively, which
+/// makes verifiers using this transcript much cheaper to express in circuits than with
+/// `Keccak256Transcript`.
+#[derive(Clone)]
+pub struct PoseidonTranscript<E: Engine> {
+  round: u16,
+  state: E::Scalar,
+  transcript: Vec<E::Scalar>,
+  constants: PoseidonConstantsCircuit<E::Scalar>,
+}
+
+// hashes the provided scalars into a single one with a sponge
+fn hash_scalars<Scalar: PrimeField>(
+  constants: &PoseidonConstantsCircuit<Scalar>,
+  input: &[Scalar],
+) -> Scalar {
+  let mut sponge = Sponge::new_with_constants(&constants.0, Simplex);
+  let acc = &mut ();
+  let parameter = IOPattern(vec![
+    SpongeOp::Absorb(input.len() as u32),
+    SpongeOp::Squeeze(1u32),
+  ]);
+
+  sponge.start(parameter, None, acc);
+  SpongeAPI::absorb(&mut sponge, input.len() as u32, input, acc);
+  let hash = SpongeAPI::squeeze(&mut sponge, 1, acc);
+  sponge.finish(acc).unwrap();
+  hash[0]
+}
+
+impl<E: Engine> TranscriptEngineTrait<E> for PoseidonTranscript<E> {
+  fn new(label: &'static [u8]) -> Self {
+    let mut transcript = Self {
+      round: 0u16,
+      state: E::Scalar::ZERO,
+      transcript: Vec::new(),
+      constants: PoseidonConstantsCircuit::default(),
+    };
+    let input = [
+      bytes_to_scalars::<E::Scalar>(PERSONA_TAG),
+      bytes_to_scalars(label),
+    ]
+    .concat();
+    transcript.state = hash_scalars(&transcript.constants, &input);
+    transcript
+  }
+
+  fn squeeze(&mut self, label: &'static [u8]) -> Result<E::Scalar, NovaError> {
+    // we gather the full input from the round, preceded by the current state of the transcript
+    let input = [
+      bytes_to_scalars(DOM_SEP_TAG),
+      vec![E::Scalar::from(u64::from(self.round)), self.state],
+      bytes_to_scalars(label),
+      std::mem::take(&mut self.transcript),
+    ]
+    .concat();
+    let output = hash_scalars(&self.constants, &input);
+
+    // update state
+    self.round = {
+      if let Some(v) = self.round.checked_add(1) {
+        v
+      } else {
+        return Err(NovaError::InternalTranscriptError);
+      }
+    };
+    self.state = output;
+
+    // squeeze out a challenge
+    Ok(output)
+  }
+
+  fn absorb<T: TranscriptReprTrait<E::GE>>(&mut self, label: &'static [u8], o: &T) {
+    self.transcript.extend(bytes_to_scalars::<E::Scalar>(label));
+    self.transcript.extend(o.to_transcript_scalars());
+  }
+
+  fn dom_sep(&mut self, bytes: &'static [u8]) {
+    self
+      .transcript
+      .extend(bytes_to_scalars::<E::Scalar>(DOM_SEP_TAG));
+    self.transcript.extend(bytes_to_scalars::<E::Scalar>(bytes));
+  }
+}
+
+// An input of a `PoseidonTranscriptCircuit`: a constant, such as a label, or an allocated scalar
+#[derive(Clone)]
+enum TranscriptInput<Scalar: PrimeField> {
+  Constant(Scalar),
+  Allocated(AllocatedNum<Scalar>),
+}
+
+/// The circuit counterpart of [`PoseidonTranscript`], which derives the same challenges from the
+/// same inputs, given as allocated scalars
+///
+/// Labels, and scalars absorbed with [`PoseidonTranscriptCircuit::absorb_constants`], are
+/// constants of the circuit.
+#[derive(Clone)]
+pub struct PoseidonTranscriptCircuit<Scalar: PrimeField> {
+  round: u16,
+  state: TranscriptInput<Scalar>,
+  transcript: Vec<TranscriptInput<Scalar>>,
+  constants: PoseidonConstantsCircuit<Scalar>,
+}
+
+impl<Scalar: PrimeField> PoseidonTranscriptCircuit<Scalar> {
+  /// Initializes the transcript, like [`PoseidonTranscript`] with the same label
+  pub fn new(label: &'static [u8]) -> Self {
+    let constants = PoseidonConstantsCircuit::default();
+    let input = [
+      bytes_to_scalars::<Scalar>(PERSONA_TAG),
+      bytes_to_scalars(label),
+    ]
+    .concat();
+    Self {
+      round: 0u16,
+      state: TranscriptInput::Constant(hash_scalars(&constants, &input)),
+      transcript: Vec::new(),
+      constants,
+    }
+  }
+
+  /// Absorbs the scalars `values` under a label, as [`PoseidonTranscript`] absorbs objects whose
+  /// representation as scalars they are
+  pub fn absorb(&mut self, label: &'static [u8], values: &[AllocatedNum<Scalar>]) {
+    self.absorb_label(label);
+    self
+      .transcript
+      .extend(values.iter().cloned().map(TranscriptInput::Allocated));
+  }
+
+  /// Absorbs scalars known when the circuit is built, such as the digest of a verifier key,
+  /// under a label
+  pub fn absorb_constants(&mut self, label: &'static [u8], values: &[Scalar]) {
+    self.absorb_label(label);
+    self
+      .transcript
+      .extend(values.iter().copied().map(TranscriptInput::Constant));
+  }
+
+  /// Adds a domain separator
+  pub fn dom_sep(&mut self, bytes: &'static [u8]) {
+    self.absorb_label(DOM_SEP_TAG);
+    self.absorb_label(bytes);
+  }
+
+  /// Returns a challenge, computed from the inputs absorbed since the previous one
+  pub fn squeeze<CS: ConstraintSystem<Scalar>>(
+    &mut self,
+    mut cs: CS,
+    label: &'static [u8],
+  ) -> Result<AllocatedNum<Scalar>, SynthesisError> {
+    let mut input = bytes_to_scalars(DOM_SEP_TAG)
+      .into_iter()
+      .map(TranscriptInput::Constant)
+      .collect::<Vec<_>>();
+    input.push(TranscriptInput::Constant(Scalar::from(u64::from(
+      self.round,
+    ))));
+    input.push(self.state.clone());
+    input.extend(
+      bytes_to_scalars(label)
+        .into_iter()
+        .map(TranscriptInput::Constant),
+    );
+    input.extend(std::mem::take(&mut self.transcript));
+
+    let input = input
+      .into_iter()
+      .map(|input| match input {
+        TranscriptInput::Constant(c) => Elt::num_from_fr::<CS>(c),
+        TranscriptInput::Allocated(v) => Elt::Allocated(v),
+      })
+      .collect::<Vec<_>>();
+    let num_absorbs = input.len() as u32;
+    let parameter = IOPattern(vec![SpongeOp::Absorb(num_absorbs), SpongeOp::Squeeze(1u32)]);
+
+    let output = {
+      let mut sponge = SpongeCircuit::new_with_constants(&self.constants.0, Simplex);
+      let acc = &mut cs.namespace(|| "hash");
+
+      sponge.start(parameter, None, acc);
+      neptune::sponge::api::SpongeAPI::absorb(&mut sponge, num_absorbs, &input, acc);
+      let output = neptune::sponge::api::SpongeAPI::squeeze(&mut sponge, 1, acc);
+      sponge
+        .finish(acc)
+        .map_err(|_| SynthesisError::Unsatisfiable)?;
+      output
+    };
+    let output = Elt::ensure_allocated(&output[0], &mut cs.namespace(|| "output"), true)?;
+
+    // update state
+    self.round = self
+      .round
+      .checked_add(1)
+      .ok_or(SynthesisError::Unsatisfiable)?;
+    self.state = TranscriptInput::Allocated(output.clone());
+
+    Ok(output)
+  }
+
+  fn absorb_label(&mut self, label: &'static [u8]) {
+    self.transcript.extend(
+      bytes_to_scalars(label)
+        .into_iter()
+        .map(TranscriptInput::Constant),
+    );
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::provider::{
-    Bn256Engine, GrumpkinEngine, PallasEngine, Secp256k1Engine, Secq256k1Engine, VestaEngine,
+    Bn256Engine, GrumpkinEngine, PallasEngine, PallasPoseidonEngine, Secp256k1Engine,
+    Secq256k1Engine, VestaEngine, VestaPoseidonEngine,
   };
   use crate::{
-    bellpepper::solver::SatisfyingAssignment, constants::NUM_CHALLENGE_BITS,
-    gadgets::utils::le_bits_to_num, traits::Engine,
+    bellpepper::solver::SatisfyingAssignment,
+    constants::NUM_CHALLENGE_BITS,
+    gadgets::utils::le_bits_to_num,
+    traits::{
+      commitment::{CommitmentEngineTrait, CommitmentTrait},
+      Engine,
+    },
   };
+  use bellpepper_core::test_cs::TestConstraintSystem;
   use ff::Field;
   use rand::rngs::OsRng;
 
@@ -226,9 +455,8 @@ mod tests {
     let mut csprng: OsRng = OsRng;
     let constants = PoseidonConstantsCircuit::<E::Scalar>::default();
     let num_absorbs = 32;
-    let mut ro: PoseidonRO<E::Scalar, E::Base> = PoseidonRO::new(constants.clone(), num_absorbs);
-    let mut ro_gadget: PoseidonROCircuit<E::Scalar> =
-      PoseidonROCircuit::new(constants, num_absorbs);
+    let mut ro: PoseidonRO<E::Scalar, E::Base> = PoseidonRO::new(constants.clone());
+    let mut ro_gadget: PoseidonROCircuit<E::Scalar> = PoseidonROCircuit::new(constants);
     let mut cs = SatisfyingAssignment::<E>::new();
     for i in 0..num_absorbs {
       let num = E::Scalar::random(&mut csprng);
@@ -239,10 +467,62 @@ mod tests {
         .unwrap();
       ro_gadget.absorb(&num_gadget);
     }
-    let num = ro.squeeze(NUM_CHALLENGE_BITS);
-    let num2_bits = ro_gadget.squeeze(&mut cs, NUM_CHALLENGE_BITS).unwrap();
-    let num2 = le_bits_to_num(&mut cs, &num2_bits).unwrap();
+    let num = ro.squeeze(NUM_CHALLENGE_BITS).unwrap();
+    let num2_bits = ro_gadget
+      .squeeze(cs.namespace(|| "squeeze"), NUM_CHALLENGE_BITS)
+      .unwrap();
+    let num2 = le_bits_to_num(cs.namespace(|| "num2"), &num2_bits).unwrap();
     assert_eq!(num.to_repr(), num2.get_value().unwrap().to_repr());
+
+    // both keep absorbing and squeezing in the same way after a squeeze
+    let num = E::Scalar::random(&mut csprng);
+    ro.absorb(num);
+    let num_gadget = AllocatedNum::alloc_infallible(cs.namespace(|| "data after squeeze"), || num);
+    ro_gadget.absorb(&num_gadget);
+    let num3 = ro.squeeze(NUM_CHALLENGE_BITS).unwrap();
+    let num4 = ro.squeeze(NUM_CHALLENGE_BITS).unwrap();
+    assert_ne!(num3, num4);
+    let num3_bits = ro_gadget
+      .squeeze(cs.namespace(|| "squeeze after absorb"), NUM_CHALLENGE_BITS)
+      .unwrap();
+    let num4_bits = ro_gadget
+      .squeeze(cs.namespace(|| "squeeze again"), NUM_CHALLENGE_BITS)
+      .unwrap();
+    let num3_gadget = le_bits_to_num(cs.namespace(|| "num3"), &num3_bits).unwrap();
+    let num4_gadget = le_bits_to_num(cs.namespace(|| "num4"), &num4_bits).unwrap();
+    assert_eq!(num3.to_repr(), num3_gadget.get_value().unwrap().to_repr());
+    assert_eq!(num4.to_repr(), num4_gadget.get_value().unwrap().to_repr());
+  }
+
+  fn test_poseidon_ro_errors_with<E: Engine>() {
+    let constants = PoseidonConstantsCircuit::<E::Scalar>::default();
+
+    // squeezing before absorbing anything is an error
+    let mut ro: PoseidonRO<E::Scalar, E::Base> = PoseidonRO::new(constants.clone());
+    assert_eq!(
+      ro.squeeze(NUM_CHALLENGE_BITS),
+      Err(NovaError::InvalidROSqueeze)
+    );
+    let mut ro_gadget: PoseidonROCircuit<E::Scalar> = PoseidonROCircuit::new(constants);
+    let mut cs = SatisfyingAssignment::<E>::new();
+    assert!(ro_gadget
+      .squeeze(cs.namespace(|| "squeeze"), NUM_CHALLENGE_BITS)
+      .is_err());
+
+    // as is squeezing more bits than the fields can hold
+    ro.absorb(E::Scalar::ONE);
+    assert_eq!(ro.squeeze(256), Err(NovaError::InvalidROSqueeze));
+    let one = AllocatedNum::alloc_infallible(cs.namespace(|| "one"), || E::Scalar::ONE);
+    ro_gadget.absorb(&one);
+    assert!(ro_gadget
+      .squeeze(cs.namespace(|| "squeeze too many bits"), 256)
+      .is_err());
+  }
+
+  #[test]
+  fn test_poseidon_ro_errors() {
+    test_poseidon_ro_errors_with::<PallasEngine>();
+    test_poseidon_ro_errors_with::<Bn256Engine>();
   }
 
   #[test]
@@ -254,4 +534,79 @@ mod tests {
     test_poseidon_ro_with::<Secp256k1Engine>();
     test_poseidon_ro_with::<Secq256k1Engine>();
   }
+
+  fn test_poseidon_transcript_with<E: Engine>() {
+    let mut transcript = PoseidonTranscript::<E>::new(b"test");
+
+    // scalars are absorbed as themselves
+    let s = E::Scalar::random(OsRng);
+    assert_eq!(
+      TranscriptReprTrait::<E::GE>::to_transcript_scalars(&s),
+      vec![s]
+    );
+    transcript.absorb(b"s", &s);
+
+    // commitments are absorbed as the coordinates of their point, whether compressed or not
+    let ck = E::CE::setup(b"test", 1);
+    let comm = E::CE::commit(&ck, &[s]);
+    assert_eq!(
+      comm.compress().to_transcript_scalars(),
+      comm.to_transcript_scalars()
+    );
+
+    // a clone squeezes the same challenges
+    let mut transcript_clone = transcript.clone();
+    let c1 = transcript.squeeze(b"c1").unwrap();
+    assert_eq!(c1, transcript_clone.squeeze(b"c1").unwrap());
+
+    // the challenges depend on what was absorbed, and on their labels
+    let mut transcript_other = PoseidonTranscript::<E>::new(b"test");
+    transcript_other.absorb(b"s", &(s + E::Scalar::ONE));
+    assert_ne!(c1, transcript_other.squeeze(b"c1").unwrap());
+    let mut transcript_other = PoseidonTranscript::<E>::new(b"test");
+    transcript_other.absorb(b"t", &s);
+    assert_ne!(c1, transcript_other.squeeze(b"c1").unwrap());
+
+    // each squeeze yields a fresh challenge
+    assert_ne!(c1, transcript.squeeze(b"c1").unwrap());
+
+    // the circuit transcript derives the same challenges from the same inputs
+    let mut transcript = PoseidonTranscript::<E>::new(b"test");
+    let mut transcript_gadget = PoseidonTranscriptCircuit::<E::Scalar>::new(b"test");
+    let mut cs = TestConstraintSystem::<E::Scalar>::new();
+    let v = (0..3).map(|_| E::Scalar::random(OsRng)).collect::<Vec<_>>();
+    let v_gadget = v
+      .iter()
+      .enumerate()
+      .map(|(i, v_i)| AllocatedNum::alloc_infallible(cs.namespace(|| format!("v {i}")), || *v_i))
+      .collect::<Vec<_>>();
+
+    transcript.absorb(b"v", &v.as_slice());
+    transcript_gadget.absorb(b"v", &v_gadget);
+    transcript.absorb(b"s", &s);
+    transcript_gadget.absorb_constants(b"s", &[s]);
+    for i in 0..2 {
+      let c = transcript.squeeze(b"c").unwrap();
+      let c_gadget = transcript_gadget
+        .squeeze(cs.namespace(|| format!("squeeze {i}")), b"c")
+        .unwrap();
+      assert_eq!(Some(c), c_gadget.get_value());
+    }
+    transcript.dom_sep(b"d");
+    transcript_gadget.dom_sep(b"d");
+    assert_eq!(
+      Some(transcript.squeeze(b"c").unwrap()),
+      transcript_gadget
+        .squeeze(cs.namespace(|| "squeeze after dom_sep"), b"c")
+        .unwrap()
+        .get_value()
+    );
+    assert!(cs.is_satisfied());
+  }
+
+  #[test]
+  fn test_poseidon_transcript() {
+    test_poseidon_transcript_with::<PallasPoseidonEngine>();
+    test_poseidon_transcript_with::<VestaPoseidonEngine>();
+  }
 }
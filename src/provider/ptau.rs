@@ -0,0 +1,238 @@
+//! This module implements loading KZG universal parameters over BN254 from the `.ptau` files
+//! output by powers-of-tau ceremonies, in the format used by snarkjs.
+//!
+//! A `.ptau` file starts with the magic bytes `ptau`, a version and a number of sections, each of
+//! which is stored as its type, its size in bytes and its contents. Those we need are the header,
+//! and the powers of tau in G1 and in G2, whose coordinates are base field elements stored in
+//! little-endian Montgomery form.
+use crate::{
+  errors::{NovaError, PCSError},
+  provider::non_hiding_kzg::UVUniversalKZGParam,
+};
+use ff::{Field, PrimeField};
+use halo2curves::{
+  bn256::{Bn256, Fq, Fq2, G1Affine, G2Affine},
+  CurveAffine,
+};
+use std::io::{Read, Seek, SeekFrom};
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+
+const HEADER_SECTION: usize = 1;
+const TAU_G1_SECTION: usize = 2;
+const TAU_G2_SECTION: usize = 3;
+
+/// The size in bytes of a base field element
+const N8: usize = 32;
+
+impl UVUniversalKZGParam<Bn256> {
+  /// Loads universal parameters from a `.ptau` file output by a powers-of-tau ceremony over
+  /// BN254, keeping the first `num_powers` powers `{ β^i G }`, and the powers `H` and `β H`
+  /// used to verify openings.
+  ///
+  /// The powers are checked as in [`UVUniversalKZGParam::from_powers`].
+  pub fn from_ptau<R: Read + Seek>(mut reader: R, num_powers: usize) -> Result<Self, NovaError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(|_| invalid_srs())?;
+    if &magic != PTAU_MAGIC {
+      return Err(invalid_srs());
+    }
+    let _version = read_u32(&mut reader)?;
+    let num_sections = read_u32(&mut reader)?;
+
+    // sections can come in any order, so we record where those we need start, and their sizes
+    let mut sections: Sections = [None; TAU_G2_SECTION + 1];
+    for _ in 0..num_sections {
+      let section_type = read_u32(&mut reader)? as usize;
+      let section_size = read_u64(&mut reader)?;
+      let start = reader.stream_position().map_err(|_| invalid_srs())?;
+      if let Some(section) = sections.get_mut(section_type) {
+        *section = Some((start, section_size));
+      }
+      let end = start.checked_add(section_size).ok_or_else(invalid_srs)?;
+      reader
+        .seek(SeekFrom::Start(end))
+        .map_err(|_| invalid_srs())?;
+    }
+
+    // the header holds the size of base field elements, the modulus, and the log of the number
+    // of powers in G2, of which there are twice as many, minus one, in G1
+    seek_section(&mut reader, &sections, HEADER_SECTION, 4 + N8 + 4)?;
+    if read_u32(&mut reader)? as usize != N8 {
+      return Err(invalid_srs());
+    }
+    let mut modulus = [0u8; N8];
+    reader.read_exact(&mut modulus).map_err(|_| invalid_srs())?;
+    if modulus.as_slice() != modulus_repr().as_ref() {
+      return Err(invalid_srs());
+    }
+    let power = read_u32(&mut reader)?;
+    let max_num_powers = 1usize.checked_shl(power + 1).ok_or_else(invalid_srs)? - 1;
+    if num_powers > max_num_powers {
+      return Err(NovaError::PCSError(PCSError::LengthError));
+    }
+
+    let r_inv = montgomery_r_inv();
+
+    seek_section(&mut reader, &sections, TAU_G1_SECTION, num_powers * 2 * N8)?;
+    let powers_of_g = (0..num_powers)
+      .map(|_| {
+        let x = read_fq(&mut reader, &r_inv)?;
+        let y = read_fq(&mut reader, &r_inv)?;
+        Option::from(G1Affine::from_xy(x, y)).ok_or_else(invalid_srs)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    seek_section(&mut reader, &sections, TAU_G2_SECTION, 2 * 4 * N8)?;
+    let powers_of_h = (0..2)
+      .map(|_| {
+        let x = read_fq2(&mut reader, &r_inv)?;
+        let y = read_fq2(&mut reader, &r_inv)?;
+        Option::from(G2Affine::from_xy(x, y)).ok_or_else(invalid_srs)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Self::from_powers(powers_of_g, powers_of_h)
+  }
+}
+
+/// The start and the size of each section we read, indexed by type
+type Sections = [Option<(u64, u64)>; TAU_G2_SECTION + 1];
+
+/// Moves `reader` to the start of a section, after checking that it holds at least `min_size` bytes
+fn seek_section<R: Seek>(
+  reader: &mut R,
+  sections: &Sections,
+  section_type: usize,
+  min_size: usize,
+) -> Result<(), NovaError> {
+  let (start, size) = sections[section_type].ok_or_else(invalid_srs)?;
+  if size < min_size as u64 {
+    return Err(invalid_srs());
+  }
+  reader
+    .seek(SeekFrom::Start(start))
+    .map_err(|_| invalid_srs())?;
+  Ok(())
+}
+
+fn invalid_srs() -> NovaError {
+  NovaError::PCSError(PCSError::InvalidSRS)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, NovaError> {
+  let mut bytes = [0u8; 4];
+  reader.read_exact(&mut bytes).map_err(|_| invalid_srs())?;
+  Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, NovaError> {
+  let mut bytes = [0u8; 8];
+  reader.read_exact(&mut bytes).map_err(|_| invalid_srs())?;
+  Ok(u64::from_le_bytes(bytes))
+}
+
+/// Returns the little-endian encoding of the modulus of the base field
+fn modulus_repr() -> <Fq as PrimeField>::Repr {
+  // the modulus is odd, so it is obtained by setting the lowest bit of the modulus minus one
+  let mut repr = (-Fq::ONE).to_repr();
+  repr.as_mut()[0] |= 1;
+  repr
+}
+
+/// Returns the inverse of the Montgomery factor R = 2^256 of the base field
+fn montgomery_r_inv() -> Fq {
+  Fq::from(2).pow_vartime([(N8 * 8) as u64]).invert().unwrap()
+}
+
+/// Reads an element of the base field stored in little-endian Montgomery form, i.e. x⋅R
+fn read_fq<R: Read>(reader: &mut R, r_inv: &Fq) -> Result<Fq, NovaError> {
+  let mut repr = <Fq as PrimeField>::Repr::default();
+  reader
+    .read_exact(repr.as_mut())
+    .map_err(|_| invalid_srs())?;
+  Option::<Fq>::from(Fq::from_repr(repr))
+    .map(|x| x * r_inv)
+    .ok_or_else(invalid_srs)
+}
+
+fn read_fq2<R: Read>(reader: &mut R, r_inv: &Fq) -> Result<Fq2, NovaError> {
+  let c0 = read_fq(reader, r_inv)?;
+  let c1 = read_fq(reader, r_inv)?;
+  Ok(Fq2 { c0, c1 })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::thread_rng;
+  use std::io::Cursor;
+
+  fn write_fq(bytes: &mut Vec<u8>, x: &Fq) {
+    let r = montgomery_r_inv().invert().unwrap();
+    bytes.extend_from_slice((*x * r).to_repr().as_ref());
+  }
+
+  fn write_section(bytes: &mut Vec<u8>, section_type: usize, contents: &[u8]) {
+    bytes.extend_from_slice(&(section_type as u32).to_le_bytes());
+    bytes.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(contents);
+  }
+
+  // writes a .ptau file holding the given powers, with 2^power powers in G2
+  fn write_ptau(power: u32, params: &UVUniversalKZGParam<Bn256>) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&(N8 as u32).to_le_bytes());
+    header.extend_from_slice(modulus_repr().as_ref());
+    header.extend_from_slice(&power.to_le_bytes());
+    header.extend_from_slice(&power.to_le_bytes());
+
+    let mut tau_g1 = Vec::new();
+    for p in &params.powers_of_g {
+      let coordinates = p.coordinates().unwrap();
+      write_fq(&mut tau_g1, coordinates.x());
+      write_fq(&mut tau_g1, coordinates.y());
+    }
+    let mut tau_g2 = Vec::new();
+    for p in &params.powers_of_h {
+      let coordinates = p.coordinates().unwrap();
+      for c in [coordinates.x(), coordinates.y()] {
+        write_fq(&mut tau_g2, &c.c0);
+        write_fq(&mut tau_g2, &c.c1);
+      }
+    }
+
+    let mut bytes = PTAU_MAGIC.to_vec();
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&4u32.to_le_bytes());
+    // the sections are not in order, and include one we do not read
+    write_section(&mut bytes, TAU_G2_SECTION, &tau_g2);
+    write_section(&mut bytes, HEADER_SECTION, &header);
+    write_section(&mut bytes, 7, &[0u8; 5]);
+    write_section(&mut bytes, TAU_G1_SECTION, &tau_g1);
+    bytes
+  }
+
+  #[test]
+  fn test_from_ptau() {
+    let power = 3;
+    let params =
+      UVUniversalKZGParam::<Bn256>::gen_srs_for_testing(&mut thread_rng(), (1 << (power + 1)) - 2);
+    let mut ptau = write_ptau(power, &params);
+
+    let num_powers = 9;
+    let loaded = UVUniversalKZGParam::<Bn256>::from_ptau(Cursor::new(&ptau), num_powers).unwrap();
+    assert_eq!(loaded.powers_of_g, params.powers_of_g[..num_powers]);
+    assert_eq!(loaded.powers_of_h, params.powers_of_h[..2]);
+
+    // there are 2^(power + 1) - 1 powers in G1
+    assert!(UVUniversalKZGParam::<Bn256>::from_ptau(Cursor::new(&ptau), 1 << (power + 1)).is_err());
+
+    // a corrupted file is rejected
+    let len = ptau.len();
+    ptau[len - 1] ^= 1;
+    assert!(
+      UVUniversalKZGParam::<Bn256>::from_ptau(Cursor::new(&ptau), (1 << (power + 1)) - 1).is_err()
+    );
+  }
+}
@@ -73,6 +73,22 @@ pub trait DlogGroup:
     bases: &[Self::PreprocessedGroupElement],
   ) -> Self;
 
+  /// A method to compute a multiexponentation with scalars that fit in a `u64`, such as bits or
+  /// small integers
+  ///
+  /// By default, the scalars are converted to field elements for `vartime_multiscalar_mul`:
+  /// groups override this when they have a cheaper multiexponentation for small scalars.
+  fn vartime_multiscalar_mul_small(
+    scalars: &[u64],
+    bases: &[Self::PreprocessedGroupElement],
+  ) -> Self {
+    let scalars = scalars
+      .iter()
+      .map(|&s| Self::Scalar::from(s))
+      .collect::<Vec<_>>();
+    Self::vartime_multiscalar_mul(&scalars, bases)
+  }
+
   /// Produce a vector of group elements using a static label
   fn from_label(label: &'static [u8], n: usize) -> Vec<Self::PreprocessedGroupElement>;
 
@@ -128,6 +144,13 @@ macro_rules! impl_traits {
         cpu_best_msm(scalars, bases)
       }
 
+      fn vartime_multiscalar_mul_small(
+        scalars: &[u64],
+        bases: &[Self::PreprocessedGroupElement],
+      ) -> Self {
+        cpu_best_msm_small(scalars, bases)
+      }
+
       fn preprocessed(&self) -> Self::PreprocessedGroupElement {
         self.to_affine()
       }
@@ -223,6 +246,10 @@ macro_rules! impl_traits {
       fn to_transcript_bytes(&self) -> Vec<u8> {
         self.to_repr().to_vec()
       }
+
+      fn to_transcript_scalars(&self) -> Vec<G::Scalar> {
+        $crate::traits::field_to_transcript_scalars(self)
+      }
     }
   };
 }
@@ -11,15 +11,18 @@ use crate::{
     nonnative::{bignat::nat_to_limbs, util::f_to_nat},
     utils::scalar_as_base,
   },
+  spartan::lookup::LookupColumn,
   traits::{
-    commitment::CommitmentEngineTrait, AbsorbInROTrait, Engine, ROTrait, TranscriptReprTrait,
+    commitment::{CommitmentEngineTrait, HidingCommitmentEngineTrait},
+    AbsorbInROTrait, Engine, ROTrait, TranscriptReprTrait,
   },
   Commitment, CommitmentKey, CE,
 };
 use abomonation::Abomonation;
 use abomonation_derive::Abomonation;
+use bitvec::field::BitField as _;
 use core::cmp::max;
-use ff::{Field, PrimeField};
+use ff::{Field, PrimeField, PrimeFieldBits};
 use once_cell::sync::OnceCell;
 
 use rayon::prelude::*;
@@ -37,6 +40,9 @@ pub struct R1CSShape<E: Engine> {
   pub(crate) A: SparseMatrix<E::Scalar>,
   pub(crate) B: SparseMatrix<E::Scalar>,
   pub(crate) C: SparseMatrix<E::Scalar>,
+  // omitted when empty, so that shapes without lookups keep their encoding and digest
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) lookups: Vec<LookupColumn<E::Scalar>>,
   #[serde(skip, default = "OnceCell::new")]
   #[abomonate_with(<E::Scalar as PrimeField>::Repr)]
   pub(crate) digest: OnceCell<E::Scalar>,
@@ -73,6 +79,19 @@ pub struct RelaxedR1CSWitness<E: Engine> {
   pub(crate) E: Vec<E::Scalar>,
 }
 
+/// A type that holds the blinds of hiding commitments to the `W` and `E` of a relaxed R1CS
+/// witness, which folding combines like the commitments
+///
+/// Witnesses do not hold their blinds, so that the non-hiding folding scheme is unchanged:
+/// protocols that commit with a [`HidingCommitmentEngineTrait`] fold the blinds along with the
+/// witness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RelaxedR1CSBlinds<E: Engine> {
+  pub(crate) r_W: E::Scalar,
+  pub(crate) r_E: E::Scalar,
+}
+
 /// A type that holds a Relaxed R1CS instance
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound = "")]
@@ -110,7 +129,57 @@ pub fn commitment_key_size<E: Engine>(S: &R1CSShape<E>, ck_floor: &CommitmentKey
   let num_cons = S.num_cons;
   let num_vars = S.num_vars;
   let ck_hint = ck_floor(S);
-  max(max(num_cons, num_vars), ck_hint)
+  let lookups_size = S
+    .lookups
+    .iter()
+    .map(|column| column.size())
+    .max()
+    .unwrap_or(0);
+  max(max(max(num_cons, num_vars), ck_hint), lookups_size)
+}
+
+/// Commits to `v`, going through [`CommitmentEngineTrait::commit_small`] for its entries that fit
+/// in a `u64`, as is the case of most witness entries, which are bits or small integers.
+///
+/// When only some entries are small, the commitment is the sum of a commitment to the small
+/// entries, with the others set to zero, and of a commitment to the other entries, so that a few
+/// large entries do not make all the others go through the general multi-scalar multiplication.
+fn commit_detecting_small<E: Engine>(ck: &CommitmentKey<E>, v: &[E::Scalar]) -> Commitment<E> {
+  let small = to_small_scalars(v);
+  let num_large = small.iter().filter(|s| s.is_none()).count();
+  if num_large == v.len() {
+    return CE::<E>::commit(ck, v);
+  }
+
+  let v_small = small.iter().map(|s| s.unwrap_or(0)).collect::<Vec<_>>();
+  if num_large == 0 {
+    return CE::<E>::commit_small(ck, &v_small);
+  }
+
+  let v_large = v
+    .par_iter()
+    .zip_eq(&small)
+    .map(|(s, small)| if small.is_some() { E::Scalar::ZERO } else { *s })
+    .collect::<Vec<_>>();
+  let (comm_small, comm_large) = rayon::join(
+    || CE::<E>::commit_small(ck, &v_small),
+    || CE::<E>::commit(ck, &v_large),
+  );
+  comm_small + comm_large
+}
+
+/// Returns each entry of `v` as a `u64` if it fits in one, and `None` otherwise.
+///
+/// The entries are read from their little-endian bits rather than from their representation,
+/// whose endianness is left to each field.
+fn to_small_scalars<F: PrimeFieldBits>(v: &[F]) -> Vec<Option<u64>> {
+  v.par_iter()
+    .map(|s| {
+      let bits = s.to_le_bits();
+      let (low, high) = bits.split_at(64);
+      high.not_any().then(|| low.load_le::<u64>())
+    })
+    .collect()
 }
 
 impl<E: Engine> R1CSShape<E> {
@@ -155,10 +224,26 @@ impl<E: Engine> R1CSShape<E> {
       A,
       B,
       C,
+      lookups: Vec::new(),
       digest: OnceCell::new(),
     })
   }
 
+  /// Declares columns of the witness whose entries must be entries of lookup tables, which the
+  /// SNARKs of `spartan::snark` and `spartan::ppsnark` prove along with the R1CS. Returns an
+  /// error if a column is not within the witness.
+  pub fn with_lookups(mut self, lookups: Vec<LookupColumn<E::Scalar>>) -> Result<Self, NovaError> {
+    if lookups
+      .iter()
+      .any(|column| column.range().end > self.num_vars)
+    {
+      return Err(NovaError::InvalidInputLength);
+    }
+    self.lookups = lookups;
+    self.digest = OnceCell::new();
+    Ok(self)
+  }
+
   /// returned the digest of the `R1CSShape`
   pub fn digest(&self) -> E::Scalar {
     self
@@ -315,7 +400,7 @@ impl<E: Engine> R1CSShape<E> {
     res_eq?;
 
     // verify if comm_W is a commitment to W
-    if U.comm_W != CE::<E>::commit(ck, &W.W) {
+    if U.comm_W != W.commit(ck) {
       return Err(NovaError::UnSat);
     }
     Ok(())
@@ -368,7 +453,7 @@ impl<E: Engine> R1CSShape<E> {
         .collect::<Vec<E::Scalar>>()
     });
 
-    let comm_T = CE::<E>::commit(ck, &T);
+    let comm_T = commit_detecting_small(ck, &T);
 
     Ok((T, comm_T))
   }
@@ -420,7 +505,31 @@ impl<E: Engine> R1CSShape<E> {
         .collect_into_vec(T)
     });
 
-    Ok(CE::<E>::commit(ck, T))
+    Ok(commit_detecting_small(ck, T))
+  }
+
+  /// A method to compute the cross-term `T` of two Relaxed R1CS instance-witness pairs,
+  /// which allows folding them with [`RelaxedR1CSInstance::fold_relaxed`] and
+  /// [`RelaxedR1CSWitness::fold_relaxed`]
+  pub(crate) fn compute_T_relaxed(
+    &self,
+    U1: &RelaxedR1CSInstance<E>,
+    W1: &RelaxedR1CSWitness<E>,
+    U2: &RelaxedR1CSInstance<E>,
+    W2: &RelaxedR1CSWitness<E>,
+  ) -> Result<Vec<E::Scalar>, NovaError> {
+    let (res_1, res_2) = rayon::join(
+      || self.multiply_witness(&W1.W, &U1.u, &U1.X),
+      || self.multiply_witness(&W2.W, &U2.u, &U2.X),
+    );
+    let ((AZ_1, BZ_1, CZ_1), (AZ_2, BZ_2, CZ_2)) = (res_1?, res_2?);
+
+    Ok(
+      (0..AZ_1.len())
+        .into_par_iter()
+        .map(|i| AZ_1[i] * BZ_2[i] + AZ_2[i] * BZ_1[i] - U1.u * CZ_2[i] - U2.u * CZ_1[i])
+        .collect(),
+    )
   }
 
   /// Pads the `R1CSShape` so that the number of variables is a power of two
@@ -444,6 +553,7 @@ impl<E: Engine> R1CSShape<E> {
         A: self.A.clone(),
         B: self.B.clone(),
         C: self.C.clone(),
+        lookups: self.lookups.clone(),
         digest: OnceCell::new(),
       };
     }
@@ -480,6 +590,7 @@ impl<E: Engine> R1CSShape<E> {
       A: A_padded,
       B: B_padded,
       C: C_padded,
+      lookups: self.lookups.clone(),
       digest: OnceCell::new(),
     }
   }
@@ -508,7 +619,7 @@ impl<E: Engine> R1CSWitness<E> {
 
   /// Commits to the witness using the supplied generators
   pub fn commit(&self, ck: &CommitmentKey<E>) -> Commitment<E> {
-    CE::<E>::commit(ck, &self.W)
+    commit_detecting_small(ck, &self.W)
   }
 }
 
@@ -610,6 +721,35 @@ impl<E: Engine> RelaxedR1CSWitness<E> {
     Ok(())
   }
 
+  /// Folds an incoming `RelaxedR1CSWitness` into the current one, given the cross-term `T`
+  /// computed with [`R1CSShape::compute_T_relaxed`]
+  pub(crate) fn fold_relaxed(
+    &self,
+    W2: &RelaxedR1CSWitness<E>,
+    T: &[E::Scalar],
+    r: &E::Scalar,
+  ) -> Result<RelaxedR1CSWitness<E>, NovaError> {
+    if self.W.len() != W2.W.len() || self.E.len() != W2.E.len() {
+      return Err(NovaError::InvalidWitnessLength);
+    }
+
+    let r_square = *r * *r;
+    let W = self
+      .W
+      .par_iter()
+      .zip_eq(&W2.W)
+      .map(|(a, b)| *a + *r * *b)
+      .collect::<Vec<E::Scalar>>();
+    let E = self
+      .E
+      .par_iter()
+      .zip_eq(T)
+      .zip_eq(&W2.E)
+      .map(|((a, b), c)| *a + *r * *b + r_square * *c)
+      .collect::<Vec<E::Scalar>>();
+    Ok(RelaxedR1CSWitness { W, E })
+  }
+
   /// Pads the provided witness to the correct length
   pub fn pad(&self, S: &R1CSShape<E>) -> RelaxedR1CSWitness<E> {
     let mut W = self.W.clone();
@@ -622,6 +762,69 @@ impl<E: Engine> RelaxedR1CSWitness<E> {
   }
 }
 
+impl<E: Engine> R1CSWitness<E>
+where
+  E::CE: HidingCommitmentEngineTrait<E>,
+{
+  /// Commits to the witness using the supplied generators, hiding it with the blind `r_W`
+  pub fn commit_hiding(&self, ck: &CommitmentKey<E>, r_W: &E::Scalar) -> Commitment<E> {
+    CE::<E>::commit_with_blind(ck, &self.W, r_W)
+  }
+}
+
+impl<E: Engine> RelaxedR1CSWitness<E>
+where
+  E::CE: HidingCommitmentEngineTrait<E>,
+{
+  /// Commits to the witness using the supplied generators, hiding `W` and `E` with `blinds`
+  pub fn commit_hiding(
+    &self,
+    ck: &CommitmentKey<E>,
+    blinds: &RelaxedR1CSBlinds<E>,
+  ) -> (Commitment<E>, Commitment<E>) {
+    rayon::join(
+      || CE::<E>::commit_with_blind(ck, &self.W, &blinds.r_W),
+      || CE::<E>::commit_with_blind(ck, &self.E, &blinds.r_E),
+    )
+  }
+}
+
+impl<E: Engine> RelaxedR1CSBlinds<E> {
+  /// A method to create the blinds of a relaxed witness from their values
+  pub fn new(r_W: E::Scalar, r_E: E::Scalar) -> Self {
+    Self { r_W, r_E }
+  }
+
+  /// Initializes the blinds of a relaxed witness from the blind `r_W` of the commitment to an
+  /// R1CS witness, whose error vector is zero
+  pub fn from_r1cs_blind(r_W: E::Scalar) -> Self {
+    Self::new(r_W, E::Scalar::ZERO)
+  }
+
+  /// Folds the blind `r_W2` of an incoming R1CS witness into the current ones, given the blind
+  /// `r_T` of the commitment to the cross-term, as in [`RelaxedR1CSWitness::fold`]
+  pub fn fold(&self, r_W2: &E::Scalar, r_T: &E::Scalar, r: &E::Scalar) -> Self {
+    Self::new(self.r_W + *r * r_W2, self.r_E + *r * r_T)
+  }
+
+  /// Folds the blinds of an incoming relaxed witness into the current ones, given the blind
+  /// `r_T` of the commitment to the cross-term, as in [`RelaxedR1CSWitness::fold_relaxed`]
+  pub(crate) fn fold_relaxed(&self, other: &Self, r_T: &E::Scalar, r: &E::Scalar) -> Self {
+    let r_square = *r * *r;
+    Self::new(
+      self.r_W + *r * other.r_W,
+      self.r_E + *r * r_T + r_square * other.r_E,
+    )
+  }
+}
+
+/// Produces the blinds of a relaxed witness whose commitments are not hiding
+impl<E: Engine> Default for RelaxedR1CSBlinds<E> {
+  fn default() -> Self {
+    Self::new(E::Scalar::ZERO, E::Scalar::ZERO)
+  }
+}
+
 impl<E: Engine> RelaxedR1CSInstance<E> {
   /// Produces a default `RelaxedR1CSInstance` given `R1CSGens` and `R1CSShape`
   pub fn default(_ck: &CommitmentKey<E>, S: &R1CSShape<E>) -> RelaxedR1CSInstance<E> {
@@ -692,6 +895,33 @@ impl<E: Engine> RelaxedR1CSInstance<E> {
     }
   }
 
+  /// Folds an incoming `RelaxedR1CSInstance` into the current one, given a commitment to the
+  /// cross-term computed with [`R1CSShape::compute_T_relaxed`]
+  pub(crate) fn fold_relaxed(
+    &self,
+    U2: &RelaxedR1CSInstance<E>,
+    comm_T: &Commitment<E>,
+    r: &E::Scalar,
+  ) -> RelaxedR1CSInstance<E> {
+    let r_square = *r * *r;
+    let X = self
+      .X
+      .par_iter()
+      .zip_eq(&U2.X)
+      .map(|(a, b)| *a + *r * *b)
+      .collect::<Vec<E::Scalar>>();
+    let comm_W = self.comm_W + U2.comm_W * *r;
+    let comm_E = self.comm_E + *comm_T * *r + U2.comm_E * r_square;
+    let u = self.u + *r * U2.u;
+
+    RelaxedR1CSInstance {
+      comm_W,
+      comm_E,
+      X,
+      u,
+    }
+  }
+
   /// Mutably folds an incoming `RelaxedR1CSInstance` into the current one
   pub fn fold_mut(&mut self, U2: &R1CSInstance<E>, comm_T: &Commitment<E>, r: &E::Scalar) {
     let (X2, comm_W_2) = (&U2.X, &U2.comm_W);
@@ -716,6 +946,16 @@ impl<E: Engine> TranscriptReprTrait<E::GE> for RelaxedR1CSInstance<E> {
     ]
     .concat()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<E::Scalar> {
+    [
+      self.comm_W.to_transcript_scalars(),
+      self.comm_E.to_transcript_scalars(),
+      self.u.to_transcript_scalars(),
+      self.X.as_slice().to_transcript_scalars(),
+    ]
+    .concat()
+  }
 }
 
 impl<E: Engine> AbsorbInROTrait<E> for RelaxedR1CSInstance<E> {
@@ -747,8 +987,9 @@ mod tests {
   use crate::{
     provider::{Bn256Engine, PallasEngine, Secp256k1Engine},
     r1cs::sparse::SparseMatrix,
-    traits::Engine,
+    traits::{snark::default_ck_hint, Engine},
   };
+  use rand::rngs::OsRng;
 
   fn tiny_r1cs<E: Engine>(num_vars: usize) -> R1CSShape<E> {
     let one = <E::Scalar as Field>::ONE;
@@ -831,4 +1072,107 @@ mod tests {
     test_pad_tiny_r1cs_with::<Bn256Engine>();
     test_pad_tiny_r1cs_with::<Secp256k1Engine>();
   }
+
+  fn test_commit_small_with<E: Engine>() {
+    let S = tiny_r1cs::<E>(4);
+    let ck = commitment_key(&S, &*default_ck_hint());
+
+    // witnesses of bits, bytes, and u64s
+    for W in [
+      vec![0, 1, 1, 0],
+      vec![3, 0, 255, 42],
+      vec![u64::MAX, 1 << 40, 7, 0],
+    ] {
+      let W = W.into_iter().map(E::Scalar::from).collect::<Vec<_>>();
+      assert!(to_small_scalars(&W).iter().all(Option::is_some));
+      let W = R1CSWitness::new(&S, W).unwrap();
+      assert_eq!(W.commit(&ck), CE::<E>::commit(&ck, &W.W));
+    }
+
+    // witnesses mixing small and large entries, and with large entries only
+    for W in [
+      vec![
+        -E::Scalar::ONE,
+        E::Scalar::ONE,
+        E::Scalar::ZERO,
+        E::Scalar::ONE,
+      ],
+      vec![-E::Scalar::ONE; 4],
+    ] {
+      let small = to_small_scalars(&W);
+      assert!(small[0].is_none());
+      let W = R1CSWitness::new(&S, W).unwrap();
+      assert_eq!(W.commit(&ck), CE::<E>::commit(&ck, &W.W));
+    }
+  }
+
+  #[test]
+  fn test_commit_small() {
+    test_commit_small_with::<PallasEngine>();
+    test_commit_small_with::<Bn256Engine>();
+    test_commit_small_with::<Secp256k1Engine>();
+  }
+
+  // produces an instance-witness pair for the shape `tiny_r1cs(4)` and the input `x`, whose
+  // commitment is hidden with the returned blind
+  fn hiding_tiny_r1cs_instance<E: Engine>(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+    x: u64,
+  ) -> (R1CSInstance<E>, R1CSWitness<E>, E::Scalar)
+  where
+    E::CE: HidingCommitmentEngineTrait<E>,
+  {
+    let x = E::Scalar::from(x);
+    let (x_sq, x_cu) = (x * x, x * x * x);
+    let W = R1CSWitness::new(S, vec![x_sq, x_cu, x_cu + x, E::Scalar::ZERO]).unwrap();
+    let r_W = E::Scalar::random(OsRng);
+    let X = vec![x, x_cu + x + E::Scalar::from(5u64)];
+    let U = R1CSInstance::new(S, W.commit_hiding(ck, &r_W), X).unwrap();
+    (U, W, r_W)
+  }
+
+  fn test_fold_hiding_with<E: Engine>()
+  where
+    E::CE: HidingCommitmentEngineTrait<E>,
+  {
+    let S = tiny_r1cs::<E>(4);
+    let ck = commitment_key(&S, &*default_ck_hint());
+
+    // hiding commitments with zero blinds coincide with non-hiding ones
+    let W = R1CSWitness::new(&S, vec![E::Scalar::ONE; 4]).unwrap();
+    assert_eq!(W.commit_hiding(&ck, &E::Scalar::ZERO), W.commit(&ck));
+
+    let (U1, W1, r_W1) = hiding_tiny_r1cs_instance(&ck, &S, 3);
+    let (U2, W2, r_W2) = hiding_tiny_r1cs_instance(&ck, &S, 5);
+    let r_U = RelaxedR1CSInstance::from_r1cs_instance(&ck, &S, U1);
+    let r_W = RelaxedR1CSWitness::from_r1cs_witness(&S, W1);
+    let blinds = RelaxedR1CSBlinds::from_r1cs_blind(r_W1);
+    assert_eq!(r_W.commit_hiding(&ck, &blinds), (r_U.comm_W, r_U.comm_E));
+
+    let (T, _) = S.commit_T(&ck, &r_U, &r_W, &U2, &W2).unwrap();
+    let r_T = E::Scalar::random(OsRng);
+    let comm_T = CE::<E>::commit_with_blind(&ck, &T, &r_T);
+    let r = E::Scalar::random(OsRng);
+
+    // the folded blinds open the commitments of the folded instance
+    let U = r_U.fold(&U2, &comm_T, &r);
+    let W = r_W.fold(&W2, &T, &r).unwrap();
+    let blinds = blinds.fold(&r_W2, &r_T, &r);
+    assert_eq!(W.commit_hiding(&ck, &blinds), (U.comm_W, U.comm_E));
+
+    // and so do they for relaxed instances
+    let T = S.compute_T_relaxed(&U, &W, &U, &W).unwrap();
+    let comm_T = CE::<E>::commit_with_blind(&ck, &T, &r_T);
+    let U = U.fold_relaxed(&U, &comm_T, &r);
+    let W = W.fold_relaxed(&W, &T, &r).unwrap();
+    let blinds = blinds.fold_relaxed(&blinds, &r_T, &r);
+    assert_eq!(W.commit_hiding(&ck, &blinds), (U.comm_W, U.comm_E));
+  }
+
+  #[test]
+  fn test_fold_hiding() {
+    test_fold_hiding_with::<PallasEngine>();
+    test_fold_hiding_with::<Secp256k1Engine>();
+  }
 }
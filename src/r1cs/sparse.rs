@@ -109,6 +109,24 @@ impl<F: PrimeField> SparseMatrix<F> {
     name = "SparseMatrix::multiply_vec_unchecked"
   )]
   pub fn multiply_vec_unchecked(&self, vector: &[F]) -> Vec<F> {
+    // preallocate the result vector
+    let mut sink = Vec::with_capacity(self.indptr.len() - 1);
+    self.multiply_vec_into_unchecked(vector, &mut sink);
+    sink
+  }
+
+  /// Multiply by a dense vector, writing the result into `sink` to reuse its allocation;
+  /// uses rayon to parallelize.
+  pub fn multiply_vec_into(&self, vector: &[F], sink: &mut Vec<F>) {
+    assert_eq!(self.cols, vector.len(), "invalid shape");
+
+    self.multiply_vec_into_unchecked(vector, sink);
+  }
+
+  /// Multiply by a dense vector, writing the result into `sink` to reuse its allocation;
+  /// uses rayon to parallelize.
+  /// This does not check that the shape of the matrix/vector are compatible.
+  pub fn multiply_vec_into_unchecked(&self, vector: &[F], sink: &mut Vec<F>) {
     self
       .indptr
       .par_windows(2)
@@ -118,7 +136,7 @@ impl<F: PrimeField> SparseMatrix<F> {
           .map(|(val, col_idx)| *val * vector[*col_idx])
           .sum()
       })
-      .collect()
+      .collect_into_vec(sink);
   }
 
   /// Multiply by a witness representing a dense vector; uses rayon to parallelize.
@@ -13,10 +13,10 @@ use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 
 use super::{
-  compute_eval_table_sparse,
+  absorb_subset, check_subset, compute_eval_table_sparse,
   math::Math,
   polys::{eq::EqPolynomial, multilinear::MultilinearPolynomial},
-  powers,
+  powers, select_subset,
   snark::batch_eval_prove,
   sumcheck::SumcheckProof,
   PolyEvalInstance, PolyEvalWitness,
@@ -119,6 +119,11 @@ where
     ck: &CommitmentKey<E>,
     S: Vec<&R1CSShape<E>>,
   ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
+    // the batched SNARKs prove folded instances, whose witnesses do not satisfy lookups
+    if S.iter().any(|s| !s.lookups.is_empty()) {
+      return Err(NovaError::UnsupportedLookups);
+    }
+
     let (pk_ee, vk_ee) = EE::setup(ck);
 
     let S = S.iter().map(|s| s.pad()).collect();
@@ -133,14 +138,16 @@ where
     Ok((pk, vk))
   }
 
-  fn prove(
+  fn prove_subset(
     ck: &CommitmentKey<E>,
     pk: &Self::ProverKey,
     S: Vec<&R1CSShape<E>>,
     U: &[RelaxedR1CSInstance<E>],
     W: &[RelaxedR1CSWitness<E>],
+    indices: &[usize],
   ) -> Result<Self, NovaError> {
     let num_instances = U.len();
+    check_subset(indices, num_instances)?;
     // Pad shapes and ensure their sizes are correct
     let S = S
       .iter()
@@ -164,6 +171,7 @@ where
       let num_instances_field = E::Scalar::from(num_instances as u64);
       transcript.absorb(b"n", &num_instances_field);
     }
+    absorb_subset::<E>(&mut transcript, indices);
     U.iter().for_each(|u| {
       transcript.absorb(b"U", u);
     });
@@ -212,35 +220,33 @@ where
     })
     .collect::<Vec<_>>();
 
+    // tau * (Az * Bz - uCz_E)
     let comb_func_outer =
-      |poly_A_comp: &E::Scalar,
-       poly_B_comp: &E::Scalar,
-       poly_C_comp: &E::Scalar,
-       poly_D_comp: &E::Scalar|
-       -> E::Scalar { *poly_A_comp * (*poly_B_comp * *poly_C_comp - *poly_D_comp) };
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - comps[3]) };
 
     // Sample challenge for random linear-combination of outer claims
     let outer_r = transcript.squeeze(b"out_r")?;
     let outer_r_powers = powers::<E>(&outer_r, num_instances);
 
     // Verify outer sumcheck: Az * Bz - uCz_E for each instance
-    let (sc_proof_outer, r_x, claims_outer) = SumcheckProof::prove_cubic_with_additive_term_batch(
+    let (sc_proof_outer, r_x, claims_outer) = SumcheckProof::prove_batch(
       &vec![E::Scalar::ZERO; num_instances],
       &num_rounds_x,
-      polys_tau,
-      polys_Az
-        .into_iter()
-        .map(MultilinearPolynomial::new)
-        .collect(),
-      polys_Bz
-        .into_iter()
-        .map(MultilinearPolynomial::new)
-        .collect(),
-      polys_uCz_E
-        .into_iter()
-        .map(MultilinearPolynomial::new)
-        .collect(),
+      zip_with!(
+        into_iter,
+        (polys_tau, polys_Az, polys_Bz, polys_uCz_E),
+        |poly_tau, poly_Az, poly_Bz, poly_uCz_E| {
+          vec![
+            poly_tau,
+            MultilinearPolynomial::new(poly_Az),
+            MultilinearPolynomial::new(poly_Bz),
+            MultilinearPolynomial::new(poly_uCz_E),
+          ]
+        }
+      )
+      .collect(),
       &outer_r_powers,
+      3,
       comb_func_outer,
       &mut transcript,
     )?;
@@ -253,8 +259,9 @@ where
     // Extract evaluations of Az, Bz from Sumcheck and Cz, E at r_x
     let (evals_Az_Bz_Cz, evals_E): (Vec<_>, Vec<_>) = zip_with!(
       par_iter,
-      (claims_outer[1], claims_outer[2], polys_Cz, polys_E, r_x),
-      |eval_Az, eval_Bz, poly_Cz, poly_E, r_x| {
+      (claims_outer, polys_Cz, polys_E, r_x),
+      |claims_outer, poly_Cz, poly_E, r_x| {
+        let (eval_Az, eval_Bz) = (&claims_outer[1], &claims_outer[2]);
         let (eval_Cz, eval_E) = rayon::join(
           || MultilinearPolynomial::evaluate_with(poly_Cz, r_x),
           || MultilinearPolynomial::evaluate_with(poly_E, r_x),
@@ -313,20 +320,20 @@ where
       })
       .collect::<Vec<_>>();
 
-    let comb_func = |poly_A_comp: &E::Scalar, poly_B_comp: &E::Scalar| -> E::Scalar {
-      *poly_A_comp * *poly_B_comp
-    };
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
 
-    let (sc_proof_inner, r_y, _claims_inner): (SumcheckProof<E>, Vec<E::Scalar>, (Vec<_>, Vec<_>)) =
-      SumcheckProof::prove_quad_batch(
-        &claims_inner_joint,
-        &num_rounds_y,
-        polys_ABCs,
-        polys_Z,
-        &inner_r_powers,
-        comb_func,
-        &mut transcript,
-      )?;
+    let (sc_proof_inner, r_y, _claims_inner) = SumcheckProof::prove_batch(
+      &claims_inner_joint,
+      &num_rounds_y,
+      zip_with!(into_iter, (polys_ABCs, polys_Z), |poly_ABC, poly_Z| {
+        vec![poly_ABC, poly_Z]
+      })
+      .collect(),
+      &inner_r_powers,
+      2,
+      comb_func,
+      &mut transcript,
+    )?;
 
     let r_y = num_rounds_y
       .iter()
@@ -394,8 +401,15 @@ where
     })
   }
 
-  fn verify(&self, vk: &Self::VerifierKey, U: &[RelaxedR1CSInstance<E>]) -> Result<(), NovaError> {
+  fn verify_subset(
+    &self,
+    vk: &Self::VerifierKey,
+    U: &[RelaxedR1CSInstance<E>],
+    indices: &[usize],
+  ) -> Result<(), NovaError> {
     let num_instances = U.len();
+    // the shapes of the instances being verified
+    let S = select_subset(&vk.S, indices, num_instances)?;
     let mut transcript = E::TE::new(b"BatchedRelaxedR1CSSNARK");
 
     transcript.absorb(b"vk", &vk.digest());
@@ -403,14 +417,14 @@ where
       let num_instances_field = E::Scalar::from(num_instances as u64);
       transcript.absorb(b"n", &num_instances_field);
     }
+    absorb_subset::<E>(&mut transcript, indices);
     U.iter().for_each(|u| {
       transcript.absorb(b"U", u);
     });
 
     let num_instances = U.len();
 
-    let (num_rounds_x, num_rounds_y): (Vec<_>, Vec<_>) = vk
-      .S
+    let (num_rounds_x, num_rounds_y): (Vec<_>, Vec<_>) = S
       .iter()
       .map(|s| (s.num_cons.log_2(), s.num_vars.log_2() + 1))
       .unzip();
@@ -573,7 +587,7 @@ where
     // Compute inner claim ∑ᵢ r³ⁱ⋅(Aᵢ(r_x, r_y) + r⋅Bᵢ(r_x, r_y) + r²⋅Cᵢ(r_x, r_y))⋅Zᵢ(r_y)
     let claim_inner_final_expected = zip_with!(
       iter,
-      (vk.S, r_x, r_y, evals_Z, inner_r_powers),
+      (S, r_x, r_y, evals_Z, inner_r_powers),
       |S, r_x, r_y, eval_Z, r_i| {
         let evals = multi_evaluate(&[&S.A, &S.B, &S.C], r_x, r_y);
         let eval = evals[0] + inner_r * evals[1] + inner_r_square * evals[2];
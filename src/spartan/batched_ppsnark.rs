@@ -7,6 +7,7 @@ use crate::{
   errors::NovaError,
   r1cs::{R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness},
   spartan::{
+    absorb_subset,
     math::Math,
     polys::{
       eq::EqPolynomial,
@@ -22,6 +23,7 @@ use crate::{
       InnerSumcheckInstance, MemorySumcheckInstance, OuterSumcheckInstance,
       R1CSShapeSparkCommitment, R1CSShapeSparkRepr, SumcheckEngine,
     },
+    select_subset,
     sumcheck::SumcheckProof,
     PolyEvalInstance, PolyEvalWitness,
   },
@@ -147,19 +149,13 @@ impl<E: Engine> SumcheckEngine<E> for WitnessBoundSumcheck<E> {
   }
 
   fn evaluation_points(&self) -> Vec<Vec<E::Scalar>> {
-    let comb_func = |poly_A_comp: &E::Scalar,
-                     poly_B_comp: &E::Scalar,
-                     _: &E::Scalar|
-     -> E::Scalar { *poly_A_comp * *poly_B_comp };
-
-    let (eval_point_0, eval_point_2, eval_point_3) = SumcheckProof::<E>::compute_eval_points_cubic(
-      &self.poly_masked_eq,
-      &self.poly_W,
-      &self.poly_W, // unused
-      &comb_func,
-    );
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
 
-    vec![vec![eval_point_0, eval_point_2, eval_point_3]]
+    vec![SumcheckProof::<E>::compute_eval_points(
+      &[&self.poly_masked_eq, &self.poly_W],
+      self.degree(),
+      &comb_func,
+    )]
   }
 
   fn bound(&mut self, r: &E::Scalar) {
@@ -202,7 +198,7 @@ pub struct BatchedRelaxedR1CSSNARK<E: Engine, EE: EvaluationEngineTrait<E>> {
   evals_mem_preprocessed: Vec<[E::Scalar; 7]>,
 
   // a PCS evaluation argument
-  eval_arg: EE::EvaluationArgument,
+  eval_arg: EE::BatchEvaluationArgument,
 }
 
 impl<E: Engine, EE: EvaluationEngineTrait<E>> BatchedRelaxedR1CSSNARKTrait<E>
@@ -227,6 +223,11 @@ where
     ck: &CommitmentKey<E>,
     S: Vec<&R1CSShape<E>>,
   ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
+    // the batched SNARKs prove folded instances, whose witnesses do not satisfy lookups
+    if S.iter().any(|s| !s.lookups.is_empty()) {
+      return Err(NovaError::UnsupportedLookups);
+    }
+
     for s in S.iter() {
       // check the provided commitment key meets minimal requirements
       if ck.length() < Self::ck_floor()(s) {
@@ -253,13 +254,20 @@ where
     Ok((pk, vk))
   }
 
-  fn prove(
+  fn prove_subset(
     ck: &CommitmentKey<E>,
     pk: &Self::ProverKey,
     S: Vec<&R1CSShape<E>>,
     U: &[RelaxedR1CSInstance<E>],
     W: &[RelaxedR1CSWitness<E>],
+    indices: &[usize],
   ) -> Result<Self, NovaError> {
+    let num_instances = U.len();
+
+    // Select the preprocessed shapes of the instances being proven
+    let S_repr = select_subset(&pk.S_repr, indices, num_instances)?;
+    let S_comm = select_subset(&pk.S_comm, indices, num_instances)?;
+
     // Pad shapes so that num_vars = num_cons = Nᵢ and check the sizes are correct
     let S = S
       .par_iter()
@@ -274,11 +282,9 @@ where
       .collect::<Result<Vec<_>, _>>()?;
 
     // N[i] = max(|Aᵢ|+|Bᵢ|+|Cᵢ|, 2*num_varsᵢ, num_consᵢ)
-    let N = pk.S_repr.iter().map(|s| s.N).collect::<Vec<_>>();
+    let N = S_repr.iter().map(|s| s.N).collect::<Vec<_>>();
     assert!(N.iter().all(|&Ni| Ni.is_power_of_two()));
 
-    let num_instances = U.len();
-
     // Pad [(Wᵢ,Eᵢ)] to the next power of 2 (not to Ni)
     let W = zip_with!(par_iter, (W, S), |w, s| w.pad(s)).collect::<Vec<RelaxedR1CSWitness<E>>>();
 
@@ -292,6 +298,7 @@ where
       let num_instances_field = E::Scalar::from(num_instances as u64);
       transcript.absorb(b"n", &num_instances_field);
     }
+    absorb_subset::<E>(&mut transcript, indices);
     U.iter().for_each(|u| {
       transcript.absorb(b"U", u);
     });
@@ -512,7 +519,7 @@ where
 
     let inner_sc_inst = zip_with!(
       par_iter,
-      (pk.S_repr, evals_Mz, polys_L_row_col),
+      (S_repr, evals_Mz, polys_L_row_col),
       |s_repr, eval_Mz, poly_L| {
         let [poly_L_row, poly_L_col] = poly_L;
         let c_square = c.square();
@@ -541,8 +548,7 @@ where
 
       // We start by computing oracles and auxiliary polynomials to help prove the claim
       // oracles correspond to [t_plus_r_inv_row, w_plus_r_inv_row, t_plus_r_inv_col, w_plus_r_inv_col]
-      let (comms_mem_oracles, polys_mem_oracles, mem_aux) = pk
-        .S_repr
+      let (comms_mem_oracles, polys_mem_oracles, mem_aux) = S_repr
         .iter()
         .zip_eq(polys_tau.iter())
         .zip_eq(polys_Z.iter())
@@ -584,7 +590,7 @@ where
 
       let instances = zip_with!(
         (
-          pk.S_repr.par_iter(),
+          S_repr.par_iter(),
           N.par_iter(),
           polys_mem_oracles.par_iter(),
           mem_aux.into_par_iter()
@@ -652,7 +658,7 @@ where
 
       let (evals_Cz_E, evals_mem_val_row_col): (Vec<_>, Vec<_>) = zip_with!(
         iter,
-        (polys_Az_Bz_Cz, polys_E, pk.S_repr),
+        (polys_Az_Bz_Cz, polys_E, S_repr),
         |ABCzs, poly_E, s_repr| {
           let [_, _, Cz] = ABCzs;
           let log_Ni = s_repr.N.log_2();
@@ -729,7 +735,7 @@ where
         comms_W_E,
         comms_L_row_col,
         comms_mem_oracles,
-        pk.S_comm
+        S_comm
       ),
       |Az_Bz_Cz, comms_W_E, L_row_col, mem_oracles, S_comm| {
         chain![
@@ -753,14 +759,14 @@ where
     .cloned()
     .collect::<Vec<_>>();
 
-    let w_vec = zip_with!(
+    let polys = zip_with!(
       (
         polys_Az_Bz_Cz.into_iter(),
         polys_W.into_iter(),
         polys_E.into_iter(),
         polys_L_row_col.into_iter(),
         polys_mem_oracles.into_iter(),
-        pk.S_repr.iter()
+        S_repr.iter()
       ),
       |Az_Bz_Cz, W, E, L_row_col, mem_oracles, S_repr| {
         chain![
@@ -781,7 +787,6 @@ where
       }
     )
     .flatten()
-    .map(|p| PolyEvalWitness::<E> { p })
     .collect::<Vec<_>>();
 
     evals_vec.iter().for_each(|evals| {
@@ -789,22 +794,20 @@ where
     });
     let evals_vec = evals_vec.into_iter().flatten().collect::<Vec<_>>();
 
-    let c = transcript.squeeze(b"c")?;
-
-    // Compute number of variables for each polynomial
-    let num_vars_u = w_vec.iter().map(|w| w.p.len().log_2()).collect::<Vec<_>>();
-    let u_batch =
-      PolyEvalInstance::<E>::batch_diff_size(&comms_vec, &evals_vec, &num_vars_u, rand_sc, c);
-    let w_batch = PolyEvalWitness::<E>::batch_diff_size(w_vec, c);
+    // each polynomial is evaluated at the suffix of rand_sc matching its number of variables
+    let points = polys
+      .iter()
+      .map(|p| rand_sc[rand_sc.len() - p.len().log_2()..].to_vec())
+      .collect::<Vec<_>>();
 
-    let eval_arg = EE::prove(
+    let eval_arg = EE::prove_batch(
       ck,
       &pk.pk_ee,
       &mut transcript,
-      &u_batch.c,
-      &w_batch.p,
-      &u_batch.x,
-      &u_batch.e,
+      &comms_vec,
+      &polys,
+      &points,
+      &evals_vec,
     )?;
 
     let comms_Az_Bz_Cz = comms_Az_Bz_Cz
@@ -834,12 +837,21 @@ where
     })
   }
 
-  fn verify(&self, vk: &Self::VerifierKey, U: &[RelaxedR1CSInstance<E>]) -> Result<(), NovaError> {
+  fn verify_subset(
+    &self,
+    vk: &Self::VerifierKey,
+    U: &[RelaxedR1CSInstance<E>],
+    indices: &[usize],
+  ) -> Result<(), NovaError> {
     let num_instances = U.len();
     let num_claims_per_instance = 10;
 
+    // Select the preprocessed shapes of the instances being verified
+    let S_comm = select_subset(&vk.S_comm, indices, num_instances)?;
+    let S_num_vars = select_subset(&vk.num_vars, indices, num_instances)?;
+
     // number of rounds of sum-check
-    let num_rounds = vk.S_comm.iter().map(|s| s.N.log_2()).collect::<Vec<_>>();
+    let num_rounds = S_comm.iter().map(|s| s.N.log_2()).collect::<Vec<_>>();
     let num_rounds_max = *num_rounds.iter().max().unwrap();
 
     let mut transcript = E::TE::new(b"BatchedRelaxedR1CSSNARK");
@@ -849,6 +861,7 @@ where
       let num_instances_field = E::Scalar::from(num_instances as u64);
       transcript.absorb(b"n", &num_instances_field);
     }
+    absorb_subset::<E>(&mut transcript, indices);
     U.iter().for_each(|u| {
       transcript.absorb(b"U", u);
     });
@@ -967,7 +980,7 @@ where
 
     let claim_sc_final_expected = zip_with!(
       (
-        vk.num_vars.iter(),
+        S_num_vars.iter(),
         rand_sc_i.iter(),
         U.iter(),
         self.evals_Az_Bz_Cz_W_E.iter().cloned(),
@@ -1117,17 +1130,15 @@ where
       transcript.absorb(b"e", &evals.as_slice()); // comm_vec is already in the transcript
     });
 
-    let c = transcript.squeeze(b"c")?;
-
-    // Compute batched polynomial evaluation instance at rand_sc
-    let u = {
+    // Each polynomial is evaluated at the suffix of rand_sc matching its number of variables
+    let (comms_vec, points, evals_vec) = {
       let num_evals = evals_vec[0].len();
 
       let evals_vec = evals_vec.into_iter().flatten().collect::<Vec<_>>();
 
-      let num_vars = num_rounds
+      let points = rand_sc_i
         .iter()
-        .flat_map(|num_rounds| vec![*num_rounds; num_evals].into_iter())
+        .flat_map(|rand_sc| vec![rand_sc.clone(); num_evals].into_iter())
         .collect::<Vec<_>>();
 
       let comms_vec = zip_with!(
@@ -1136,7 +1147,7 @@ where
           U.iter(),
           comms_L_row_col.into_iter(),
           comms_mem_oracles.into_iter(),
-          vk.S_comm.iter()
+          S_comm.iter()
         ),
         |Az_Bz_Cz, U, L_row_col, mem_oracles, S_comm| {
           chain![
@@ -1159,11 +1170,17 @@ where
       .flatten()
       .collect::<Vec<_>>();
 
-      PolyEvalInstance::<E>::batch_diff_size(&comms_vec, &evals_vec, &num_vars, rand_sc, c)
+      (comms_vec, points, evals_vec)
     };
 
-    // verify
-    EE::verify(&vk.vk_ee, &mut transcript, &u.c, &u.x, &u.e, &self.eval_arg)?;
+    EE::verify_batch(
+      &vk.vk_ee,
+      &mut transcript,
+      &comms_vec,
+      &points,
+      &evals_vec,
+      &self.eval_arg,
+    )?;
 
     Ok(())
   }
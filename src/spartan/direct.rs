@@ -1,6 +1,9 @@
-//! This module provides interfaces to directly prove a step circuit by using Spartan SNARK.
-//! In particular, it supports any SNARK that implements `RelaxedR1CSSNARK` trait
-//! (e.g., with the SNARKs implemented in ppsnark.rs or snark.rs).
+//! This module provides interfaces to directly prove a circuit by using Spartan SNARK, which then
+//! acts as a standalone SNARK for statements that do not need IVC.
+//! In particular, it supports any `bellpepper_core::Circuit` whose public inputs are the ones
+//! the circuit declares, and any SNARK that implements `RelaxedR1CSSNARK` trait
+//! (e.g., with the SNARKs implemented in ppsnark.rs, snark.rs, or zksnark.rs).
+//! Step circuits can be proven with the `DirectCircuit` adapter.
 use crate::{
   bellpepper::{
     r1cs::{NovaShape, NovaWitness},
@@ -8,7 +11,8 @@ use crate::{
     solver::SatisfyingAssignment,
   },
   errors::NovaError,
-  r1cs::{R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness},
+  r1cs::{commitment_key, R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness},
+  spartan::lookup::LookupColumn,
   traits::{
     circuit::StepCircuit,
     snark::{DigestHelperTrait, RelaxedR1CSSNARKTrait},
@@ -18,14 +22,23 @@ use crate::{
 };
 use bellpepper_core::{num::AllocatedNum, Circuit, ConstraintSystem, SynthesisError};
 use core::marker::PhantomData;
-use ff::Field;
+use ff::{Field, PrimeField};
 use serde::{Deserialize, Serialize};
 
-struct DirectCircuit<E: Engine, SC: StepCircuit<E::Scalar>> {
+/// A circuit that executes a step circuit on the input `z_i`, and whose public inputs are
+/// `z_i || z_{i+1}`, where `z_{i+1}` is the output of the step circuit
+pub struct DirectCircuit<E: Engine, SC: StepCircuit<E::Scalar>> {
   z_i: Option<Vec<E::Scalar>>, // inputs to the circuit
   sc: SC,                      // step circuit to be executed
 }
 
+impl<E: Engine, SC: StepCircuit<E::Scalar>> DirectCircuit<E, SC> {
+  /// Creates a circuit executing `sc` on `z_i`, which is only needed when proving
+  pub fn new(z_i: Option<Vec<E::Scalar>>, sc: SC) -> Self {
+    Self { z_i, sc }
+  }
+}
+
 impl<E: Engine, SC: StepCircuit<E::Scalar>> Circuit<E::Scalar> for DirectCircuit<E, SC> {
   fn synthesize<CS: ConstraintSystem<E::Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
     // obtain the arity information
@@ -55,6 +68,19 @@ impl<E: Engine, SC: StepCircuit<E::Scalar>> Circuit<E::Scalar> for DirectCircuit
   }
 }
 
+// R1CS shapes require an even number of public inputs, so circuits declaring an odd number of
+// them get an additional public input fixed to zero
+pub(in crate::spartan) fn pad_inputs<F: PrimeField, CS: ConstraintSystem<F>>(
+  cs: &mut CS,
+  num_io: usize,
+) -> Result<(), NovaError> {
+  if num_io % 2 != 0 {
+    cs.alloc_input(|| "padding", || Ok(F::ZERO))
+      .map_err(|_e| NovaError::SynthesisError)?;
+  }
+  Ok(())
+}
+
 /// A type that holds the prover key
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
@@ -66,6 +92,7 @@ where
   S: R1CSShape<E>,
   ck: CommitmentKey<E>,
   pk: S::ProverKey,
+  num_io: usize,
 }
 
 /// A type that holds the verifier key
@@ -77,6 +104,7 @@ where
   S: RelaxedR1CSSNARKTrait<E>,
 {
   vk: S::VerifierKey,
+  num_io: usize,
 }
 
 impl<E: Engine, S: RelaxedR1CSSNARKTrait<E>> VerifierKey<E, S> {
@@ -84,55 +112,87 @@ impl<E: Engine, S: RelaxedR1CSSNARKTrait<E>> VerifierKey<E, S> {
   pub fn digest(&self) -> E::Scalar {
     self.vk.digest()
   }
+
+  /// Returns the number of public inputs declared by the circuit
+  pub fn num_io(&self) -> usize {
+    self.num_io
+  }
 }
 
-/// A direct SNARK proving a step circuit
+/// A direct SNARK proving a circuit
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct DirectSNARK<E, S, C>
 where
   E: Engine,
   S: RelaxedR1CSSNARKTrait<E>,
-  C: StepCircuit<E::Scalar>,
+  C: Circuit<E::Scalar>,
 {
   comm_W: Commitment<E>, // commitment to the witness
   snark: S,              // snark proving the witness is satisfying
   _p: PhantomData<C>,
 }
 
-impl<E: Engine, S: RelaxedR1CSSNARKTrait<E>, C: StepCircuit<E::Scalar>> DirectSNARK<E, S, C> {
-  /// Produces prover and verifier keys for the direct SNARK
-  pub fn setup(sc: C) -> Result<(ProverKey<E, S>, VerifierKey<E, S>), NovaError> {
-    // construct a circuit that can be synthesized
-    let circuit: DirectCircuit<E, C> = DirectCircuit { z_i: None, sc };
+impl<E: Engine, S: RelaxedR1CSSNARKTrait<E>, C: Circuit<E::Scalar>> DirectSNARK<E, S, C> {
+  /// Produces prover and verifier keys for the direct SNARK, from an instance of the circuit
+  /// that need not carry any witness values
+  pub fn setup(circuit: C) -> Result<(ProverKey<E, S>, VerifierKey<E, S>), NovaError> {
+    Self::setup_with_lookups(circuit, Vec::new())
+  }
 
+  /// Produces prover and verifier keys for the direct SNARK, like [`DirectSNARK::setup`], for
+  /// the circuit along with columns of its auxiliary variables, in the order the circuit
+  /// allocates them, whose values must be entries of lookup tables
+  pub fn setup_with_lookups(
+    circuit: C,
+    lookups: Vec<LookupColumn<E::Scalar>>,
+  ) -> Result<(ProverKey<E, S>, VerifierKey<E, S>), NovaError> {
     let mut cs: ShapeCS<E> = ShapeCS::new();
-    let _ = circuit.synthesize(&mut cs);
-    let (shape, ck) = cs.r1cs_shape_and_key(&*S::ck_floor());
+    circuit
+      .synthesize(&mut cs)
+      .map_err(|_e| NovaError::SynthesisError)?;
+
+    // Don't count One as a public input
+    let num_io = cs.num_inputs() - 1;
+    pad_inputs(&mut cs, num_io)?;
+    let shape = cs.r1cs_shape().with_lookups(lookups)?;
+    let ck = commitment_key(&shape, &*S::ck_floor());
 
     let (pk, vk) = S::setup(&ck, &shape)?;
 
-    let pk = ProverKey { S: shape, ck, pk };
+    let pk = ProverKey {
+      S: shape,
+      ck,
+      pk,
+      num_io,
+    };
 
-    let vk = VerifierKey { vk };
+    let vk = VerifierKey { vk, num_io };
 
     Ok((pk, vk))
   }
 
-  /// Produces a proof of satisfiability of the provided circuit
-  pub fn prove(pk: &ProverKey<E, S>, sc: C, z_i: &[E::Scalar]) -> Result<Self, NovaError> {
-    let mut cs = SatisfyingAssignment::<E>::new();
+  /// Produces a proof of satisfiability of the provided circuit, whose public inputs must be `io`
+  pub fn prove(pk: &ProverKey<E, S>, circuit: C, io: &[E::Scalar]) -> Result<Self, NovaError> {
+    if io.len() != pk.num_io {
+      return Err(NovaError::InvalidInputLength);
+    }
 
-    let circuit: DirectCircuit<E, C> = DirectCircuit {
-      z_i: Some(z_i.to_vec()),
-      sc,
-    };
+    let mut cs = SatisfyingAssignment::<E>::new();
+    circuit
+      .synthesize(&mut cs)
+      .map_err(|_e| NovaError::SynthesisError)?;
+    pad_inputs(&mut cs, pk.num_io)?;
 
-    let _ = circuit.synthesize(&mut cs);
     let (u, w) = cs
       .r1cs_instance_and_witness(&pk.S, &pk.ck)
       .map_err(|_e| NovaError::UnSat)?;
 
+    // the circuit must declare the claimed public inputs
+    if u.X[..pk.num_io] != io[..] {
+      return Err(NovaError::UnSat);
+    }
+
     // convert the instance and witness to relaxed form
     let (u_relaxed, w_relaxed) = (
       RelaxedR1CSInstance::from_r1cs_instance_unchecked(&u.comm_W, &u.X),
@@ -149,10 +209,19 @@ impl<E: Engine, S: RelaxedR1CSSNARKTrait<E>, C: StepCircuit<E::Scalar>> DirectSN
     })
   }
 
-  /// Verifies a proof of satisfiability
+  /// Verifies a proof of satisfiability with the public inputs `io`
   pub fn verify(&self, vk: &VerifierKey<E, S>, io: &[E::Scalar]) -> Result<(), NovaError> {
-    // construct an instance using the provided commitment to the witness and z_i and z_{i+1}
-    let u_relaxed = RelaxedR1CSInstance::from_r1cs_instance_unchecked(&self.comm_W, io);
+    if io.len() != vk.num_io {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    // construct an instance using the provided commitment to the witness and the public inputs,
+    // padded as in the shape
+    let mut X = io.to_vec();
+    if vk.num_io % 2 != 0 {
+      X.push(E::Scalar::ZERO);
+    }
+    let u_relaxed = RelaxedR1CSInstance::from_r1cs_instance_unchecked(&self.comm_W, &X);
 
     // verify the snark using the constructed instance
     self.snark.verify(&vk.vk, &u_relaxed)?;
@@ -165,9 +234,7 @@ impl<E: Engine, S: RelaxedR1CSSNARKTrait<E>, C: StepCircuit<E::Scalar>> DirectSN
 mod tests {
   use super::*;
   use crate::provider::{Bn256Engine, PallasEngine, Secp256k1Engine};
-  use ::bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
-  use core::marker::PhantomData;
-  use ff::PrimeField;
+  use crate::spartan::lookup::LookupTable;
 
   #[derive(Clone, Debug, Default)]
   struct CubicCircuit<F: PrimeField> {
@@ -227,6 +294,9 @@ mod tests {
     type Spp = crate::spartan::ppsnark::RelaxedR1CSSNARK<E, EE>;
     test_direct_snark_with::<E, Spp>();
 
+    type Szk = crate::spartan::zksnark::RelaxedR1CSSNARK<E>;
+    test_direct_snark_with::<E, Szk>();
+
     type E2 = Bn256Engine;
     type EE2 = crate::provider::ipa_pc::EvaluationEngine<E2>;
     type S2 = crate::spartan::snark::RelaxedR1CSSNARK<E2, EE2>;
@@ -235,6 +305,9 @@ mod tests {
     type S2pp = crate::spartan::ppsnark::RelaxedR1CSSNARK<E2, EE2>;
     test_direct_snark_with::<E2, S2pp>();
 
+    type S2zk = crate::spartan::zksnark::RelaxedR1CSSNARK<E2>;
+    test_direct_snark_with::<E2, S2zk>();
+
     type E3 = Secp256k1Engine;
     type EE3 = crate::provider::ipa_pc::EvaluationEngine<E3>;
     type S3 = crate::spartan::snark::RelaxedR1CSSNARK<E3, EE3>;
@@ -242,6 +315,9 @@ mod tests {
 
     type S3pp = crate::spartan::ppsnark::RelaxedR1CSSNARK<E3, EE3>;
     test_direct_snark_with::<E3, S3pp>();
+
+    type S3zk = crate::spartan::zksnark::RelaxedR1CSSNARK<E3>;
+    test_direct_snark_with::<E3, S3zk>();
   }
 
   fn test_direct_snark_with<E: Engine, S: RelaxedR1CSSNARKTrait<E>>() {
@@ -249,7 +325,10 @@ mod tests {
 
     // produce keys
     let (pk, vk) =
-      DirectSNARK::<E, S, CubicCircuit<<E as Engine>::Scalar>>::setup(circuit.clone()).unwrap();
+      DirectSNARK::<E, S, DirectCircuit<E, CubicCircuit<<E as Engine>::Scalar>>>::setup(
+        DirectCircuit::new(None, circuit.clone()),
+      )
+      .unwrap();
 
     let num_steps = 3;
 
@@ -258,20 +337,24 @@ mod tests {
     let mut z_i = z0;
 
     for _i in 0..num_steps {
-      // produce a SNARK
-      let res = DirectSNARK::prove(&pk, circuit.clone(), &z_i);
-      assert!(res.is_ok());
-
       let z_i_plus_one = circuit.output(&z_i);
-
-      let snark = res.unwrap();
-
-      // verify the SNARK
       let io = z_i
         .clone()
         .into_iter()
         .chain(z_i_plus_one.clone())
         .collect::<Vec<_>>();
+
+      // produce a SNARK
+      let res = DirectSNARK::prove(
+        &pk,
+        DirectCircuit::new(Some(z_i.clone()), circuit.clone()),
+        &io,
+      );
+      assert!(res.is_ok());
+
+      let snark = res.unwrap();
+
+      // verify the SNARK
       let res = snark.verify(&vk, &io);
       assert!(res.is_ok());
 
@@ -282,4 +365,183 @@ mod tests {
     // sanity: check the claimed output with a direct computation of the same
     assert_eq!(z_i, vec![<E as Engine>::Scalar::from(2460515u64)]);
   }
+
+  // A circuit proving knowledge of a preimage `x` of the public output `y = x^3 + x + 5`
+  #[derive(Clone, Debug)]
+  struct PreimageCircuit<F: PrimeField> {
+    x: Option<F>,
+  }
+
+  impl<F: PrimeField> Circuit<F> for PreimageCircuit<F> {
+    fn synthesize<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+      let x = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+        self.x.ok_or(SynthesisError::AssignmentMissing)
+      })?;
+      let x_sq = x.square(cs.namespace(|| "x_sq"))?;
+      let x_cu = x_sq.mul(cs.namespace(|| "x_cu"), &x)?;
+      let y = AllocatedNum::alloc_input(cs.namespace(|| "y"), || {
+        Ok(x_cu.get_value().unwrap() + x.get_value().unwrap() + F::from(5u64))
+      })?;
+
+      cs.enforce(
+        || "y = x^3 + x + 5",
+        |lc| {
+          lc + x_cu.get_variable()
+            + x.get_variable()
+            + CS::one()
+            + CS::one()
+            + CS::one()
+            + CS::one()
+            + CS::one()
+        },
+        |lc| lc + CS::one(),
+        |lc| lc + y.get_variable(),
+      );
+
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_direct_snark_circuit() {
+    type E = PallasEngine;
+    type EE = crate::provider::ipa_pc::EvaluationEngine<E>;
+    type S = crate::spartan::snark::RelaxedR1CSSNARK<E, EE>;
+    test_direct_snark_circuit_with::<E, S>();
+
+    type Spp = crate::spartan::ppsnark::RelaxedR1CSSNARK<E, EE>;
+    test_direct_snark_circuit_with::<E, Spp>();
+
+    type E2 = Bn256Engine;
+    type EE2 = crate::provider::ipa_pc::EvaluationEngine<E2>;
+    type S2 = crate::spartan::snark::RelaxedR1CSSNARK<E2, EE2>;
+    test_direct_snark_circuit_with::<E2, S2>();
+  }
+
+  fn test_direct_snark_circuit_with<E: Engine, S: RelaxedR1CSSNARKTrait<E>>() {
+    type F<E> = <E as Engine>::Scalar;
+
+    // the circuit declares a single public input, which gets padded
+    let (pk, vk) =
+      DirectSNARK::<E, S, PreimageCircuit<F<E>>>::setup(PreimageCircuit { x: None }).unwrap();
+    assert_eq!(vk.num_io(), 1);
+
+    let circuit = PreimageCircuit {
+      x: Some(F::<E>::from(3u64)),
+    };
+    let io = [F::<E>::from(35u64)];
+
+    let snark = DirectSNARK::prove(&pk, circuit.clone(), &io).unwrap();
+    assert!(snark.verify(&vk, &io).is_ok());
+
+    // the proof does not verify for other public inputs
+    assert!(snark.verify(&vk, &[F::<E>::from(36u64)]).is_err());
+    assert_eq!(snark.verify(&vk, &[]), Err(NovaError::InvalidInputLength));
+
+    // nor can one prove a statement the circuit does not declare
+    assert!(DirectSNARK::prove(&pk, circuit, &[F::<E>::from(36u64)]).is_err());
+  }
+
+  // A circuit proving knowledge of four values, looked up in a range table, of public sum
+  #[derive(Clone, Debug)]
+  struct SumCircuit<F: PrimeField> {
+    values: Option<[u64; 4]>,
+  }
+
+  impl<F: PrimeField> Circuit<F> for SumCircuit<F> {
+    fn synthesize<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+      // the values are the first four auxiliary variables
+      let values = (0..4)
+        .map(|i| {
+          AllocatedNum::alloc(cs.namespace(|| format!("value {i}")), || {
+            let values = self.values.ok_or(SynthesisError::AssignmentMissing)?;
+            Ok(F::from(values[i]))
+          })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      let sum = AllocatedNum::alloc_input(cs.namespace(|| "sum"), || {
+        let values = self.values.ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(F::from(values.iter().sum::<u64>()))
+      })?;
+
+      cs.enforce(
+        || "sum = values[0] + values[1] + values[2] + values[3]",
+        |lc| {
+          values
+            .iter()
+            .fold(lc, |lc, value| lc + value.get_variable())
+        },
+        |lc| lc + CS::one(),
+        |lc| lc + sum.get_variable(),
+      );
+
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_direct_snark_lookups() {
+    type E = PallasEngine;
+    type EE = crate::provider::ipa_pc::EvaluationEngine<E>;
+    type S = crate::spartan::snark::RelaxedR1CSSNARK<E, EE>;
+    test_direct_snark_lookups_with::<E, S>();
+
+    type Spp = crate::spartan::ppsnark::RelaxedR1CSSNARK<E, EE>;
+    test_direct_snark_lookups_with::<E, Spp>();
+
+    // the zero-knowledge SNARK does not support lookups
+    type Szk = crate::spartan::zksnark::RelaxedR1CSSNARK<E>;
+    let lookups = vec![LookupColumn::new(LookupTable::Range(4), 0, 4).unwrap()];
+    assert!(matches!(
+      DirectSNARK::<E, Szk, SumCircuit<<E as Engine>::Scalar>>::setup_with_lookups(
+        SumCircuit { values: None },
+        lookups
+      ),
+      Err(NovaError::UnsupportedLookups)
+    ));
+  }
+
+  fn test_direct_snark_lookups_with<E: Engine, S: RelaxedR1CSSNARKTrait<E>>() {
+    type F<E> = <E as Engine>::Scalar;
+
+    // the values are 4-bit integers
+    let lookups = vec![LookupColumn::new(LookupTable::Range(4), 0, 4).unwrap()];
+    let (pk, vk) = DirectSNARK::<E, S, SumCircuit<F<E>>>::setup_with_lookups(
+      SumCircuit { values: None },
+      lookups,
+    )
+    .unwrap();
+
+    let circuit = SumCircuit {
+      values: Some([3, 15, 0, 7]),
+    };
+    let io = [F::<E>::from(25u64)];
+    let snark = DirectSNARK::prove(&pk, circuit, &io).unwrap();
+    assert!(snark.verify(&vk, &io).is_ok());
+
+    // the keys of the circuit without lookups do not verify the proof
+    let (_, vk_without_lookups) =
+      DirectSNARK::<E, S, SumCircuit<F<E>>>::setup(SumCircuit { values: None }).unwrap();
+    assert!(snark.verify(&vk_without_lookups, &io).is_err());
+
+    // values out of the table cannot be proven
+    let circuit = SumCircuit {
+      values: Some([3, 16, 0, 7]),
+    };
+    let io = [F::<E>::from(26u64)];
+    assert_eq!(
+      DirectSNARK::prove(&pk, circuit, &io).err(),
+      Some(NovaError::InvalidLookup)
+    );
+
+    // columns must be within the witness
+    let lookups = vec![LookupColumn::new(LookupTable::Range(4), 4, 4).unwrap()];
+    assert!(matches!(
+      DirectSNARK::<E, S, SumCircuit<F<E>>>::setup_with_lookups(
+        SumCircuit { values: None },
+        lookups
+      ),
+      Err(NovaError::InvalidInputLength)
+    ));
+  }
 }
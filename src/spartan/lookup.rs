@@ -0,0 +1,751 @@
+//! This module implements a lookup argument, which proves that every entry of a committed vector
+//! is an entry of a table, using the logUp technique behind the memory checks of ppsnark.rs.
+//!
+//! Given a committed vector w and a table t, the prover commits to the multiplicities m of the
+//! table entries in w, and, for a random challenge r, to the oracles m[j]/(t[j] + r) and
+//! 1/(w[i] + r). A single sum-check then proves that
+//!   ∑ⱼ m[j]/(t[j] + r) = ∑ᵢ 1/(w[i] + r)
+//! and that the oracles are well-formed, after which all the claimed evaluations are opened with
+//! the polynomial commitment scheme.
+//!
+//! When there are more lookups than table entries, the table is repeated up to the number of
+//! lookups rather than padded with zeros, so that every multiplicity the prover commits to is the
+//! multiplicity of an actual table entry.
+//!
+//! R1CS shapes declare the columns of their witness to check against tables with
+//! [`LookupColumn`], which the SNARKs of snark.rs and ppsnark.rs prove along with the R1CS.
+//!
+//! Large tables are meant to be decomposed: e.g. a 64-bit range check of a value is performed by
+//! looking up each of its 16-bit limbs in [`LookupTable::Range`]`(16)`, the decomposition itself
+//! being enforced by the circuit producing the committed vector.
+use crate::{
+  errors::NovaError,
+  spartan::{
+    batch_invert,
+    math::Math,
+    polys::{
+      identity::IdentityPolynomial, multilinear::MultilinearPolynomial, power::PowPolynomial,
+    },
+    powers,
+    snark::{batch_eval_prove, batch_eval_verify},
+    sumcheck::SumcheckProof,
+    PolyEvalInstance, PolyEvalWitness,
+  },
+  traits::{
+    commitment::{CommitmentEngineTrait, CommitmentTrait},
+    evaluation::EvaluationEngineTrait,
+    Engine, TranscriptEngineTrait,
+  },
+  zip_with, Commitment, CommitmentKey, CompressedCommitment,
+};
+use abomonation::Abomonation;
+use abomonation_derive::Abomonation;
+use ff::{Field, PrimeField};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A table against which the entries of a committed vector are looked up
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LookupTable<F: PrimeField> {
+  /// The table of the integers in `[0, 2^num_bits)`, whose evaluations are computed succinctly
+  Range(usize),
+  /// A table given by its entries. It is padded to a power-of-two size by repeating its first
+  /// entry, and the verifier evaluates it in time linear in its size.
+  Fixed(Vec<F>),
+}
+
+// The entries of a fixed table are abomonated as their representations, like the values of a
+// `SparseMatrix`
+impl<F: PrimeField> Abomonation for LookupTable<F>
+where
+  F::Repr: Abomonation,
+{
+  unsafe fn entomb<W: std::io::Write>(&self, bytes: &mut W) -> std::io::Result<()> {
+    match self {
+      Self::Range(_) => Ok(()),
+      Self::Fixed(values) => {
+        let values = values as *const Vec<F> as *const Vec<F::Repr>;
+        (*values).entomb(bytes)
+      }
+    }
+  }
+
+  unsafe fn exhume<'a, 'b>(&'a mut self, bytes: &'b mut [u8]) -> Option<&'b mut [u8]> {
+    match self {
+      Self::Range(_) => Some(bytes),
+      Self::Fixed(values) => {
+        let values = values as *mut Vec<F> as *mut Vec<F::Repr>;
+        (*values).exhume(bytes)
+      }
+    }
+  }
+
+  fn extent(&self) -> usize {
+    match self {
+      Self::Range(_) => 0,
+      Self::Fixed(values) => {
+        let values = values as *const Vec<F> as *const Vec<F::Repr>;
+        unsafe { (*values).extent() }
+      }
+    }
+  }
+}
+
+impl<F: PrimeField> LookupTable<F> {
+  /// The number of variables of the multilinear extension of the (padded) table
+  pub fn num_vars(&self) -> usize {
+    match self {
+      Self::Range(num_bits) => *num_bits,
+      Self::Fixed(values) => values.len().next_power_of_two().log_2(),
+    }
+  }
+
+  /// The entries of the (padded) table
+  pub fn values(&self) -> Vec<F> {
+    match self {
+      Self::Range(num_bits) => (0..1u64 << num_bits).map(F::from).collect(),
+      Self::Fixed(values) => {
+        let mut values = values.clone();
+        values.resize(values.len().next_power_of_two(), values[0]);
+        values
+      }
+    }
+  }
+
+  /// Evaluates the multilinear extension of the (padded) table at `r`
+  pub fn evaluate(&self, r: &[F]) -> F {
+    match self {
+      Self::Range(num_bits) => IdentityPolynomial::new(*num_bits).evaluate(r),
+      Self::Fixed(_) => MultilinearPolynomial::evaluate_with(&self.values(), r),
+    }
+  }
+
+  /// Returns the number of times each entry of the (padded) table is looked up in `lookups`, or
+  /// an error if one of them is not in the table.
+  pub fn multiplicities(&self, lookups: &[F]) -> Result<Vec<F>, NovaError> {
+    let values = self.values();
+    let mut positions = HashMap::with_capacity(values.len());
+    for (j, value) in values.iter().enumerate().rev() {
+      // a duplicated entry is attributed its first position
+      positions.insert(value.to_repr().as_ref().to_vec(), j);
+    }
+
+    let mut counts = vec![0u64; values.len()];
+    for lookup in lookups {
+      let j = positions
+        .get(lookup.to_repr().as_ref())
+        .ok_or(NovaError::InvalidLookup)?;
+      counts[*j] += 1;
+    }
+
+    Ok(counts.into_iter().map(F::from).collect())
+  }
+}
+
+/// A column of variables of the witness of an R1CS shape whose entries are all entries of a
+/// table: the `2^num_vars` variables starting at `offset`, which must be a multiple of their
+/// number. Circuits place such a column by allocating its variables consecutively, after
+/// allocating enough other variables to align it.
+///
+/// Lookups are checked on the witness of the instance being proven, so they are only meaningful
+/// for instances that are not folded, such as those of `DirectSNARK`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Abomonation)]
+#[abomonation_bounds(where <F as PrimeField>::Repr: Abomonation)]
+pub struct LookupColumn<F: PrimeField> {
+  table: LookupTable<F>,
+  offset: usize,
+  num_vars: usize,
+}
+
+impl<F: PrimeField> LookupColumn<F> {
+  /// Declares that the `num_lookups` variables starting at `offset` are entries of `table`.
+  /// `num_lookups` must be a power of two, and `offset` a multiple of it.
+  pub fn new(table: LookupTable<F>, offset: usize, num_lookups: usize) -> Result<Self, NovaError> {
+    if num_lookups == 0 || !num_lookups.is_power_of_two() || offset % num_lookups != 0 {
+      return Err(NovaError::InvalidInputLength);
+    }
+    if matches!(&table, LookupTable::Fixed(values) if values.is_empty()) {
+      return Err(NovaError::InvalidInputLength);
+    }
+    Ok(Self {
+      table,
+      offset,
+      num_vars: num_lookups.log_2(),
+    })
+  }
+
+  /// The table the entries of the column are looked up in
+  pub fn table(&self) -> &LookupTable<F> {
+    &self.table
+  }
+
+  /// The range of the variables of the column
+  pub fn range(&self) -> std::ops::Range<usize> {
+    self.offset..self.offset + (1 << self.num_vars)
+  }
+
+  /// The size of the vectors the argument commits to, which is the largest of the number of
+  /// lookups and the size of the (padded) table
+  pub(crate) fn size(&self) -> usize {
+    1 << self.num_vars.max(self.table.num_vars())
+  }
+
+  // The point at which the evaluation of the column over its `num_vars` variables at `r` is an
+  // evaluation of a vector of `2^num_vars_vector` entries containing it
+  fn point_in(&self, num_vars_vector: usize, r: &[F]) -> Vec<F> {
+    let index = self.offset >> self.num_vars;
+    let num_vars_index = num_vars_vector - self.num_vars;
+    (0..num_vars_index)
+      .map(|i| {
+        if (index >> (num_vars_index - 1 - i)) & 1 == 1 {
+          F::ONE
+        } else {
+          F::ZERO
+        }
+      })
+      .chain(r.iter().cloned())
+      .collect()
+  }
+
+  // Returns the number of variables of a vector of `len` entries containing the column, or an
+  // error if it does not contain it
+  fn num_vars_in(&self, len: usize) -> Result<usize, NovaError> {
+    if !len.is_power_of_two() || self.range().end > len {
+      return Err(NovaError::InvalidInputLength);
+    }
+    Ok(len.log_2())
+  }
+}
+
+/// A proof that every entry of a committed vector is an entry of a [`LookupTable`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct LookupProof<E: Engine, EE: EvaluationEngineTrait<E>> {
+  comm_m: CompressedCommitment<E>,
+  comm_t_plus_r_inv: CompressedCommitment<E>,
+  comm_w_plus_r_inv: CompressedCommitment<E>,
+
+  sc: SumcheckProof<E>,
+
+  eval_t_plus_r_inv: E::Scalar,
+  eval_w_plus_r_inv: E::Scalar,
+  eval_m: E::Scalar,
+  eval_w: E::Scalar,
+
+  sc_proof_batch: SumcheckProof<E>,
+  evals_batch: Vec<E::Scalar>,
+  eval_arg: EE::EvaluationArgument,
+}
+
+impl<E: Engine, EE: EvaluationEngineTrait<E>> LookupProof<E, EE> {
+  /// Proves that every entry of `lookups`, whose commitment is `comm_lookups`, is an entry of
+  /// `table`. The number of lookups must be a power of two, and the commitment key must support
+  /// vectors of the size of the largest of `lookups` and the padded table.
+  pub fn prove(
+    ck: &CommitmentKey<E>,
+    pk_ee: &EE::ProverKey,
+    table: &LookupTable<E::Scalar>,
+    comm_lookups: &Commitment<E>,
+    lookups: &[E::Scalar],
+    transcript: &mut E::TE,
+  ) -> Result<Self, NovaError> {
+    let column = LookupColumn::new(table.clone(), 0, lookups.len())?;
+    Self::prove_column(ck, pk_ee, &column, comm_lookups, lookups, transcript)
+  }
+
+  /// Verifies that every entry of the vector of `num_lookups` entries committed in
+  /// `comm_lookups` is an entry of `table`
+  pub fn verify(
+    &self,
+    vk_ee: &EE::VerifierKey,
+    table: &LookupTable<E::Scalar>,
+    comm_lookups: &Commitment<E>,
+    num_lookups: usize,
+    transcript: &mut E::TE,
+  ) -> Result<(), NovaError> {
+    let column = LookupColumn::new(table.clone(), 0, num_lookups)?;
+    self.verify_column(vk_ee, &column, comm_lookups, num_lookups, transcript)
+  }
+
+  /// Proves that the entries of `column` in `vector`, whose commitment is `comm`, are entries of
+  /// its table. The size of `vector` must be a power of two.
+  pub(in crate::spartan) fn prove_column(
+    ck: &CommitmentKey<E>,
+    pk_ee: &EE::ProverKey,
+    column: &LookupColumn<E::Scalar>,
+    comm: &Commitment<E>,
+    vector: &[E::Scalar],
+    transcript: &mut E::TE,
+  ) -> Result<Self, NovaError> {
+    column.num_vars_in(vector.len())?;
+    let m = column.table.multiplicities(&vector[column.range()])?;
+    Self::prove_with_multiplicities(ck, pk_ee, column, comm, vector, m, transcript)
+  }
+
+  // Proves the lookups of `column` with the multiplicities `m` of the entries of its padded table
+  fn prove_with_multiplicities(
+    ck: &CommitmentKey<E>,
+    pk_ee: &EE::ProverKey,
+    column: &LookupColumn<E::Scalar>,
+    comm: &Commitment<E>,
+    vector: &[E::Scalar],
+    mut m: Vec<E::Scalar>,
+    transcript: &mut E::TE,
+  ) -> Result<Self, NovaError> {
+    let num_vars_vector = column.num_vars_in(vector.len())?;
+    let lookups = &vector[column.range()];
+    let (num_vars_w, num_rounds) = (column.num_vars, column.size().log_2());
+    let n = 1 << num_rounds;
+
+    Self::absorb_table(&column.table, transcript);
+    transcript.absorb(b"w", comm);
+
+    // commit to the multiplicities of the entries of the repeated table, which are zero beyond
+    // its first copy
+    m.resize(n, E::Scalar::ZERO);
+    let comm_m = E::CE::commit(ck, &m);
+    transcript.absorb(b"m", &comm_m);
+
+    let r = transcript.squeeze(b"r")?;
+
+    // compute the oracles m[j]/(t[j] + r) and 1/(w[i] + r), the latter zero-padded, and the
+    // auxiliary polynomials t + r and w + r, where t is repeated and w is zero-padded
+    let (t_plus_r, w_plus_r) = {
+      let t = column.table.values();
+      let mut w = lookups.to_vec();
+      w.resize(n, E::Scalar::ZERO);
+      rayon::join(
+        || {
+          (0..n)
+            .into_par_iter()
+            .map(|j| t[j % t.len()] + r)
+            .collect::<Vec<_>>()
+        },
+        || w.par_iter().map(|w| *w + r).collect::<Vec<_>>(),
+      )
+    };
+    let t_plus_r_inv = {
+      let inv = batch_invert::<E>(&t_plus_r)?;
+      zip_with!(par_iter, (inv, m), |inv, m| *inv * m).collect::<Vec<_>>()
+    };
+    let w_plus_r_inv = {
+      let mut inv = batch_invert::<E>(&w_plus_r[..lookups.len()])?;
+      inv.resize(n, E::Scalar::ZERO);
+      inv
+    };
+    let (comm_t_plus_r_inv, comm_w_plus_r_inv) = rayon::join(
+      || E::CE::commit(ck, &t_plus_r_inv),
+      || E::CE::commit(ck, &w_plus_r_inv),
+    );
+    transcript.absorb(b"l", &[comm_t_plus_r_inv, comm_w_plus_r_inv].as_slice());
+
+    let rho = transcript.squeeze(b"r")?;
+    let s = transcript.squeeze(b"r")?;
+    let coeffs = powers::<E>(&s, 3);
+
+    let mut sel = vec![E::Scalar::ONE; lookups.len()];
+    sel.resize(n, E::Scalar::ZERO);
+
+    // prove, with the sel polynomial one on the actual lookups and zero on the padding, that
+    //   0 = ∑ t_plus_r_inv[i] - w_plus_r_inv[i]
+    //   0 = ∑ eq[i] * (t_plus_r_inv[i] * t_plus_r[i] - m[i])
+    //   0 = ∑ eq[i] * (w_plus_r_inv[i] * w_plus_r[i] - sel[i])
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar {
+      coeffs[0] * (comps[0] - comps[1])
+        + coeffs[1] * comps[6] * (comps[0] * comps[2] - comps[4])
+        + coeffs[2] * comps[6] * (comps[1] * comps[3] - comps[5])
+    };
+    let (sc, rand_sc, evals) = SumcheckProof::prove(
+      &E::Scalar::ZERO,
+      num_rounds,
+      vec![
+        MultilinearPolynomial::new(t_plus_r_inv.clone()),
+        MultilinearPolynomial::new(w_plus_r_inv.clone()),
+        MultilinearPolynomial::new(t_plus_r),
+        MultilinearPolynomial::new(w_plus_r),
+        MultilinearPolynomial::new(m.clone()),
+        MultilinearPolynomial::new(sel),
+        MultilinearPolynomial::new(PowPolynomial::new(&rho, num_rounds).evals()),
+      ],
+      3,
+      comb_func,
+      transcript,
+    )?;
+
+    let (eval_t_plus_r_inv, eval_w_plus_r_inv, eval_m) = (evals[0], evals[1], evals[4]);
+    let rand_w = &rand_sc[num_rounds - num_vars_w..];
+    let eval_w = MultilinearPolynomial::evaluate_with(lookups, rand_w);
+
+    transcript.absorb(
+      b"e",
+      &[eval_t_plus_r_inv, eval_w_plus_r_inv, eval_m, eval_w].as_slice(),
+    );
+
+    let u_vec = Self::eval_instances(
+      [comm_t_plus_r_inv, comm_w_plus_r_inv, comm_m, *comm],
+      [eval_t_plus_r_inv, eval_w_plus_r_inv, eval_m, eval_w],
+      &rand_sc,
+      column.point_in(num_vars_vector, rand_w),
+    );
+    let w_vec = [t_plus_r_inv, w_plus_r_inv, m, vector.to_vec()]
+      .into_iter()
+      .map(|p| PolyEvalWitness { p })
+      .collect();
+
+    let (batched_u, batched_w, sc_proof_batch, evals_batch) =
+      batch_eval_prove(u_vec, w_vec, transcript)?;
+
+    let eval_arg = EE::prove(
+      ck,
+      pk_ee,
+      transcript,
+      &batched_u.c,
+      &batched_w.p,
+      &batched_u.x,
+      &batched_u.e,
+    )?;
+
+    Ok(Self {
+      comm_m: comm_m.compress(),
+      comm_t_plus_r_inv: comm_t_plus_r_inv.compress(),
+      comm_w_plus_r_inv: comm_w_plus_r_inv.compress(),
+      sc,
+      eval_t_plus_r_inv,
+      eval_w_plus_r_inv,
+      eval_m,
+      eval_w,
+      sc_proof_batch,
+      evals_batch,
+      eval_arg,
+    })
+  }
+
+  /// Verifies that the entries of `column` in the vector of `len` entries committed in `comm`
+  /// are entries of its table
+  pub(in crate::spartan) fn verify_column(
+    &self,
+    vk_ee: &EE::VerifierKey,
+    column: &LookupColumn<E::Scalar>,
+    comm: &Commitment<E>,
+    len: usize,
+    transcript: &mut E::TE,
+  ) -> Result<(), NovaError> {
+    let num_vars_vector = column.num_vars_in(len)?;
+    let (num_vars_w, num_vars_t, num_rounds) = (
+      column.num_vars,
+      column.table.num_vars(),
+      column.size().log_2(),
+    );
+
+    Self::absorb_table(&column.table, transcript);
+    transcript.absorb(b"w", comm);
+
+    let comm_m = Commitment::<E>::decompress(&self.comm_m)?;
+    transcript.absorb(b"m", &comm_m);
+
+    let r = transcript.squeeze(b"r")?;
+
+    let comm_t_plus_r_inv = Commitment::<E>::decompress(&self.comm_t_plus_r_inv)?;
+    let comm_w_plus_r_inv = Commitment::<E>::decompress(&self.comm_w_plus_r_inv)?;
+    transcript.absorb(b"l", &[comm_t_plus_r_inv, comm_w_plus_r_inv].as_slice());
+
+    let rho = transcript.squeeze(b"r")?;
+    let s = transcript.squeeze(b"r")?;
+    let coeffs = powers::<E>(&s, 3);
+
+    let (claim_sc_final, rand_sc) = self.sc.verify(E::Scalar::ZERO, num_rounds, 3, transcript)?;
+
+    let claim_sc_final_expected = {
+      let eval_eq = PowPolynomial::new(&rho, num_rounds).evaluate(&rand_sc);
+      // the repeated table only depends on the last variables
+      let eval_t_plus_r = column.table.evaluate(&rand_sc[num_rounds - num_vars_t..]) + r;
+      // the first Lagrange polynomial in the padding variables of the lookups
+      let eval_sel = rand_sc[..num_rounds - num_vars_w]
+        .iter()
+        .map(|r| E::Scalar::ONE - r)
+        .product::<E::Scalar>();
+      let eval_w_plus_r = eval_sel * self.eval_w + r;
+
+      coeffs[0] * (self.eval_t_plus_r_inv - self.eval_w_plus_r_inv)
+        + coeffs[1] * eval_eq * (self.eval_t_plus_r_inv * eval_t_plus_r - self.eval_m)
+        + coeffs[2] * eval_eq * (self.eval_w_plus_r_inv * eval_w_plus_r - eval_sel)
+    };
+
+    if claim_sc_final != claim_sc_final_expected {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    transcript.absorb(
+      b"e",
+      &[
+        self.eval_t_plus_r_inv,
+        self.eval_w_plus_r_inv,
+        self.eval_m,
+        self.eval_w,
+      ]
+      .as_slice(),
+    );
+
+    let u_vec = Self::eval_instances(
+      [comm_t_plus_r_inv, comm_w_plus_r_inv, comm_m, *comm],
+      [
+        self.eval_t_plus_r_inv,
+        self.eval_w_plus_r_inv,
+        self.eval_m,
+        self.eval_w,
+      ],
+      &rand_sc,
+      column.point_in(num_vars_vector, &rand_sc[num_rounds - num_vars_w..]),
+    );
+
+    let batched_u = batch_eval_verify(u_vec, transcript, &self.sc_proof_batch, &self.evals_batch)?;
+
+    EE::verify(
+      vk_ee,
+      transcript,
+      &batched_u.c,
+      &batched_u.x,
+      &batched_u.e,
+      &self.eval_arg,
+    )?;
+
+    Ok(())
+  }
+
+  fn absorb_table(table: &LookupTable<E::Scalar>, transcript: &mut E::TE) {
+    match table {
+      LookupTable::Range(num_bits) => transcript.absorb(b"T", &E::Scalar::from(*num_bits as u64)),
+      LookupTable::Fixed(values) => transcript.absorb(b"T", &values.as_slice()),
+    }
+  }
+
+  // The evaluation claims about the oracles, the multiplicities and the vector of the lookups
+  fn eval_instances(
+    comms: [Commitment<E>; 4],
+    evals: [E::Scalar; 4],
+    rand_sc: &[E::Scalar],
+    point_w: Vec<E::Scalar>,
+  ) -> Vec<PolyEvalInstance<E>> {
+    let points = [
+      rand_sc.to_vec(),
+      rand_sc.to_vec(),
+      rand_sc.to_vec(),
+      point_w,
+    ];
+    zip_with!(into_iter, (comms, evals, points), |c, e, x| {
+      PolyEvalInstance { c, x, e }
+    })
+    .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::provider::{ipa_pc, Bn256Engine, PallasEngine};
+
+  fn prove_and_verify<E: Engine, EE: EvaluationEngineTrait<E>>(
+    table: &LookupTable<E::Scalar>,
+    lookups: &[E::Scalar],
+  ) -> Result<(), NovaError> {
+    let n = lookups.len().max(table.values().len());
+    let ck = E::CE::setup(b"ck", n);
+    let (pk_ee, vk_ee) = EE::setup(&ck);
+    let comm_lookups = E::CE::commit(&ck, lookups);
+
+    let mut transcript = E::TE::new(b"lookup");
+    let proof =
+      LookupProof::<E, EE>::prove(&ck, &pk_ee, table, &comm_lookups, lookups, &mut transcript)?;
+
+    let mut transcript = E::TE::new(b"lookup");
+    proof.verify(&vk_ee, table, &comm_lookups, lookups.len(), &mut transcript)
+  }
+
+  fn test_lookup_with<E: Engine, EE: EvaluationEngineTrait<E>>() {
+    let to_scalars = |v: &[u64]| v.iter().map(|x| E::Scalar::from(*x)).collect::<Vec<_>>();
+
+    // fewer lookups than table entries, with repetitions
+    let table = LookupTable::Range(4);
+    let lookups = to_scalars(&[3, 15, 0, 3]);
+    assert!(prove_and_verify::<E, EE>(&table, &lookups).is_ok());
+
+    // more lookups than table entries
+    let table = LookupTable::Range(2);
+    let lookups = to_scalars(&[3, 1, 0, 3, 2, 2, 1, 1]);
+    assert!(prove_and_verify::<E, EE>(&table, &lookups).is_ok());
+
+    // a fixed table, which gets padded
+    let table = LookupTable::Fixed(to_scalars(&[7, 11, 13, 17, 19]));
+    let lookups = to_scalars(&[19, 7, 7, 13]);
+    assert!(prove_and_verify::<E, EE>(&table, &lookups).is_ok());
+
+    // a value out of the table
+    let table = LookupTable::Range(4);
+    let lookups = to_scalars(&[3, 16, 0, 3]);
+    assert_eq!(
+      prove_and_verify::<E, EE>(&table, &lookups),
+      Err(NovaError::InvalidLookup)
+    );
+  }
+
+  #[test]
+  fn test_lookup() {
+    test_lookup_with::<PallasEngine, ipa_pc::EvaluationEngine<PallasEngine>>();
+    test_lookup_with::<Bn256Engine, ipa_pc::EvaluationEngine<Bn256Engine>>();
+  }
+
+  #[test]
+  fn test_lookup_soundness() {
+    type E = PallasEngine;
+    type EE = ipa_pc::EvaluationEngine<E>;
+    type F = <E as Engine>::Scalar;
+
+    let table = LookupTable::Range(4);
+    let lookups = [3, 15, 0, 3].map(F::from);
+    let ck = <E as Engine>::CE::setup(b"ck", 16);
+    let (pk_ee, vk_ee) = EE::setup(&ck);
+    let comm_lookups = <E as Engine>::CE::commit(&ck, &lookups);
+
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    let proof = LookupProof::<E, EE>::prove(
+      &ck,
+      &pk_ee,
+      &table,
+      &comm_lookups,
+      &lookups,
+      &mut transcript,
+    )
+    .unwrap();
+
+    // the proof does not verify for other lookups
+    let other_lookups = [3, 15, 1, 3].map(F::from);
+    let comm_other_lookups = <E as Engine>::CE::commit(&ck, &other_lookups);
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(proof
+      .verify(&vk_ee, &table, &comm_other_lookups, 4, &mut transcript)
+      .is_err());
+
+    // nor with a tampered evaluation
+    let mut bad_proof = proof.clone();
+    bad_proof.eval_w += F::ONE;
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(bad_proof
+      .verify(&vk_ee, &table, &comm_lookups, 4, &mut transcript)
+      .is_err());
+  }
+
+  #[test]
+  fn test_lookup_repeated_table_soundness() {
+    type E = PallasEngine;
+    type EE = ipa_pc::EvaluationEngine<E>;
+    type F = <E as Engine>::Scalar;
+
+    // more lookups than entries of a fixed table, one of which, zero, is not in the table
+    let table = LookupTable::Fixed([7, 11, 13, 17].map(F::from).to_vec());
+    let lookups = [7, 0, 11, 13, 17, 7, 7, 11].map(F::from);
+    let column = LookupColumn::new(table.clone(), 0, lookups.len()).unwrap();
+    let ck = <E as Engine>::CE::setup(b"ck", lookups.len());
+    let (pk_ee, vk_ee) = EE::setup(&ck);
+    let comm_lookups = <E as Engine>::CE::commit(&ck, &lookups);
+
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(matches!(
+      LookupProof::<E, EE>::prove(
+        &ck,
+        &pk_ee,
+        &table,
+        &comm_lookups,
+        &lookups,
+        &mut transcript
+      ),
+      Err(NovaError::InvalidLookup)
+    ));
+
+    // a cheating prover attributes the lookup of zero to a row beyond the table, which would
+    // hold a zero if the table were padded with zeros
+    let m = [3, 2, 1, 1, 1, 0, 0, 0].map(F::from).to_vec();
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    let proof = LookupProof::<E, EE>::prove_with_multiplicities(
+      &ck,
+      &pk_ee,
+      &column,
+      &comm_lookups,
+      &lookups,
+      m,
+      &mut transcript,
+    )
+    .unwrap();
+
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(proof
+      .verify(
+        &vk_ee,
+        &table,
+        &comm_lookups,
+        lookups.len(),
+        &mut transcript
+      )
+      .is_err());
+  }
+
+  #[test]
+  fn test_lookup_column() {
+    type E = PallasEngine;
+    type EE = ipa_pc::EvaluationEngine<E>;
+    type F = <E as Engine>::Scalar;
+
+    // a column of four entries of [0, 4) in the middle of a larger vector
+    let vector = [9, 9, 9, 9, 9, 9, 9, 9, 3, 1, 0, 3, 9, 9, 9, 9].map(F::from);
+    let table = LookupTable::Range(2);
+    let column = LookupColumn::new(table.clone(), 8, 4).unwrap();
+    let ck = <E as Engine>::CE::setup(b"ck", vector.len());
+    let (pk_ee, vk_ee) = EE::setup(&ck);
+    let comm = <E as Engine>::CE::commit(&ck, &vector);
+
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    let proof =
+      LookupProof::<E, EE>::prove_column(&ck, &pk_ee, &column, &comm, &vector, &mut transcript)
+        .unwrap();
+
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(proof
+      .verify_column(&vk_ee, &column, &comm, vector.len(), &mut transcript)
+      .is_ok());
+
+    // the proof does not verify for another column of the vector
+    let other_column = LookupColumn::new(table.clone(), 4, 4).unwrap();
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(proof
+      .verify_column(&vk_ee, &other_column, &comm, vector.len(), &mut transcript)
+      .is_err());
+
+    // whose entries are not in the table
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(matches!(
+      LookupProof::<E, EE>::prove_column(
+        &ck,
+        &pk_ee,
+        &other_column,
+        &comm,
+        &vector,
+        &mut transcript
+      ),
+      Err(NovaError::InvalidLookup)
+    ));
+
+    // columns must be aligned and within the vector
+    assert!(LookupColumn::new(table.clone(), 6, 4).is_err());
+    let outside_column = LookupColumn::new(table, 16, 4).unwrap();
+    let mut transcript = <E as Engine>::TE::new(b"lookup");
+    assert!(LookupProof::<E, EE>::prove_column(
+      &ck,
+      &pk_ee,
+      &outside_column,
+      &comm,
+      &vector,
+      &mut transcript
+    )
+    .is_err());
+  }
+}
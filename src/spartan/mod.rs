@@ -3,29 +3,68 @@
 //! We provide two implementations, one in snark.rs (which does not use any preprocessing)
 //! and another in ppsnark.rs (which uses preprocessing to keep the verifier's state small if the PCS provides a succinct verifier)
 //! We also provide direct.rs that allows proving a step circuit directly with either of the two SNARKs.
+//! pcs_snark.rs proves circuits directly over polynomial commitment schemes that cannot be folded.
 //!
 //! In polynomial.rs we also provide foundational types and functions for manipulating multilinear polynomials.
+//! The sumcheck protocol used by these SNARKs is exposed in sumcheck.rs, for use by other protocols.
+//! lookup.rs implements a lookup argument for committed vectors against fixed tables.
+//! zksnark.rs implements a zero-knowledge variant of the SNARK in snark.rs.
 
 pub mod batched;
 pub mod batched_ppsnark;
 pub mod direct;
+pub mod lookup;
+pub mod pcs_snark;
 #[macro_use]
 mod macros;
 pub(crate) mod math;
 pub mod polys;
 pub mod ppsnark;
 pub mod snark;
-mod sumcheck;
+pub mod sumcheck;
+pub mod zksnark;
 
 use crate::{
+  errors::NovaError,
   r1cs::{R1CSShape, SparseMatrix},
-  traits::Engine,
-  Commitment,
+  traits::{evaluation::EvaluationEngineTrait, Engine, TranscriptEngineTrait},
+  Commitment, CommitmentKey,
 };
 use ff::Field;
 use itertools::Itertools as _;
 use polys::multilinear::SparsePolynomial;
 use rayon::{iter::IntoParallelRefIterator, prelude::*};
+use serde::{Deserialize, Serialize};
+use sumcheck::SumcheckProof;
+
+// Inverts all the entries of `v` with a single field inversion.
+// Returns an error if one of the entries is zero.
+fn batch_invert<E: Engine>(v: &[E::Scalar]) -> Result<Vec<E::Scalar>, NovaError> {
+  let mut products = vec![E::Scalar::ZERO; v.len()];
+  let mut acc = E::Scalar::ONE;
+
+  for i in 0..v.len() {
+    products[i] = acc;
+    acc *= v[i];
+  }
+
+  // we can compute an inversion only if acc is non-zero
+  if acc == E::Scalar::ZERO {
+    return Err(NovaError::InternalError);
+  }
+
+  // compute the inverse once for all entries
+  acc = acc.invert().unwrap();
+
+  let mut inv = vec![E::Scalar::ZERO; v.len()];
+  for i in 0..v.len() {
+    let tmp = acc * v[v.len() - 1 - i];
+    inv[v.len() - 1 - i] = products[v.len() - 1 - i] * acc;
+    acc = tmp;
+  }
+
+  Ok(inv)
+}
 
 // Creates a vector of the first `n` powers of `s`.
 fn powers<E: Engine>(s: &E::Scalar, n: usize) -> Vec<E::Scalar> {
@@ -38,6 +77,97 @@ fn powers<E: Engine>(s: &E::Scalar, n: usize) -> Vec<E::Scalar> {
   powers
 }
 
+// Checks that `indices` is a non-empty, strictly increasing list of `num_instances` indices.
+fn check_subset(indices: &[usize], num_instances: usize) -> Result<(), NovaError> {
+  if indices.is_empty()
+    || indices.len() != num_instances
+    || !indices.iter().tuple_windows().all(|(a, b)| a < b)
+  {
+    return Err(NovaError::InvalidInputLength);
+  }
+  Ok(())
+}
+
+// Returns the entries of `items` at `indices`, which must be a non-empty, strictly increasing
+// list of `num_instances` indices into `items`.
+fn select_subset<'a, T>(
+  items: &'a [T],
+  indices: &[usize],
+  num_instances: usize,
+) -> Result<Vec<&'a T>, NovaError> {
+  check_subset(indices, num_instances)?;
+  indices
+    .iter()
+    .map(|&i| items.get(i).ok_or(NovaError::InvalidIndex))
+    .collect()
+}
+
+// Binds the transcript of a batched SNARK to the indices of the shapes it proves.
+fn absorb_subset<E: Engine>(transcript: &mut E::TE, indices: &[usize]) {
+  let indices = indices
+    .iter()
+    .map(|&i| E::Scalar::from(i as u64))
+    .collect::<Vec<_>>();
+  transcript.absorb(b"I", &indices.as_slice());
+}
+
+// Checks the batch of `indices` with `check`. If the check fails, bisects the batch to find a
+// failing entry, and returns its index together with the error of checking it alone. If each
+// half of a failing batch passes on its own, the batch is still rejected, with its range and
+// error.
+fn bisect(
+  indices: &[usize],
+  check: &impl Fn(&[usize]) -> Result<(), NovaError>,
+) -> Result<(), NovaError> {
+  match check(indices) {
+    Ok(()) => Ok(()),
+    Err(error) if indices.len() == 1 => Err(NovaError::BatchVerifyError {
+      index: indices[0],
+      error: Box::new(error),
+    }),
+    Err(error) => {
+      let (left, right) = indices.split_at(indices.len() / 2);
+      bisect(left, check)?;
+      bisect(right, check)?;
+      Err(NovaError::BatchRangeVerifyError {
+        start: indices[0],
+        end: indices[indices.len() - 1] + 1,
+        error: Box::new(error),
+      })
+    }
+  }
+}
+
+// Verifies the evaluation arguments `args` for the claims `u_vec`, each with its own transcript,
+// with a single batched check of the evaluation engine, bisecting the batch if it fails.
+fn batch_eval_arg_verify<E: Engine, EE: EvaluationEngineTrait<E>>(
+  vk_ee: &EE::VerifierKey,
+  u_vec: &[(E::TE, PolyEvalInstance<E>)],
+  args: &[&EE::EvaluationArgument],
+) -> Result<(), NovaError> {
+  if u_vec.len() != args.len() {
+    return Err(NovaError::InvalidInputLength);
+  }
+  if u_vec.is_empty() {
+    return Ok(());
+  }
+
+  let check = |indices: &[usize]| -> Result<(), NovaError> {
+    let mut transcripts = indices
+      .iter()
+      .map(|&i| u_vec[i].0.clone())
+      .collect::<Vec<_>>();
+    let (comms, points, evals): (Vec<_>, Vec<_>, Vec<_>) = indices
+      .iter()
+      .map(|&i| (u_vec[i].1.c, u_vec[i].1.x.clone(), u_vec[i].1.e))
+      .multiunzip();
+    let args = indices.iter().map(|&i| args[i]).collect::<Vec<_>>();
+    EE::batch_verify(vk_ee, &mut transcripts, &comms, &points, &evals, &args)
+  };
+
+  bisect(&(0..u_vec.len()).collect::<Vec<_>>(), &check)
+}
+
 /// A type that holds a witness to a polynomial evaluation instance
 pub struct PolyEvalWitness<E: Engine> {
   p: Vec<E::Scalar>, // polynomial
@@ -52,12 +182,20 @@ impl<E: Engine> PolyEvalWitness<E> {
   /// being padded with 0 to the maximum size of all polynomials.
   fn batch_diff_size(W: Vec<PolyEvalWitness<E>>, s: E::Scalar) -> PolyEvalWitness<E> {
     let powers = powers::<E>(&s, W.len());
+    Self::weighted_sum_diff_size(W, &powers)
+  }
 
+  /// Given [Pᵢ] and [wᵢ], compute P = ∑ᵢ wᵢ⋅Pᵢ, where polynomials of different sizes are
+  /// handled as in `batch_diff_size`
+  fn weighted_sum_diff_size(
+    W: Vec<PolyEvalWitness<E>>,
+    weights: &[E::Scalar],
+  ) -> PolyEvalWitness<E> {
     let size_max = W.iter().map(|w| w.p.len()).max().unwrap();
-    // Scale the input polynomials by the power of s
+    // Scale the input polynomials by their weights
     let p = W
       .into_par_iter()
-      .zip_eq(powers.par_iter())
+      .zip_eq(weights.par_iter())
       .map(|(mut w, s)| {
         if *s != E::Scalar::ONE {
           w.p.par_iter_mut().for_each(|e| *e *= s);
@@ -119,9 +257,9 @@ impl<E: Engine> PolyEvalWitness<E> {
 
 /// A type that holds a polynomial evaluation instance
 pub struct PolyEvalInstance<E: Engine> {
-  c: Commitment<E>,  // commitment to the polynomial
-  x: Vec<E::Scalar>, // evaluation point
-  e: E::Scalar,      // claimed evaluation
+  pub(crate) c: Commitment<E>,  // commitment to the polynomial
+  pub(crate) x: Vec<E::Scalar>, // evaluation point
+  pub(crate) e: E::Scalar,      // claimed evaluation
 }
 
 impl<E: Engine> PolyEvalInstance<E> {
@@ -193,6 +331,137 @@ impl<E: Engine> PolyEvalInstance<E> {
   }
 }
 
+/// An argument of the evaluations of several polynomials, each at its own point, which any
+/// evaluation engine can use to implement `EvaluationEngineTrait::prove_batch`, and which the
+/// engines without a native multi-point opening (i.e., other than Zeromorph) use.
+///
+/// The claims are first reduced to claims at a single point: when every point is a suffix of
+/// the longest one, that point is used directly, and otherwise the claims are reduced with a
+/// sum-check. A random combination of the polynomials is then opened at that point with a single
+/// evaluation argument, so the cost of the engine's argument does not grow with the number of
+/// polynomials.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "A: Serialize", deserialize = "A: Deserialize<'de>"))]
+pub struct BatchEvalArgument<E: Engine, A> {
+  sc_proof: Option<SumcheckProof<E>>, // absent when the points are suffixes of a common point
+  evals: Vec<E::Scalar>,              // evaluations at the point chosen by the sum-check
+  arg: A,
+}
+
+impl<E: Engine, A> BatchEvalArgument<E, A> {
+  /// Proves that `evals[i]` is the evaluation of `polys[i]`, committed in `comms[i]`, at
+  /// `points[i]`, using the evaluation engine `EE` for the final opening
+  pub fn prove<EE: EvaluationEngineTrait<E, EvaluationArgument = A>>(
+    ck: &CommitmentKey<E>,
+    pk: &EE::ProverKey,
+    transcript: &mut E::TE,
+    comms: &[Commitment<E>],
+    polys: &[Vec<E::Scalar>],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+  ) -> Result<Self, NovaError> {
+    check_batch_lengths::<E>(comms, points, evals)?;
+    if polys.len() != points.len()
+      || polys
+        .iter()
+        .zip_eq(points)
+        .any(|(p, x)| p.len() != 1 << x.len())
+    {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    let w_vec = polys
+      .iter()
+      .map(|p| PolyEvalWitness { p: p.clone() })
+      .collect::<Vec<_>>();
+
+    let (u, w, sc_proof, evals) = match common_point(points) {
+      Some(x) => {
+        let num_vars = points.iter().map(|x| x.len()).collect::<Vec<_>>();
+        let gamma = transcript.squeeze(b"g")?;
+        let u = PolyEvalInstance::batch_diff_size(comms, evals, &num_vars, x, gamma);
+        let w = PolyEvalWitness::batch_diff_size(w_vec, gamma);
+        (u, w, None, Vec::new())
+      }
+      None => {
+        let u_vec = instances::<E>(comms, points, evals);
+        let (u, w, sc_proof, evals) = snark::batch_eval_prove(u_vec, w_vec, transcript)?;
+        (u, w, Some(sc_proof), evals)
+      }
+    };
+
+    let arg = EE::prove(ck, pk, transcript, &u.c, &w.p, &u.x, &u.e)?;
+
+    Ok(Self {
+      sc_proof,
+      evals,
+      arg,
+    })
+  }
+
+  /// Verifies that `evals[i]` is the evaluation at `points[i]` of the polynomial committed in
+  /// `comms[i]`
+  pub fn verify<EE: EvaluationEngineTrait<E, EvaluationArgument = A>>(
+    &self,
+    vk: &EE::VerifierKey,
+    transcript: &mut E::TE,
+    comms: &[Commitment<E>],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+  ) -> Result<(), NovaError> {
+    check_batch_lengths::<E>(comms, points, evals)?;
+
+    let u = match (common_point(points), &self.sc_proof) {
+      (Some(x), None) if self.evals.is_empty() => {
+        let num_vars = points.iter().map(|x| x.len()).collect::<Vec<_>>();
+        let gamma = transcript.squeeze(b"g")?;
+        PolyEvalInstance::batch_diff_size(comms, evals, &num_vars, x, gamma)
+      }
+      (None, Some(sc_proof)) if self.evals.len() == comms.len() => {
+        let u_vec = instances::<E>(comms, points, evals);
+        snark::batch_eval_verify(u_vec, transcript, sc_proof, &self.evals)?
+      }
+      _ => return Err(NovaError::ProofVerifyError),
+    };
+
+    EE::verify(vk, transcript, &u.c, &u.x, &u.e, &self.arg)
+  }
+}
+
+// Checks that a batch of evaluation claims is non-empty and that its parts have the same length.
+fn check_batch_lengths<E: Engine>(
+  comms: &[Commitment<E>],
+  points: &[Vec<E::Scalar>],
+  evals: &[E::Scalar],
+) -> Result<(), NovaError> {
+  if comms.is_empty() || comms.len() != points.len() || comms.len() != evals.len() {
+    return Err(NovaError::InvalidInputLength);
+  }
+  Ok(())
+}
+
+// Returns the longest of `points` if all the others are suffixes of it.
+fn common_point<F: PartialEq + Clone>(points: &[Vec<F>]) -> Option<Vec<F>> {
+  let longest = points.iter().max_by_key(|x| x.len())?;
+  points
+    .iter()
+    .all(|x| longest.ends_with(x))
+    .then(|| longest.clone())
+}
+
+fn instances<E: Engine>(
+  comms: &[Commitment<E>],
+  points: &[Vec<E::Scalar>],
+  evals: &[E::Scalar],
+) -> Vec<PolyEvalInstance<E>> {
+  zip_with!(iter, (comms, points, evals), |c, x, e| PolyEvalInstance {
+    c: *c,
+    x: x.clone(),
+    e: *e,
+  })
+  .collect()
+}
+
 /// Bounds "row" variables of (A, B, C) matrices viewed as 2d multilinear polynomials
 pub fn compute_eval_table_sparse<E: Engine>(
   S: &R1CSShape<E>,
@@ -232,3 +501,46 @@ pub fn compute_eval_table_sparse<E: Engine>(
 
   (A_evals, B_evals, C_evals)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_bisect() {
+    let indices = (0..4).collect::<Vec<_>>();
+
+    // a failing entry is found and reported with its own error
+    let check = |indices: &[usize]| {
+      if indices.contains(&2) {
+        Err(NovaError::ProofVerifyError)
+      } else {
+        Ok(())
+      }
+    };
+    assert_eq!(
+      bisect(&indices, &check),
+      Err(NovaError::BatchVerifyError {
+        index: 2,
+        error: Box::new(NovaError::ProofVerifyError),
+      })
+    );
+
+    // a batch that fails although its halves pass is not accepted
+    let check = |indices: &[usize]| {
+      if indices.len() == 4 {
+        Err(NovaError::ProofVerifyError)
+      } else {
+        Ok(())
+      }
+    };
+    assert_eq!(
+      bisect(&indices, &check),
+      Err(NovaError::BatchRangeVerifyError {
+        start: 0,
+        end: 4,
+        error: Box::new(NovaError::ProofVerifyError),
+      })
+    );
+  }
+}
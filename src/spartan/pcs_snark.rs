@@ -0,0 +1,399 @@
+//! This module implements a Spartan SNARK for R1CS that is generic over a `PCSEngineTrait`, i.e.,
+//! over polynomial commitment schemes whose commitments cannot be folded, such as the Merkle
+//! roots of `provider::basefold`. Without folding there is no relaxed instance to prove: like
+//! `direct::DirectSNARK`, it proves a circuit directly. The witness is committed with the scheme,
+//! and Spartan's outer and inner sum-checks reduce the satisfiability of the circuit's R1CS
+//! instance to a single evaluation of the committed witness, which the scheme then proves.
+use crate::{
+  bellpepper::{r1cs::NovaShape, shape_cs::ShapeCS, solver::SatisfyingAssignment},
+  digest::{DigestComputer, SimpleDigestible},
+  errors::NovaError,
+  r1cs::{R1CSShape, SparseMatrix},
+  spartan::{
+    compute_eval_table_sparse,
+    direct::pad_inputs,
+    polys::{eq::EqPolynomial, multilinear::MultilinearPolynomial, multilinear::SparsePolynomial},
+    sumcheck::SumcheckProof,
+  },
+  traits::{pcs::PCSEngineTrait, Engine, TranscriptEngineTrait},
+};
+use bellpepper_core::{Circuit, ConstraintSystem};
+use core::marker::PhantomData;
+use ff::Field;
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A type that holds the prover key
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProverKey<E: Engine, PCS: PCSEngineTrait<E>> {
+  S: R1CSShape<E>, // padded
+  ck: PCS::CommitmentKey,
+  num_io: usize,
+  vk_digest: E::Scalar,
+}
+
+/// A type that holds the verifier key
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerifierKey<E: Engine, PCS: PCSEngineTrait<E>> {
+  S: R1CSShape<E>, // padded
+  vk: PCS::VerifierKey,
+  num_io: usize,
+  #[serde(skip, default = "OnceCell::new")]
+  digest: OnceCell<E::Scalar>,
+}
+
+impl<E: Engine, PCS: PCSEngineTrait<E>> SimpleDigestible for VerifierKey<E, PCS> {}
+
+impl<E: Engine, PCS: PCSEngineTrait<E>> VerifierKey<E, PCS> {
+  /// Returns the digest of the verifier's key
+  pub fn digest(&self) -> E::Scalar {
+    self
+      .digest
+      .get_or_try_init(|| {
+        let dc = DigestComputer::<E::Scalar, _>::new(self);
+        dc.digest()
+      })
+      .cloned()
+      .expect("Failure to retrieve digest!")
+  }
+
+  /// Returns the number of public inputs declared by the circuit
+  pub fn num_io(&self) -> usize {
+    self.num_io
+  }
+}
+
+/// A proof of satisfiability of a circuit, whose witness is committed with `PCS`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct R1CSSNARK<E, PCS, C>
+where
+  E: Engine,
+  PCS: PCSEngineTrait<E>,
+  C: Circuit<E::Scalar>,
+{
+  comm_W: PCS::Commitment,
+  sc_proof_outer: SumcheckProof<E>,
+  claims_outer: (E::Scalar, E::Scalar, E::Scalar),
+  sc_proof_inner: SumcheckProof<E>,
+  eval_W: E::Scalar,
+  eval_arg: PCS::EvaluationArgument,
+  _p: PhantomData<C>,
+}
+
+impl<E, PCS, C> R1CSSNARK<E, PCS, C>
+where
+  E: Engine,
+  PCS: PCSEngineTrait<E>,
+  C: Circuit<E::Scalar>,
+{
+  /// Produces prover and verifier keys from an instance of the circuit that need not carry any
+  /// witness values
+  pub fn setup(circuit: C) -> Result<(ProverKey<E, PCS>, VerifierKey<E, PCS>), NovaError> {
+    let mut cs: ShapeCS<E> = ShapeCS::new();
+    circuit
+      .synthesize(&mut cs)
+      .map_err(|_e| NovaError::SynthesisError)?;
+
+    // Don't count One as a public input
+    let num_io = cs.num_inputs() - 1;
+    pad_inputs(&mut cs, num_io)?;
+    let S = cs.r1cs_shape().pad();
+    if !S.is_regular_shape() {
+      return Err(NovaError::InternalError);
+    }
+
+    let (ck, vk) = PCS::setup(b"ck", S.num_vars)?;
+    let vk = VerifierKey {
+      S: S.clone(),
+      vk,
+      num_io,
+      digest: OnceCell::new(),
+    };
+    let pk = ProverKey {
+      S,
+      ck,
+      num_io,
+      vk_digest: vk.digest(),
+    };
+
+    Ok((pk, vk))
+  }
+
+  /// Produces a proof of satisfiability of the provided circuit, whose public inputs must be `io`
+  pub fn prove(pk: &ProverKey<E, PCS>, circuit: C, io: &[E::Scalar]) -> Result<Self, NovaError> {
+    if io.len() != pk.num_io {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    let mut cs = SatisfyingAssignment::<E>::new();
+    circuit
+      .synthesize(&mut cs)
+      .map_err(|_e| NovaError::SynthesisError)?;
+    pad_inputs(&mut cs, pk.num_io)?;
+
+    let S = &pk.S;
+    let (input_assignment, mut W) = cs.to_assignments();
+    let X = input_assignment[1..].to_owned();
+
+    // the circuit must declare the claimed public inputs, and the witness fits in the shape
+    if X.len() != S.num_io || X[..pk.num_io] != io[..] || W.len() > S.num_vars {
+      return Err(NovaError::UnSat);
+    }
+    W.resize(S.num_vars, E::Scalar::ZERO);
+
+    let comm_W = PCS::commit(&pk.ck, &W)?;
+
+    let mut transcript = E::TE::new(b"R1CSSNARK");
+    transcript.absorb(b"vk", &pk.vk_digest);
+    transcript.absorb(b"C", &comm_W);
+    transcript.absorb(b"X", &X.as_slice());
+
+    // the full assignment z = (W, 1, X)
+    let mut z = [W.clone(), vec![E::Scalar::ONE], X].concat();
+
+    let (num_rounds_x, num_rounds_y) = (
+      usize::try_from(S.num_cons.ilog2()).unwrap(),
+      (usize::try_from(S.num_vars.ilog2()).unwrap() + 1),
+    );
+
+    // outer sum-check
+    let tau = (0..num_rounds_x)
+      .map(|_i| transcript.squeeze(b"t"))
+      .collect::<Result<EqPolynomial<_>, NovaError>>()?;
+
+    let (poly_Az, poly_Bz, poly_Cz) = S.multiply_vec(&z)?;
+
+    // tau * (Az * Bz - Cz)
+    let comb_func_outer =
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - comps[3]) };
+    let (sc_proof_outer, r_x, claims_outer) = SumcheckProof::prove(
+      &E::Scalar::ZERO, // claim is zero
+      num_rounds_x,
+      vec![
+        MultilinearPolynomial::new(tau.evals()),
+        MultilinearPolynomial::new(poly_Az),
+        MultilinearPolynomial::new(poly_Bz),
+        MultilinearPolynomial::new(poly_Cz),
+      ],
+      3,
+      comb_func_outer,
+      &mut transcript,
+    )?;
+    let (claim_Az, claim_Bz, claim_Cz) = (claims_outer[1], claims_outer[2], claims_outer[3]);
+    transcript.absorb(b"claims_outer", &[claim_Az, claim_Bz, claim_Cz].as_slice());
+
+    // inner sum-check
+    let r = transcript.squeeze(b"r")?;
+    let claim_inner_joint = claim_Az + r * claim_Bz + r * r * claim_Cz;
+
+    let poly_ABC = {
+      let evals_rx = EqPolynomial::evals_from_points(&r_x);
+      let (evals_A, evals_B, evals_C) = compute_eval_table_sparse(S, &evals_rx);
+      (0..evals_A.len())
+        .into_par_iter()
+        .map(|i| evals_A[i] + r * evals_B[i] + r * r * evals_C[i])
+        .collect::<Vec<E::Scalar>>()
+    };
+    z.resize(S.num_vars * 2, E::Scalar::ZERO);
+
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
+    let (sc_proof_inner, r_y, _claims_inner) = SumcheckProof::prove(
+      &claim_inner_joint,
+      num_rounds_y,
+      vec![
+        MultilinearPolynomial::new(poly_ABC),
+        MultilinearPolynomial::new(z),
+      ],
+      2,
+      comb_func,
+      &mut transcript,
+    )?;
+
+    // prove the evaluation of the witness the inner sum-check ends with
+    let eval_W = MultilinearPolynomial::evaluate_with(&W, &r_y[1..]);
+    transcript.absorb(b"e", &eval_W);
+    let eval_arg = PCS::prove(&pk.ck, &mut transcript, &comm_W, &W, &r_y[1..], &eval_W)?;
+
+    Ok(R1CSSNARK {
+      comm_W,
+      sc_proof_outer,
+      claims_outer: (claim_Az, claim_Bz, claim_Cz),
+      sc_proof_inner,
+      eval_W,
+      eval_arg,
+      _p: PhantomData,
+    })
+  }
+
+  /// Verifies a proof of satisfiability with the public inputs `io`
+  pub fn verify(&self, vk: &VerifierKey<E, PCS>, io: &[E::Scalar]) -> Result<(), NovaError> {
+    if io.len() != vk.num_io {
+      return Err(NovaError::InvalidInputLength);
+    }
+    let S = &vk.S;
+
+    // the public inputs, padded as in the shape
+    let mut X = io.to_vec();
+    X.resize(S.num_io, E::Scalar::ZERO);
+
+    let mut transcript = E::TE::new(b"R1CSSNARK");
+    transcript.absorb(b"vk", &vk.digest());
+    transcript.absorb(b"C", &self.comm_W);
+    transcript.absorb(b"X", &X.as_slice());
+
+    let (num_rounds_x, num_rounds_y) = (
+      usize::try_from(S.num_cons.ilog2()).unwrap(),
+      (usize::try_from(S.num_vars.ilog2()).unwrap() + 1),
+    );
+
+    // outer sum-check
+    let tau = (0..num_rounds_x)
+      .map(|_i| transcript.squeeze(b"t"))
+      .collect::<Result<EqPolynomial<_>, NovaError>>()?;
+
+    let (claim_outer_final, r_x) =
+      self
+        .sc_proof_outer
+        .verify(E::Scalar::ZERO, num_rounds_x, 3, &mut transcript)?;
+
+    // verify claim_outer_final
+    let (claim_Az, claim_Bz, claim_Cz) = self.claims_outer;
+    if claim_outer_final != tau.evaluate(&r_x) * (claim_Az * claim_Bz - claim_Cz) {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+    transcript.absorb(b"claims_outer", &[claim_Az, claim_Bz, claim_Cz].as_slice());
+
+    // inner sum-check
+    let r = transcript.squeeze(b"r")?;
+    let claim_inner_joint = claim_Az + r * claim_Bz + r * r * claim_Cz;
+
+    let (claim_inner_final, r_y) =
+      self
+        .sc_proof_inner
+        .verify(claim_inner_joint, num_rounds_y, 2, &mut transcript)?;
+
+    // verify claim_inner_final
+    let eval_Z = {
+      let eval_X = {
+        // constant term
+        let mut poly_X = vec![(0, E::Scalar::ONE)];
+        // remaining inputs
+        poly_X.extend(X.iter().enumerate().map(|(i, x)| (i + 1, *x)));
+        SparsePolynomial::new(usize::try_from(S.num_vars.ilog2()).unwrap(), poly_X)
+          .evaluate(&r_y[1..])
+      };
+      (E::Scalar::ONE - r_y[0]) * self.eval_W + r_y[0] * eval_X
+    };
+
+    let (T_x, T_y) = rayon::join(
+      || EqPolynomial::evals_from_points(&r_x),
+      || EqPolynomial::evals_from_points(&r_y),
+    );
+    // evaluations of the R1CS matrices
+    let evaluate_with_table = |M: &SparseMatrix<E::Scalar>| -> E::Scalar {
+      M.indptr
+        .par_windows(2)
+        .enumerate()
+        .map(|(row_idx, ptrs)| {
+          M.get_row_unchecked(ptrs.try_into().unwrap())
+            .map(|(val, col_idx)| T_x[row_idx] * T_y[*col_idx] * val)
+            .sum::<E::Scalar>()
+        })
+        .sum()
+    };
+    let evals = [&S.A, &S.B, &S.C]
+      .into_par_iter()
+      .map(evaluate_with_table)
+      .collect::<Vec<_>>();
+
+    if claim_inner_final != (evals[0] + r * evals[1] + r * r * evals[2]) * eval_Z {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    // verify the evaluation of the witness
+    transcript.absorb(b"e", &self.eval_W);
+    PCS::verify(
+      &vk.vk,
+      &mut transcript,
+      &self.comm_W,
+      &r_y[1..],
+      &self.eval_W,
+      &self.eval_arg,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::provider::{
+    basefold::BasefoldEngine, hyrax_pc::HyraxEngine, Bn256Engine, PallasEngine, Secp256k1Engine,
+  };
+  use bellpepper_core::{num::AllocatedNum, SynthesisError};
+  use ff::PrimeField;
+
+  // proves knowledge of y = x^3 + x + 5, with x and y public
+  #[derive(Clone)]
+  struct CubicCircuit<F: PrimeField> {
+    x: F,
+  }
+
+  impl<F: PrimeField> Circuit<F> for CubicCircuit<F> {
+    fn synthesize<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+      let x = AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(self.x))?;
+      let x_sq = x.square(cs.namespace(|| "x_sq"))?;
+      let x_cu = x_sq.mul(cs.namespace(|| "x_cu"), &x)?;
+      let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+        Ok(x_cu.get_value().unwrap() + self.x + F::from(5u64))
+      })?;
+      cs.enforce(
+        || "y = x^3 + x + 5",
+        |lc| lc + x_cu.get_variable() + x.get_variable() + (F::from(5u64), CS::one()),
+        |lc| lc + CS::one(),
+        |lc| lc + y.get_variable(),
+      );
+      x.inputize(cs.namespace(|| "input x"))?;
+      y.inputize(cs.namespace(|| "input y"))?;
+      Ok(())
+    }
+  }
+
+  fn test_pcs_snark_with<E: Engine, PCS: PCSEngineTrait<E>>() {
+    let circuit = CubicCircuit {
+      x: E::Scalar::from(3u64),
+    };
+    let io = [E::Scalar::from(3u64), E::Scalar::from(35u64)];
+
+    let (pk, vk) = R1CSSNARK::<E, PCS, _>::setup(circuit.clone()).unwrap();
+    let proof = R1CSSNARK::prove(&pk, circuit.clone(), &io).unwrap();
+    assert!(proof.verify(&vk, &io).is_ok());
+
+    // the proof does not verify with other public inputs
+    let wrong_io = [E::Scalar::from(3u64), E::Scalar::from(36u64)];
+    assert!(proof.verify(&vk, &wrong_io).is_err());
+
+    // and the prover refuses to prove inputs the circuit does not declare
+    assert!(R1CSSNARK::prove(&pk, circuit, &wrong_io).is_err());
+
+    // a tampered evaluation of the witness is rejected
+    let mut bad_proof = proof.clone();
+    bad_proof.eval_W += E::Scalar::ONE;
+    assert!(bad_proof.verify(&vk, &io).is_err());
+  }
+
+  #[test]
+  fn test_pcs_snark_basefold() {
+    test_pcs_snark_with::<PallasEngine, BasefoldEngine<PallasEngine>>();
+    test_pcs_snark_with::<Bn256Engine, BasefoldEngine<Bn256Engine>>();
+  }
+
+  #[test]
+  fn test_pcs_snark_hyrax() {
+    test_pcs_snark_with::<PallasEngine, HyraxEngine<PallasEngine>>();
+    test_pcs_snark_with::<Secp256k1Engine, HyraxEngine<Secp256k1Engine>>();
+  }
+}
@@ -9,7 +9,7 @@ use itertools::Itertools as _;
 use rand_core::{CryptoRng, RngCore};
 use rayon::prelude::{
   IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
-  IntoParallelRefMutIterator, ParallelIterator,
+  IntoParallelRefMutIterator, ParallelIterator, ParallelSlice, ParallelSliceMut,
 };
 use serde::{Deserialize, Serialize};
 
@@ -90,7 +90,39 @@ impl<Scalar: PrimeField> MultilinearPolynomial<Scalar> {
         *a += *r * (*b - *a);
       });
 
-    self.Z.resize(n, Scalar::ZERO);
+    self.Z.truncate(n);
+    self.num_vars -= 1;
+  }
+
+  /// Binds the polynomial's bottom variable using the given scalar.
+  ///
+  /// This operation modifies the polynomial in-place, reusing its allocation. The sum-check
+  /// provers bind the top variable instead, since their verifiers, and the claims they reduce,
+  /// take the first challenge as the top variable of the point; binding the bottom one would
+  /// reverse that point and change the proofs. Binding from the bottom serves reductions that
+  /// fold adjacent evaluations, such as HyperKZG's.
+  pub fn bind_poly_var_bot(&mut self, r: &Scalar) {
+    assert!(self.num_vars > 0);
+
+    let n = self.len() / 2;
+
+    // the evaluation folded from the pair at 2i and 2i + 1 goes to index i: the one at index 0
+    // is folded first, and then those at [k, 2k) for k = 1, 2, 4, ..., which are computed from
+    // the pairs at [2k, 4k), in parallel, since these are only overwritten in later steps
+    self.Z[0] += *r * (self.Z[1] - self.Z[0]);
+    let mut k = 1;
+    while k < n {
+      let (dst, src) = self.Z.split_at_mut(2 * k);
+      dst[k..]
+        .par_iter_mut()
+        .zip_eq(src[..2 * k].par_chunks(2))
+        .for_each(|(a, pair)| {
+          *a = pair[0] + *r * (pair[1] - pair[0]);
+        });
+      k *= 2;
+    }
+
+    self.Z.truncate(n);
     self.num_vars -= 1;
   }
 
@@ -101,25 +133,44 @@ impl<Scalar: PrimeField> MultilinearPolynomial<Scalar> {
   pub fn evaluate(&self, r: &[Scalar]) -> Scalar {
     // r must have a value for each variable
     assert_eq!(r.len(), self.get_num_vars());
-    let chis = EqPolynomial::evals_from_points(r);
-
-    zip_with!(
-      (chis.into_par_iter(), self.Z.par_iter()),
-      |chi_i, Z_i| chi_i * Z_i
-    )
-    .sum()
+    Self::evaluate_with(&self.Z, r)
   }
 
   /// Evaluates the polynomial with the given evaluations and point.
+  ///
+  /// Rather than the table of eq(r, ·) over all the 2^|r| inputs, this materializes the tables
+  /// of its two halves eq(r_hi, ·) and eq(r_lo, ·), whose products are its entries, in O(√n) space.
   pub fn evaluate_with(Z: &[Scalar], r: &[Scalar]) -> Scalar {
-    zip_with!(
-      (
-        EqPolynomial::evals_from_points(r).into_par_iter(),
-        Z.par_iter()
-      ),
-      |a, b| a * b
-    )
-    .sum()
+    Self::evaluate_batch(&[Z], r)[0]
+  }
+
+  /// Evaluates many polynomials, given by their evaluations, at the same point, sharing the
+  /// tables of eq(r, ·) between them as in `evaluate_with`.
+  pub fn evaluate_batch(Z_vec: &[&[Scalar]], r: &[Scalar]) -> Vec<Scalar> {
+    // the top variables index blocks of evaluations, and the bottom ones index within blocks
+    let (r_hi, r_lo) = r.split_at(r.len() / 2);
+    let (chis_hi, chis_lo) = rayon::join(
+      || EqPolynomial::evals_from_points(r_hi),
+      || EqPolynomial::evals_from_points(r_lo),
+    );
+
+    Z_vec
+      .par_iter()
+      .map(|Z| {
+        // the evaluations of a polynomial are exactly as many as the points of the domain
+        assert_eq!(Z.len(), chis_hi.len() * chis_lo.len());
+
+        zip_with!(
+          (chis_hi.par_iter(), Z.par_chunks(chis_lo.len())),
+          |chi_hi, block| {
+            *chi_hi
+              * zip_with!((chis_lo.iter(), block.iter()), |chi_lo, Z_i| *chi_lo * Z_i)
+                .sum::<Scalar>()
+          }
+        )
+        .sum::<Scalar>()
+      })
+      .collect()
   }
 }
 
@@ -411,4 +462,48 @@ mod tests {
     partial_and_evaluate_with::<bn256::Scalar>();
     partial_and_evaluate_with::<secp256k1::Scalar>();
   }
+
+  fn bind_and_evaluate_with<F: PrimeField>() {
+    let n = 7;
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+    let poly = MultilinearPolynomial::random(n, &mut rng);
+    let pt: Vec<_> = std::iter::from_fn(|| Some(F::random(&mut rng)))
+      .take(n)
+      .collect();
+    let eval = poly.evaluate(&pt);
+
+    // the evaluations with the full table of eq(pt, ·)
+    let chis = EqPolynomial::evals_from_points(&pt);
+    let eval_expected = zip_with!(iter, (chis, poly.Z), |chi, Z_i| *chi * Z_i).sum::<F>();
+    assert_eq!(eval, eval_expected);
+
+    // binding the top variable to the first coordinate
+    let mut poly_top = poly.clone();
+    poly_top.bind_poly_var_top(&pt[0]);
+    assert_eq!(poly_top.get_num_vars(), n - 1);
+    assert_eq!(poly_top.evaluate(&pt[1..]), eval);
+
+    // binding the bottom variables to the last coordinates, down to a constant
+    let mut poly_bot = poly.clone();
+    for i in (0..n).rev() {
+      poly_bot.bind_poly_var_bot(&pt[i]);
+      assert_eq!(poly_bot.get_num_vars(), i);
+      assert_eq!(poly_bot.evaluate(&pt[..i]), eval);
+    }
+    assert_eq!(poly_bot.Z, vec![eval]);
+
+    // evaluating many polynomials at once
+    let other = MultilinearPolynomial::random(n, &mut rng);
+    assert_eq!(
+      MultilinearPolynomial::evaluate_batch(&[poly.Z.as_slice(), other.Z.as_slice()], &pt),
+      vec![eval, other.evaluate(&pt)]
+    );
+  }
+
+  #[test]
+  fn test_bind_and_evaluate() {
+    bind_and_evaluate_with::<Fp>();
+    bind_and_evaluate_with::<bn256::Scalar>();
+    bind_and_evaluate_with::<secp256k1::Scalar>();
+  }
 }
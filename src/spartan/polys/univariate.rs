@@ -84,9 +84,15 @@ impl<Scalar: PrimeField> UniPoly<Scalar> {
     self.coeffs.last()
   }
 
+  /// Interpolates the polynomial of degree `evals.len() - 1` whose evaluations at
+  /// `0, 1, ..., evals.len() - 1` are `evals`.
   pub fn from_evals(evals: &[Scalar]) -> Self {
-    // we only support degree-2 or degree-3 univariate polynomials
-    assert!(evals.len() == 3 || evals.len() == 4);
+    assert!(evals.len() >= 2);
+    // degree-2 and degree-3 polynomials have dedicated formulas, other degrees
+    // go through the generic interpolation
+    if evals.len() != 3 && evals.len() != 4 {
+      return Self::from_evals_generic(evals);
+    }
     let two_inv = Scalar::from(2).invert().unwrap();
     let coeffs = if evals.len() == 3 {
       // ax^2 + bx + c
@@ -115,6 +121,39 @@ impl<Scalar: PrimeField> UniPoly<Scalar> {
     UniPoly { coeffs }
   }
 
+  // Newton interpolation over the nodes 0, 1, ..., n - 1:
+  // p(x) = ∑ₖ Δᵏp(0)/k! ⋅ x(x - 1)⋯(x - k + 1)
+  fn from_evals_generic(evals: &[Scalar]) -> Self {
+    let mut coeffs = vec![Scalar::ZERO; evals.len()];
+    // the forward differences Δᵏp(j), for j = 0, ..., n - k - 1
+    let mut diffs = evals.to_vec();
+    // the coefficients of x(x - 1)⋯(x - k + 1)
+    let mut basis = vec![Scalar::ONE];
+    let mut factorial = Scalar::ONE;
+
+    for k in 0..evals.len() {
+      if k > 0 {
+        factorial *= Scalar::from(k as u64);
+      }
+      let scale = diffs[0] * factorial.invert().unwrap();
+      for (j, b) in basis.iter().enumerate() {
+        coeffs[j] += scale * b;
+      }
+
+      diffs = diffs.windows(2).map(|w| w[1] - w[0]).collect();
+
+      // multiply the basis polynomial by (x - k)
+      let k = Scalar::from(k as u64);
+      basis.push(Scalar::ZERO);
+      for j in (1..basis.len()).rev() {
+        basis[j] = basis[j - 1] - k * basis[j];
+      }
+      basis[0] *= -k;
+    }
+
+    UniPoly { coeffs }
+  }
+
   pub fn degree(&self) -> usize {
     self.coeffs.len() - 1
   }
@@ -150,6 +189,10 @@ impl<Scalar: PrimeField> UniPoly<Scalar> {
 }
 
 impl<Scalar: PrimeField> CompressedUniPoly<Scalar> {
+  pub(crate) fn coeffs_except_linear_term(&self) -> &[Scalar] {
+    &self.coeffs_except_linear_term
+  }
+
   // we require eval(0) + eval(1) = hint, so we can solve for the linear term as:
   // linear_term = hint - 2 * constant_term - deg2 term - deg3 term
   pub fn decompress(&self, hint: &Scalar) -> UniPoly<Scalar> {
@@ -176,6 +219,10 @@ impl<G: Group> TranscriptReprTrait<G> for UniPoly<G::Scalar> {
       .flat_map(|&t| t.to_repr().as_ref().to_vec())
       .collect::<Vec<u8>>()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<G::Scalar> {
+    self.compress().coeffs_except_linear_term
+  }
 }
 
 impl<Scalar: PrimeField> Index<usize> for UniPoly<Scalar> {
@@ -300,4 +347,42 @@ mod tests {
     test_from_evals_cubic_with::<bn256_grumpkin::bn256::Scalar>();
     test_from_evals_cubic_with::<secp256k1::Scalar>()
   }
+
+  fn test_from_evals_generic_with<F: PrimeField>() {
+    // polynomial is 2x^5 + x^4 + 3x^2 + 5x + 7
+    let coeffs = [7, 5, 3, 0, 1, 2].map(F::from);
+    let eval = |x: u64| {
+      coeffs
+        .iter()
+        .rev()
+        .fold(F::ZERO, |acc, coeff| acc * F::from(x) + coeff)
+    };
+    let evals = (0..6).map(eval).collect::<Vec<_>>();
+    let poly = UniPoly::from_evals(&evals);
+    assert_eq!(poly.coeffs, coeffs.to_vec());
+
+    let hint = evals[0] + evals[1];
+    let decompressed_poly = poly.compress().decompress(&hint);
+    assert_eq!(decompressed_poly, poly);
+
+    assert_eq!(poly.evaluate(&F::from(9)), eval(9));
+
+    // a line: 4x + 1
+    let poly = UniPoly::from_evals(&[F::ONE, F::from(5)]);
+    assert_eq!(poly.coeffs, vec![F::ONE, F::from(4)]);
+
+    // the generic interpolation agrees with the dedicated formulas
+    let evals = [F::ONE, F::from(7), F::from(23), F::from(55)];
+    assert_eq!(
+      UniPoly::from_evals_generic(&evals),
+      UniPoly::from_evals(&evals)
+    );
+  }
+
+  #[test]
+  fn test_from_evals_generic() {
+    test_from_evals_generic_with::<pasta_curves::pallas::Scalar>();
+    test_from_evals_generic_with::<bn256_grumpkin::bn256::Scalar>();
+    test_from_evals_generic_with::<secp256k1::Scalar>()
+  }
 }
@@ -8,6 +8,8 @@ use crate::{
   errors::NovaError,
   r1cs::{R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness},
   spartan::{
+    batch_eval_arg_verify, batch_invert,
+    lookup::{LookupColumn, LookupProof},
     math::Math,
     polys::{
       eq::EqPolynomial,
@@ -23,7 +25,7 @@ use crate::{
   traits::{
     commitment::{CommitmentEngineTrait, CommitmentTrait, Len},
     evaluation::EvaluationEngineTrait,
-    snark::{DigestHelperTrait, RelaxedR1CSSNARKTrait},
+    snark::{DigestHelperTrait, RelaxedR1CSSNARKTrait, SetupCache},
     Engine, TranscriptEngineTrait, TranscriptReprTrait,
   },
   zip_with, Commitment, CommitmentKey, CompressedCommitment,
@@ -34,6 +36,7 @@ use core::cmp::max;
 use ff::{Field, PrimeField};
 use itertools::Itertools as _;
 use once_cell::sync::OnceCell;
+use rand::rngs::OsRng;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -92,6 +95,16 @@ pub struct R1CSShapeSparkCommitment<E: Engine> {
 
 impl<E: Engine> TranscriptReprTrait<E::GE> for R1CSShapeSparkCommitment<E> {
   fn to_transcript_bytes(&self) -> Vec<u8> {
+    self.comms().as_slice().to_transcript_bytes()
+  }
+
+  fn to_transcript_scalars(&self) -> Vec<E::Scalar> {
+    self.comms().as_slice().to_transcript_scalars()
+  }
+}
+
+impl<E: Engine> R1CSShapeSparkCommitment<E> {
+  fn comms(&self) -> [Commitment<E>; 7] {
     [
       self.comm_row,
       self.comm_col,
@@ -101,8 +114,6 @@ impl<E: Engine> TranscriptReprTrait<E::GE> for R1CSShapeSparkCommitment<E> {
       self.comm_ts_row,
       self.comm_ts_col,
     ]
-    .as_slice()
-    .to_transcript_bytes()
   }
 }
 
@@ -183,8 +194,9 @@ impl<E: Engine> R1CSShapeSparkRepr<E> {
     }
   }
 
-  pub(in crate::spartan) fn commit(&self, ck: &CommitmentKey<E>) -> R1CSShapeSparkCommitment<E> {
-    let comm_vec: Vec<Commitment<E>> = [
+  // returns the vectors of the representation, in the order of their commitments
+  fn vecs(&self) -> [&Vec<E::Scalar>; 7] {
+    [
       &self.row,
       &self.col,
       &self.val_A,
@@ -193,9 +205,14 @@ impl<E: Engine> R1CSShapeSparkRepr<E> {
       &self.ts_row,
       &self.ts_col,
     ]
-    .par_iter()
-    .map(|v| E::CE::commit(ck, v))
-    .collect();
+  }
+
+  pub(in crate::spartan) fn commit(&self, ck: &CommitmentKey<E>) -> R1CSShapeSparkCommitment<E> {
+    let comm_vec: Vec<Commitment<E>> = self
+      .vecs()
+      .par_iter()
+      .map(|v| E::CE::commit(ck, v))
+      .collect();
 
     R1CSShapeSparkCommitment {
       N: self.row.len(),
@@ -221,31 +238,171 @@ impl<E: Engine> R1CSShapeSparkRepr<E> {
     Vec<E::Scalar>,
     Vec<E::Scalar>,
   ) {
+    let mut L_row = Vec::with_capacity(self.N);
+    let mut L_col = Vec::with_capacity(self.N);
+    let (mem_row, mem_col) = self.evaluation_oracles_into(S, r_x, z, &mut L_row, &mut L_col);
+
+    (mem_row, mem_col, L_row, L_col)
+  }
+
+  // computes the same oracles as `evaluation_oracles`, writing L_row and L_col into the given
+  // vectors to reuse their allocations
+  fn evaluation_oracles_into(
+    &self,
+    S: &R1CSShape<E>,
+    r_x: &E::Scalar,
+    z: &[E::Scalar],
+    L_row: &mut Vec<E::Scalar>,
+    L_col: &mut Vec<E::Scalar>,
+  ) -> (Vec<E::Scalar>, Vec<E::Scalar>) {
     let mem_row = PowPolynomial::new(r_x, self.N.log_2()).evals();
     let mem_col = padded::<E>(z, self.N, &E::Scalar::ZERO);
 
-    let (L_row, L_col) = {
-      let mut L_row = vec![mem_row[0]; self.N]; // we place mem_row[0] since resized row is appended with 0s
-      let mut L_col = vec![mem_col[self.N - 1]; self.N]; // we place mem_col[N-1] since resized col is appended with N-1
+    L_row.clear();
+    L_row.resize(self.N, mem_row[0]); // we place mem_row[0] since resized row is appended with 0s
+    L_col.clear();
+    L_col.resize(self.N, mem_col[self.N - 1]); // we place mem_col[N-1] since resized col is appended with N-1
+
+    for (i, (val_r, val_c)) in S
+      .A
+      .iter()
+      .chain(S.B.iter())
+      .chain(S.C.iter())
+      .map(|(r, c, _)| (mem_row[r], mem_col[c]))
+      .enumerate()
+    {
+      L_row[i] = val_r;
+      L_col[i] = val_c;
+    }
+
+    (mem_row, mem_col)
+  }
+}
+
+/// The Spark representation of an `R1CSShape` and the commitments to it, whose computation
+/// dominates the cost of setting up the SNARK, together with the digests of the shape and of the
+/// commitment key they were computed for. It can be persisted and reused by later setups.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct R1CSShapeSparkCache<E: Engine> {
+  shape_digest: E::Scalar,
+  ck_digest: E::Scalar,
+  S_repr: R1CSShapeSparkRepr<E>,
+  S_comm: R1CSShapeSparkCommitment<E>,
+}
+
+impl<E: Engine> R1CSShapeSparkCache<E> {
+  /// Computes the Spark representation of `S` and commits to it using `ck`, where
+  /// `shape_digest` and `ck_digest` are the digests of `S` and `ck`
+  pub fn new(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+    shape_digest: E::Scalar,
+    ck_digest: E::Scalar,
+  ) -> Self {
+    let S_repr = R1CSShapeSparkRepr::new(&S.pad());
+    let S_comm = S_repr.commit(ck);
+
+    Self {
+      shape_digest,
+      ck_digest,
+      S_repr,
+      S_comm,
+    }
+  }
+
+  /// Returns the digests of `S` and `ck`, which identify the data computed for them
+  pub fn digests(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+  ) -> Result<(E::Scalar, E::Scalar), NovaError> {
+    Ok((S.digest(), ck_digest::<E>(ck)?))
+  }
 
-      for (i, (val_r, val_c)) in S
-        .A
+  /// Returns the key under which the data computed for the shape and the commitment key of
+  /// digests `shape_digest` and `ck_digest` is stored in a `SetupCache`
+  pub fn cache_key(shape_digest: E::Scalar, ck_digest: E::Scalar) -> String {
+    let to_hex = |digest: E::Scalar| -> String {
+      digest
+        .to_repr()
+        .as_ref()
         .iter()
-        .chain(S.B.iter())
-        .chain(S.C.iter())
-        .map(|(r, c, _)| (mem_row[r], mem_col[c]))
-        .enumerate()
-      {
-        L_row[i] = val_r;
-        L_col[i] = val_c;
-      }
-      (L_row, L_col)
+        .map(|b| format!("{b:02x}"))
+        .collect()
     };
+    format!("spark_{}_{}", to_hex(shape_digest), to_hex(ck_digest))
+  }
 
-    (mem_row, mem_col, L_row, L_col)
+  /// Checks that the data was computed for `S` and `ck`, whose digests are `shape_digest` and
+  /// `ck_digest`. The Spark representation is cheap to recompute, and the commitments are
+  /// checked against a single commitment to a random linear combination of the vectors they
+  /// commit to.
+  pub fn check(
+    &self,
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+    shape_digest: E::Scalar,
+    ck_digest: E::Scalar,
+  ) -> Result<(), NovaError> {
+    if self.shape_digest != shape_digest || self.ck_digest != ck_digest {
+      return Err(NovaError::SetupCacheError);
+    }
+
+    let S_repr = R1CSShapeSparkRepr::<E>::new(&S.pad());
+    if self.S_repr.N != S_repr.N || self.S_comm.N != S_repr.N || self.S_repr.vecs() != S_repr.vecs()
+    {
+      return Err(NovaError::SetupCacheError);
+    }
+
+    let comms = [
+      self.S_comm.comm_row,
+      self.S_comm.comm_col,
+      self.S_comm.comm_val_A,
+      self.S_comm.comm_val_B,
+      self.S_comm.comm_val_C,
+      self.S_comm.comm_ts_row,
+      self.S_comm.comm_ts_col,
+    ];
+
+    let powers_of_r = powers::<E>(&E::Scalar::random(OsRng), comms.len());
+    let comm_joint = comms
+      .into_iter()
+      .zip_eq(powers_of_r.iter())
+      .map(|(comm, r)| comm * *r)
+      .reduce(|acc, comm| acc + comm)
+      .unwrap();
+    let vecs = S_repr.vecs();
+    let vec_joint = (0..S_repr.N)
+      .into_par_iter()
+      .map(|i| {
+        vecs
+          .iter()
+          .zip_eq(powers_of_r.iter())
+          .map(|(v, r)| v[i] * r)
+          .sum::<E::Scalar>()
+      })
+      .collect::<Vec<E::Scalar>>();
+
+    if E::CE::commit(ck, &vec_joint) != comm_joint {
+      return Err(NovaError::SetupCacheError);
+    }
+
+    Ok(())
   }
 }
 
+// Computes the digest of a commitment key
+fn ck_digest<E: Engine>(ck: &CommitmentKey<E>) -> Result<E::Scalar, NovaError> {
+  #[derive(Serialize)]
+  #[serde(bound = "")]
+  struct CommitmentKeyRef<'a, E: Engine>(&'a CommitmentKey<E>);
+  impl<E: Engine> SimpleDigestible for CommitmentKeyRef<'_, E> {}
+
+  DigestComputer::<E::Scalar, _>::new(&CommitmentKeyRef::<E>(ck))
+    .digest()
+    .map_err(|_| NovaError::DigestError)
+}
+
 /// Defines a trait for implementing sum-check in a generic manner
 pub trait SumcheckEngine<E: Engine>: Send + Sync {
   /// returns the initial claims
@@ -284,9 +441,6 @@ pub(in crate::spartan) struct MemorySumcheckInstance<E: Engine> {
 
   // eq
   poly_eq: MultilinearPolynomial<E::Scalar>,
-
-  // zero polynomial
-  poly_zero: MultilinearPolynomial<E::Scalar>,
 }
 
 impl<E: Engine> MemorySumcheckInstance<E> {
@@ -323,6 +477,52 @@ impl<E: Engine> MemorySumcheckInstance<E> {
     L_col: &[E::Scalar],
     ts_col: &[E::Scalar],
   ) -> Result<([Commitment<E>; 4], [Vec<E::Scalar>; 4], [Vec<E::Scalar>; 4]), NovaError> {
+    let (poly_vec, aux_poly_vec) = Self::oracles(
+      r, gamma, mem_row, addr_row, L_row, ts_row, mem_col, addr_col, L_col, ts_col,
+    )?;
+
+    let (
+      (comm_t_plus_r_inv_row, comm_w_plus_r_inv_row),
+      (comm_t_plus_r_inv_col, comm_w_plus_r_inv_col),
+    ) = rayon::join(
+      || {
+        rayon::join(
+          || E::CE::commit(ck, &poly_vec[0]),
+          || E::CE::commit(ck, &poly_vec[1]),
+        )
+      },
+      || {
+        rayon::join(
+          || E::CE::commit(ck, &poly_vec[2]),
+          || E::CE::commit(ck, &poly_vec[3]),
+        )
+      },
+    );
+
+    let comm_vec = [
+      comm_t_plus_r_inv_row,
+      comm_w_plus_r_inv_row,
+      comm_t_plus_r_inv_col,
+      comm_w_plus_r_inv_col,
+    ];
+
+    Ok((comm_vec, poly_vec, aux_poly_vec))
+  }
+
+  /// Computes the oracles of `compute_oracles` without committing to them, which lets a prover
+  /// recompute them rather than keep them in memory
+  fn oracles(
+    r: &E::Scalar,
+    gamma: &E::Scalar,
+    mem_row: &[E::Scalar],
+    addr_row: &[E::Scalar],
+    L_row: &[E::Scalar],
+    ts_row: &[E::Scalar],
+    mem_col: &[E::Scalar],
+    addr_col: &[E::Scalar],
+    L_col: &[E::Scalar],
+    ts_col: &[E::Scalar],
+  ) -> Result<([Vec<E::Scalar>; 4], [Vec<E::Scalar>; 4]), NovaError> {
     // hash the tuples of (addr,val) memory contents and read responses into a single field element using `hash_func`
     let hash_func_vec = |mem: &[E::Scalar],
                          addr: &[E::Scalar],
@@ -349,33 +549,6 @@ impl<E: Engine> MemorySumcheckInstance<E> {
       || hash_func_vec(mem_col, addr_col, L_col),
     );
 
-    let batch_invert = |v: &[E::Scalar]| -> Result<Vec<E::Scalar>, NovaError> {
-      let mut products = vec![E::Scalar::ZERO; v.len()];
-      let mut acc = E::Scalar::ONE;
-
-      for i in 0..v.len() {
-        products[i] = acc;
-        acc *= v[i];
-      }
-
-      // we can compute an inversion only if acc is non-zero
-      if acc == E::Scalar::ZERO {
-        return Err(NovaError::InternalError);
-      }
-
-      // compute the inverse once for all entries
-      acc = acc.invert().unwrap();
-
-      let mut inv = vec![E::Scalar::ZERO; v.len()];
-      for i in 0..v.len() {
-        let tmp = acc * v[v.len() - 1 - i];
-        inv[v.len() - 1 - i] = products[v.len() - 1 - i] * acc;
-        acc = tmp;
-      }
-
-      Ok(inv)
-    };
-
     // compute vectors TS[i]/(T[i] + r) and 1/(W[i] + r)
     let helper = |T: &[E::Scalar],
                   W: &[E::Scalar],
@@ -395,7 +568,8 @@ impl<E: Engine> MemorySumcheckInstance<E> {
         || {
           rayon::join(
             || {
-              let inv = batch_invert(&T.par_iter().map(|e| *e + *r).collect::<Vec<E::Scalar>>())?;
+              let inv =
+                batch_invert::<E>(&T.par_iter().map(|e| *e + *r).collect::<Vec<E::Scalar>>())?;
 
               // compute inv[i] * TS[i] in parallel
               Ok(
@@ -403,7 +577,7 @@ impl<E: Engine> MemorySumcheckInstance<E> {
                   .collect::<Vec<_>>(),
               )
             },
-            || batch_invert(&W.par_iter().map(|e| *e + *r).collect::<Vec<E::Scalar>>()),
+            || batch_invert::<E>(&W.par_iter().map(|e| *e + *r).collect::<Vec<E::Scalar>>()),
           )
         },
         || {
@@ -423,46 +597,16 @@ impl<E: Engine> MemorySumcheckInstance<E> {
       || helper(&T_col, &W_col, ts_col, r),
     );
 
-    let t_plus_r_inv_row = t_plus_r_inv_row?;
-    let w_plus_r_inv_row = w_plus_r_inv_row?;
-    let t_plus_r_inv_col = t_plus_r_inv_col?;
-    let w_plus_r_inv_col = w_plus_r_inv_col?;
-
-    let (
-      (comm_t_plus_r_inv_row, comm_w_plus_r_inv_row),
-      (comm_t_plus_r_inv_col, comm_w_plus_r_inv_col),
-    ) = rayon::join(
-      || {
-        rayon::join(
-          || E::CE::commit(ck, &t_plus_r_inv_row),
-          || E::CE::commit(ck, &w_plus_r_inv_row),
-        )
-      },
-      || {
-        rayon::join(
-          || E::CE::commit(ck, &t_plus_r_inv_col),
-          || E::CE::commit(ck, &w_plus_r_inv_col),
-        )
-      },
-    );
-
-    let comm_vec = [
-      comm_t_plus_r_inv_row,
-      comm_w_plus_r_inv_row,
-      comm_t_plus_r_inv_col,
-      comm_w_plus_r_inv_col,
-    ];
-
     let poly_vec = [
-      t_plus_r_inv_row,
-      w_plus_r_inv_row,
-      t_plus_r_inv_col,
-      w_plus_r_inv_col,
+      t_plus_r_inv_row?,
+      w_plus_r_inv_row?,
+      t_plus_r_inv_col?,
+      w_plus_r_inv_col?,
     ];
 
     let aux_poly_vec = [t_plus_r_row?, w_plus_r_row?, t_plus_r_col?, w_plus_r_col?];
 
-    Ok((comm_vec, poly_vec, aux_poly_vec))
+    Ok((poly_vec, aux_poly_vec))
   }
 
   pub fn new(
@@ -475,8 +619,6 @@ impl<E: Engine> MemorySumcheckInstance<E> {
     let [t_plus_r_inv_row, w_plus_r_inv_row, t_plus_r_inv_col, w_plus_r_inv_col] = polys_oracle;
     let [t_plus_r_row, w_plus_r_row, t_plus_r_col, w_plus_r_col] = polys_aux;
 
-    let zero = vec![E::Scalar::ZERO; poly_eq.len()];
-
     Self {
       w_plus_r_row: MultilinearPolynomial::new(w_plus_r_row),
       t_plus_r_row: MultilinearPolynomial::new(t_plus_r_row),
@@ -489,7 +631,6 @@ impl<E: Engine> MemorySumcheckInstance<E> {
       w_plus_r_inv_col: MultilinearPolynomial::new(w_plus_r_inv_col),
       ts_col: MultilinearPolynomial::new(ts_col),
       poly_eq: MultilinearPolynomial::new(poly_eq),
-      poly_zero: MultilinearPolynomial::new(zero),
     }
   }
 }
@@ -515,88 +656,73 @@ impl<E: Engine> SumcheckEngine<E> for MemorySumcheckInstance<E> {
   }
 
   fn evaluation_points(&self) -> Vec<Vec<E::Scalar>> {
-    let comb_func = |poly_A_comp: &E::Scalar,
-                     poly_B_comp: &E::Scalar,
-                     _poly_C_comp: &E::Scalar|
-     -> E::Scalar { *poly_A_comp - *poly_B_comp };
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] - comps[1] };
 
     let comb_func2 =
-      |poly_A_comp: &E::Scalar,
-       poly_B_comp: &E::Scalar,
-       poly_C_comp: &E::Scalar,
-       _poly_D_comp: &E::Scalar|
-       -> E::Scalar { *poly_A_comp * (*poly_B_comp * *poly_C_comp - E::Scalar::ONE) };
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - E::Scalar::ONE) };
 
     let comb_func3 =
-      |poly_A_comp: &E::Scalar,
-       poly_B_comp: &E::Scalar,
-       poly_C_comp: &E::Scalar,
-       poly_D_comp: &E::Scalar|
-       -> E::Scalar { *poly_A_comp * (*poly_B_comp * *poly_C_comp - *poly_D_comp) };
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - comps[3]) };
+
+    let degree = self.degree();
 
     // inv related evaluation points
     // 0 = ∑ TS[i]/(T[i] + r) - 1/(W[i] + r)
-    let (eval_inv_0_row, eval_inv_2_row, eval_inv_3_row) =
-      SumcheckProof::<E>::compute_eval_points_cubic(
-        &self.t_plus_r_inv_row,
-        &self.w_plus_r_inv_row,
-        &self.poly_zero,
-        &comb_func,
-      );
+    let eval_inv_row = SumcheckProof::<E>::compute_eval_points(
+      &[&self.t_plus_r_inv_row, &self.w_plus_r_inv_row],
+      degree,
+      &comb_func,
+    );
 
-    let (eval_inv_0_col, eval_inv_2_col, eval_inv_3_col) =
-      SumcheckProof::<E>::compute_eval_points_cubic(
-        &self.t_plus_r_inv_col,
-        &self.w_plus_r_inv_col,
-        &self.poly_zero,
-        &comb_func,
-      );
+    let eval_inv_col = SumcheckProof::<E>::compute_eval_points(
+      &[&self.t_plus_r_inv_col, &self.w_plus_r_inv_col],
+      degree,
+      &comb_func,
+    );
 
     // row related evaluation points
     // 0 = ∑ eq[i] * (inv_T[i] * (T[i] + r) - TS[i]))
-    let (eval_T_0_row, eval_T_2_row, eval_T_3_row) =
-      SumcheckProof::<E>::compute_eval_points_cubic_with_additive_term(
+    let eval_T_row = SumcheckProof::<E>::compute_eval_points(
+      &[
         &self.poly_eq,
         &self.t_plus_r_inv_row,
         &self.t_plus_r_row,
         &self.ts_row,
-        &comb_func3,
-      );
+      ],
+      degree,
+      &comb_func3,
+    );
     // 0 = ∑ eq[i] * (inv_W[i] * (T[i] + r) - 1))
-    let (eval_W_0_row, eval_W_2_row, eval_W_3_row) =
-      SumcheckProof::<E>::compute_eval_points_cubic_with_additive_term(
-        &self.poly_eq,
-        &self.w_plus_r_inv_row,
-        &self.w_plus_r_row,
-        &self.poly_zero,
-        &comb_func2,
-      );
+    let eval_W_row = SumcheckProof::<E>::compute_eval_points(
+      &[&self.poly_eq, &self.w_plus_r_inv_row, &self.w_plus_r_row],
+      degree,
+      &comb_func2,
+    );
 
     // column related evaluation points
-    let (eval_T_0_col, eval_T_2_col, eval_T_3_col) =
-      SumcheckProof::<E>::compute_eval_points_cubic_with_additive_term(
+    let eval_T_col = SumcheckProof::<E>::compute_eval_points(
+      &[
         &self.poly_eq,
         &self.t_plus_r_inv_col,
         &self.t_plus_r_col,
         &self.ts_col,
-        &comb_func3,
-      );
-    let (eval_W_0_col, eval_W_2_col, eval_W_3_col) =
-      SumcheckProof::<E>::compute_eval_points_cubic_with_additive_term(
-        &self.poly_eq,
-        &self.w_plus_r_inv_col,
-        &self.w_plus_r_col,
-        &self.poly_zero,
-        &comb_func2,
-      );
+      ],
+      degree,
+      &comb_func3,
+    );
+    let eval_W_col = SumcheckProof::<E>::compute_eval_points(
+      &[&self.poly_eq, &self.w_plus_r_inv_col, &self.w_plus_r_col],
+      degree,
+      &comb_func2,
+    );
 
     vec![
-      vec![eval_inv_0_row, eval_inv_2_row, eval_inv_3_row],
-      vec![eval_inv_0_col, eval_inv_2_col, eval_inv_3_col],
-      vec![eval_T_0_row, eval_T_2_row, eval_T_3_row],
-      vec![eval_W_0_row, eval_W_2_row, eval_W_3_row],
-      vec![eval_T_0_col, eval_T_2_col, eval_T_3_col],
-      vec![eval_W_0_col, eval_W_2_col, eval_W_3_col],
+      eval_inv_row,
+      eval_inv_col,
+      eval_T_row,
+      eval_W_row,
+      eval_T_col,
+      eval_W_col,
     ]
   }
 
@@ -643,8 +769,6 @@ pub(in crate::spartan) struct OuterSumcheckInstance<E: Engine> {
 
   poly_Mz: MultilinearPolynomial<E::Scalar>,
   eval_Mz_at_tau: E::Scalar,
-
-  poly_zero: MultilinearPolynomial<E::Scalar>,
 }
 
 impl<E: Engine> OuterSumcheckInstance<E> {
@@ -656,7 +780,6 @@ impl<E: Engine> OuterSumcheckInstance<E> {
     Mz: Vec<E::Scalar>,
     eval_Mz_at_tau: &E::Scalar,
   ) -> Self {
-    let zero = vec![E::Scalar::ZERO; tau.len()];
     Self {
       poly_tau: MultilinearPolynomial::new(tau),
       poly_Az: MultilinearPolynomial::new(Az),
@@ -664,9 +787,20 @@ impl<E: Engine> OuterSumcheckInstance<E> {
       poly_uCz_E: MultilinearPolynomial::new(uCz_E),
       poly_Mz: MultilinearPolynomial::new(Mz),
       eval_Mz_at_tau: *eval_Mz_at_tau,
-      poly_zero: MultilinearPolynomial::new(zero),
     }
   }
+
+  // returns the vectors of the polynomials, which keep the capacity they were created with after
+  // the sum-check binds them, so they can be reused
+  fn into_buffers(self) -> Vec<Vec<E::Scalar>> {
+    vec![
+      self.poly_tau.Z,
+      self.poly_Az.Z,
+      self.poly_Bz.Z,
+      self.poly_uCz_E.Z,
+      self.poly_Mz.Z,
+    ]
+  }
 }
 
 impl<E: Engine> SumcheckEngine<E> for OuterSumcheckInstance<E> {
@@ -688,38 +822,28 @@ impl<E: Engine> SumcheckEngine<E> for OuterSumcheckInstance<E> {
 
   fn evaluation_points(&self) -> Vec<Vec<E::Scalar>> {
     let comb_func =
-      |poly_A_comp: &E::Scalar,
-       poly_B_comp: &E::Scalar,
-       poly_C_comp: &E::Scalar,
-       poly_D_comp: &E::Scalar|
-       -> E::Scalar { *poly_A_comp * (*poly_B_comp * *poly_C_comp - *poly_D_comp) };
-
-    let (eval_point_h_0, eval_point_h_2, eval_point_h_3) =
-      SumcheckProof::<E>::compute_eval_points_cubic_with_additive_term(
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - comps[3]) };
+
+    let eval_point_h = SumcheckProof::<E>::compute_eval_points(
+      &[
         &self.poly_tau,
         &self.poly_Az,
         &self.poly_Bz,
         &self.poly_uCz_E,
-        &comb_func,
-      );
+      ],
+      self.degree(),
+      &comb_func,
+    );
 
-    let comb_func2 = |poly_A_comp: &E::Scalar,
-                      poly_B_comp: &E::Scalar,
-                      _poly_C_comp: &E::Scalar|
-     -> E::Scalar { *poly_A_comp * *poly_B_comp };
+    let comb_func2 = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
 
-    let (eval_point_e_0, eval_point_e_2, eval_point_e_3) =
-      SumcheckProof::<E>::compute_eval_points_cubic(
-        &self.poly_tau,
-        &self.poly_Mz,
-        &self.poly_zero,
-        &comb_func2,
-      );
+    let eval_point_e = SumcheckProof::<E>::compute_eval_points(
+      &[&self.poly_tau, &self.poly_Mz],
+      self.degree(),
+      &comb_func2,
+    );
 
-    vec![
-      vec![eval_point_h_0, eval_point_h_2, eval_point_h_3],
-      vec![eval_point_e_0, eval_point_e_2, eval_point_e_3],
-    ]
+    vec![eval_point_h, eval_point_e]
   }
 
   fn bound(&mut self, r: &E::Scalar) {
@@ -776,16 +900,13 @@ impl<E: Engine> SumcheckEngine<E> for InnerSumcheckInstance<E> {
   }
 
   fn evaluation_points(&self) -> Vec<Vec<E::Scalar>> {
-    let (poly_A, poly_B, poly_C) = (&self.poly_L_row, &self.poly_L_col, &self.poly_val);
-    let comb_func = |poly_A_comp: &E::Scalar,
-                     poly_B_comp: &E::Scalar,
-                     poly_C_comp: &E::Scalar|
-     -> E::Scalar { *poly_A_comp * *poly_B_comp * *poly_C_comp };
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] * comps[2] };
 
-    let (eval_point_0, eval_point_2, eval_point_3) =
-      SumcheckProof::<E>::compute_eval_points_cubic(poly_A, poly_B, poly_C, &comb_func);
-
-    vec![vec![eval_point_0, eval_point_2, eval_point_3]]
+    vec![SumcheckProof::<E>::compute_eval_points(
+      &[&self.poly_L_row, &self.poly_L_col, &self.poly_val],
+      self.degree(),
+      &comb_func,
+    )]
   }
 
   fn bound(&mut self, r: &E::Scalar) {
@@ -813,6 +934,58 @@ pub struct ProverKey<E: Engine, EE: EvaluationEngineTrait<E>> {
   S_comm: R1CSShapeSparkCommitment<E>,
   #[abomonate_with(<E::Scalar as PrimeField>::Repr)]
   vk_digest: E::Scalar, // digest of verifier's key
+  #[abomonation_skip]
+  #[serde(skip)]
+  memory_budget: Option<usize>, // in bytes, not part of the key's persisted form
+}
+
+impl<E: Engine, EE: EvaluationEngineTrait<E>> ProverKey<E, EE> {
+  /// An upper bound on the number of vectors of size `N` that the default prover keeps in
+  /// memory at once
+  const DEFAULT_PROVER_VECTORS: usize = 34;
+
+  /// An upper bound on the number of vectors of size `N` that the prover keeps in memory at
+  /// once when it moves vectors into the sum-check instances instead of cloning them
+  const LOW_MEMORY_PROVER_VECTORS: usize = 26;
+
+  // the number of bytes taken by the given number of vectors of size `N`
+  fn vectors_size(&self, num_vectors: usize) -> usize {
+    num_vectors
+      .saturating_mul(self.S_repr.N)
+      .saturating_mul(std::mem::size_of::<E::Scalar>())
+  }
+
+  /// Sets a bound, in bytes, on the memory the prover may use for the vectors it computes,
+  /// which is checked against an upper bound on the number of vectors the prover keeps at once.
+  /// If the default prover does not fit in the budget, the prover moves vectors into the
+  /// sum-check instances instead of cloning them, and recomputes them afterwards in buffers
+  /// reclaimed from the instances, which takes more time but produces the same proofs.
+  /// Returns `NovaError::InsufficientMemoryBudget` if even that does not fit in the budget, see
+  /// `min_memory_budget`. `None`, the default, places no bound on the memory.
+  pub fn set_memory_budget(&mut self, memory_budget: Option<usize>) -> Result<(), NovaError> {
+    if memory_budget.map_or(false, |budget| budget < self.min_memory_budget()) {
+      return Err(NovaError::InsufficientMemoryBudget);
+    }
+    self.memory_budget = memory_budget;
+    Ok(())
+  }
+
+  /// Returns the bound on the memory of the prover set with `set_memory_budget`
+  pub fn memory_budget(&self) -> Option<usize> {
+    self.memory_budget
+  }
+
+  /// Returns the smallest memory budget, in bytes, that `set_memory_budget` accepts
+  pub fn min_memory_budget(&self) -> usize {
+    self.vectors_size(Self::LOW_MEMORY_PROVER_VECTORS)
+  }
+
+  // whether the prover should trade time for memory to stay within the memory budget
+  fn low_memory(&self) -> bool {
+    self.memory_budget.map_or(false, |budget| {
+      budget < self.vectors_size(Self::DEFAULT_PROVER_VECTORS)
+    })
+  }
 }
 
 /// A type that represents the verifier's key
@@ -824,6 +997,7 @@ pub struct VerifierKey<E: Engine, EE: EvaluationEngineTrait<E>> {
   num_vars: usize,
   vk_ee: EE::VerifierKey,
   S_comm: R1CSShapeSparkCommitment<E>,
+  lookups: Vec<LookupColumn<E::Scalar>>,
   #[abomonation_skip]
   #[serde(skip, default = "OnceCell::new")]
   digest: OnceCell<E::Scalar>,
@@ -837,6 +1011,8 @@ impl<E: Engine, EE: EvaluationEngineTrait<E>> SimpleDigestible for VerifierKey<E
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct RelaxedR1CSSNARK<E: Engine, EE: EvaluationEngineTrait<E>> {
+  lookups: Vec<LookupProof<E, EE>>, // one for each lookup column of the shape
+
   // commitment to oracles: the first three are for Az, Bz, Cz,
   // and the last two are for memory reads
   comm_Az: CompressedCommitment<E>,
@@ -987,101 +1163,400 @@ where
       inner_claims,
     ))
   }
-}
-
-impl<E: Engine, EE: EvaluationEngineTrait<E>> VerifierKey<E, EE> {
-  fn new(
-    num_cons: usize,
-    num_vars: usize,
-    S_comm: R1CSShapeSparkCommitment<E>,
-    vk_ee: EE::VerifierKey,
-  ) -> Self {
-    VerifierKey {
-      num_cons,
-      num_vars,
-      S_comm,
-      vk_ee,
-      digest: Default::default(),
-    }
-  }
-}
-impl<E: Engine, EE: EvaluationEngineTrait<E>> DigestHelperTrait<E> for VerifierKey<E, EE> {
-  /// Returns the digest of the verifier's key
-  fn digest(&self) -> E::Scalar {
-    self
-      .digest
-      .get_or_try_init(|| {
-        let dc = DigestComputer::new(self);
-        dc.digest()
-      })
-      .cloned()
-      .expect("Failure to retrieve digest!")
-  }
-}
-
-impl<E: Engine, EE: EvaluationEngineTrait<E>> RelaxedR1CSSNARKTrait<E> for RelaxedR1CSSNARK<E, EE>
-where
-  <E::Scalar as PrimeField>::Repr: Abomonation,
-{
-  type ProverKey = ProverKey<E, EE>;
-  type VerifierKey = VerifierKey<E, EE>;
-
-  fn ck_floor() -> Box<dyn for<'a> Fn(&'a R1CSShape<E>) -> usize> {
-    Box::new(|shape: &R1CSShape<E>| -> usize {
-      // the commitment key should be large enough to commit to the R1CS matrices
-      shape.A.len() + shape.B.len() + shape.C.len()
-    })
-  }
 
-  fn setup(
+  // Produces the keys for the prover and the verifier from the Spark representation of `S` and
+  // the commitments to it
+  fn setup_from_spark(
     ck: &CommitmentKey<E>,
     S: &R1CSShape<E>,
-  ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
-    // check the provided commitment key meets minimal requirements
-    if ck.length() < Self::ck_floor()(S) {
-      return Err(NovaError::InvalidCommitmentKeyLength);
-    }
+    S_repr: R1CSShapeSparkRepr<E>,
+    S_comm: R1CSShapeSparkCommitment<E>,
+  ) -> (ProverKey<E, EE>, VerifierKey<E, EE>) {
     let (pk_ee, vk_ee) = EE::setup(ck);
 
     // pad the R1CS matrices
     let S = S.pad();
 
-    let S_repr = R1CSShapeSparkRepr::new(&S);
-    let S_comm = S_repr.commit(ck);
-
-    let vk = VerifierKey::new(S.num_cons, S.num_vars, S_comm.clone(), vk_ee);
+    let vk = VerifierKey::new(
+      S.num_cons,
+      S.num_vars,
+      S_comm.clone(),
+      S.lookups.clone(),
+      vk_ee,
+    );
 
     let pk = ProverKey {
       pk_ee,
       S_repr,
       S_comm,
       vk_digest: vk.digest(),
+      memory_budget: None,
     };
 
-    Ok((pk, vk))
+    (pk, vk)
   }
 
-  /// produces a succinct proof of satisfiability of a `RelaxedR1CS` instance
-  #[tracing::instrument(skip_all, name = "PPSNARK::prove")]
-  fn prove(
-    ck: &CommitmentKey<E>,
-    pk: &Self::ProverKey,
-    S: &R1CSShape<E>,
+  // Replays the verification of the proof up to its evaluation argument, returning the
+  // transcript and the claim about the evaluation of a committed polynomial the proof reduces to
+  fn verify_reduce(
+    &self,
+    vk: &VerifierKey<E, EE>,
     U: &RelaxedR1CSInstance<E>,
-    W: &RelaxedR1CSWitness<E>,
-  ) -> Result<Self, NovaError> {
-    // pad the R1CSShape
-    let S = S.pad();
+  ) -> Result<(E::TE, PolyEvalInstance<E>), NovaError> {
+    let mut transcript = E::TE::new(b"RelaxedR1CSSNARK");
+
+    // append the verifier key (including commitment to R1CS matrices) and the RelaxedR1CSInstance to the transcript
+    transcript.absorb(b"vk", &vk.digest());
+    transcript.absorb(b"U", U);
+
+    // verify the lookups of the witness
+    if self.lookups.len() != vk.lookups.len() {
+      return Err(NovaError::ProofVerifyError);
+    }
+    for (lookup, column) in self.lookups.iter().zip_eq(vk.lookups.iter()) {
+      lookup.verify_column(&vk.vk_ee, column, &U.comm_W, vk.num_vars, &mut transcript)?;
+    }
+
+    let comm_Az = Commitment::<E>::decompress(&self.comm_Az)?;
+    let comm_Bz = Commitment::<E>::decompress(&self.comm_Bz)?;
+    let comm_Cz = Commitment::<E>::decompress(&self.comm_Cz)?;
+    let comm_L_row = Commitment::<E>::decompress(&self.comm_L_row)?;
+    let comm_L_col = Commitment::<E>::decompress(&self.comm_L_col)?;
+    let comm_t_plus_r_inv_row = Commitment::<E>::decompress(&self.comm_t_plus_r_inv_row)?;
+    let comm_w_plus_r_inv_row = Commitment::<E>::decompress(&self.comm_w_plus_r_inv_row)?;
+    let comm_t_plus_r_inv_col = Commitment::<E>::decompress(&self.comm_t_plus_r_inv_col)?;
+    let comm_w_plus_r_inv_col = Commitment::<E>::decompress(&self.comm_w_plus_r_inv_col)?;
+
+    transcript.absorb(b"c", &[comm_Az, comm_Bz, comm_Cz].as_slice());
+
+    let num_rounds_sc = vk.S_comm.N.log_2();
+    let tau = transcript.squeeze(b"t")?;
+    let tau_coords = PowPolynomial::new(&tau, num_rounds_sc).coordinates();
+
+    transcript.absorb(
+      b"e",
+      &[
+        self.eval_Az_at_tau,
+        self.eval_Bz_at_tau,
+        self.eval_Cz_at_tau,
+      ]
+      .as_slice(),
+    );
+
+    transcript.absorb(b"e", &vec![comm_L_row, comm_L_col].as_slice());
+
+    // add claims about Az, Bz, and Cz to be checked later
+    // since all the three polynomials are opened at tau,
+    // we can combine them into a single polynomial opened at tau
+    let eval_vec = vec![
+      self.eval_Az_at_tau,
+      self.eval_Bz_at_tau,
+      self.eval_Cz_at_tau,
+    ];
+    let comm_vec = vec![comm_Az, comm_Bz, comm_Cz];
+    transcript.absorb(b"e", &eval_vec.as_slice()); // c_vec is already in the transcript
+    let c = transcript.squeeze(b"c")?;
+    let u: PolyEvalInstance<E> = PolyEvalInstance::batch(&comm_vec, &tau_coords, &eval_vec, &c);
+    let claim = u.e;
+
+    let gamma = transcript.squeeze(b"g")?;
+
+    let r = transcript.squeeze(b"r")?;
+
+    transcript.absorb(
+      b"l",
+      &vec![
+        comm_t_plus_r_inv_row,
+        comm_w_plus_r_inv_row,
+        comm_t_plus_r_inv_col,
+        comm_w_plus_r_inv_col,
+      ]
+      .as_slice(),
+    );
+
+    let rho = transcript.squeeze(b"r")?;
+
+    let num_claims = 9;
+    let s = transcript.squeeze(b"r")?;
+    let coeffs = powers::<E>(&s, num_claims);
+    let claim = (coeffs[7] + coeffs[8]) * claim; // rest are zeros
+
+    // verify sc
+    let (claim_sc_final, rand_sc) = self.sc.verify(claim, num_rounds_sc, 3, &mut transcript)?;
+
+    // verify claim_sc_final
+    let claim_sc_final_expected = {
+      let rand_eq_bound_rand_sc = {
+        let poly_eq_coords = PowPolynomial::new(&rho, num_rounds_sc).coordinates();
+        EqPolynomial::new(poly_eq_coords).evaluate(&rand_sc)
+      };
+      let taus_bound_rand_sc = PowPolynomial::new(&tau, num_rounds_sc).evaluate(&rand_sc);
+
+      let eval_t_plus_r_row = {
+        let eval_addr_row = IdentityPolynomial::new(num_rounds_sc).evaluate(&rand_sc);
+        let eval_val_row = taus_bound_rand_sc;
+        let eval_t = eval_addr_row + gamma * eval_val_row;
+        eval_t + r
+      };
+
+      let eval_w_plus_r_row = {
+        let eval_addr_row = self.eval_row;
+        let eval_val_row = self.eval_L_row;
+        let eval_w = eval_addr_row + gamma * eval_val_row;
+        eval_w + r
+      };
+
+      let eval_t_plus_r_col = {
+        let eval_addr_col = IdentityPolynomial::new(num_rounds_sc).evaluate(&rand_sc);
+
+        // memory contents is z, so we compute eval_Z from eval_W and eval_X
+        let eval_val_col = {
+          // rand_sc was padded, so we now remove the padding
+          let (factor, rand_sc_unpad) = {
+            let l = vk.S_comm.N.log_2() - (2 * vk.num_vars).log_2();
+
+            let mut factor = E::Scalar::ONE;
+            for r_p in rand_sc.iter().take(l) {
+              factor *= E::Scalar::ONE - r_p
+            }
+
+            let rand_sc_unpad = rand_sc[l..].to_vec();
+
+            (factor, rand_sc_unpad)
+          };
+
+          let eval_X = {
+            // constant term
+            let mut poly_X = vec![(0, U.u)];
+            //remaining inputs
+            poly_X.extend(
+              (0..U.X.len())
+                .map(|i| (i + 1, U.X[i]))
+                .collect::<Vec<(usize, E::Scalar)>>(),
+            );
+            SparsePolynomial::new(vk.num_vars.log_2(), poly_X).evaluate(&rand_sc_unpad[1..])
+          };
+
+          self.eval_W + factor * rand_sc_unpad[0] * eval_X
+        };
+        let eval_t = eval_addr_col + gamma * eval_val_col;
+        eval_t + r
+      };
+
+      let eval_w_plus_r_col = {
+        let eval_addr_col = self.eval_col;
+        let eval_val_col = self.eval_L_col;
+        let eval_w = eval_addr_col + gamma * eval_val_col;
+        eval_w + r
+      };
+
+      let claim_mem_final_expected: E::Scalar = coeffs[0]
+        * (self.eval_t_plus_r_inv_row - self.eval_w_plus_r_inv_row)
+        + coeffs[1] * (self.eval_t_plus_r_inv_col - self.eval_w_plus_r_inv_col)
+        + coeffs[2]
+          * (rand_eq_bound_rand_sc
+            * (self.eval_t_plus_r_inv_row * eval_t_plus_r_row - self.eval_ts_row))
+        + coeffs[3]
+          * (rand_eq_bound_rand_sc
+            * (self.eval_w_plus_r_inv_row * eval_w_plus_r_row - E::Scalar::ONE))
+        + coeffs[4]
+          * (rand_eq_bound_rand_sc
+            * (self.eval_t_plus_r_inv_col * eval_t_plus_r_col - self.eval_ts_col))
+        + coeffs[5]
+          * (rand_eq_bound_rand_sc
+            * (self.eval_w_plus_r_inv_col * eval_w_plus_r_col - E::Scalar::ONE));
+
+      let claim_outer_final_expected = coeffs[6]
+        * taus_bound_rand_sc
+        * (self.eval_Az * self.eval_Bz - U.u * self.eval_Cz - self.eval_E)
+        + coeffs[7] * taus_bound_rand_sc * (self.eval_Az + c * self.eval_Bz + c * c * self.eval_Cz);
+      let claim_inner_final_expected = coeffs[8]
+        * self.eval_L_row
+        * self.eval_L_col
+        * (self.eval_val_A + c * self.eval_val_B + c * c * self.eval_val_C);
+
+      claim_mem_final_expected + claim_outer_final_expected + claim_inner_final_expected
+    };
+
+    if claim_sc_final_expected != claim_sc_final {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    let eval_vec = vec![
+      self.eval_W,
+      self.eval_Az,
+      self.eval_Bz,
+      self.eval_Cz,
+      self.eval_E,
+      self.eval_L_row,
+      self.eval_L_col,
+      self.eval_val_A,
+      self.eval_val_B,
+      self.eval_val_C,
+      self.eval_t_plus_r_inv_row,
+      self.eval_row,
+      self.eval_w_plus_r_inv_row,
+      self.eval_ts_row,
+      self.eval_t_plus_r_inv_col,
+      self.eval_col,
+      self.eval_w_plus_r_inv_col,
+      self.eval_ts_col,
+    ]
+    .into_iter()
+    .collect::<Vec<E::Scalar>>();
+    let comm_vec = [
+      U.comm_W,
+      comm_Az,
+      comm_Bz,
+      comm_Cz,
+      U.comm_E,
+      comm_L_row,
+      comm_L_col,
+      vk.S_comm.comm_val_A,
+      vk.S_comm.comm_val_B,
+      vk.S_comm.comm_val_C,
+      comm_t_plus_r_inv_row,
+      vk.S_comm.comm_row,
+      comm_w_plus_r_inv_row,
+      vk.S_comm.comm_ts_row,
+      comm_t_plus_r_inv_col,
+      vk.S_comm.comm_col,
+      comm_w_plus_r_inv_col,
+      vk.S_comm.comm_ts_col,
+    ];
+    transcript.absorb(b"e", &eval_vec.as_slice()); // comm_vec is already in the transcript
+    let c = transcript.squeeze(b"c")?;
+    let u: PolyEvalInstance<E> = PolyEvalInstance::batch(&comm_vec, &rand_sc, &eval_vec, &c);
+
+    Ok((transcript, u))
+  }
+}
+
+impl<E: Engine, EE: EvaluationEngineTrait<E>> VerifierKey<E, EE> {
+  fn new(
+    num_cons: usize,
+    num_vars: usize,
+    S_comm: R1CSShapeSparkCommitment<E>,
+    lookups: Vec<LookupColumn<E::Scalar>>,
+    vk_ee: EE::VerifierKey,
+  ) -> Self {
+    VerifierKey {
+      num_cons,
+      num_vars,
+      S_comm,
+      lookups,
+      vk_ee,
+      digest: Default::default(),
+    }
+  }
+}
+impl<E: Engine, EE: EvaluationEngineTrait<E>> DigestHelperTrait<E> for VerifierKey<E, EE> {
+  /// Returns the digest of the verifier's key
+  fn digest(&self) -> E::Scalar {
+    self
+      .digest
+      .get_or_try_init(|| {
+        let dc = DigestComputer::new(self);
+        dc.digest()
+      })
+      .cloned()
+      .expect("Failure to retrieve digest!")
+  }
+}
+
+impl<E: Engine, EE: EvaluationEngineTrait<E>> RelaxedR1CSSNARKTrait<E> for RelaxedR1CSSNARK<E, EE>
+where
+  <E::Scalar as PrimeField>::Repr: Abomonation,
+{
+  type ProverKey = ProverKey<E, EE>;
+  type VerifierKey = VerifierKey<E, EE>;
+
+  fn ck_floor() -> Box<dyn for<'a> Fn(&'a R1CSShape<E>) -> usize> {
+    Box::new(|shape: &R1CSShape<E>| -> usize {
+      // the commitment key should be large enough to commit to the R1CS matrices
+      shape.A.len() + shape.B.len() + shape.C.len()
+    })
+  }
+
+  fn setup(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+  ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
+    // check the provided commitment key meets minimal requirements
+    if ck.length() < Self::ck_floor()(S) {
+      return Err(NovaError::InvalidCommitmentKeyLength);
+    }
+
+    let S_repr = R1CSShapeSparkRepr::new(&S.pad());
+    let S_comm = S_repr.commit(ck);
+    Ok(Self::setup_from_spark(ck, S, S_repr, S_comm))
+  }
+
+  fn setup_with_cache(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+    cache: &dyn SetupCache,
+  ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
+    // check the provided commitment key meets minimal requirements
+    if ck.length() < Self::ck_floor()(S) {
+      return Err(NovaError::InvalidCommitmentKeyLength);
+    }
+
+    // reuse the stored Spark representation if it was computed for this shape and commitment key
+    let (shape_digest, ck_digest) = R1CSShapeSparkCache::digests(ck, S)?;
+    let key = R1CSShapeSparkCache::<E>::cache_key(shape_digest, ck_digest);
+    let stored = cache
+      .load(&key)
+      .and_then(|bytes| bincode::deserialize::<R1CSShapeSparkCache<E>>(&bytes).ok())
+      .filter(|spark| spark.check(ck, S, shape_digest, ck_digest).is_ok());
+
+    let spark = match stored {
+      Some(spark) => spark,
+      None => {
+        let spark = R1CSShapeSparkCache::new(ck, S, shape_digest, ck_digest);
+        let bytes = bincode::serialize(&spark).map_err(|_| NovaError::SetupCacheError)?;
+        cache.store(&key, &bytes)?;
+        spark
+      }
+    };
+
+    Ok(Self::setup_from_spark(ck, S, spark.S_repr, spark.S_comm))
+  }
+
+  /// produces a succinct proof of satisfiability of a `RelaxedR1CS` instance
+  #[tracing::instrument(skip_all, name = "PPSNARK::prove")]
+  fn prove(
+    ck: &CommitmentKey<E>,
+    pk: &Self::ProverKey,
+    S: &R1CSShape<E>,
+    U: &RelaxedR1CSInstance<E>,
+    W: &RelaxedR1CSWitness<E>,
+  ) -> Result<Self, NovaError> {
+    // pad the R1CSShape
+    let S = S.pad();
     // sanity check that R1CSShape has all required size characteristics
     assert!(S.is_regular_shape());
 
     let W = W.pad(&S); // pad the witness
     let mut transcript = E::TE::new(b"RelaxedR1CSSNARK");
 
+    // when the default prover does not fit in the memory budget, vectors are moved into the
+    // sum-check instances rather than cloned, and recomputed once the sum-check is done
+    let low_memory = pk.low_memory();
+
     // append the verifier key (which includes commitment to R1CS matrices) and the RelaxedR1CSInstance to the transcript
     transcript.absorb(b"vk", &pk.vk_digest);
     transcript.absorb(b"U", U);
 
+    // prove the lookups of the witness, which only hold for witnesses that are not folded
+    let lookups = S
+      .lookups
+      .iter()
+      .map(|column| {
+        LookupProof::prove_column(ck, &pk.pk_ee, column, &U.comm_W, &W.W, &mut transcript)
+      })
+      .collect::<Result<Vec<_>, NovaError>>()?;
+
     // compute the full satisfying assignment by concatenating W.W, U.u, and U.X
     let z = [W.W.clone(), vec![U.u], U.X.clone()].concat();
 
@@ -1102,7 +1577,7 @@ where
     let tau_coords = PowPolynomial::new(&tau, num_rounds_sc).coordinates();
 
     // (1) send commitments to Az, Bz, and Cz along with their evaluations at tau
-    let (Az, Bz, Cz, W, E) = {
+    let (mut Az, mut Bz, Cz, W, E) = {
       Az.resize(pk.S_repr.N, E::Scalar::ZERO);
       Bz.resize(pk.S_repr.N, E::Scalar::ZERO);
       Cz.resize(pk.S_repr.N, E::Scalar::ZERO);
@@ -1112,17 +1587,17 @@ where
       (Az, Bz, Cz, W, E)
     };
     let (eval_Az_at_tau, eval_Bz_at_tau, eval_Cz_at_tau) = {
-      let evals_at_tau = [&Az, &Bz, &Cz]
-        .into_par_iter()
-        .map(|p| MultilinearPolynomial::evaluate_with(p, &tau_coords))
-        .collect::<Vec<E::Scalar>>();
+      let evals_at_tau = MultilinearPolynomial::evaluate_batch(
+        &[Az.as_slice(), Bz.as_slice(), Cz.as_slice()],
+        &tau_coords,
+      );
       (evals_at_tau[0], evals_at_tau[1], evals_at_tau[2])
     };
 
     // (2) send commitments to the following two oracles
     // L_row(i) = eq(tau, row(i)) for all i
     // L_col(i) = z(col(i)) for all i
-    let (mem_row, mem_col, L_row, L_col) = pk.S_repr.evaluation_oracles(&S, &tau, &z);
+    let (mem_row, mem_col, mut L_row, mut L_col) = pk.S_repr.evaluation_oracles(&S, &tau, &z);
     let (comm_L_row, comm_L_col) =
       rayon::join(|| E::CE::commit(ck, &L_row), || E::CE::commit(ck, &L_col));
 
@@ -1151,18 +1626,27 @@ where
     let gamma = transcript.squeeze(b"g")?;
     let r = transcript.squeeze(b"r")?;
 
-    let ((mut outer_sc_inst, mut inner_sc_inst), mem_res) = rayon::join(
+    let take_or_clone = |v: &mut Vec<E::Scalar>| {
+      if low_memory {
+        std::mem::take(v)
+      } else {
+        v.clone()
+      }
+    };
+    let (Az_sc, Bz_sc) = (take_or_clone(&mut Az), take_or_clone(&mut Bz));
+
+    let ((mut outer_sc_inst, val), mem_res) = rayon::join(
       || {
         // a sum-check instance to prove the first claim
         let outer_sc_inst = OuterSumcheckInstance::new(
           PowPolynomial::new(&tau, num_rounds_sc).evals(),
-          Az.clone(),
-          Bz.clone(),
+          Az_sc,
+          Bz_sc,
           (0..Cz.len())
             .map(|i| U.u * Cz[i] + E[i])
             .collect::<Vec<E::Scalar>>(),
-          w.p.clone(), // Mz = Az + r * Bz + r^2 * Cz
-          &u.e,        // eval_Az_at_tau + r * eval_Az_at_tau + r^2 * eval_Cz_at_tau
+          w.p,  // Mz = Az + r * Bz + r^2 * Cz
+          &u.e, // eval_Az_at_tau + r * eval_Az_at_tau + r^2 * eval_Cz_at_tau
         );
 
         // a sum-check instance to prove the second claim
@@ -1174,14 +1658,8 @@ where
           .zip_eq(pk.S_repr.val_C.par_iter())
           .map(|((v_a, v_b), v_c)| *v_a + c * *v_b + c * c * *v_c)
           .collect::<Vec<E::Scalar>>();
-        let inner_sc_inst = InnerSumcheckInstance {
-          claim: eval_Az_at_tau + c * eval_Bz_at_tau + c * c * eval_Cz_at_tau,
-          poly_L_row: MultilinearPolynomial::new(L_row.clone()),
-          poly_L_col: MultilinearPolynomial::new(L_col.clone()),
-          poly_val: MultilinearPolynomial::new(val),
-        };
 
-        (outer_sc_inst, inner_sc_inst)
+        (outer_sc_inst, val)
       },
       || {
         // a third sum-check instance to prove the read-only memory claim
@@ -1209,9 +1687,15 @@ where
         let rho = transcript.squeeze(b"r")?;
         let poly_eq = MultilinearPolynomial::new(PowPolynomial::new(&rho, num_rounds_sc).evals());
 
+        let (mem_oracles_sc, mem_oracles) = if low_memory {
+          (mem_oracles, Default::default())
+        } else {
+          (mem_oracles.clone(), mem_oracles)
+        };
+
         Ok::<_, NovaError>((
           MemorySumcheckInstance::new(
-            mem_oracles.clone(),
+            mem_oracles_sc,
             mem_aux,
             poly_eq.Z,
             pk.S_repr.ts_row.clone(),
@@ -1224,6 +1708,15 @@ where
     );
 
     let (mut mem_sc_inst, comm_mem_oracles, mem_oracles) = mem_res?;
+    drop((mem_row, mem_col));
+
+    // a sum-check instance to prove the second claim
+    let mut inner_sc_inst = InnerSumcheckInstance {
+      claim: eval_Az_at_tau + c * eval_Bz_at_tau + c * c * eval_Cz_at_tau,
+      poly_L_row: MultilinearPolynomial::new(take_or_clone(&mut L_row)),
+      poly_L_col: MultilinearPolynomial::new(take_or_clone(&mut L_col)),
+      poly_val: MultilinearPolynomial::new(val),
+    };
 
     let (sc, rand_sc, claims_mem, claims_outer, claims_inner) = Self::prove_helper(
       &mut mem_sc_inst,
@@ -1231,6 +1724,14 @@ where
       &mut inner_sc_inst,
       &mut transcript,
     )?;
+    drop((mem_sc_inst, inner_sc_inst));
+    // the prover that recomputes vectors reuses the buffers of the outer sum-check instance
+    let buffers = if low_memory {
+      outer_sc_inst.into_buffers()
+    } else {
+      drop(outer_sc_inst);
+      Vec::new()
+    };
 
     // claims from the end of the sum-check
     let eval_Az = claims_outer[0][0];
@@ -1240,28 +1741,28 @@ where
     let eval_L_col = claims_inner[0][1];
 
     let eval_t_plus_r_inv_row = claims_mem[0][0];
-    let eval_w_plus_r_inv_row = claims_mem[0][1];
-    let eval_ts_row = claims_mem[0][2];
-
-    let eval_t_plus_r_inv_col = claims_mem[1][0];
-    let eval_w_plus_r_inv_col = claims_mem[1][1];
-    let eval_ts_col = claims_mem[1][2];
-
-    // compute the remaining claims that did not come for free from the sum-check prover
-    let (eval_W, eval_Cz, eval_E, eval_val_A, eval_val_B, eval_val_C, eval_row, eval_col) = {
-      let e = [
-        &W,
-        &Cz,
-        &E,
-        &pk.S_repr.val_A,
-        &pk.S_repr.val_B,
-        &pk.S_repr.val_C,
-        &pk.S_repr.row,
-        &pk.S_repr.col,
-      ]
-      .into_par_iter()
-      .map(|p| MultilinearPolynomial::evaluate_with(p, &rand_sc))
-      .collect::<Vec<E::Scalar>>();
+    let eval_w_plus_r_inv_row = claims_mem[0][1];
+    let eval_ts_row = claims_mem[0][2];
+
+    let eval_t_plus_r_inv_col = claims_mem[1][0];
+    let eval_w_plus_r_inv_col = claims_mem[1][1];
+    let eval_ts_col = claims_mem[1][2];
+
+    // compute the remaining claims that did not come for free from the sum-check prover
+    let (eval_W, eval_Cz, eval_E, eval_val_A, eval_val_B, eval_val_C, eval_row, eval_col) = {
+      let e = MultilinearPolynomial::evaluate_batch(
+        &[
+          W.as_slice(),
+          Cz.as_slice(),
+          E.as_slice(),
+          pk.S_repr.val_A.as_slice(),
+          pk.S_repr.val_B.as_slice(),
+          pk.S_repr.val_C.as_slice(),
+          pk.S_repr.row.as_slice(),
+          pk.S_repr.col.as_slice(),
+        ],
+        &rand_sc,
+      );
       (e[0], e[1], e[2], e[3], e[4], e[5], e[6], e[7])
     };
 
@@ -1309,34 +1810,105 @@ where
       comm_mem_oracles[3],
       pk.S_comm.comm_ts_col,
     ];
-    let poly_vec = [
-      &W,
-      &Az,
-      &Bz,
-      &Cz,
-      &E,
-      &L_row,
-      &L_col,
-      &pk.S_repr.val_A,
-      &pk.S_repr.val_B,
-      &pk.S_repr.val_C,
-      mem_oracles[0].as_ref(),
-      &pk.S_repr.row,
-      mem_oracles[1].as_ref(),
-      &pk.S_repr.ts_row,
-      mem_oracles[2].as_ref(),
-      &pk.S_repr.col,
-      mem_oracles[3].as_ref(),
-      &pk.S_repr.ts_col,
-    ];
     transcript.absorb(b"e", &eval_vec.as_slice()); // comm_vec is already in the transcript
     let c = transcript.squeeze(b"c")?;
-    let w: PolyEvalWitness<E> = PolyEvalWitness::batch(&poly_vec, &c);
+    let w: PolyEvalWitness<E> = if low_memory {
+      // accumulate the weighted polynomials one at a time, recomputing the ones that were
+      // consumed by the sum-check in the buffers of the outer sum-check instance
+      let mut buffers = buffers.into_iter();
+      let mut buffer = || {
+        let mut v = buffers.next().unwrap_or_default();
+        v.clear();
+        v
+      };
+      let (mut Az, mut Bz, mut L_row, mut L_col) = (buffer(), buffer(), buffer(), buffer());
+      let mut p = buffer();
+      p.resize(pk.S_repr.N, E::Scalar::ZERO);
+
+      let powers_of_c = powers::<E>(&c, comm_vec.len());
+      let mut accumulate = |v: &[E::Scalar], i: usize| {
+        p.par_iter_mut()
+          .zip_eq(v.par_iter())
+          .for_each(|(acc, x)| *acc += *x * powers_of_c[i]);
+      };
+
+      for (v, i) in [
+        (&W, 0),
+        (&Cz, 3),
+        (&E, 4),
+        (&pk.S_repr.val_A, 7),
+        (&pk.S_repr.val_B, 8),
+        (&pk.S_repr.val_C, 9),
+        (&pk.S_repr.row, 11),
+        (&pk.S_repr.ts_row, 13),
+        (&pk.S_repr.col, 15),
+        (&pk.S_repr.ts_col, 17),
+      ] {
+        accumulate(v, i);
+      }
+      drop((W, Cz, E));
+
+      rayon::join(
+        || S.A.multiply_vec_into(&z, &mut Az),
+        || S.B.multiply_vec_into(&z, &mut Bz),
+      );
+      Az.resize(pk.S_repr.N, E::Scalar::ZERO);
+      Bz.resize(pk.S_repr.N, E::Scalar::ZERO);
+      accumulate(&Az, 1);
+      accumulate(&Bz, 2);
+      drop((Az, Bz));
+
+      let (mem_row, mem_col) = pk
+        .S_repr
+        .evaluation_oracles_into(&S, &tau, &z, &mut L_row, &mut L_col);
+      accumulate(&L_row, 5);
+      accumulate(&L_col, 6);
+      let (mem_oracles, _) = MemorySumcheckInstance::<E>::oracles(
+        &r,
+        &gamma,
+        &mem_row,
+        &pk.S_repr.row,
+        &L_row,
+        &pk.S_repr.ts_row,
+        &mem_col,
+        &pk.S_repr.col,
+        &L_col,
+        &pk.S_repr.ts_col,
+      )?;
+      for (v, i) in mem_oracles.iter().zip_eq([10, 12, 14, 16]) {
+        accumulate(v, i);
+      }
+
+      PolyEvalWitness { p }
+    } else {
+      let poly_vec = [
+        &W,
+        &Az,
+        &Bz,
+        &Cz,
+        &E,
+        &L_row,
+        &L_col,
+        &pk.S_repr.val_A,
+        &pk.S_repr.val_B,
+        &pk.S_repr.val_C,
+        &mem_oracles[0],
+        &pk.S_repr.row,
+        &mem_oracles[1],
+        &pk.S_repr.ts_row,
+        &mem_oracles[2],
+        &pk.S_repr.col,
+        &mem_oracles[3],
+        &pk.S_repr.ts_col,
+      ];
+      PolyEvalWitness::batch(&poly_vec, &c)
+    };
     let u: PolyEvalInstance<E> = PolyEvalInstance::batch(&comm_vec, &rand_sc, &eval_vec, &c);
 
     let eval_arg = EE::prove(ck, &pk.pk_ee, &mut transcript, &u.c, &w.p, &rand_sc, &u.e)?;
 
     Ok(RelaxedR1CSSNARK {
+      lookups,
       comm_Az: comm_Az.compress(),
       comm_Bz: comm_Bz.compress(),
       comm_Cz: comm_Cz.compress(),
@@ -1382,233 +1954,44 @@ where
 
   /// verifies a proof of satisfiability of a `RelaxedR1CS` instance
   fn verify(&self, vk: &Self::VerifierKey, U: &RelaxedR1CSInstance<E>) -> Result<(), NovaError> {
-    let mut transcript = E::TE::new(b"RelaxedR1CSSNARK");
-
-    // append the verifier key (including commitment to R1CS matrices) and the RelaxedR1CSInstance to the transcript
-    transcript.absorb(b"vk", &vk.digest());
-    transcript.absorb(b"U", U);
-
-    let comm_Az = Commitment::<E>::decompress(&self.comm_Az)?;
-    let comm_Bz = Commitment::<E>::decompress(&self.comm_Bz)?;
-    let comm_Cz = Commitment::<E>::decompress(&self.comm_Cz)?;
-    let comm_L_row = Commitment::<E>::decompress(&self.comm_L_row)?;
-    let comm_L_col = Commitment::<E>::decompress(&self.comm_L_col)?;
-    let comm_t_plus_r_inv_row = Commitment::<E>::decompress(&self.comm_t_plus_r_inv_row)?;
-    let comm_w_plus_r_inv_row = Commitment::<E>::decompress(&self.comm_w_plus_r_inv_row)?;
-    let comm_t_plus_r_inv_col = Commitment::<E>::decompress(&self.comm_t_plus_r_inv_col)?;
-    let comm_w_plus_r_inv_col = Commitment::<E>::decompress(&self.comm_w_plus_r_inv_col)?;
-
-    transcript.absorb(b"c", &[comm_Az, comm_Bz, comm_Cz].as_slice());
-
-    let num_rounds_sc = vk.S_comm.N.log_2();
-    let tau = transcript.squeeze(b"t")?;
-    let tau_coords = PowPolynomial::new(&tau, num_rounds_sc).coordinates();
-
-    transcript.absorb(
-      b"e",
-      &[
-        self.eval_Az_at_tau,
-        self.eval_Bz_at_tau,
-        self.eval_Cz_at_tau,
-      ]
-      .as_slice(),
-    );
-
-    transcript.absorb(b"e", &vec![comm_L_row, comm_L_col].as_slice());
-
-    // add claims about Az, Bz, and Cz to be checked later
-    // since all the three polynomials are opened at tau,
-    // we can combine them into a single polynomial opened at tau
-    let eval_vec = vec![
-      self.eval_Az_at_tau,
-      self.eval_Bz_at_tau,
-      self.eval_Cz_at_tau,
-    ];
-    let comm_vec = vec![comm_Az, comm_Bz, comm_Cz];
-    transcript.absorb(b"e", &eval_vec.as_slice()); // c_vec is already in the transcript
-    let c = transcript.squeeze(b"c")?;
-    let u: PolyEvalInstance<E> = PolyEvalInstance::batch(&comm_vec, &tau_coords, &eval_vec, &c);
-    let claim = u.e;
-
-    let gamma = transcript.squeeze(b"g")?;
-
-    let r = transcript.squeeze(b"r")?;
-
-    transcript.absorb(
-      b"l",
-      &vec![
-        comm_t_plus_r_inv_row,
-        comm_w_plus_r_inv_row,
-        comm_t_plus_r_inv_col,
-        comm_w_plus_r_inv_col,
-      ]
-      .as_slice(),
-    );
-
-    let rho = transcript.squeeze(b"r")?;
-
-    let num_claims = 9;
-    let s = transcript.squeeze(b"r")?;
-    let coeffs = powers::<E>(&s, num_claims);
-    let claim = (coeffs[7] + coeffs[8]) * claim; // rest are zeros
-
-    // verify sc
-    let (claim_sc_final, rand_sc) = self.sc.verify(claim, num_rounds_sc, 3, &mut transcript)?;
-
-    // verify claim_sc_final
-    let claim_sc_final_expected = {
-      let rand_eq_bound_rand_sc = {
-        let poly_eq_coords = PowPolynomial::new(&rho, num_rounds_sc).coordinates();
-        EqPolynomial::new(poly_eq_coords).evaluate(&rand_sc)
-      };
-      let taus_bound_rand_sc = PowPolynomial::new(&tau, num_rounds_sc).evaluate(&rand_sc);
-
-      let eval_t_plus_r_row = {
-        let eval_addr_row = IdentityPolynomial::new(num_rounds_sc).evaluate(&rand_sc);
-        let eval_val_row = taus_bound_rand_sc;
-        let eval_t = eval_addr_row + gamma * eval_val_row;
-        eval_t + r
-      };
-
-      let eval_w_plus_r_row = {
-        let eval_addr_row = self.eval_row;
-        let eval_val_row = self.eval_L_row;
-        let eval_w = eval_addr_row + gamma * eval_val_row;
-        eval_w + r
-      };
-
-      let eval_t_plus_r_col = {
-        let eval_addr_col = IdentityPolynomial::new(num_rounds_sc).evaluate(&rand_sc);
-
-        // memory contents is z, so we compute eval_Z from eval_W and eval_X
-        let eval_val_col = {
-          // rand_sc was padded, so we now remove the padding
-          let (factor, rand_sc_unpad) = {
-            let l = vk.S_comm.N.log_2() - (2 * vk.num_vars).log_2();
-
-            let mut factor = E::Scalar::ONE;
-            for r_p in rand_sc.iter().take(l) {
-              factor *= E::Scalar::ONE - r_p
-            }
-
-            let rand_sc_unpad = rand_sc[l..].to_vec();
-
-            (factor, rand_sc_unpad)
-          };
-
-          let eval_X = {
-            // constant term
-            let mut poly_X = vec![(0, U.u)];
-            //remaining inputs
-            poly_X.extend(
-              (0..U.X.len())
-                .map(|i| (i + 1, U.X[i]))
-                .collect::<Vec<(usize, E::Scalar)>>(),
-            );
-            SparsePolynomial::new(vk.num_vars.log_2(), poly_X).evaluate(&rand_sc_unpad[1..])
-          };
-
-          self.eval_W + factor * rand_sc_unpad[0] * eval_X
-        };
-        let eval_t = eval_addr_col + gamma * eval_val_col;
-        eval_t + r
-      };
-
-      let eval_w_plus_r_col = {
-        let eval_addr_col = self.eval_col;
-        let eval_val_col = self.eval_L_col;
-        let eval_w = eval_addr_col + gamma * eval_val_col;
-        eval_w + r
-      };
-
-      let claim_mem_final_expected: E::Scalar = coeffs[0]
-        * (self.eval_t_plus_r_inv_row - self.eval_w_plus_r_inv_row)
-        + coeffs[1] * (self.eval_t_plus_r_inv_col - self.eval_w_plus_r_inv_col)
-        + coeffs[2]
-          * (rand_eq_bound_rand_sc
-            * (self.eval_t_plus_r_inv_row * eval_t_plus_r_row - self.eval_ts_row))
-        + coeffs[3]
-          * (rand_eq_bound_rand_sc
-            * (self.eval_w_plus_r_inv_row * eval_w_plus_r_row - E::Scalar::ONE))
-        + coeffs[4]
-          * (rand_eq_bound_rand_sc
-            * (self.eval_t_plus_r_inv_col * eval_t_plus_r_col - self.eval_ts_col))
-        + coeffs[5]
-          * (rand_eq_bound_rand_sc
-            * (self.eval_w_plus_r_inv_col * eval_w_plus_r_col - E::Scalar::ONE));
+    let (mut transcript, u) = self.verify_reduce(vk, U)?;
 
-      let claim_outer_final_expected = coeffs[6]
-        * taus_bound_rand_sc
-        * (self.eval_Az * self.eval_Bz - U.u * self.eval_Cz - self.eval_E)
-        + coeffs[7] * taus_bound_rand_sc * (self.eval_Az + c * self.eval_Bz + c * c * self.eval_Cz);
-      let claim_inner_final_expected = coeffs[8]
-        * self.eval_L_row
-        * self.eval_L_col
-        * (self.eval_val_A + c * self.eval_val_B + c * c * self.eval_val_C);
+    // verify
+    EE::verify(&vk.vk_ee, &mut transcript, &u.c, &u.x, &u.e, &self.eval_arg)?;
 
-      claim_mem_final_expected + claim_outer_final_expected + claim_inner_final_expected
-    };
+    Ok(())
+  }
 
-    if claim_sc_final_expected != claim_sc_final {
-      return Err(NovaError::InvalidSumcheckProof);
+  /// verifies a batch of proofs of satisfiability of `RelaxedR1CS` instances, checking all of
+  /// their evaluation arguments at once
+  fn batch_verify(
+    snarks: &[&Self],
+    vk: &Self::VerifierKey,
+    U: &[RelaxedR1CSInstance<E>],
+  ) -> Result<(), NovaError> {
+    if snarks.len() != U.len() {
+      return Err(NovaError::InvalidInputLength);
     }
 
-    let eval_vec = vec![
-      self.eval_W,
-      self.eval_Az,
-      self.eval_Bz,
-      self.eval_Cz,
-      self.eval_E,
-      self.eval_L_row,
-      self.eval_L_col,
-      self.eval_val_A,
-      self.eval_val_B,
-      self.eval_val_C,
-      self.eval_t_plus_r_inv_row,
-      self.eval_row,
-      self.eval_w_plus_r_inv_row,
-      self.eval_ts_row,
-      self.eval_t_plus_r_inv_col,
-      self.eval_col,
-      self.eval_w_plus_r_inv_col,
-      self.eval_ts_col,
-    ]
-    .into_iter()
-    .collect::<Vec<E::Scalar>>();
-    let comm_vec = [
-      U.comm_W,
-      comm_Az,
-      comm_Bz,
-      comm_Cz,
-      U.comm_E,
-      comm_L_row,
-      comm_L_col,
-      vk.S_comm.comm_val_A,
-      vk.S_comm.comm_val_B,
-      vk.S_comm.comm_val_C,
-      comm_t_plus_r_inv_row,
-      vk.S_comm.comm_row,
-      comm_w_plus_r_inv_row,
-      vk.S_comm.comm_ts_row,
-      comm_t_plus_r_inv_col,
-      vk.S_comm.comm_col,
-      comm_w_plus_r_inv_col,
-      vk.S_comm.comm_ts_col,
-    ];
-    transcript.absorb(b"e", &eval_vec.as_slice()); // comm_vec is already in the transcript
-    let c = transcript.squeeze(b"c")?;
-    let u: PolyEvalInstance<E> = PolyEvalInstance::batch(&comm_vec, &rand_sc, &eval_vec, &c);
-
-    // verify
-    EE::verify(
-      &vk.vk_ee,
-      &mut transcript,
-      &u.c,
-      &rand_sc,
-      &u.e,
-      &self.eval_arg,
-    )?;
+    // reduce each proof to a claim about the evaluation of a committed polynomial
+    let u_vec = snarks
+      .par_iter()
+      .zip_eq(U.par_iter())
+      .enumerate()
+      .map(|(index, (snark, U))| {
+        snark
+          .verify_reduce(vk, U)
+          .map_err(|error| NovaError::BatchVerifyError {
+            index,
+            error: Box::new(error),
+          })
+      })
+      .collect::<Result<Vec<_>, NovaError>>()?;
 
-    Ok(())
+    let args = snarks
+      .iter()
+      .map(|snark| &snark.eval_arg)
+      .collect::<Vec<_>>();
+    batch_eval_arg_verify::<E, EE>(&vk.vk_ee, &u_vec, &args)
   }
 }
@@ -9,7 +9,8 @@ use crate::{
   errors::NovaError,
   r1cs::{R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness, SparseMatrix},
   spartan::{
-    compute_eval_table_sparse,
+    batch_eval_arg_verify, compute_eval_table_sparse,
+    lookup::LookupProof,
     polys::{eq::EqPolynomial, multilinear::MultilinearPolynomial, multilinear::SparsePolynomial},
     powers,
     sumcheck::SumcheckProof,
@@ -20,7 +21,7 @@ use crate::{
     snark::{DigestHelperTrait, RelaxedR1CSSNARKTrait},
     Engine, TranscriptEngineTrait,
   },
-  CommitmentKey,
+  zip_with, Commitment, CommitmentKey,
 };
 
 use abomonation::Abomonation;
@@ -40,6 +41,19 @@ pub struct ProverKey<E: Engine, EE: EvaluationEngineTrait<E>> {
   pk_ee: EE::ProverKey,
   #[abomonate_with(<E::Scalar as ff::PrimeField>::Repr)]
   vk_digest: E::Scalar, // digest of the verifier's key
+  #[abomonation_skip]
+  #[serde(skip)]
+  unified_opening: bool, // not part of the key's persisted form
+}
+
+impl<E: Engine, EE: EvaluationEngineTrait<E>> ProverKey<E, EE> {
+  /// Sets whether the prover reduces the claims about the witness and the error vector to a
+  /// single claim directly from the sum-check that batches them, rather than sending their
+  /// evaluations at the point the sum-check ends at. This makes proofs smaller and cheaper to
+  /// verify, and the verifier accepts proofs produced either way. It is off by default.
+  pub fn set_unified_opening(&mut self, unified_opening: bool) {
+    self.unified_opening = unified_opening;
+  }
 }
 
 /// A type that represents the verifier's key
@@ -86,16 +100,166 @@ impl<E: Engine, EE: EvaluationEngineTrait<E>> DigestHelperTrait<E> for VerifierK
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct RelaxedR1CSSNARK<E: Engine, EE: EvaluationEngineTrait<E>> {
-  sc_proof_outer: SumcheckProof<E>,
-  claims_outer: (E::Scalar, E::Scalar, E::Scalar),
-  eval_E: E::Scalar,
-  sc_proof_inner: SumcheckProof<E>,
-  eval_W: E::Scalar,
-  sc_proof_batch: SumcheckProof<E>,
-  evals_batch: Vec<E::Scalar>,
+  lookups: Vec<LookupProof<E, EE>>, // one for each lookup column of the shape
+  pub(crate) sc_proof_outer: SumcheckProof<E>,
+  pub(crate) claims_outer: (E::Scalar, E::Scalar, E::Scalar),
+  pub(crate) eval_E: E::Scalar,
+  pub(crate) sc_proof_inner: SumcheckProof<E>,
+  pub(crate) eval_W: E::Scalar,
+  pub(crate) sc_proof_batch: SumcheckProof<E>,
+  pub(crate) evals_batch: Vec<E::Scalar>, // empty when the claims are reduced with a unified opening
   eval_arg: EE::EvaluationArgument,
 }
 
+impl<E: Engine, EE: EvaluationEngineTrait<E>> RelaxedR1CSSNARK<E, EE> {
+  // Replays the verification of the proof up to its evaluation argument, returning the
+  // transcript and the claim about the evaluation of a committed polynomial the proof reduces to
+  pub(crate) fn verify_reduce(
+    &self,
+    vk: &VerifierKey<E, EE>,
+    U: &RelaxedR1CSInstance<E>,
+  ) -> Result<(E::TE, PolyEvalInstance<E>), NovaError> {
+    let mut transcript = E::TE::new(b"RelaxedR1CSSNARK");
+
+    // append the digest of R1CS matrices and the RelaxedR1CSInstance to the transcript
+    transcript.absorb(b"vk", &vk.digest());
+    transcript.absorb(b"U", U);
+
+    // verify the lookups of the witness
+    if self.lookups.len() != vk.S.lookups.len() {
+      return Err(NovaError::ProofVerifyError);
+    }
+    for (lookup, column) in self.lookups.iter().zip_eq(vk.S.lookups.iter()) {
+      lookup.verify_column(&vk.vk_ee, column, &U.comm_W, vk.S.num_vars, &mut transcript)?;
+    }
+
+    let (num_rounds_x, num_rounds_y) = (
+      usize::try_from(vk.S.num_cons.ilog2()).unwrap(),
+      (usize::try_from(vk.S.num_vars.ilog2()).unwrap() + 1),
+    );
+
+    // outer sum-check
+    let tau = (0..num_rounds_x)
+      .map(|_i| transcript.squeeze(b"t"))
+      .collect::<Result<EqPolynomial<_>, NovaError>>()?;
+
+    let (claim_outer_final, r_x) =
+      self
+        .sc_proof_outer
+        .verify(E::Scalar::ZERO, num_rounds_x, 3, &mut transcript)?;
+
+    // verify claim_outer_final
+    let (claim_Az, claim_Bz, claim_Cz) = self.claims_outer;
+    let taus_bound_rx = tau.evaluate(&r_x);
+    let claim_outer_final_expected =
+      taus_bound_rx * (claim_Az * claim_Bz - U.u * claim_Cz - self.eval_E);
+    if claim_outer_final != claim_outer_final_expected {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    transcript.absorb(
+      b"claims_outer",
+      &[
+        self.claims_outer.0,
+        self.claims_outer.1,
+        self.claims_outer.2,
+        self.eval_E,
+      ]
+      .as_slice(),
+    );
+
+    // inner sum-check
+    let r = transcript.squeeze(b"r")?;
+    let claim_inner_joint =
+      self.claims_outer.0 + r * self.claims_outer.1 + r * r * self.claims_outer.2;
+
+    let (claim_inner_final, r_y) =
+      self
+        .sc_proof_inner
+        .verify(claim_inner_joint, num_rounds_y, 2, &mut transcript)?;
+
+    // verify claim_inner_final
+    let eval_Z = {
+      let eval_X = {
+        // constant term
+        let mut poly_X = vec![(0, U.u)];
+        //remaining inputs
+        poly_X.extend(
+          (0..U.X.len())
+            .map(|i| (i + 1, U.X[i]))
+            .collect::<Vec<(usize, E::Scalar)>>(),
+        );
+        SparsePolynomial::new(usize::try_from(vk.S.num_vars.ilog2()).unwrap(), poly_X)
+          .evaluate(&r_y[1..])
+      };
+      (E::Scalar::ONE - r_y[0]) * self.eval_W + r_y[0] * eval_X
+    };
+
+    // compute evaluations of R1CS matrices
+    let multi_evaluate = |M_vec: &[&SparseMatrix<E::Scalar>],
+                          r_x: &[E::Scalar],
+                          r_y: &[E::Scalar]|
+     -> Vec<E::Scalar> {
+      let evaluate_with_table =
+        |M: &SparseMatrix<E::Scalar>, T_x: &[E::Scalar], T_y: &[E::Scalar]| -> E::Scalar {
+          M.indptr
+            .par_windows(2)
+            .enumerate()
+            .map(|(row_idx, ptrs)| {
+              M.get_row_unchecked(ptrs.try_into().unwrap())
+                .map(|(val, col_idx)| T_x[row_idx] * T_y[*col_idx] * val)
+                .sum::<E::Scalar>()
+            })
+            .sum()
+        };
+
+      let (T_x, T_y) = rayon::join(
+        || EqPolynomial::evals_from_points(r_x),
+        || EqPolynomial::evals_from_points(r_y),
+      );
+
+      (0..M_vec.len())
+        .into_par_iter()
+        .map(|i| evaluate_with_table(M_vec[i], &T_x, &T_y))
+        .collect()
+    };
+
+    let evals = multi_evaluate(&[&vk.S.A, &vk.S.B, &vk.S.C], &r_x, &r_y);
+
+    let claim_inner_final_expected = (evals[0] + r * evals[1] + r * r * evals[2]) * eval_Z;
+    if claim_inner_final != claim_inner_final_expected {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    // add claims about W and E polynomials
+    let u_vec: Vec<PolyEvalInstance<E>> = vec![
+      PolyEvalInstance {
+        c: U.comm_W,
+        x: r_y[1..].to_vec(),
+        e: self.eval_W,
+      },
+      PolyEvalInstance {
+        c: U.comm_E,
+        x: r_x,
+        e: self.eval_E,
+      },
+    ];
+
+    let batched_u = if self.evals_batch.is_empty() {
+      batch_eval_verify_unified(u_vec, &mut transcript, &self.sc_proof_batch)?
+    } else {
+      batch_eval_verify(
+        u_vec,
+        &mut transcript,
+        &self.sc_proof_batch,
+        &self.evals_batch,
+      )?
+    };
+
+    Ok((transcript, batched_u))
+  }
+}
+
 impl<E: Engine, EE: EvaluationEngineTrait<E>> RelaxedR1CSSNARKTrait<E> for RelaxedR1CSSNARK<E, EE>
 where
   <E::Scalar as ff::PrimeField>::Repr: Abomonation,
@@ -116,6 +280,7 @@ where
     let pk = ProverKey {
       pk_ee,
       vk_digest: vk.digest(),
+      unified_opening: false,
     };
 
     Ok((pk, vk))
@@ -142,6 +307,15 @@ where
     transcript.absorb(b"vk", &pk.vk_digest);
     transcript.absorb(b"U", U);
 
+    // prove the lookups of the witness, which only hold for witnesses that are not folded
+    let lookups = S
+      .lookups
+      .iter()
+      .map(|column| {
+        LookupProof::prove_column(ck, &pk.pk_ee, column, &U.comm_W, &W.W, &mut transcript)
+      })
+      .collect::<Result<Vec<_>, NovaError>>()?;
+
     // compute the full satisfying assignment by concatenating W.W, U.u, and U.X
     let mut z = [W.W.clone(), vec![U.u], U.X.clone()].concat();
 
@@ -155,8 +329,8 @@ where
       .map(|_i| transcript.squeeze(b"t"))
       .collect::<Result<EqPolynomial<_>, NovaError>>()?;
 
-    let mut poly_tau = MultilinearPolynomial::new(tau.evals());
-    let (mut poly_Az, mut poly_Bz, poly_Cz, mut poly_uCz_E) = {
+    let poly_tau = MultilinearPolynomial::new(tau.evals());
+    let (poly_Az, poly_Bz, poly_Cz, poly_uCz_E) = {
       let (poly_Az, poly_Bz, poly_Cz) = S.multiply_vec(&z)?;
       let poly_uCz_E = (0..S.num_cons)
         .map(|i| U.u * poly_Cz[i] + W.E[i])
@@ -169,27 +343,25 @@ where
       )
     };
 
+    // tau * (Az * Bz - uCz_E)
     let comb_func_outer =
-      |poly_A_comp: &E::Scalar,
-       poly_B_comp: &E::Scalar,
-       poly_C_comp: &E::Scalar,
-       poly_D_comp: &E::Scalar|
-       -> E::Scalar { *poly_A_comp * (*poly_B_comp * *poly_C_comp - *poly_D_comp) };
-    let (sc_proof_outer, r_x, claims_outer) = SumcheckProof::prove_cubic_with_additive_term(
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - comps[3]) };
+    let (sc_proof_outer, r_x, claims_outer) = SumcheckProof::prove(
       &E::Scalar::ZERO, // claim is zero
       num_rounds_x,
-      &mut poly_tau,
-      &mut poly_Az,
-      &mut poly_Bz,
-      &mut poly_uCz_E,
+      vec![poly_tau, poly_Az, poly_Bz, poly_uCz_E],
+      3,
       comb_func_outer,
       &mut transcript,
     )?;
 
     // claims from the end of sum-check
     let (claim_Az, claim_Bz): (E::Scalar, E::Scalar) = (claims_outer[1], claims_outer[2]);
-    let claim_Cz = poly_Cz.evaluate(&r_x);
-    let eval_E = MultilinearPolynomial::new(W.E.clone()).evaluate(&r_x);
+    let (claim_Cz, eval_E) = {
+      let evals =
+        MultilinearPolynomial::evaluate_batch(&[poly_Cz.evaluations(), W.E.as_slice()], &r_x);
+      (evals[0], evals[1])
+    };
     transcript.absorb(
       b"claims_outer",
       &[claim_Az, claim_Bz, claim_Cz, eval_E].as_slice(),
@@ -218,14 +390,15 @@ where
       z
     };
 
-    let comb_func = |poly_A_comp: &E::Scalar, poly_B_comp: &E::Scalar| -> E::Scalar {
-      *poly_A_comp * *poly_B_comp
-    };
-    let (sc_proof_inner, r_y, _claims_inner) = SumcheckProof::prove_quad(
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
+    let (sc_proof_inner, r_y, _claims_inner) = SumcheckProof::prove(
       &claim_inner_joint,
       num_rounds_y,
-      &mut MultilinearPolynomial::new(poly_ABC),
-      &mut MultilinearPolynomial::new(poly_z),
+      vec![
+        MultilinearPolynomial::new(poly_ABC),
+        MultilinearPolynomial::new(poly_z),
+      ],
+      2,
       comb_func,
       &mut transcript,
     )?;
@@ -263,8 +436,13 @@ where
     let (w_vec, u_vec): (Vec<PolyEvalWitness<E>>, Vec<PolyEvalInstance<E>>) =
       w_u_vec.into_iter().unzip();
 
-    let (batched_u, batched_w, sc_proof_batch, claims_batch_left) =
-      batch_eval_prove(u_vec, w_vec, &mut transcript)?;
+    let (batched_u, batched_w, sc_proof_batch, claims_batch_left) = if pk.unified_opening {
+      let (batched_u, batched_w, sc_proof_batch) =
+        batch_eval_prove_unified(u_vec, w_vec, &mut transcript)?;
+      (batched_u, batched_w, sc_proof_batch, Vec::new())
+    } else {
+      batch_eval_prove(u_vec, w_vec, &mut transcript)?
+    };
 
     let eval_arg = EE::prove(
       ck,
@@ -277,6 +455,7 @@ where
     )?;
 
     Ok(RelaxedR1CSSNARK {
+      lookups,
       sc_proof_outer,
       claims_outer: (claim_Az, claim_Bz, claim_Cz),
       eval_E,
@@ -290,142 +469,45 @@ where
 
   /// verifies a proof of satisfiability of a `RelaxedR1CS` instance
   fn verify(&self, vk: &Self::VerifierKey, U: &RelaxedR1CSInstance<E>) -> Result<(), NovaError> {
-    let mut transcript = E::TE::new(b"RelaxedR1CSSNARK");
-
-    // append the digest of R1CS matrices and the RelaxedR1CSInstance to the transcript
-    transcript.absorb(b"vk", &vk.digest());
-    transcript.absorb(b"U", U);
-
-    let (num_rounds_x, num_rounds_y) = (
-      usize::try_from(vk.S.num_cons.ilog2()).unwrap(),
-      (usize::try_from(vk.S.num_vars.ilog2()).unwrap() + 1),
-    );
-
-    // outer sum-check
-    let tau = (0..num_rounds_x)
-      .map(|_i| transcript.squeeze(b"t"))
-      .collect::<Result<EqPolynomial<_>, NovaError>>()?;
-
-    let (claim_outer_final, r_x) =
-      self
-        .sc_proof_outer
-        .verify(E::Scalar::ZERO, num_rounds_x, 3, &mut transcript)?;
-
-    // verify claim_outer_final
-    let (claim_Az, claim_Bz, claim_Cz) = self.claims_outer;
-    let taus_bound_rx = tau.evaluate(&r_x);
-    let claim_outer_final_expected =
-      taus_bound_rx * (claim_Az * claim_Bz - U.u * claim_Cz - self.eval_E);
-    if claim_outer_final != claim_outer_final_expected {
-      return Err(NovaError::InvalidSumcheckProof);
-    }
-
-    transcript.absorb(
-      b"claims_outer",
-      &[
-        self.claims_outer.0,
-        self.claims_outer.1,
-        self.claims_outer.2,
-        self.eval_E,
-      ]
-      .as_slice(),
-    );
-
-    // inner sum-check
-    let r = transcript.squeeze(b"r")?;
-    let claim_inner_joint =
-      self.claims_outer.0 + r * self.claims_outer.1 + r * r * self.claims_outer.2;
-
-    let (claim_inner_final, r_y) =
-      self
-        .sc_proof_inner
-        .verify(claim_inner_joint, num_rounds_y, 2, &mut transcript)?;
+    let (mut transcript, u) = self.verify_reduce(vk, U)?;
 
-    // verify claim_inner_final
-    let eval_Z = {
-      let eval_X = {
-        // constant term
-        let mut poly_X = vec![(0, U.u)];
-        //remaining inputs
-        poly_X.extend(
-          (0..U.X.len())
-            .map(|i| (i + 1, U.X[i]))
-            .collect::<Vec<(usize, E::Scalar)>>(),
-        );
-        SparsePolynomial::new(usize::try_from(vk.S.num_vars.ilog2()).unwrap(), poly_X)
-          .evaluate(&r_y[1..])
-      };
-      (E::Scalar::ONE - r_y[0]) * self.eval_W + r_y[0] * eval_X
-    };
-
-    // compute evaluations of R1CS matrices
-    let multi_evaluate = |M_vec: &[&SparseMatrix<E::Scalar>],
-                          r_x: &[E::Scalar],
-                          r_y: &[E::Scalar]|
-     -> Vec<E::Scalar> {
-      let evaluate_with_table =
-        |M: &SparseMatrix<E::Scalar>, T_x: &[E::Scalar], T_y: &[E::Scalar]| -> E::Scalar {
-          M.indptr
-            .par_windows(2)
-            .enumerate()
-            .map(|(row_idx, ptrs)| {
-              M.get_row_unchecked(ptrs.try_into().unwrap())
-                .map(|(val, col_idx)| T_x[row_idx] * T_y[*col_idx] * val)
-                .sum::<E::Scalar>()
-            })
-            .sum()
-        };
-
-      let (T_x, T_y) = rayon::join(
-        || EqPolynomial::evals_from_points(r_x),
-        || EqPolynomial::evals_from_points(r_y),
-      );
-
-      (0..M_vec.len())
-        .into_par_iter()
-        .map(|i| evaluate_with_table(M_vec[i], &T_x, &T_y))
-        .collect()
-    };
+    // verify
+    EE::verify(&vk.vk_ee, &mut transcript, &u.c, &u.x, &u.e, &self.eval_arg)?;
 
-    let evals = multi_evaluate(&[&vk.S.A, &vk.S.B, &vk.S.C], &r_x, &r_y);
+    Ok(())
+  }
 
-    let claim_inner_final_expected = (evals[0] + r * evals[1] + r * r * evals[2]) * eval_Z;
-    if claim_inner_final != claim_inner_final_expected {
-      return Err(NovaError::InvalidSumcheckProof);
+  /// verifies a batch of proofs of satisfiability of `RelaxedR1CS` instances, checking all of
+  /// their evaluation arguments at once
+  fn batch_verify(
+    snarks: &[&Self],
+    vk: &Self::VerifierKey,
+    U: &[RelaxedR1CSInstance<E>],
+  ) -> Result<(), NovaError> {
+    if snarks.len() != U.len() {
+      return Err(NovaError::InvalidInputLength);
     }
 
-    // add claims about W and E polynomials
-    let u_vec: Vec<PolyEvalInstance<E>> = vec![
-      PolyEvalInstance {
-        c: U.comm_W,
-        x: r_y[1..].to_vec(),
-        e: self.eval_W,
-      },
-      PolyEvalInstance {
-        c: U.comm_E,
-        x: r_x,
-        e: self.eval_E,
-      },
-    ];
-
-    let batched_u = batch_eval_verify(
-      u_vec,
-      &mut transcript,
-      &self.sc_proof_batch,
-      &self.evals_batch,
-    )?;
-
-    // verify
-    EE::verify(
-      &vk.vk_ee,
-      &mut transcript,
-      &batched_u.c,
-      &batched_u.x,
-      &batched_u.e,
-      &self.eval_arg,
-    )?;
+    // reduce each proof to a claim about the evaluation of a committed polynomial
+    let u_vec = snarks
+      .par_iter()
+      .zip_eq(U.par_iter())
+      .enumerate()
+      .map(|(index, (snark, U))| {
+        snark
+          .verify_reduce(vk, U)
+          .map_err(|error| NovaError::BatchVerifyError {
+            index,
+            error: Box::new(error),
+          })
+      })
+      .collect::<Result<Vec<_>, NovaError>>()?;
 
-    Ok(())
+    let args = snarks
+      .iter()
+      .map(|snark| &snark.eval_arg)
+      .collect::<Vec<_>>();
+    batch_eval_arg_verify::<E, EE>(&vk.vk_ee, &u_vec, &args)
   }
 }
 
@@ -488,18 +570,25 @@ pub(in crate::spartan) fn batch_eval_prove<E: Engine>(
     .collect();
 
   // For each i, check eᵢ = ∑ₓ Pᵢ(x)eq(xᵢ,x), where x ∈ {0,1}^nᵢ
-  let comb_func = |poly_P: &E::Scalar, poly_eq: &E::Scalar| -> E::Scalar { *poly_P * *poly_eq };
-  let (sc_proof_batch, r, claims_batch) = SumcheckProof::prove_quad_batch(
+  let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
+  let (sc_proof_batch, r, claims_batch) = SumcheckProof::prove_batch(
     &claims,
     &num_rounds,
-    polys_P,
-    polys_eq,
+    zip_with!(into_iter, (polys_P, polys_eq), |poly_P, poly_eq| vec![
+      poly_P, poly_eq
+    ])
+    .collect(),
     &powers_of_rho,
+    2,
     comb_func,
     transcript,
   )?;
 
-  let (claims_batch_left, _): (Vec<E::Scalar>, Vec<E::Scalar>) = claims_batch;
+  // the evaluations Pᵢ(r)
+  let claims_batch_left = claims_batch
+    .into_iter()
+    .map(|claims| claims[0])
+    .collect::<Vec<E::Scalar>>();
 
   transcript.absorb(b"l", &claims_batch_left.as_slice());
 
@@ -567,3 +656,117 @@ pub(in crate::spartan) fn batch_eval_verify<E: Engine>(
 
   Ok(u_joint)
 }
+
+/// Proves a batch of polynomial evaluation claims using Sumcheck like `batch_eval_prove`,
+/// but reduces them to a single claim without sending the evaluations of the polynomials
+/// at the point where the sum-check ends.
+///
+/// # Details
+///
+/// The sum-check ends at a point r with the claim e = ∑ᵢ ρⁱ⋅eq(xᵢ, r_hi)⋅Pᵢ(r_hi), where
+/// r_hi are the last nᵢ coordinates of r and r_lo the others. Since Pᵢ, padded with 0 to
+/// n variables, evaluates to L₀(r_lo)⋅Pᵢ(r_hi) at r, this is a claim about the evaluation
+/// at r of P = ∑ᵢ wᵢ⋅Pᵢ with wᵢ = ρⁱ⋅eq(xᵢ, r_hi)/L₀(r_lo), whose commitment the verifier
+/// derives from the commitments Cᵢ.
+pub(in crate::spartan) fn batch_eval_prove_unified<E: Engine>(
+  u_vec: Vec<PolyEvalInstance<E>>,
+  w_vec: Vec<PolyEvalWitness<E>>,
+  transcript: &mut E::TE,
+) -> Result<(PolyEvalInstance<E>, PolyEvalWitness<E>, SumcheckProof<E>), NovaError> {
+  let num_claims = u_vec.len();
+  assert_eq!(w_vec.len(), num_claims);
+
+  let num_rounds = u_vec.iter().map(|u| u.x.len()).collect::<Vec<_>>();
+  w_vec
+    .iter()
+    .zip_eq(num_rounds.iter())
+    .for_each(|(w, num_vars)| assert_eq!(w.p.len(), 1 << num_vars));
+
+  // generate a challenge, and powers of it for random linear combination
+  let rho = transcript.squeeze(b"r")?;
+  let powers_of_rho = powers::<E>(&rho, num_claims);
+
+  let claims = u_vec.iter().map(|u| u.e).collect::<Vec<_>>();
+  let polys_P = w_vec
+    .iter()
+    .map(|w| MultilinearPolynomial::new(w.p.clone()));
+  let polys_eq = u_vec
+    .iter()
+    .map(|u| MultilinearPolynomial::new(EqPolynomial::evals_from_points(&u.x)));
+
+  let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
+  let (sc_proof_batch, r, claims_batch) = SumcheckProof::prove_batch(
+    &claims,
+    &num_rounds,
+    polys_P
+      .zip_eq(polys_eq)
+      .map(|(poly_P, poly_eq)| vec![poly_P, poly_eq])
+      .collect(),
+    &powers_of_rho,
+    2,
+    comb_func,
+    transcript,
+  )?;
+
+  // the final claim of the sum-check
+  let e = zip_with!(iter, (claims_batch, powers_of_rho), |claims, rho_i| {
+    claims[0] * claims[1] * rho_i
+  })
+  .sum::<E::Scalar>();
+
+  let weights = unified_weights(&u_vec, &r, &powers_of_rho)?;
+  let c = zip_with!(iter, (u_vec, weights), |u, w_i| u.c * *w_i)
+    .fold(Commitment::<E>::default(), |acc, item| acc + item);
+  let w_joint = PolyEvalWitness::weighted_sum_diff_size(w_vec, &weights);
+
+  Ok((PolyEvalInstance { c, x: r, e }, w_joint, sc_proof_batch))
+}
+
+/// Verifies a batch of polynomial evaluation claims reduced to a single claim
+/// by `batch_eval_prove_unified`.
+pub(in crate::spartan) fn batch_eval_verify_unified<E: Engine>(
+  u_vec: Vec<PolyEvalInstance<E>>,
+  transcript: &mut E::TE,
+  sc_proof_batch: &SumcheckProof<E>,
+) -> Result<PolyEvalInstance<E>, NovaError> {
+  // generate a challenge
+  let rho = transcript.squeeze(b"r")?;
+  let powers_of_rho = powers::<E>(&rho, u_vec.len());
+
+  let num_rounds = u_vec.iter().map(|u| u.x.len()).collect::<Vec<_>>();
+  let claims = u_vec.iter().map(|u| u.e).collect::<Vec<_>>();
+
+  let (claim_batch_final, r) =
+    sc_proof_batch.verify_batch(&claims, &num_rounds, &powers_of_rho, 2, transcript)?;
+
+  let weights = unified_weights(&u_vec, &r, &powers_of_rho)?;
+  let c = zip_with!(iter, (u_vec, weights), |u, w_i| u.c * *w_i)
+    .fold(Commitment::<E>::default(), |acc, item| acc + item);
+
+  Ok(PolyEvalInstance {
+    c,
+    x: r,
+    e: claim_batch_final,
+  })
+}
+
+// computes the weights wᵢ = ρⁱ⋅eq(xᵢ, r_hi)/L₀(r_lo) of `batch_eval_prove_unified`
+fn unified_weights<E: Engine>(
+  u_vec: &[PolyEvalInstance<E>],
+  r: &[E::Scalar],
+  powers_of_rho: &[E::Scalar],
+) -> Result<Vec<E::Scalar>, NovaError> {
+  zip_with!(iter, (u_vec, powers_of_rho), |u, rho_i| {
+    let (r_lo, r_hi) = r.split_at(r.len() - u.x.len());
+    let lagrange_eval = r_lo
+      .iter()
+      .map(|r| E::Scalar::ONE - r)
+      .product::<E::Scalar>();
+    let eq_eval = EqPolynomial::new(r_hi.to_vec()).evaluate(&u.x);
+
+    Option::<E::Scalar>::from(lagrange_eval.invert())
+      .map(|inv| *rho_i * eq_eval * inv)
+      .ok_or(NovaError::InternalError)
+  })
+  .collect()
+}
@@ -1,3 +1,6 @@
+//! This module implements the sumcheck protocol for sums of a combine function of multilinear
+//! polynomials, in unbatched and batched forms that share the same transcript discipline: in each
+//! round, the prover absorbs its univariate polynomial and squeezes the verifier's challenge.
 use crate::errors::NovaError;
 use crate::spartan::polys::{
   multilinear::MultilinearPolynomial,
@@ -9,17 +12,32 @@ use itertools::Itertools as _;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// A sumcheck proof, made of the univariate polynomial sent by the prover in each round.
+///
+/// The prover proves that a claim `c` equals `∑ₓ g(P₁(x), …, Pₙ(x))` over `x ∈ {0,1}^m`, where
+/// the `Pᵢ` are multilinear polynomials and `g` is a combine function of degree `d`. The batched
+/// form proves a random linear combination of several such claims, possibly over a different
+/// number of variables, in a single run of the protocol.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub(crate) struct SumcheckProof<E: Engine> {
+pub struct SumcheckProof<E: Engine> {
   compressed_polys: Vec<CompressedUniPoly<E::Scalar>>,
 }
 
 impl<E: Engine> SumcheckProof<E> {
-  pub fn new(compressed_polys: Vec<CompressedUniPoly<E::Scalar>>) -> Self {
+  pub(crate) fn new(compressed_polys: Vec<CompressedUniPoly<E::Scalar>>) -> Self {
     Self { compressed_polys }
   }
 
+  pub(crate) fn compressed_polys(&self) -> &[CompressedUniPoly<E::Scalar>] {
+    &self.compressed_polys
+  }
+
+  /// Verifies the proof of `claim` over `num_rounds` variables, for a combine function of
+  /// degree `degree_bound`.
+  ///
+  /// Returns the claim reduced to the random point `r` of the verifier, i.e. the value
+  /// `g(P₁(r), …, Pₙ(r))` which must then be checked, along with `r`.
   pub fn verify(
     &self,
     claim: E::Scalar,
@@ -62,6 +80,12 @@ impl<E: Engine> SumcheckProof<E> {
     Ok((e, r))
   }
 
+  /// Verifies the proof of the linear combination of `claims` with `coeffs`, where the `i`-th
+  /// claim is over `num_rounds[i]` variables.
+  ///
+  /// Returns the combined claim reduced to the random point `r` of the verifier, which has
+  /// `maxᵢ num_rounds[i]` entries. The `i`-th claim is reduced to the last `num_rounds[i]`
+  /// entries of `r`.
   pub fn verify_batch(
     &self,
     claims: &[E::Scalar],
@@ -96,121 +120,133 @@ impl<E: Engine> SumcheckProof<E> {
     self.verify(claim, num_rounds_max, degree_bound, transcript)
   }
 
+  /// Computes the evaluations at `0, 2, 3, …, degree` of the univariate polynomial
+  /// `∑ₓ g(P₁(X, x), …, Pₙ(X, x))` sent by the prover in a round, where `g` is `comb_func`.
+  ///
+  /// The evaluation at 1 is not computed, as it is implied by the claim of the round.
   #[inline]
-  pub(in crate::spartan) fn compute_eval_points_quad<F>(
-    poly_A: &MultilinearPolynomial<E::Scalar>,
-    poly_B: &MultilinearPolynomial<E::Scalar>,
+  pub(in crate::spartan) fn compute_eval_points<F>(
+    polys: &[&MultilinearPolynomial<E::Scalar>],
+    degree: usize,
     comb_func: &F,
-  ) -> (E::Scalar, E::Scalar)
+  ) -> Vec<E::Scalar>
   where
-    F: Fn(&E::Scalar, &E::Scalar) -> E::Scalar + Sync,
+    F: Fn(&[E::Scalar]) -> E::Scalar + Sync,
   {
-    let len = poly_A.len() / 2;
+    let len = polys[0].len() / 2;
     (0..len)
       .into_par_iter()
-      .map(|i| {
-        // eval 0: bound_func is A(low)
-        let eval_point_0 = comb_func(&poly_A[i], &poly_B[i]);
-
-        // eval 2: bound_func is -A(low) + 2*A(high)
-        let poly_A_bound_point = poly_A[len + i] + poly_A[len + i] - poly_A[i];
-        let poly_B_bound_point = poly_B[len + i] + poly_B[len + i] - poly_B[i];
-        let eval_point_2 = comb_func(&poly_A_bound_point, &poly_B_bound_point);
-        (eval_point_0, eval_point_2)
-      })
+      .fold(
+        || {
+          (
+            vec![E::Scalar::ZERO; degree],
+            vec![E::Scalar::ZERO; polys.len()],
+            vec![E::Scalar::ZERO; polys.len()],
+          )
+        },
+        |(mut evals, mut bound_point, mut step), i| {
+          // eval 0: bound_func is P(low)
+          for (j, poly) in polys.iter().enumerate() {
+            bound_point[j] = poly[i];
+            step[j] = poly[len + i] - poly[i];
+          }
+          evals[0] += comb_func(&bound_point);
+
+          // eval t: bound_func is P(low) + t⋅(P(high) - P(low)); computed incrementally from
+          // eval 1, i.e. P(high), which is skipped
+          for (j, poly) in polys.iter().enumerate() {
+            bound_point[j] = poly[len + i];
+          }
+          for eval in evals.iter_mut().skip(1) {
+            for (p, s) in bound_point.iter_mut().zip_eq(step.iter()) {
+              *p += s;
+            }
+            *eval += comb_func(&bound_point);
+          }
+
+          (evals, bound_point, step)
+        },
+      )
+      .map(|(evals, _, _)| evals)
       .reduce(
-        || (E::Scalar::ZERO, E::Scalar::ZERO),
-        |a, b| (a.0 + b.0, a.1 + b.1),
+        || vec![E::Scalar::ZERO; degree],
+        |mut acc, evals| {
+          for (acc, eval) in acc.iter_mut().zip_eq(evals) {
+            *acc += eval;
+          }
+          acc
+        },
       )
   }
 
-  pub fn prove_quad<F>(
+  /// Proves that `claim` equals `∑ₓ g(P₁(x), …, Pₙ(x))` over `x ∈ {0,1}^num_rounds`, where the
+  /// `Pᵢ` are `polys` and `g` is `comb_func`, which must have degree at most `degree`.
+  ///
+  /// Returns the proof, the random point `r` of the verifier and the evaluations `Pᵢ(r)`.
+  pub fn prove<F>(
     claim: &E::Scalar,
     num_rounds: usize,
-    poly_A: &mut MultilinearPolynomial<E::Scalar>,
-    poly_B: &mut MultilinearPolynomial<E::Scalar>,
+    polys: Vec<MultilinearPolynomial<E::Scalar>>,
+    degree: usize,
     comb_func: F,
     transcript: &mut E::TE,
   ) -> Result<(Self, Vec<E::Scalar>, Vec<E::Scalar>), NovaError>
   where
-    F: Fn(&E::Scalar, &E::Scalar) -> E::Scalar + Sync,
+    F: Fn(&[E::Scalar]) -> E::Scalar + Sync,
   {
-    let mut r: Vec<E::Scalar> = Vec::new();
-    let mut polys: Vec<CompressedUniPoly<E::Scalar>> = Vec::new();
-    let mut claim_per_round = *claim;
-    for _ in 0..num_rounds {
-      let poly = {
-        let (eval_point_0, eval_point_2) =
-          Self::compute_eval_points_quad(poly_A, poly_B, &comb_func);
-
-        let evals = vec![eval_point_0, claim_per_round - eval_point_0, eval_point_2];
-        UniPoly::from_evals(&evals)
-      };
-
-      // append the prover's message to the transcript
-      transcript.absorb(b"p", &poly);
-
-      //derive the verifier's challenge for the next round
-      let r_i = transcript.squeeze(b"c")?;
-      r.push(r_i);
-      polys.push(poly.compress());
-
-      // Set up next round
-      claim_per_round = poly.evaluate(&r_i);
-
-      // bind all tables to the verifier's challenge
-      rayon::join(
-        || poly_A.bind_poly_var_top(&r_i),
-        || poly_B.bind_poly_var_top(&r_i),
-      );
-    }
-
-    Ok((
-      SumcheckProof {
-        compressed_polys: polys,
-      },
-      r,
-      vec![poly_A[0], poly_B[0]],
-    ))
+    let (proof, r, mut evals) = Self::prove_batch(
+      &[*claim],
+      &[num_rounds],
+      vec![polys],
+      &[E::Scalar::ONE],
+      degree,
+      comb_func,
+      transcript,
+    )?;
+    Ok((proof, r, evals.remove(0)))
   }
 
-  pub fn prove_quad_batch<F>(
+  /// Proves the linear combination with `coeffs` of the claims `cᵢ = ∑ₓ g(Pᵢ₁(x), …, Pᵢₙ(x))`,
+  /// where `x ∈ {0,1}^num_rounds[i]`, the `Pᵢⱼ` are `polys[i]`, and `g` is `comb_func`, which
+  /// must have degree at most `degree`.
+  ///
+  /// Claims over fewer variables are padded, and reduced to the last `num_rounds[i]` entries of
+  /// the random point `r` of the verifier. Returns the proof, `r`, and for each claim the
+  /// evaluations of its polynomials at its point.
+  pub fn prove_batch<F>(
     claims: &[E::Scalar],
     num_rounds: &[usize],
-    mut poly_A_vec: Vec<MultilinearPolynomial<E::Scalar>>,
-    mut poly_B_vec: Vec<MultilinearPolynomial<E::Scalar>>,
+    mut polys: Vec<Vec<MultilinearPolynomial<E::Scalar>>>,
     coeffs: &[E::Scalar],
+    degree: usize,
     comb_func: F,
     transcript: &mut E::TE,
-  ) -> Result<(Self, Vec<E::Scalar>, (Vec<E::Scalar>, Vec<E::Scalar>)), NovaError>
+  ) -> Result<(Self, Vec<E::Scalar>, Vec<Vec<E::Scalar>>), NovaError>
   where
-    F: Fn(&E::Scalar, &E::Scalar) -> E::Scalar + Sync,
+    F: Fn(&[E::Scalar]) -> E::Scalar + Sync,
   {
-    let num_claims = claims.len();
-
-    assert_eq!(num_rounds.len(), num_claims);
-    assert_eq!(poly_A_vec.len(), num_claims);
-    assert_eq!(poly_B_vec.len(), num_claims);
-    assert_eq!(coeffs.len(), num_claims);
+    let num_instances = claims.len();
+    assert!(degree >= 1);
+    assert_eq!(num_rounds.len(), num_instances);
+    assert_eq!(polys.len(), num_instances);
+    assert_eq!(coeffs.len(), num_instances);
 
-    for (i, &num_rounds) in num_rounds.iter().enumerate() {
+    for (i, (polys, &num_rounds)) in polys.iter().zip_eq(num_rounds).enumerate() {
       let expected_size = 1 << num_rounds;
-
-      // Direct indexing with the assumption that the index will always be in bounds
-      let a = &poly_A_vec[i];
-      let b = &poly_B_vec[i];
-
-      for (l, polyname) in [(a.len(), "poly_A_vec"), (b.len(), "poly_B_vec")].iter() {
+      assert!(!polys.is_empty());
+      for (j, poly) in polys.iter().enumerate() {
         assert_eq!(
-          *l, expected_size,
-          "Mismatch in size for {} at index {}",
-          polyname, i
+          poly.len(),
+          expected_size,
+          "Mismatch in size for polynomial {} at index {}",
+          j,
+          i
         );
       }
     }
 
     let num_rounds_max = *num_rounds.iter().max().unwrap();
-    let mut e = zip_with!(
+    let mut claim_per_round = zip_with!(
       iter,
       (claims, num_rounds, coeffs),
       |claim, num_rounds, coeff| {
@@ -220,29 +256,34 @@ impl<E: Engine> SumcheckProof<E> {
     )
     .sum();
     let mut r: Vec<E::Scalar> = Vec::new();
-    let mut quad_polys: Vec<CompressedUniPoly<E::Scalar>> = Vec::new();
+    let mut compressed_polys: Vec<CompressedUniPoly<E::Scalar>> = Vec::new();
 
     for current_round in 0..num_rounds_max {
       let remaining_rounds = num_rounds_max - current_round;
-      let evals: Vec<(E::Scalar, E::Scalar)> = zip_with!(
+      let evals: Vec<Vec<E::Scalar>> = zip_with!(
         par_iter,
-        (num_rounds, claims, poly_A_vec, poly_B_vec),
-        |num_rounds, claim, poly_A, poly_B| {
+        (num_rounds, claims, polys),
+        |num_rounds, claim, polys| {
           if remaining_rounds <= *num_rounds {
-            Self::compute_eval_points_quad(poly_A, poly_B, &comb_func)
+            let polys = polys.iter().collect::<Vec<_>>();
+            Self::compute_eval_points(&polys, degree, &comb_func)
           } else {
+            // the claim does not depend on the padding variables
             let remaining_variables = remaining_rounds - num_rounds - 1;
             let scaled_claim = E::Scalar::from((1 << remaining_variables) as u64) * claim;
-            (scaled_claim, scaled_claim)
+            vec![scaled_claim; degree]
           }
         }
       )
       .collect();
 
-      let evals_combined_0 = (0..evals.len()).map(|i| evals[i].0 * coeffs[i]).sum();
-      let evals_combined_2 = (0..evals.len()).map(|i| evals[i].1 * coeffs[i]).sum();
+      // evaluations at 0, 2, 3, …, degree of the combined polynomial
+      let evals_combined = (0..degree)
+        .map(|k| zip_with!(iter, (evals, coeffs), |e, coeff| e[k] * coeff).sum::<E::Scalar>())
+        .collect::<Vec<_>>();
 
-      let evals = vec![evals_combined_0, e - evals_combined_0, evals_combined_2];
+      let mut evals = vec![evals_combined[0], claim_per_round - evals_combined[0]];
+      evals.extend_from_slice(&evals_combined[1..]);
       let poly = UniPoly::from_evals(&evals);
 
       // append the prover's message to the transcript
@@ -252,361 +293,155 @@ impl<E: Engine> SumcheckProof<E> {
       let r_i = transcript.squeeze(b"c")?;
       r.push(r_i);
 
-      // bound all tables to the verifier's challenge
+      // bind all tables to the verifier's challenge
       zip_with_for_each!(
-        (
-          num_rounds.par_iter(),
-          poly_A_vec.par_iter_mut(),
-          poly_B_vec.par_iter_mut()
-        ),
-        |num_rounds, poly_A, poly_B| {
+        (num_rounds.par_iter(), polys.par_iter_mut()),
+        |num_rounds, polys| {
           if remaining_rounds <= *num_rounds {
-            let _ = rayon::join(
-              || poly_A.bind_poly_var_top(&r_i),
-              || poly_B.bind_poly_var_top(&r_i),
-            );
+            polys
+              .par_iter_mut()
+              .for_each(|poly| poly.bind_poly_var_top(&r_i));
           }
         }
       );
 
-      e = poly.evaluate(&r_i);
-      quad_polys.push(poly.compress());
+      claim_per_round = poly.evaluate(&r_i);
+      compressed_polys.push(poly.compress());
     }
-    poly_A_vec.iter().for_each(|p| assert_eq!(p.len(), 1));
-    poly_B_vec.iter().for_each(|p| assert_eq!(p.len(), 1));
 
-    let poly_A_final = poly_A_vec
-      .into_iter()
-      .map(|poly| poly[0])
-      .collect::<Vec<_>>();
-    let poly_B_final = poly_B_vec
+    let evals = polys
       .into_iter()
-      .map(|poly| poly[0])
+      .map(|polys| polys.into_iter().map(|poly| poly[0]).collect::<Vec<_>>())
       .collect::<Vec<_>>();
 
-    let eval_expected = zip_with!(
-      iter,
-      (poly_A_final, poly_B_final, coeffs),
-      |eA, eB, coeff| comb_func(eA, eB) * coeff
-    )
-    .sum::<E::Scalar>();
-    assert_eq!(e, eval_expected);
-
-    let claims_prod = (poly_A_final, poly_B_final);
+    debug_assert_eq!(
+      claim_per_round,
+      zip_with!(iter, (evals, coeffs), |e, coeff| comb_func(e) * coeff).sum::<E::Scalar>()
+    );
 
-    Ok((SumcheckProof::new(quad_polys), r, claims_prod))
+    Ok((Self::new(compressed_polys), r, evals))
   }
+}
 
-  #[inline]
-  pub(in crate::spartan) fn compute_eval_points_cubic<F>(
-    poly_A: &MultilinearPolynomial<E::Scalar>,
-    poly_B: &MultilinearPolynomial<E::Scalar>,
-    poly_C: &MultilinearPolynomial<E::Scalar>,
-    comb_func: &F,
-  ) -> (E::Scalar, E::Scalar, E::Scalar)
-  where
-    F: Fn(&E::Scalar, &E::Scalar, &E::Scalar) -> E::Scalar + Sync,
-  {
-    let len = poly_A.len() / 2;
-    (0..len)
-      .into_par_iter()
-      .map(|i| {
-        // eval 0: bound_func is A(low)
-        let eval_point_0 = comb_func(&poly_A[i], &poly_B[i], &poly_C[i]);
-
-        // eval 2: bound_func is -A(low) + 2*A(high)
-        let poly_A_bound_point = poly_A[len + i] + poly_A[len + i] - poly_A[i];
-        let poly_B_bound_point = poly_B[len + i] + poly_B[len + i] - poly_B[i];
-        let poly_C_bound_point = poly_C[len + i] + poly_C[len + i] - poly_C[i];
-        let eval_point_2 = comb_func(
-          &poly_A_bound_point,
-          &poly_B_bound_point,
-          &poly_C_bound_point,
-        );
-
-        // eval 3: bound_func is -2A(low) + 3A(high); computed incrementally with bound_func applied to eval(2)
-        let poly_A_bound_point = poly_A_bound_point + poly_A[len + i] - poly_A[i];
-        let poly_B_bound_point = poly_B_bound_point + poly_B[len + i] - poly_B[i];
-        let poly_C_bound_point = poly_C_bound_point + poly_C[len + i] - poly_C[i];
-        let eval_point_3 = comb_func(
-          &poly_A_bound_point,
-          &poly_B_bound_point,
-          &poly_C_bound_point,
-        );
-        (eval_point_0, eval_point_2, eval_point_3)
-      })
-      .reduce(
-        || (E::Scalar::ZERO, E::Scalar::ZERO, E::Scalar::ZERO),
-        |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2),
-      )
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::provider::{Bn256Engine, PallasEngine, Secp256k1Engine};
+  use ff::PrimeField;
+  use rand_chacha::ChaCha20Rng;
+  use rand_core::SeedableRng;
+
+  // A ⋅ B ⋅ C ⋅ (A + D), of degree 4
+  fn comb_func<F: Field>(comps: &[F]) -> F {
+    comps[0] * comps[1] * comps[2] * (comps[0] + comps[3])
   }
 
-  #[inline]
-  pub(in crate::spartan) fn compute_eval_points_cubic_with_additive_term<F>(
-    poly_A: &MultilinearPolynomial<E::Scalar>,
-    poly_B: &MultilinearPolynomial<E::Scalar>,
-    poly_C: &MultilinearPolynomial<E::Scalar>,
-    poly_D: &MultilinearPolynomial<E::Scalar>,
-    comb_func: &F,
-  ) -> (E::Scalar, E::Scalar, E::Scalar)
-  where
-    F: Fn(&E::Scalar, &E::Scalar, &E::Scalar, &E::Scalar) -> E::Scalar + Sync,
-  {
-    let len = poly_A.len() / 2;
-    (0..len)
-      .into_par_iter()
-      .map(|i| {
-        // eval 0: bound_func is A(low)
-        let eval_point_0 = comb_func(&poly_A[i], &poly_B[i], &poly_C[i], &poly_D[i]);
-
-        // eval 2: bound_func is -A(low) + 2*A(high)
-        let poly_A_bound_point = poly_A[len + i] + poly_A[len + i] - poly_A[i];
-        let poly_B_bound_point = poly_B[len + i] + poly_B[len + i] - poly_B[i];
-        let poly_C_bound_point = poly_C[len + i] + poly_C[len + i] - poly_C[i];
-        let poly_D_bound_point = poly_D[len + i] + poly_D[len + i] - poly_D[i];
-        let eval_point_2 = comb_func(
-          &poly_A_bound_point,
-          &poly_B_bound_point,
-          &poly_C_bound_point,
-          &poly_D_bound_point,
-        );
-
-        // eval 3: bound_func is -2A(low) + 3A(high); computed incrementally with bound_func applied to eval(2)
-        let poly_A_bound_point = poly_A_bound_point + poly_A[len + i] - poly_A[i];
-        let poly_B_bound_point = poly_B_bound_point + poly_B[len + i] - poly_B[i];
-        let poly_C_bound_point = poly_C_bound_point + poly_C[len + i] - poly_C[i];
-        let poly_D_bound_point = poly_D_bound_point + poly_D[len + i] - poly_D[i];
-        let eval_point_3 = comb_func(
-          &poly_A_bound_point,
-          &poly_B_bound_point,
-          &poly_C_bound_point,
-          &poly_D_bound_point,
-        );
-        (eval_point_0, eval_point_2, eval_point_3)
-      })
-      .reduce(
-        || (E::Scalar::ZERO, E::Scalar::ZERO, E::Scalar::ZERO),
-        |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2),
-      )
+  fn random_polys<F: PrimeField>(
+    num_vars: usize,
+    rng: &mut ChaCha20Rng,
+  ) -> Vec<MultilinearPolynomial<F>> {
+    (0..4)
+      .map(|_| MultilinearPolynomial::random(num_vars, rng))
+      .collect()
   }
 
-  pub fn prove_cubic_with_additive_term<F>(
-    claim: &E::Scalar,
-    num_rounds: usize,
-    poly_A: &mut MultilinearPolynomial<E::Scalar>,
-    poly_B: &mut MultilinearPolynomial<E::Scalar>,
-    poly_C: &mut MultilinearPolynomial<E::Scalar>,
-    poly_D: &mut MultilinearPolynomial<E::Scalar>,
-    comb_func: F,
-    transcript: &mut E::TE,
-  ) -> Result<(Self, Vec<E::Scalar>, Vec<E::Scalar>), NovaError>
-  where
-    F: Fn(&E::Scalar, &E::Scalar, &E::Scalar, &E::Scalar) -> E::Scalar + Sync,
-  {
-    let mut r: Vec<E::Scalar> = Vec::new();
-    let mut polys: Vec<CompressedUniPoly<E::Scalar>> = Vec::new();
-    let mut claim_per_round = *claim;
-
-    for _ in 0..num_rounds {
-      let poly = {
-        // Make an iterator returning the contributions to the evaluations
-        let (eval_point_0, eval_point_2, eval_point_3) =
-          Self::compute_eval_points_cubic_with_additive_term(
-            poly_A, poly_B, poly_C, poly_D, &comb_func,
-          );
-
-        let evals = vec![
-          eval_point_0,
-          claim_per_round - eval_point_0,
-          eval_point_2,
-          eval_point_3,
-        ];
-        UniPoly::from_evals(&evals)
-      };
+  fn sum<F: PrimeField>(polys: &[MultilinearPolynomial<F>]) -> F {
+    (0..polys[0].len())
+      .map(|i| comb_func(&polys.iter().map(|poly| poly[i]).collect::<Vec<_>>()))
+      .sum()
+  }
 
-      // append the prover's message to the transcript
-      transcript.absorb(b"p", &poly);
+  fn test_sumcheck_with<E: Engine>() {
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+    let num_rounds = 5;
+    let polys = random_polys::<E::Scalar>(num_rounds, &mut rng);
+    let claim = sum(&polys);
+
+    let mut transcript = E::TE::new(b"test");
+    let (proof, r, evals) = SumcheckProof::<E>::prove(
+      &claim,
+      num_rounds,
+      polys.clone(),
+      4,
+      comb_func::<E::Scalar>,
+      &mut transcript,
+    )
+    .unwrap();
 
-      //derive the verifier's challenge for the next round
-      let r_i = transcript.squeeze(b"c")?;
-      r.push(r_i);
-      polys.push(poly.compress());
+    for (poly, eval) in polys.iter().zip_eq(evals.iter()) {
+      assert_eq!(poly.evaluate(&r), *eval);
+    }
 
-      // Set up next round
-      claim_per_round = poly.evaluate(&r_i);
+    let mut transcript = E::TE::new(b"test");
+    let (claim_final, r_verifier) = proof.verify(claim, num_rounds, 4, &mut transcript).unwrap();
+    assert_eq!(r_verifier, r);
+    assert_eq!(claim_final, comb_func(&evals));
 
-      // bound all tables to the verifier's challenge
-      rayon::join(
-        || {
-          rayon::join(
-            || poly_A.bind_poly_var_top(&r_i),
-            || poly_B.bind_poly_var_top(&r_i),
-          )
-        },
-        || {
-          rayon::join(
-            || poly_C.bind_poly_var_top(&r_i),
-            || poly_D.bind_poly_var_top(&r_i),
-          )
-        },
-      );
-    }
+    // the proof does not verify for another claim or degree
+    let mut transcript = E::TE::new(b"test");
+    let (claim_final, _) = proof
+      .verify(claim + E::Scalar::ONE, num_rounds, 4, &mut transcript)
+      .unwrap();
+    assert_ne!(claim_final, comb_func(&evals));
 
-    Ok((
-      SumcheckProof {
-        compressed_polys: polys,
-      },
-      r,
-      vec![poly_A[0], poly_B[0], poly_C[0], poly_D[0]],
-    ))
+    let mut transcript = E::TE::new(b"test");
+    assert!(proof.verify(claim, num_rounds, 3, &mut transcript).is_err());
   }
 
-  pub fn prove_cubic_with_additive_term_batch<F>(
-    claims: &[E::Scalar],
-    num_rounds: &[usize],
-    mut poly_A_vec: Vec<MultilinearPolynomial<E::Scalar>>,
-    mut poly_B_vec: Vec<MultilinearPolynomial<E::Scalar>>,
-    mut poly_C_vec: Vec<MultilinearPolynomial<E::Scalar>>,
-    mut poly_D_vec: Vec<MultilinearPolynomial<E::Scalar>>,
-    coeffs: &[E::Scalar],
-    comb_func: F,
-    transcript: &mut E::TE,
-  ) -> Result<(Self, Vec<E::Scalar>, Vec<Vec<E::Scalar>>), NovaError>
-  where
-    F: Fn(&E::Scalar, &E::Scalar, &E::Scalar, &E::Scalar) -> E::Scalar + Sync,
-  {
-    let num_instances = claims.len();
-    assert_eq!(num_rounds.len(), num_instances);
-    assert_eq!(coeffs.len(), num_instances);
-    assert_eq!(poly_A_vec.len(), num_instances);
-    assert_eq!(poly_B_vec.len(), num_instances);
-    assert_eq!(poly_C_vec.len(), num_instances);
-    assert_eq!(poly_D_vec.len(), num_instances);
-
-    for (i, &num_rounds) in num_rounds.iter().enumerate() {
-      let expected_size = 1 << num_rounds;
+  #[test]
+  fn test_sumcheck() {
+    test_sumcheck_with::<PallasEngine>();
+    test_sumcheck_with::<Bn256Engine>();
+    test_sumcheck_with::<Secp256k1Engine>();
+  }
 
-      // Direct indexing with the assumption that the index will always be in bounds
-      let a = &poly_A_vec[i];
-      let b = &poly_B_vec[i];
-      let c = &poly_C_vec[i];
-      let d = &poly_D_vec[i];
-
-      for (l, polyname) in [
-        (a.len(), "poly_A"),
-        (b.len(), "poly_B"),
-        (c.len(), "poly_C"),
-        (d.len(), "poly_D"),
-      ]
+  fn test_sumcheck_batch_with<E: Engine>() {
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+    let num_rounds = [3, 5, 1];
+    let polys = num_rounds
       .iter()
-      {
-        assert_eq!(
-          *l, expected_size,
-          "Mismatch in size for {} at index {}",
-          polyname, i
-        );
-      }
-    }
-
-    let num_rounds_max = *num_rounds.iter().max().unwrap();
+      .map(|&num_vars| random_polys::<E::Scalar>(num_vars, &mut rng))
+      .collect::<Vec<_>>();
+    let claims = polys.iter().map(|polys| sum(polys)).collect::<Vec<_>>();
+    let coeffs = (0..num_rounds.len())
+      .map(|_| E::Scalar::random(&mut rng))
+      .collect::<Vec<_>>();
 
-    let mut r: Vec<E::Scalar> = Vec::new();
-    let mut polys: Vec<CompressedUniPoly<E::Scalar>> = Vec::new();
-    let mut claim_per_round = zip_with!(
-      iter,
-      (claims, num_rounds, coeffs),
-      |claim, num_rounds, coeff| {
-        let scaled_claim = E::Scalar::from((1 << (num_rounds_max - num_rounds)) as u64) * claim;
-        scaled_claim * *coeff
-      }
+    let mut transcript = E::TE::new(b"test");
+    let (proof, r, evals) = SumcheckProof::<E>::prove_batch(
+      &claims,
+      &num_rounds,
+      polys.clone(),
+      &coeffs,
+      4,
+      comb_func::<E::Scalar>,
+      &mut transcript,
     )
-    .sum();
-
-    for current_round in 0..num_rounds_max {
-      let remaining_rounds = num_rounds_max - current_round;
-      let evals: Vec<(E::Scalar, E::Scalar, E::Scalar)> = zip_with!(
-        par_iter,
-        (num_rounds, claims, poly_A_vec, poly_B_vec, poly_C_vec, poly_D_vec),
-        |num_rounds, claim, poly_A, poly_B, poly_C, poly_D| {
-          if remaining_rounds <= *num_rounds {
-            Self::compute_eval_points_cubic_with_additive_term(
-              poly_A, poly_B, poly_C, poly_D, &comb_func,
-            )
-          } else {
-            let remaining_variables = remaining_rounds - num_rounds - 1;
-            let scaled_claim = E::Scalar::from((1 << remaining_variables) as u64) * claim;
-            (scaled_claim, scaled_claim, scaled_claim)
-          }
-        }
-      )
-      .collect();
-
-      let evals_combined_0 = (0..num_instances).map(|i| evals[i].0 * coeffs[i]).sum();
-      let evals_combined_2 = (0..num_instances).map(|i| evals[i].1 * coeffs[i]).sum();
-      let evals_combined_3 = (0..num_instances).map(|i| evals[i].2 * coeffs[i]).sum();
-
-      let evals = vec![
-        evals_combined_0,
-        claim_per_round - evals_combined_0,
-        evals_combined_2,
-        evals_combined_3,
-      ];
-      let poly = UniPoly::from_evals(&evals);
-
-      // append the prover's message to the transcript
-      transcript.absorb(b"p", &poly);
-
-      //derive the verifier's challenge for the next round
-      let r_i = transcript.squeeze(b"c")?;
-      r.push(r_i);
-
-      polys.push(poly.compress());
-
-      // Set up next round
-      claim_per_round = poly.evaluate(&r_i);
-
-      // bound all the tables to the verifier's challenge
-
-      zip_with_for_each!(
-        (
-          num_rounds.par_iter(),
-          poly_A_vec.par_iter_mut(),
-          poly_B_vec.par_iter_mut(),
-          poly_C_vec.par_iter_mut(),
-          poly_D_vec.par_iter_mut()
-        ),
-        |num_rounds, poly_A, poly_B, poly_C, poly_D| {
-          if remaining_rounds <= *num_rounds {
-            let _ = rayon::join(
-              || {
-                rayon::join(
-                  || poly_A.bind_poly_var_top(&r_i),
-                  || poly_B.bind_poly_var_top(&r_i),
-                )
-              },
-              || {
-                rayon::join(
-                  || poly_C.bind_poly_var_top(&r_i),
-                  || poly_D.bind_poly_var_top(&r_i),
-                )
-              },
-            );
-          }
-        }
-      );
+    .unwrap();
+
+    // each claim is reduced to the last entries of r
+    let num_rounds_max = 5;
+    for (polys, (evals, num_rounds)) in polys.iter().zip_eq(evals.iter().zip_eq(num_rounds)) {
+      let r = &r[num_rounds_max - num_rounds..];
+      for (poly, eval) in polys.iter().zip_eq(evals.iter()) {
+        assert_eq!(poly.evaluate(r), *eval);
+      }
     }
 
-    let poly_A_final = poly_A_vec.into_iter().map(|poly| poly[0]).collect();
-    let poly_B_final = poly_B_vec.into_iter().map(|poly| poly[0]).collect();
-    let poly_C_final = poly_C_vec.into_iter().map(|poly| poly[0]).collect();
-    let poly_D_final = poly_D_vec.into_iter().map(|poly| poly[0]).collect();
-
-    Ok((
-      SumcheckProof {
-        compressed_polys: polys,
-      },
-      r,
-      vec![poly_A_final, poly_B_final, poly_C_final, poly_D_final],
-    ))
+    let mut transcript = E::TE::new(b"test");
+    let (claim_final, r_verifier) = proof
+      .verify_batch(&claims, &num_rounds, &coeffs, 4, &mut transcript)
+      .unwrap();
+    assert_eq!(r_verifier, r);
+    let claim_final_expected =
+      zip_with!(iter, (evals, coeffs), |e, coeff| comb_func(e) * coeff).sum::<E::Scalar>();
+    assert_eq!(claim_final, claim_final_expected);
+  }
+
+  #[test]
+  fn test_sumcheck_batch() {
+    test_sumcheck_batch_with::<PallasEngine>();
+    test_sumcheck_batch_with::<Bn256Engine>();
+    test_sumcheck_batch_with::<Secp256k1Engine>();
   }
 }
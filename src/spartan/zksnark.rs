@@ -0,0 +1,568 @@
+//! This module implements `RelaxedR1CSSNARKTrait` using a zero-knowledge variant of the Spartan
+//! SNARK in snark.rs, which is specialized to Pedersen commitments and the IPA.
+//!
+//! Before running the sum-checks, the prover masks its witness by folding the instance with a
+//! uniformly random relaxed R1CS instance, whose commitments are hiding, as is the commitment to
+//! the cross-term. The witness and the error vector of the folded instance are independent of the
+//! prover's witness, so the sum-check polynomials and the claimed evaluations, which are computed
+//! over them, reveal nothing about it. The evaluations of the (hiding) commitments to the folded
+//! witness and error vector are finally proven with `HidingInnerProductArgument`.
+use crate::{
+  digest::{DigestComputer, SimpleDigestible},
+  errors::NovaError,
+  provider::{
+    ipa_pc::{self, HidingInnerProductArgument},
+    pedersen::CommitmentEngine,
+    traits::DlogGroup,
+  },
+  r1cs::{R1CSShape, RelaxedR1CSBlinds, RelaxedR1CSInstance, RelaxedR1CSWitness, SparseMatrix},
+  spartan::{
+    compute_eval_table_sparse,
+    polys::{eq::EqPolynomial, multilinear::MultilinearPolynomial, multilinear::SparsePolynomial},
+    sumcheck::SumcheckProof,
+  },
+  traits::{
+    commitment::HidingCommitmentEngineTrait,
+    evaluation::EvaluationEngineTrait,
+    snark::{DigestHelperTrait, RelaxedR1CSSNARKTrait},
+    Engine, TranscriptEngineTrait,
+  },
+  Commitment, CommitmentKey,
+};
+
+use abomonation::Abomonation;
+use abomonation_derive::Abomonation;
+use ff::Field;
+use once_cell::sync::OnceCell;
+use rand::rngs::OsRng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A type that represents the prover's key
+#[derive(Clone, Serialize, Deserialize, Abomonation)]
+#[serde(bound = "")]
+#[abomonation_bounds(where <E::Scalar as ff::PrimeField>::Repr: Abomonation)]
+pub struct ProverKey<E: Engine> {
+  pk_ee: ipa_pc::ProverKey<E>,
+  #[abomonate_with(<E::Scalar as ff::PrimeField>::Repr)]
+  vk_digest: E::Scalar, // digest of the verifier's key
+}
+
+/// A type that represents the verifier's key
+#[derive(Clone, Serialize, Deserialize, Abomonation)]
+#[serde(bound = "")]
+#[abomonation_bounds(where <E::Scalar as ff::PrimeField>::Repr: Abomonation)]
+pub struct VerifierKey<E: Engine> {
+  vk_ee: ipa_pc::VerifierKey<E>,
+  S: R1CSShape<E>,
+  #[abomonation_skip]
+  #[serde(skip, default = "OnceCell::new")]
+  digest: OnceCell<E::Scalar>,
+}
+
+impl<E: Engine> SimpleDigestible for VerifierKey<E> {}
+
+impl<E: Engine> VerifierKey<E> {
+  fn new(shape: R1CSShape<E>, vk_ee: ipa_pc::VerifierKey<E>) -> Self {
+    VerifierKey {
+      vk_ee,
+      S: shape,
+      digest: OnceCell::new(),
+    }
+  }
+}
+
+impl<E: Engine> DigestHelperTrait<E> for VerifierKey<E> {
+  /// Returns the digest of the verifier's key.
+  fn digest(&self) -> E::Scalar {
+    self
+      .digest
+      .get_or_try_init(|| {
+        let dc = DigestComputer::<E::Scalar, _>::new(self);
+        dc.digest()
+      })
+      .cloned()
+      .expect("Failure to retrieve digest!")
+  }
+}
+
+/// A zero-knowledge succinct proof of knowledge of a witness to a relaxed R1CS instance
+/// The proof is produced using Spartan's combination of the sum-check and the hiding
+/// inner product argument, over a witness masked with a random relaxed R1CS instance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RelaxedR1CSSNARK<E: Engine> {
+  U_r: RelaxedR1CSInstance<E>,
+  comm_T: Commitment<E>,
+  sc_proof_outer: SumcheckProof<E>,
+  claims_outer: (E::Scalar, E::Scalar, E::Scalar),
+  eval_E: E::Scalar,
+  sc_proof_inner: SumcheckProof<E>,
+  eval_W: E::Scalar,
+  eval_arg_W: HidingInnerProductArgument<E>,
+  eval_arg_E: HidingInnerProductArgument<E>,
+}
+
+impl<E> RelaxedR1CSSNARKTrait<E> for RelaxedR1CSSNARK<E>
+where
+  E: Engine<CE = CommitmentEngine<E>>,
+  E::GE: DlogGroup,
+  <E::Scalar as ff::PrimeField>::Repr: Abomonation,
+{
+  type ProverKey = ProverKey<E>;
+  type VerifierKey = VerifierKey<E>;
+
+  fn setup(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+  ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
+    // the lookup argument reveals the looked-up entries of the witness
+    if !S.lookups.is_empty() {
+      return Err(NovaError::UnsupportedLookups);
+    }
+
+    let (pk_ee, vk_ee) = ipa_pc::EvaluationEngine::<E>::setup(ck);
+
+    let S = S.pad();
+
+    let vk: VerifierKey<E> = VerifierKey::new(S, vk_ee);
+
+    let pk = ProverKey {
+      pk_ee,
+      vk_digest: vk.digest(),
+    };
+
+    Ok((pk, vk))
+  }
+
+  /// produces a zero-knowledge succinct proof of satisfiability of a `RelaxedR1CS` instance
+  #[tracing::instrument(skip_all, name = "ZKSNARK::prove")]
+  fn prove(
+    ck: &CommitmentKey<E>,
+    pk: &Self::ProverKey,
+    S: &R1CSShape<E>,
+    U: &RelaxedR1CSInstance<E>,
+    W: &RelaxedR1CSWitness<E>,
+  ) -> Result<Self, NovaError> {
+    // pad the R1CSShape
+    let S = S.pad();
+    // sanity check that R1CSShape has all required size characteristics
+    assert!(S.is_regular_shape());
+
+    let W = W.pad(&S); // pad the witness
+    let mut transcript = E::TE::new(b"ZKRelaxedR1CSSNARK");
+
+    // append the digest of vk (which includes R1CS matrices) and the RelaxedR1CSInstance to the transcript
+    transcript.absorb(b"vk", &pk.vk_digest);
+    transcript.absorb(b"U", U);
+
+    // mask the witness by folding the instance with a random one, keeping track of the blinds
+    // of the hiding commitments in the folded instance, as those of `U` are zero
+    let (U_r, W_r, blinds_r) = sample_random_instance_witness(ck, &S)?;
+    let T = S.compute_T_relaxed(U, &W, &U_r, &W_r)?;
+    let blind_T = E::Scalar::random(OsRng);
+    let comm_T = CommitmentEngine::<E>::commit_with_blind(ck, &T, &blind_T);
+
+    transcript.absorb(b"U_r", &U_r);
+    transcript.absorb(b"T", &comm_T);
+    let r_fold = transcript.squeeze(b"f")?;
+
+    let U = U.fold_relaxed(&U_r, &comm_T, &r_fold);
+    let W = W.fold_relaxed(&W_r, &T, &r_fold)?;
+    let blinds = RelaxedR1CSBlinds::default().fold_relaxed(&blinds_r, &blind_T, &r_fold);
+
+    // compute the full satisfying assignment by concatenating W.W, U.u, and U.X
+    let mut z = [W.W.clone(), vec![U.u], U.X.clone()].concat();
+
+    let (num_rounds_x, num_rounds_y) = (
+      usize::try_from(S.num_cons.ilog2()).unwrap(),
+      (usize::try_from(S.num_vars.ilog2()).unwrap() + 1),
+    );
+
+    // outer sum-check
+    let tau = (0..num_rounds_x)
+      .map(|_i| transcript.squeeze(b"t"))
+      .collect::<Result<EqPolynomial<_>, NovaError>>()?;
+
+    let poly_tau = MultilinearPolynomial::new(tau.evals());
+    let (poly_Az, poly_Bz, poly_Cz, poly_uCz_E) = {
+      let (poly_Az, poly_Bz, poly_Cz) = S.multiply_vec(&z)?;
+      let poly_uCz_E = (0..S.num_cons)
+        .map(|i| U.u * poly_Cz[i] + W.E[i])
+        .collect::<Vec<E::Scalar>>();
+      (
+        MultilinearPolynomial::new(poly_Az),
+        MultilinearPolynomial::new(poly_Bz),
+        MultilinearPolynomial::new(poly_Cz),
+        MultilinearPolynomial::new(poly_uCz_E),
+      )
+    };
+
+    // tau * (Az * Bz - uCz_E)
+    let comb_func_outer =
+      |comps: &[E::Scalar]| -> E::Scalar { comps[0] * (comps[1] * comps[2] - comps[3]) };
+    let (sc_proof_outer, r_x, claims_outer) = SumcheckProof::prove(
+      &E::Scalar::ZERO, // claim is zero
+      num_rounds_x,
+      vec![poly_tau, poly_Az, poly_Bz, poly_uCz_E],
+      3,
+      comb_func_outer,
+      &mut transcript,
+    )?;
+
+    // claims from the end of sum-check
+    let (claim_Az, claim_Bz): (E::Scalar, E::Scalar) = (claims_outer[1], claims_outer[2]);
+    let claim_Cz = poly_Cz.evaluate(&r_x);
+    let eval_E = MultilinearPolynomial::new(W.E.clone()).evaluate(&r_x);
+    transcript.absorb(
+      b"claims_outer",
+      &[claim_Az, claim_Bz, claim_Cz, eval_E].as_slice(),
+    );
+
+    // inner sum-check
+    let r = transcript.squeeze(b"r")?;
+    let claim_inner_joint = claim_Az + r * claim_Bz + r * r * claim_Cz;
+
+    let poly_ABC = {
+      // compute the initial evaluation table for R(\tau, x)
+      let evals_rx = EqPolynomial::evals_from_points(&r_x.clone());
+
+      let (evals_A, evals_B, evals_C) = compute_eval_table_sparse(&S, &evals_rx);
+
+      assert_eq!(evals_A.len(), evals_B.len());
+      assert_eq!(evals_A.len(), evals_C.len());
+      (0..evals_A.len())
+        .into_par_iter()
+        .map(|i| evals_A[i] + r * evals_B[i] + r * r * evals_C[i])
+        .collect::<Vec<E::Scalar>>()
+    };
+
+    let poly_z = {
+      z.resize(S.num_vars * 2, E::Scalar::ZERO);
+      z
+    };
+
+    let comb_func = |comps: &[E::Scalar]| -> E::Scalar { comps[0] * comps[1] };
+    let (sc_proof_inner, r_y, _claims_inner) = SumcheckProof::prove(
+      &claim_inner_joint,
+      num_rounds_y,
+      vec![
+        MultilinearPolynomial::new(poly_ABC),
+        MultilinearPolynomial::new(poly_z),
+      ],
+      2,
+      comb_func,
+      &mut transcript,
+    )?;
+
+    // prove the claims about the W and E polynomials under their hiding commitments
+    let eval_W = MultilinearPolynomial::evaluate_with(&W.W, &r_y[1..]);
+    let eval_arg_W = HidingInnerProductArgument::prove(
+      ck,
+      &pk.pk_ee,
+      &mut transcript,
+      &U.comm_W,
+      &W.W,
+      &blinds.r_W,
+      &r_y[1..],
+      &eval_W,
+    )?;
+    let eval_arg_E = HidingInnerProductArgument::prove(
+      ck,
+      &pk.pk_ee,
+      &mut transcript,
+      &U.comm_E,
+      &W.E,
+      &blinds.r_E,
+      &r_x,
+      &eval_E,
+    )?;
+
+    Ok(RelaxedR1CSSNARK {
+      U_r,
+      comm_T,
+      sc_proof_outer,
+      claims_outer: (claim_Az, claim_Bz, claim_Cz),
+      eval_E,
+      sc_proof_inner,
+      eval_W,
+      eval_arg_W,
+      eval_arg_E,
+    })
+  }
+
+  /// verifies a proof of satisfiability of a `RelaxedR1CS` instance
+  fn verify(&self, vk: &Self::VerifierKey, U: &RelaxedR1CSInstance<E>) -> Result<(), NovaError> {
+    let mut transcript = E::TE::new(b"ZKRelaxedR1CSSNARK");
+
+    // append the digest of R1CS matrices and the RelaxedR1CSInstance to the transcript
+    transcript.absorb(b"vk", &vk.digest());
+    transcript.absorb(b"U", U);
+
+    // fold the instance with the random one
+    if self.U_r.X.len() != U.X.len() {
+      return Err(NovaError::InvalidInputLength);
+    }
+    transcript.absorb(b"U_r", &self.U_r);
+    transcript.absorb(b"T", &self.comm_T);
+    let r_fold = transcript.squeeze(b"f")?;
+
+    let U = U.fold_relaxed(&self.U_r, &self.comm_T, &r_fold);
+
+    let (num_rounds_x, num_rounds_y) = (
+      usize::try_from(vk.S.num_cons.ilog2()).unwrap(),
+      (usize::try_from(vk.S.num_vars.ilog2()).unwrap() + 1),
+    );
+
+    // outer sum-check
+    let tau = (0..num_rounds_x)
+      .map(|_i| transcript.squeeze(b"t"))
+      .collect::<Result<EqPolynomial<_>, NovaError>>()?;
+
+    let (claim_outer_final, r_x) =
+      self
+        .sc_proof_outer
+        .verify(E::Scalar::ZERO, num_rounds_x, 3, &mut transcript)?;
+
+    // verify claim_outer_final
+    let (claim_Az, claim_Bz, claim_Cz) = self.claims_outer;
+    let taus_bound_rx = tau.evaluate(&r_x);
+    let claim_outer_final_expected =
+      taus_bound_rx * (claim_Az * claim_Bz - U.u * claim_Cz - self.eval_E);
+    if claim_outer_final != claim_outer_final_expected {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    transcript.absorb(
+      b"claims_outer",
+      &[
+        self.claims_outer.0,
+        self.claims_outer.1,
+        self.claims_outer.2,
+        self.eval_E,
+      ]
+      .as_slice(),
+    );
+
+    // inner sum-check
+    let r = transcript.squeeze(b"r")?;
+    let claim_inner_joint =
+      self.claims_outer.0 + r * self.claims_outer.1 + r * r * self.claims_outer.2;
+
+    let (claim_inner_final, r_y) =
+      self
+        .sc_proof_inner
+        .verify(claim_inner_joint, num_rounds_y, 2, &mut transcript)?;
+
+    // verify claim_inner_final
+    let eval_Z = {
+      let eval_X = {
+        // constant term
+        let mut poly_X = vec![(0, U.u)];
+        //remaining inputs
+        poly_X.extend(
+          (0..U.X.len())
+            .map(|i| (i + 1, U.X[i]))
+            .collect::<Vec<(usize, E::Scalar)>>(),
+        );
+        SparsePolynomial::new(usize::try_from(vk.S.num_vars.ilog2()).unwrap(), poly_X)
+          .evaluate(&r_y[1..])
+      };
+      (E::Scalar::ONE - r_y[0]) * self.eval_W + r_y[0] * eval_X
+    };
+
+    // compute evaluations of R1CS matrices
+    let multi_evaluate = |M_vec: &[&SparseMatrix<E::Scalar>],
+                          r_x: &[E::Scalar],
+                          r_y: &[E::Scalar]|
+     -> Vec<E::Scalar> {
+      let evaluate_with_table =
+        |M: &SparseMatrix<E::Scalar>, T_x: &[E::Scalar], T_y: &[E::Scalar]| -> E::Scalar {
+          M.indptr
+            .par_windows(2)
+            .enumerate()
+            .map(|(row_idx, ptrs)| {
+              M.get_row_unchecked(ptrs.try_into().unwrap())
+                .map(|(val, col_idx)| T_x[row_idx] * T_y[*col_idx] * val)
+                .sum::<E::Scalar>()
+            })
+            .sum()
+        };
+
+      let (T_x, T_y) = rayon::join(
+        || EqPolynomial::evals_from_points(r_x),
+        || EqPolynomial::evals_from_points(r_y),
+      );
+
+      (0..M_vec.len())
+        .into_par_iter()
+        .map(|i| evaluate_with_table(M_vec[i], &T_x, &T_y))
+        .collect()
+    };
+
+    let evals = multi_evaluate(&[&vk.S.A, &vk.S.B, &vk.S.C], &r_x, &r_y);
+
+    let claim_inner_final_expected = (evals[0] + r * evals[1] + r * r * evals[2]) * eval_Z;
+    if claim_inner_final != claim_inner_final_expected {
+      return Err(NovaError::InvalidSumcheckProof);
+    }
+
+    // verify the claims about the W and E polynomials
+    self.eval_arg_W.verify(
+      &vk.vk_ee,
+      &mut transcript,
+      &U.comm_W,
+      &r_y[1..],
+      &self.eval_W,
+    )?;
+    self
+      .eval_arg_E
+      .verify(&vk.vk_ee, &mut transcript, &U.comm_E, &r_x, &self.eval_E)?;
+
+    Ok(())
+  }
+}
+
+// Samples a uniformly random satisfying instance-witness pair for the padded shape `S`, whose
+// commitments are hiding, and returns it along with the blinds of these commitments
+fn sample_random_instance_witness<E>(
+  ck: &CommitmentKey<E>,
+  S: &R1CSShape<E>,
+) -> Result<
+  (
+    RelaxedR1CSInstance<E>,
+    RelaxedR1CSWitness<E>,
+    RelaxedR1CSBlinds<E>,
+  ),
+  NovaError,
+>
+where
+  E: Engine<CE = CommitmentEngine<E>>,
+  E::GE: DlogGroup,
+{
+  let W = (0..S.num_vars)
+    .map(|_| E::Scalar::random(OsRng))
+    .collect::<Vec<_>>();
+  let X = (0..S.num_io)
+    .map(|_| E::Scalar::random(OsRng))
+    .collect::<Vec<_>>();
+  let u = E::Scalar::random(OsRng);
+  let blinds = RelaxedR1CSBlinds::new(E::Scalar::random(OsRng), E::Scalar::random(OsRng));
+
+  // the error vector is the one making the random instance satisfying
+  let (AZ, BZ, CZ) = S.multiply_witness(&W, &u, &X)?;
+  let E = (0..S.num_cons)
+    .into_par_iter()
+    .map(|i| AZ[i] * BZ[i] - u * CZ[i])
+    .collect::<Vec<_>>();
+
+  let W = RelaxedR1CSWitness { W, E };
+  let (comm_W, comm_E) = W.commit_hiding(ck, &blinds);
+
+  Ok((
+    RelaxedR1CSInstance {
+      comm_W,
+      comm_E,
+      X,
+      u,
+    },
+    W,
+    blinds,
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    bellpepper::{
+      r1cs::{NovaShape, NovaWitness},
+      shape_cs::ShapeCS,
+      solver::SatisfyingAssignment,
+    },
+    provider::{Bn256Engine, PallasEngine, Secp256k1Engine},
+    traits::snark::default_ck_hint,
+  };
+  use ::bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+  use ff::PrimeField;
+
+  fn synthesize_cubic<Scalar: PrimeField, CS: ConstraintSystem<Scalar>>(
+    cs: &mut CS,
+    x_val: Option<Scalar>,
+  ) -> Result<(), SynthesisError> {
+    // Consider a cubic equation: `x^3 + x + 5 = y`, where `x` and `y` are respectively the input and output.
+    let x = AllocatedNum::alloc_infallible(cs.namespace(|| "x"), || x_val.unwrap());
+    let _ = x.inputize(cs.namespace(|| "x is input"));
+
+    let x_sq = x.square(cs.namespace(|| "x_sq"))?;
+    let x_cu = x_sq.mul(cs.namespace(|| "x_cu"), &x)?;
+    let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+      Ok(x_cu.get_value().unwrap() + x.get_value().unwrap() + Scalar::from(5u64))
+    })?;
+    let _ = y.inputize(cs.namespace(|| "y is output"));
+
+    cs.enforce(
+      || "y = x^3 + x + 5",
+      |lc| {
+        lc + x_cu.get_variable()
+          + x.get_variable()
+          + CS::one()
+          + CS::one()
+          + CS::one()
+          + CS::one()
+          + CS::one()
+      },
+      |lc| lc + CS::one(),
+      |lc| lc + y.get_variable(),
+    );
+
+    Ok(())
+  }
+
+  fn test_zksnark_with<E>()
+  where
+    E: Engine<CE = CommitmentEngine<E>>,
+    E::GE: DlogGroup,
+    <E::Scalar as ff::PrimeField>::Repr: Abomonation,
+  {
+    let mut cs: ShapeCS<E> = ShapeCS::new();
+    let _ = synthesize_cubic(&mut cs, None);
+    let (shape, ck) = cs.r1cs_shape_and_key(&*default_ck_hint());
+
+    let mut cs = SatisfyingAssignment::<E>::new();
+    let _ = synthesize_cubic(&mut cs, Some(E::Scalar::from(5)));
+    let (U, W) = cs.r1cs_instance_and_witness(&shape, &ck).unwrap();
+    let (U, W) = (
+      RelaxedR1CSInstance::from_r1cs_instance_unchecked(&U.comm_W, &U.X),
+      RelaxedR1CSWitness::from_r1cs_witness(&shape, W),
+    );
+
+    let (pk, vk) = RelaxedR1CSSNARK::<E>::setup(&ck, &shape).unwrap();
+
+    let snark = RelaxedR1CSSNARK::prove(&ck, &pk, &shape, &U, &W).unwrap();
+    assert!(snark.verify(&vk, &U).is_ok());
+
+    // a second proof of the same statement is masked with fresh randomness
+    let other_snark = RelaxedR1CSSNARK::prove(&ck, &pk, &shape, &U, &W).unwrap();
+    assert!(other_snark.verify(&vk, &U).is_ok());
+    assert_ne!(snark.U_r.comm_W, other_snark.U_r.comm_W);
+    assert_ne!(snark.eval_W, other_snark.eval_W);
+
+    // the proof does not verify for another instance
+    let mut bad_U = U.clone();
+    bad_U.X[1] += E::Scalar::ONE;
+    assert!(snark.verify(&vk, &bad_U).is_err());
+
+    // nor with a tampered evaluation
+    let mut bad_snark = snark;
+    bad_snark.eval_W += E::Scalar::ONE;
+    assert!(bad_snark.verify(&vk, &U).is_err());
+  }
+
+  #[test]
+  fn test_zksnark() {
+    test_zksnark_with::<PallasEngine>();
+    test_zksnark_with::<Bn256Engine>();
+    test_zksnark_with::<Secp256k1Engine>();
+  }
+}
@@ -45,10 +45,7 @@ use ff::Field;
 use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
 
-use crate::supernova::{
-  num_ro_inputs,
-  utils::{get_from_vec_alloc_relaxed_r1cs, get_selector_vec_from_index},
-};
+use crate::supernova::utils::{get_from_vec_alloc_relaxed_r1cs, get_selector_vec_from_index};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Abomonation)]
 pub struct SuperNovaAugmentedCircuitParams {
@@ -166,6 +163,7 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
       AllocatedR1CSInstance<E>,
       AllocatedPoint<E>,
       Option<AllocatedNum<E::Base>>,
+      AllocatedNum<E::Base>,
       Vec<Boolean>,
     ),
     SynthesisError,
@@ -268,6 +266,7 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
       u,
       T,
       program_counter,
+      last_augmented_circuit_index,
       last_augmented_circuit_selector,
     ))
   }
@@ -330,20 +329,11 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
     U: &[AllocatedRelaxedR1CSInstance<E>],
     u: &AllocatedR1CSInstance<E>,
     T: &AllocatedPoint<E>,
-    arity: usize,
     last_augmented_circuit_selector: &[Boolean],
     program_counter: &Option<AllocatedNum<E::Base>>,
   ) -> Result<(Vec<AllocatedRelaxedR1CSInstance<E>>, AllocatedBit), SynthesisError> {
     // Check that u.x[0] = Hash(params, i, program_counter, z0, zi, U[])
-    let mut ro = E::ROCircuit::new(
-      self.ro_consts.clone(),
-      num_ro_inputs(
-        self.num_augmented_circuits,
-        self.params.get_n_limbs(),
-        arity,
-        self.params.is_primary_circuit,
-      ),
-    );
+    let mut ro = E::ROCircuit::new(self.ro_consts.clone());
     ro.absorb(params);
     ro.absorb(i);
 
@@ -444,12 +434,22 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
     }
 
     // Allocate witnesses
-    let (params, i, z_0, z_i, U, u, T, program_counter, last_augmented_circuit_selector) = self
-      .alloc_witness(
-        cs.namespace(|| "allocate the circuit witness"),
-        arity,
-        num_augmented_circuits,
-      )?;
+    let (
+      params,
+      i,
+      z_0,
+      z_i,
+      U,
+      u,
+      T,
+      program_counter,
+      last_augmented_circuit_index,
+      last_augmented_circuit_selector,
+    ) = self.alloc_witness(
+      cs.namespace(|| "allocate the circuit witness"),
+      arity,
+      num_augmented_circuits,
+    )?;
 
     // Compute variable indicating if this is the base case
     let zero = alloc_zero(cs.namespace(|| "zero"));
@@ -467,7 +467,6 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
       &U,
       &u,
       &T,
-      arity,
       &last_augmented_circuit_selector,
       &program_counter,
     )?;
@@ -519,11 +518,24 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
       &Boolean::from(is_base_case),
     )?;
 
-    let (program_counter_new, z_next) = self.step_circuit.enforcing_synthesize(
-      &mut cs.namespace(|| "F"),
-      program_counter.as_ref(),
-      &z_input,
-    )?;
+    // The primary step circuit is given the program counter, which it must match. The secondary
+    // step circuit is given the index of the primary circuit folded at this step, which was range
+    // checked by the instance selector, so that it can dispatch on it. Its output program counter
+    // is ignored.
+    let (program_counter_new, z_next) = if self.params.is_primary_circuit {
+      self.step_circuit.enforcing_synthesize(
+        &mut cs.namespace(|| "F"),
+        program_counter.as_ref(),
+        &z_input,
+      )?
+    } else {
+      let (_, z_next) = self.step_circuit.synthesize(
+        &mut cs.namespace(|| "F"),
+        Some(&last_augmented_circuit_index),
+        &z_input,
+      )?;
+      (None, z_next)
+    };
 
     if z_next.len() != arity {
       return Err(SynthesisError::IncompatibleLengthVector(
@@ -546,15 +558,7 @@ impl<'a, E: Engine, SC: EnforcingStepCircuit<E::Base>> SuperNovaAugmentedCircuit
     // https://eprint.iacr.org/2022/1758.pdf
 
     // Compute the new hash H(params, i+1, program_counter, z0, z_{i+1}, U_next)
-    let mut ro = E::ROCircuit::new(
-      self.ro_consts.clone(),
-      num_ro_inputs(
-        self.num_augmented_circuits,
-        self.params.get_n_limbs(),
-        self.step_circuit.arity(),
-        self.params.is_primary_circuit,
-      ),
-    );
+    let mut ro = E::ROCircuit::new(self.ro_consts.clone());
     ro.absorb(&params);
     ro.absorb(&i_next);
     // optionally absorb program counter if exist
@@ -4,6 +4,8 @@ use thiserror::Error;
 
 use crate::errors::NovaError;
 
+use super::trace::Divergence;
+
 /// Errors returned by Nova
 #[derive(Clone, Debug, Eq, PartialEq, Error)]
 pub enum SuperNovaError {
@@ -13,7 +15,57 @@ pub enum SuperNovaError {
   /// missig commitment key
   #[error("MissingCK")]
   MissingCK,
-  /// Extended error for supernova
+  /// returned when an instance does not satisfy one of the constraints of its circuit
   #[error("UnSatIndex")]
-  UnSatIndex(&'static str, usize),
+  UnSatIndex {
+    /// the unsatisfied instance: `r_primary`, `r_secondary` or `l_secondary`
+    instance: &'static str,
+    /// index of the primary circuit of the instance, `None` for the secondary circuit
+    circuit_index: Option<usize>,
+    /// number of steps proven when the instance was checked
+    step: usize,
+    /// index of the first unsatisfied constraint
+    constraint: usize,
+  },
+  /// returned when the program counter does not select any of the primary circuits
+  #[error("InvalidProgramCounter")]
+  InvalidProgramCounter {
+    /// index of the step the program counter was used for
+    step: usize,
+    /// the program counter
+    program_counter: String,
+    /// the number of primary circuits
+    num_circuits: usize,
+  },
+  /// returned when the circuit supplied for a step is not the one selected by the program counter
+  #[error("CircuitIndexMismatch")]
+  CircuitIndexMismatch {
+    /// index of the step
+    step: usize,
+    /// index of the circuit selected by the program counter
+    program_counter: usize,
+    /// index of the supplied circuit
+    circuit_index: usize,
+  },
+  /// returned when the length of an input or output of a circuit differs from its arity
+  #[error("ArityMismatch")]
+  ArityMismatch {
+    /// index of the primary circuit, `None` for the secondary circuit
+    circuit_index: Option<usize>,
+    /// the arity of the circuit
+    arity: usize,
+    /// the length of the input or output
+    len: usize,
+  },
+  /// returned when replaying an execution trace produces a transition that differs from the
+  /// recorded one
+  #[error("TraceDivergence")]
+  TraceDivergence {
+    /// index of the first step whose transition differs from the trace
+    step: usize,
+    /// index of the circuit executed at that step
+    circuit_index: usize,
+    /// the part of the transition that differs
+    divergence: Divergence,
+  },
 }
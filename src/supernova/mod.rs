@@ -374,6 +374,13 @@ where
       .expect("Failure in retrieving digest")
   }
 
+  /// Precomputes fixed-base tables in the commitment keys of the primary and secondary circuits,
+  /// for windows of `window_size` bits, as [`crate::PublicParams::precompute_commitment_tables`]
+  pub fn precompute_commitment_tables(&mut self, window_size: usize) -> Result<(), NovaError> {
+    E1::CE::precompute_tables(&mut self.ck_primary, window_size)?;
+    E2::CE::precompute_tables(&mut self.ck_secondary, window_size)
+  }
+
   /// All of the primary circuit digests of this [PublicParams]
   pub fn circuit_param_digests(&self) -> CircuitDigests<E1> {
     let digests = self
@@ -455,22 +462,46 @@ where
     let num_augmented_circuits = non_uniform_circuit.num_circuits();
     let circuit_index = non_uniform_circuit.initial_circuit_index();
 
+    // check the initial program counter selects one of the circuits, and that it is the one
+    // provided
+    if circuit_index >= pp.circuit_shapes.len() {
+      return Err(SuperNovaError::InvalidProgramCounter {
+        step: 0,
+        program_counter: format!("{:?}", E1::Scalar::from(circuit_index as u64)),
+        num_circuits: pp.circuit_shapes.len(),
+      });
+    }
+    if c_primary.circuit_index() != circuit_index {
+      return Err(SuperNovaError::CircuitIndexMismatch {
+        step: 0,
+        program_counter: circuit_index,
+        circuit_index: c_primary.circuit_index(),
+      });
+    }
+
     // check the length of the secondary initial input
     if z0_secondary.len() != pp.circuit_shape_secondary.F_arity {
-      return Err(SuperNovaError::NovaError(
-        NovaError::InvalidStepOutputLength,
-      ));
+      return Err(SuperNovaError::ArityMismatch {
+        circuit_index: None,
+        arity: pp.circuit_shape_secondary.F_arity,
+        len: z0_secondary.len(),
+      });
     }
 
     // check the arity of all the primary circuits match the initial input length
-    pp.circuit_shapes.iter().try_for_each(|circuit| {
-      if circuit.F_arity != z0_primary.len() {
-        return Err(SuperNovaError::NovaError(
-          NovaError::InvalidStepOutputLength,
-        ));
-      }
-      Ok(())
-    })?;
+    pp.circuit_shapes
+      .iter()
+      .enumerate()
+      .try_for_each(|(i, circuit)| {
+        if circuit.F_arity != z0_primary.len() {
+          return Err(SuperNovaError::ArityMismatch {
+            circuit_index: Some(i),
+            arity: circuit.F_arity,
+            len: z0_primary.len(),
+          });
+        }
+        Ok(())
+      })?;
 
     // base case for the primary
     let mut cs_primary = SatisfyingAssignment::<E1>::new();
@@ -502,9 +533,11 @@ where
         NovaError::SynthesisError
       })?;
     if zi_primary.len() != pp[circuit_index].F_arity {
-      return Err(SuperNovaError::NovaError(
-        NovaError::InvalidStepOutputLength,
-      ));
+      return Err(SuperNovaError::ArityMismatch {
+        circuit_index: Some(circuit_index),
+        arity: pp[circuit_index].F_arity,
+        len: zi_primary.len(),
+      });
     }
     let (u_primary, w_primary) = cs_primary
       .r1cs_instance_and_witness(&pp[circuit_index].r1cs_shape, &pp.ck_primary)
@@ -539,7 +572,11 @@ where
       .synthesize(&mut cs_secondary)
       .map_err(|_| NovaError::SynthesisError)?;
     if zi_secondary.len() != pp.circuit_shape_secondary.F_arity {
-      return Err(NovaError::InvalidStepOutputLength.into());
+      return Err(SuperNovaError::ArityMismatch {
+        circuit_index: None,
+        arity: pp.circuit_shape_secondary.F_arity,
+        len: zi_secondary.len(),
+      });
     }
     let (u_secondary, w_secondary) = cs_secondary
       .r1cs_instance_and_witness(&pp.circuit_shape_secondary.r1cs_shape, &pp.ck_secondary)
@@ -624,8 +661,23 @@ where
       return Ok(());
     }
 
+    // check the program counter selects one of the circuits, and that it is the one provided
     let circuit_index = c_primary.circuit_index();
-    assert_eq!(self.program_counter, E1::Scalar::from(circuit_index as u64));
+    let num_circuits = pp.circuit_shapes.len();
+    let program_counter = (0..num_circuits)
+      .find(|&i| self.program_counter == E1::Scalar::from(i as u64))
+      .ok_or_else(|| SuperNovaError::InvalidProgramCounter {
+        step: self.i,
+        program_counter: format!("{:?}", self.program_counter),
+        num_circuits,
+      })?;
+    if circuit_index != program_counter {
+      return Err(SuperNovaError::CircuitIndexMismatch {
+        step: self.i,
+        program_counter,
+        circuit_index,
+      });
+    }
 
     // fold the secondary circuit's instance
     let (nifs_secondary, (r_U_secondary_folded, r_W_secondary_folded)) = NIFS::prove(
@@ -675,9 +727,11 @@ where
       .synthesize(&mut cs_primary)
       .map_err(|_| SuperNovaError::NovaError(NovaError::SynthesisError))?;
     if zi_primary.len() != pp[circuit_index].F_arity {
-      return Err(SuperNovaError::NovaError(
-        NovaError::InvalidInitialInputLength,
-      ));
+      return Err(SuperNovaError::ArityMismatch {
+        circuit_index: Some(circuit_index),
+        arity: pp[circuit_index].F_arity,
+        len: zi_primary.len(),
+      });
     }
 
     let (l_u_primary, l_w_primary) = cs_primary
@@ -741,9 +795,11 @@ where
       .synthesize(&mut cs_secondary)
       .map_err(|_| SuperNovaError::NovaError(NovaError::SynthesisError))?;
     if zi_secondary.len() != pp.circuit_shape_secondary.F_arity {
-      return Err(SuperNovaError::NovaError(
-        NovaError::InvalidInitialInputLength,
-      ));
+      return Err(SuperNovaError::ArityMismatch {
+        circuit_index: None,
+        arity: pp.circuit_shape_secondary.F_arity,
+        len: zi_secondary.len(),
+      });
     }
 
     let (l_u_secondary_next, l_w_secondary_next) = cs_secondary
@@ -872,14 +928,7 @@ where
     }
 
     let hash_primary = {
-      let num_absorbs = num_ro_inputs(
-        self.num_augmented_circuits,
-        pp.augmented_circuit_params_primary.get_n_limbs(),
-        pp[circuit_index].F_arity,
-        true, // is_primary
-      );
-
-      let mut hasher = <E2 as Engine>::RO::new(pp.ro_consts_secondary.clone(), num_absorbs);
+      let mut hasher = <E2 as Engine>::RO::new(pp.ro_consts_secondary.clone());
       hasher.absorb(self.pp_digest);
       hasher.absorb(E1::Scalar::from(self.i as u64));
       hasher.absorb(self.program_counter);
@@ -892,17 +941,11 @@ where
       }
 
       self.r_U_secondary.absorb_in_ro(&mut hasher);
-      hasher.squeeze(NUM_HASH_BITS)
+      hasher.squeeze(NUM_HASH_BITS)?
     };
 
     let hash_secondary = {
-      let num_absorbs = num_ro_inputs(
-        self.num_augmented_circuits,
-        pp.augmented_circuit_params_secondary.get_n_limbs(),
-        pp.circuit_shape_secondary.F_arity,
-        false, // is_primary
-      );
-      let mut hasher = <E1 as Engine>::RO::new(pp.ro_consts_primary.clone(), num_absorbs);
+      let mut hasher = <E1 as Engine>::RO::new(pp.ro_consts_primary.clone());
       hasher.absorb(scalar_as_base::<E1>(self.pp_digest));
       hasher.absorb(E2::Scalar::from(self.i as u64));
 
@@ -921,7 +964,7 @@ where
           ))
           .absorb_in_ro(&mut hasher);
       });
-      hasher.squeeze(NUM_HASH_BITS)
+      hasher.squeeze(NUM_HASH_BITS)?
     };
 
     if hash_primary != self.l_u_secondary.X[0] {
@@ -949,9 +992,12 @@ where
           .enumerate()
           .try_for_each(|(i, (u, w))| {
             if let (Some(u), Some(w)) = (u, w) {
-              pp[i].r1cs_shape.is_sat_relaxed(&pp.ck_primary, u, w)?
+              pp[i]
+                .r1cs_shape
+                .is_sat_relaxed(&pp.ck_primary, u, w)
+                .map_err(|err| self.unsat_error(err, "r_primary", Some(i)))?
             }
-            Ok(())
+            Ok::<(), SuperNovaError>(())
           })
       },
       || {
@@ -974,21 +1020,30 @@ where
       },
     );
 
-    res_r_primary.map_err(|err| match err {
-      NovaError::UnSatIndex(i) => SuperNovaError::UnSatIndex("r_primary", i),
-      e => SuperNovaError::NovaError(e),
-    })?;
-    res_r_secondary.map_err(|err| match err {
-      NovaError::UnSatIndex(i) => SuperNovaError::UnSatIndex("r_secondary", i),
-      e => SuperNovaError::NovaError(e),
-    })?;
-    res_l_secondary.map_err(|err| match err {
-      NovaError::UnSatIndex(i) => SuperNovaError::UnSatIndex("l_secondary", i),
-      e => SuperNovaError::NovaError(e),
-    })?;
+    res_r_primary?;
+    res_r_secondary.map_err(|err| self.unsat_error(err, "r_secondary", None))?;
+    res_l_secondary.map_err(|err| self.unsat_error(err, "l_secondary", None))?;
 
     Ok((self.zi_primary.clone(), self.zi_secondary.clone()))
   }
+
+  // Attaches the circuit index and step to the error of a satisfiability check of `instance`
+  fn unsat_error(
+    &self,
+    err: NovaError,
+    instance: &'static str,
+    circuit_index: Option<usize>,
+  ) -> SuperNovaError {
+    match err {
+      NovaError::UnSatIndex(constraint) => SuperNovaError::UnSatIndex {
+        instance,
+        circuit_index,
+        step: self.i,
+        constraint,
+      },
+      e => SuperNovaError::NovaError(e),
+    }
+  }
 }
 
 /// SuperNova helper trait, for implementors that provide sets of sub-circuits to be proved via NIVC. `C1` must be a
@@ -1074,22 +1129,10 @@ pub fn circuit_digest<
   circuit_params.digest()
 }
 
-/// Compute the number of absorbs for the random-oracle computing the circuit output
-/// X = H(vk, i, pc, z0, zi, U)
-fn num_ro_inputs(num_circuits: usize, num_limbs: usize, arity: usize, is_primary: bool) -> usize {
-  let num_circuits = if is_primary { 1 } else { num_circuits };
-
-  // [W(x,y,∞), E(x,y,∞), u] + [X0, X1] * #num_limb
-  let instance_size = 3 + 3 + 1 + 2 * num_limbs;
-
-  2 // params, i
-    + usize::from(is_primary) // optional program counter
-      + 2 * arity // z0, zi
-      + num_circuits * instance_size
-}
-
 pub mod error;
+pub mod rom;
 pub mod snark;
+pub mod trace;
 pub(crate) mod utils;
 
 #[cfg(test)]
@@ -0,0 +1,289 @@
+//! This module implements instruction fetch from a program ROM for non-uniform computations.
+//!
+//! A [`ProgramROM`] is the list of the circuit indices to execute at each address of a program.
+//! Wrapping each primary circuit of a [`NonUniformCircuit`](super::NonUniformCircuit) in a
+//! [`ROMCircuit`] carries the current address in the primary input, and records at each step the
+//! read of the executed circuit index at that address into an offline memory-checking
+//! accumulator. Step circuits are thus given the current address as their program counter, and
+//! return the next address rather than the next circuit index, which the wrapper fetches from the
+//! program.
+//!
+//! The accumulator is checked once, at compression: the verifier keys of
+//! [`CompressedSNARK::setup_with_rom`](super::snark::CompressedSNARK::setup_with_rom) hold the
+//! program, and [`CompressedSNARK::verify`](super::snark::CompressedSNARK::verify) then rejects
+//! proofs that fail [`ProgramROM::verify`]: the product of the fingerprints of all reads must equal
+//! the product of the fingerprints of the program entries, raised to the number of times each of
+//! them was executed. The fingerprints use random
+//! challenges derived from a hash chain of the reads, which is computed alongside the product.
+use std::sync::Arc;
+
+use bellpepper::gadgets::Assignment;
+use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+use ff::{Field, PrimeField};
+use itertools::Itertools as _;
+
+use crate::{
+  constants::NUM_HASH_BITS,
+  errors::NovaError,
+  gadgets::utils::le_bits_to_num,
+  traits::{circuit_supernova::StepCircuit, Engine, ROCircuitTrait, ROConstantsCircuit, ROTrait},
+};
+
+/// The number of elements of the ROM state appended to the primary input of a [`ROMCircuit`]:
+/// the current address, the challenges `gamma` and `alpha`, the product of the fingerprints of
+/// the reads and the hash of the reads.
+pub const ROM_STATE_LEN: usize = 5;
+
+type NativeRO<E> =
+  <<E as Engine>::ROCircuit as ROCircuitTrait<<E as Engine>::Base>>::NativeRO<<E as Engine>::Base>;
+
+/// A program, given as the circuit index to execute at each address.
+///
+/// The circuits of the program are over `E::Base`, i.e. `E` is the engine of the secondary curve
+/// when the program is run on the primary circuits.
+#[derive(Clone)]
+pub struct ProgramROM<E: Engine> {
+  program: Vec<usize>,
+  ro_consts: ROConstantsCircuit<E>,
+}
+
+impl<E: Engine> ProgramROM<E> {
+  /// Create a new `ProgramROM` for `program`
+  pub fn new(program: Vec<usize>) -> Self {
+    Self {
+      program,
+      ro_consts: ROConstantsCircuit::<E>::default(),
+    }
+  }
+
+  /// The circuit indices of the program
+  pub fn program(&self) -> &[usize] {
+    &self.program
+  }
+
+  /// Returns the number of times each address of the program is executed, given the address of
+  /// each step of an execution.
+  pub fn multiplicities(&self, addresses: &[usize]) -> Result<Vec<u64>, NovaError> {
+    let mut multiplicities = vec![0u64; self.program.len()];
+    for &address in addresses {
+      *multiplicities
+        .get_mut(address)
+        .ok_or(NovaError::InvalidIndex)? += 1;
+    }
+    Ok(multiplicities)
+  }
+
+  /// Returns the initial ROM state of an execution, given the address of each of its steps.
+  ///
+  /// The state must be appended to the primary input `z0` of the execution, which starts at
+  /// `addresses[0]`.
+  pub fn initial_state(&self, addresses: &[usize]) -> Result<Vec<E::Base>, NovaError> {
+    let entry = *addresses.first().ok_or(NovaError::InvalidNumSteps)?;
+    let multiplicities = self.multiplicities(addresses)?;
+    let hash = addresses.iter().try_fold(E::Base::ZERO, |hash, &address| {
+      self.hash_read(hash, address, self.program[address])
+    })?;
+    let (gamma, alpha) = self.challenges(hash, &multiplicities)?;
+
+    Ok(vec![
+      E::Base::from(entry as u64),
+      gamma,
+      alpha,
+      E::Base::ONE,
+      E::Base::ZERO,
+    ])
+  }
+
+  /// Checks the ROM state `zn_state` reached after the last step of an execution that started
+  /// from `z0_state`, given the number of times each address of the program was executed.
+  pub fn verify(
+    &self,
+    z0_state: &[E::Base],
+    zn_state: &[E::Base],
+    multiplicities: &[u64],
+  ) -> Result<(), NovaError> {
+    if z0_state.len() != ROM_STATE_LEN
+      || zn_state.len() != ROM_STATE_LEN
+      || multiplicities.len() != self.program.len()
+    {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    let (gamma, alpha) = (z0_state[1], z0_state[2]);
+
+    // the accumulators start empty, and the challenges are carried unchanged
+    if z0_state[3] != E::Base::ONE
+      || z0_state[4] != E::Base::ZERO
+      || zn_state[1] != gamma
+      || zn_state[2] != alpha
+    {
+      return Err(NovaError::ProofVerifyError);
+    }
+
+    // the challenges are derived from the reads and the multiplicities
+    if self.challenges(zn_state[4], multiplicities)? != (gamma, alpha) {
+      return Err(NovaError::ProofVerifyError);
+    }
+
+    let table = self
+      .program
+      .iter()
+      .zip_eq(multiplicities)
+      .enumerate()
+      .map(|(address, (&circuit_index, &m))| {
+        fingerprint(gamma, alpha, address, circuit_index).pow_vartime([m])
+      })
+      .product::<E::Base>();
+
+    if table != zn_state[3] {
+      return Err(NovaError::ProofVerifyError);
+    }
+
+    Ok(())
+  }
+
+  fn hash_read(
+    &self,
+    hash: E::Base,
+    address: usize,
+    circuit_index: usize,
+  ) -> Result<E::Base, NovaError> {
+    let mut ro = NativeRO::<E>::new(self.ro_consts.clone());
+    ro.absorb(hash);
+    ro.absorb(E::Base::from(address as u64));
+    ro.absorb(E::Base::from(circuit_index as u64));
+    ro.squeeze(NUM_HASH_BITS)
+  }
+
+  // Derives the challenges (gamma, alpha) from the hash of the reads and the multiplicities
+  fn challenges(
+    &self,
+    hash: E::Base,
+    multiplicities: &[u64],
+  ) -> Result<(E::Base, E::Base), NovaError> {
+    let mut ro = NativeRO::<E>::new(self.ro_consts.clone());
+    ro.absorb(hash);
+    for &m in multiplicities {
+      ro.absorb(E::Base::from(m));
+    }
+    Ok((ro.squeeze(NUM_HASH_BITS)?, ro.squeeze(NUM_HASH_BITS)?))
+  }
+
+  // The circuit index at `address`. Addresses outside of the program fetch the circuit index 0:
+  // the corresponding read can never be matched, so no step can be executed from there.
+  fn fetch(&self, address: E::Base) -> usize {
+    field_as_index(address)
+      .and_then(|address| self.program.get(address).copied())
+      .unwrap_or_default()
+  }
+}
+
+/// A primary circuit fetching its successor from a [`ProgramROM`].
+///
+/// The wrapped circuit is synthesized with the current address as its program counter, and must
+/// return the next address. Its primary input is extended with the [`ROM_STATE_LEN`] elements of
+/// the ROM state, which start with [`ProgramROM::initial_state`].
+#[derive(Clone)]
+pub struct ROMCircuit<E: Engine, C> {
+  rom: Arc<ProgramROM<E>>,
+  circuit: C,
+}
+
+impl<E: Engine, C: StepCircuit<E::Base>> ROMCircuit<E, C> {
+  /// Wrap `circuit` to fetch its successor from `rom`
+  pub fn new(rom: &Arc<ProgramROM<E>>, circuit: C) -> Self {
+    Self {
+      rom: rom.clone(),
+      circuit,
+    }
+  }
+}
+
+impl<E: Engine, C: StepCircuit<E::Base>> StepCircuit<E::Base> for ROMCircuit<E, C> {
+  fn arity(&self) -> usize {
+    self.circuit.arity() + ROM_STATE_LEN
+  }
+
+  fn circuit_index(&self) -> usize {
+    self.circuit.circuit_index()
+  }
+
+  fn synthesize<CS: ConstraintSystem<E::Base>>(
+    &self,
+    cs: &mut CS,
+    pc: Option<&AllocatedNum<E::Base>>,
+    z: &[AllocatedNum<E::Base>],
+  ) -> Result<(Option<AllocatedNum<E::Base>>, Vec<AllocatedNum<E::Base>>), SynthesisError> {
+    if z.len() != self.arity() {
+      return Err(SynthesisError::IncompatibleLengthVector(
+        "ROM state".to_string(),
+      ));
+    }
+    let pc = pc.ok_or(SynthesisError::AssignmentMissing)?;
+    let (z, state) = z.split_at(self.circuit.arity());
+    let (address, gamma, alpha, read_acc, hash_acc) =
+      (&state[0], &state[1], &state[2], &state[3], &state[4]);
+
+    // Record the read of the executed circuit index `pc` at `address`
+    let alpha_pc = alpha.mul(cs.namespace(|| "alpha * pc"), pc)?;
+    let read_acc_next = AllocatedNum::alloc(cs.namespace(|| "read_acc_next"), || {
+      Ok(
+        *read_acc.get_value().get()?
+          * (*gamma.get_value().get()?
+            - address.get_value().get()?
+            - alpha_pc.get_value().get()?),
+      )
+    })?;
+    cs.enforce(
+      || "read_acc_next = read_acc * (gamma - address - alpha * pc)",
+      |lc| lc + read_acc.get_variable(),
+      |lc| lc + gamma.get_variable() - address.get_variable() - alpha_pc.get_variable(),
+      |lc| lc + read_acc_next.get_variable(),
+    );
+
+    let mut ro = E::ROCircuit::new(self.rom.ro_consts.clone());
+    ro.absorb(hash_acc);
+    ro.absorb(address);
+    ro.absorb(pc);
+    let hash_bits = ro.squeeze(cs.namespace(|| "hash read"), NUM_HASH_BITS)?;
+    let hash_acc_next = le_bits_to_num(cs.namespace(|| "hash_acc_next"), &hash_bits)?;
+
+    // Execute the step, which returns the next address
+    let (next_address, mut z_next) =
+      self
+        .circuit
+        .synthesize(&mut cs.namespace(|| "F"), Some(address), z)?;
+    let next_address = next_address.ok_or(SynthesisError::AssignmentMissing)?;
+
+    // Fetch the next circuit index, which is checked by the read of the next step
+    let next_pc = AllocatedNum::alloc(cs.namespace(|| "next_pc"), || {
+      let next_address = next_address.get_value().get()?;
+      Ok(E::Base::from(self.rom.fetch(*next_address) as u64))
+    })?;
+
+    z_next.extend([
+      next_address,
+      gamma.clone(),
+      alpha.clone(),
+      read_acc_next,
+      hash_acc_next,
+    ]);
+
+    Ok((Some(next_pc), z_next))
+  }
+}
+
+// Computes the fingerprint of the read of `circuit_index` at `address`
+fn fingerprint<F: PrimeField>(gamma: F, alpha: F, address: usize, circuit_index: usize) -> F {
+  gamma - F::from(address as u64) - alpha * F::from(circuit_index as u64)
+}
+
+// Converts a field element into an index, if it is small enough
+fn field_as_index<F: PrimeField>(x: F) -> Option<usize> {
+  let repr = x.to_repr();
+  let (low, high) = repr.as_ref().split_at(8);
+  if high.iter().any(|b| *b != 0) {
+    return None;
+  }
+  usize::try_from(u64::from_le_bytes(low.try_into().ok()?)).ok()
+}
@@ -1,9 +1,13 @@
 //! This module defines a final compressing SNARK for supernova proofs
 
-use super::{error::SuperNovaError, PublicParams, RecursiveSNARK};
+use super::{
+  error::SuperNovaError,
+  rom::{ProgramROM, ROM_STATE_LEN},
+  PublicParams, RecursiveSNARK,
+};
 use crate::{
   constants::NUM_HASH_BITS,
-  r1cs::{R1CSInstance, RelaxedR1CSWitness},
+  r1cs::R1CSInstance,
   traits::{
     circuit_supernova::StepCircuit,
     snark::{BatchedRelaxedR1CSSNARKTrait, RelaxedR1CSSNARKTrait},
@@ -69,6 +73,8 @@ where
 {
   vk_primary: S1::VerifierKey,
   vk_secondary: S2::VerifierKey,
+  // the program whose ROM accumulator is checked by `verify`, if any
+  rom: Option<Vec<usize>>,
   _p: PhantomData<(C1, C2)>,
 }
 
@@ -84,7 +90,7 @@ where
   S1: BatchedRelaxedR1CSSNARKTrait<E1>,
   S2: RelaxedR1CSSNARKTrait<E2>,
 {
-  r_U_primary: Vec<RelaxedR1CSInstance<E1>>,
+  r_U_primary: Vec<Option<RelaxedR1CSInstance<E1>>>,
   r_W_snark_primary: S1,
 
   r_U_secondary: RelaxedR1CSInstance<E2>,
@@ -97,6 +103,9 @@ where
 
   zn_primary: Vec<E1::Scalar>,
   zn_secondary: Vec<E2::Scalar>,
+
+  // the number of times each address of the program was executed, for programs run from a ROM
+  rom_multiplicities: Option<Vec<u64>>,
   _p: PhantomData<(E1, E2, C1, C2, S1, S2)>,
 }
 
@@ -132,12 +141,33 @@ where
     let verifier_key = VerifierKey {
       vk_primary,
       vk_secondary,
+      rom: None,
       _p: PhantomData,
     };
 
     Ok((prover_key, verifier_key))
   }
 
+  /// Creates prover and verifier keys for `CompressedSNARK`s of programs run from `rom`, whose
+  /// primary inputs end with the ROM state of [`ROMCircuit`](super::rom::ROMCircuit)s
+  ///
+  /// The verifier key holds the program, and [`CompressedSNARK::verify`] checks the ROM
+  /// accumulator against it: proofs must be produced with [`CompressedSNARK::prove_with_rom`].
+  pub fn setup_with_rom(
+    pp: &PublicParams<E1, E2, C1, C2>,
+    rom: &ProgramROM<E2>,
+  ) -> Result<
+    (
+      ProverKey<E1, E2, C1, C2, S1, S2>,
+      VerifierKey<E1, E2, C1, C2, S1, S2>,
+    ),
+    SuperNovaError,
+  > {
+    let (prover_key, mut verifier_key) = Self::setup(pp)?;
+    verifier_key.rom = Some(rom.program().to_vec());
+    Ok((prover_key, verifier_key))
+  }
+
   /// Create a new `CompressedSNARK`
   pub fn prove(
     pp: &PublicParams<E1, E2, C1, C2>,
@@ -158,39 +188,40 @@ where
 
     let (nifs_secondary, (f_U_secondary, f_W_secondary)) = res_secondary?;
 
-    // Prepare the list of primary Relaxed R1CS instances (a default instance is provided for
-    // uninitialized circuits)
-    let r_U_primary = recursive_snark
+    // Only the circuits that were executed at least once carry a non-trivial running instance:
+    // the others are left in their default state, which the verifier reconstructs on its own.
+    // We thus prove the primary instances of the executed circuits only.
+    let indices = recursive_snark
       .r_U_primary
       .iter()
       .enumerate()
-      .map(|(idx, r_U)| {
-        r_U
-          .clone()
-          .unwrap_or_else(|| RelaxedR1CSInstance::default(&pp.ck_primary, &pp[idx].r1cs_shape))
-      })
+      .filter_map(|(idx, r_U)| r_U.is_some().then_some(idx))
       .collect::<Vec<_>>();
 
-    // Prepare the list of primary relaxed R1CS witnesses (a default witness is provided for
-    // uninitialized circuits)
-    let r_W_primary: Vec<RelaxedR1CSWitness<E1>> = recursive_snark
-      .r_W_primary
+    let (r_U_primary, r_W_primary): (Vec<_>, Vec<_>) = recursive_snark
+      .r_U_primary
       .iter()
-      .enumerate()
-      .map(|(idx, r_W)| {
-        r_W
-          .clone()
-          .unwrap_or_else(|| RelaxedR1CSWitness::default(&pp[idx].r1cs_shape))
-      })
+      .zip(recursive_snark.r_W_primary.iter())
+      .filter_map(|(r_U, r_W)| r_U.clone().zip(r_W.clone()))
+      .unzip();
+
+    if r_U_primary.len() != indices.len() {
+      return Err(NovaError::InternalError.into());
+    }
+
+    let S_primary = indices
+      .iter()
+      .map(|&idx| &pp[idx].r1cs_shape)
       .collect::<Vec<_>>();
 
-    // Generate a primary SNARK proof for the list of primary circuits
-    let r_W_snark_primary = S1::prove(
+    // Generate a primary SNARK proof for the list of executed primary circuits
+    let r_W_snark_primary = S1::prove_subset(
       &pp.ck_primary,
       &pk.pk_primary,
-      pp.primary_r1cs_shapes(),
+      S_primary,
       &r_U_primary,
       &r_W_primary,
+      &indices,
     )?;
 
     // Generate a secondary SNARK proof for the secondary circuit
@@ -203,7 +234,7 @@ where
     )?;
 
     let compressed_snark = CompressedSNARK {
-      r_U_primary,
+      r_U_primary: recursive_snark.r_U_primary.clone(),
       r_W_snark_primary,
 
       r_U_secondary: recursive_snark.r_U_secondary.clone(),
@@ -217,12 +248,26 @@ where
       zn_primary: recursive_snark.zi_primary.clone(),
       zn_secondary: recursive_snark.zi_secondary.clone(),
 
+      rom_multiplicities: None,
       _p: PhantomData,
     };
 
     Ok(compressed_snark)
   }
 
+  /// Create a new `CompressedSNARK` of a program run from a ROM, given the number of times each
+  /// address of the program was executed, see [`ProgramROM::multiplicities`]
+  pub fn prove_with_rom(
+    pp: &PublicParams<E1, E2, C1, C2>,
+    pk: &ProverKey<E1, E2, C1, C2, S1, S2>,
+    recursive_snark: &RecursiveSNARK<E1, E2>,
+    multiplicities: &[u64],
+  ) -> Result<Self, SuperNovaError> {
+    let mut compressed_snark = Self::prove(pp, pk, recursive_snark)?;
+    compressed_snark.rom_multiplicities = Some(multiplicities.to_vec());
+    Ok(compressed_snark)
+  }
+
   /// Verify the correctness of the `CompressedSNARK`
   pub fn verify(
     &self,
@@ -231,24 +276,30 @@ where
     z0_primary: &[E1::Scalar],
     z0_secondary: &[E2::Scalar],
   ) -> Result<(Vec<E1::Scalar>, Vec<E2::Scalar>), SuperNovaError> {
-    let last_circuit_idx = field_as_usize(self.program_counter);
-
-    let num_field_primary_ro = 3 // params_next, i_new, program_counter_new
-    + 2 * pp[last_circuit_idx].F_arity // zo, z1
-    + (7 + 2 * pp.augmented_circuit_params_primary.get_n_limbs()); // # 1 * (7 + [X0, X1]*#num_limb)
+    if self.r_U_primary.len() != pp.circuit_shapes.len() {
+      return Err(NovaError::ProofVerifyError.into());
+    }
 
-    // secondary circuit
-    // NOTE: This count ensure the number of witnesses sent by the prover must equal the number of
-    // NIVC circuits
-    let num_field_secondary_ro = 2 // params_next, i_new
-    + 2 * pp.circuit_shape_secondary.F_arity // zo, z1
-    + pp.circuit_shapes.len() * (7 + 2 * pp.augmented_circuit_params_primary.get_n_limbs()); // #num_augment
+    // For programs run from a ROM, check the accumulator at the end of the primary inputs
+    if let Some(program) = &vk.rom {
+      let multiplicities = self
+        .rom_multiplicities
+        .as_ref()
+        .ok_or(NovaError::ProofVerifyError)?;
+      if z0_primary.len() < ROM_STATE_LEN || self.zn_primary.len() < ROM_STATE_LEN {
+        return Err(NovaError::ProofVerifyError.into());
+      }
+      ProgramROM::<E2>::new(program.clone()).verify(
+        &z0_primary[z0_primary.len() - ROM_STATE_LEN..],
+        &self.zn_primary[self.zn_primary.len() - ROM_STATE_LEN..],
+        multiplicities,
+      )?;
+    }
 
     // Compute the primary and secondary hashes given the digest, program counter, instances, and
     // witnesses provided by the prover
     let (hash_primary, hash_secondary) = {
-      let mut hasher =
-        <E2 as Engine>::RO::new(pp.ro_consts_secondary.clone(), num_field_primary_ro);
+      let mut hasher = <E2 as Engine>::RO::new(pp.ro_consts_secondary.clone());
 
       hasher.absorb(pp.digest());
       hasher.absorb(E1::Scalar::from(self.num_steps as u64));
@@ -264,8 +315,7 @@ where
 
       self.r_U_secondary.absorb_in_ro(&mut hasher);
 
-      let mut hasher2 =
-        <E1 as Engine>::RO::new(pp.ro_consts_primary.clone(), num_field_secondary_ro);
+      let mut hasher2 = <E1 as Engine>::RO::new(pp.ro_consts_primary.clone());
 
       hasher2.absorb(scalar_as_base::<E1>(pp.digest()));
       hasher2.absorb(E2::Scalar::from(self.num_steps as u64));
@@ -278,13 +328,20 @@ where
         hasher2.absorb(*e);
       }
 
-      self.r_U_primary.iter().for_each(|U| {
-        U.absorb_in_ro(&mut hasher2);
-      });
+      // circuits that were never executed are in their default state
+      self
+        .r_U_primary
+        .iter()
+        .enumerate()
+        .for_each(|(idx, U)| match U {
+          Some(U) => U.absorb_in_ro(&mut hasher2),
+          None => RelaxedR1CSInstance::default(&pp.ck_primary, &pp[idx].r1cs_shape)
+            .absorb_in_ro(&mut hasher2),
+        });
 
       (
-        hasher.squeeze(NUM_HASH_BITS),
-        hasher2.squeeze(NUM_HASH_BITS),
+        hasher.squeeze(NUM_HASH_BITS)?,
+        hasher2.squeeze(NUM_HASH_BITS)?,
       )
     };
 
@@ -297,10 +354,16 @@ where
       return Err(NovaError::ProofVerifyError.into());
     }
 
-    // Verify the primary SNARK
+    // Verify the primary SNARK over the instances of the executed circuits
+    let (indices, r_U_primary): (Vec<_>, Vec<_>) = self
+      .r_U_primary
+      .iter()
+      .enumerate()
+      .filter_map(|(idx, U)| U.clone().map(|U| (idx, U)))
+      .unzip();
     let res_primary = self
       .r_W_snark_primary
-      .verify(&vk.vk_primary, &self.r_U_primary);
+      .verify_subset(&vk.vk_primary, &r_U_primary, &indices);
 
     // Fold the secondary circuit's instance
     let f_U_secondary = self.nifs_secondary.verify(
@@ -323,10 +386,6 @@ where
   }
 }
 
-fn field_as_usize<F: PrimeField>(x: F) -> usize {
-  u32::from_le_bytes(x.to_repr().as_ref()[0..4].try_into().unwrap()) as usize
-}
-
 #[cfg(test)]
 mod test {
   use super::*;
@@ -576,6 +635,87 @@ mod test {
     test_nivc_trivial_with_compression_with::<Secp256k1Engine, Secq256k1Engine, S1<_>, S2<_>>();
   }
 
+  fn test_compression_of_executed_circuits_with<E1, E2, S1, S2>()
+  where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+    S1: BatchedRelaxedR1CSSNARKTrait<E1>,
+    S2: RelaxedR1CSSNARKTrait<E2>,
+    <E1::Scalar as PrimeField>::Repr: Abomonation,
+    <E2::Scalar as PrimeField>::Repr: Abomonation,
+  {
+    let secondary_circuit = TrivialSecondaryCircuit::default();
+    let test_circuits = TestCircuit::new(1);
+
+    let pp = PublicParams::setup(&test_circuits[0], &*S1::ck_floor(), &*S2::ck_floor());
+
+    let z0_primary = vec![E1::Scalar::from(17u64)];
+    let z0_secondary = vec![<E2 as Engine>::Scalar::ZERO];
+
+    let mut recursive_snark = RecursiveSNARK::new(
+      &pp,
+      &test_circuits[0],
+      &test_circuits[0],
+      &secondary_circuit,
+      &z0_primary,
+      &z0_secondary,
+    )
+    .unwrap();
+
+    // only the square circuit is executed, the cube circuit stays in its default state
+    recursive_snark
+      .prove_step(&pp, &test_circuits[0], &secondary_circuit)
+      .unwrap();
+    recursive_snark
+      .verify(&pp, &z0_primary, &z0_secondary)
+      .unwrap();
+
+    let (prover_key, verifier_key) = CompressedSNARK::<_, _, _, _, S1, S2>::setup(&pp).unwrap();
+
+    let compressed_snark = CompressedSNARK::prove(&pp, &prover_key, &recursive_snark).unwrap();
+    assert_eq!(
+      compressed_snark.r_U_primary[0],
+      recursive_snark.r_U_primary[0]
+    );
+    assert!(compressed_snark.r_U_primary[1].is_none());
+
+    let compressed_verify_res =
+      compressed_snark.verify(&pp, &verifier_key, &z0_primary, &z0_secondary);
+    assert!(compressed_verify_res.is_ok());
+
+    // claiming the default instance of the cube circuit was proven must fail
+    let mut bad_compressed_snark = compressed_snark.clone();
+    bad_compressed_snark.r_U_primary[1] = Some(RelaxedR1CSInstance::default(
+      &pp.ck_primary,
+      &pp[1].r1cs_shape,
+    ));
+    let bad_verify_res =
+      bad_compressed_snark.verify(&pp, &verifier_key, &z0_primary, &z0_secondary);
+    assert!(bad_verify_res.is_err());
+
+    // a proof that claims no circuit was executed is rejected rather than making the verifier panic
+    let mut empty_compressed_snark = compressed_snark.clone();
+    empty_compressed_snark.r_U_primary = vec![None; pp.circuit_shapes.len()];
+    let empty_verify_res =
+      empty_compressed_snark.verify(&pp, &verifier_key, &z0_primary, &z0_secondary);
+    assert!(empty_verify_res.is_err());
+    let empty_subset_res =
+      compressed_snark
+        .r_W_snark_primary
+        .verify_subset(&verifier_key.vk_primary, &[], &[]);
+    assert_eq!(empty_subset_res, Err(NovaError::InvalidInputLength));
+  }
+
+  #[test]
+  fn test_compression_of_executed_circuits() {
+    // ppSNARK
+    test_compression_of_executed_circuits_with::<PallasEngine, VestaEngine, S1PP<_>, S2<_>>();
+    test_compression_of_executed_circuits_with::<Bn256Engine, GrumpkinEngine, S1PP<_>, S2<_>>();
+    // classic SNARK
+    test_compression_of_executed_circuits_with::<PallasEngine, VestaEngine, S1<_>, S2<_>>();
+    test_compression_of_executed_circuits_with::<Bn256Engine, GrumpkinEngine, S1<_>, S2<_>>();
+  }
+
   #[derive(Clone)]
   struct BigPowerCircuit<E: Engine> {
     _p: PhantomData<E>,
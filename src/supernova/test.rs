@@ -1,4 +1,5 @@
 use crate::gadgets::utils::alloc_zero;
+use crate::provider::ipa_pc;
 use crate::provider::poseidon::PoseidonConstantsCircuit;
 use crate::provider::Bn256Engine;
 use crate::provider::GrumpkinEngine;
@@ -6,20 +7,29 @@ use crate::provider::PallasEngine;
 use crate::provider::Secp256k1Engine;
 use crate::provider::Secq256k1Engine;
 use crate::provider::VestaEngine;
+use crate::spartan::{batched::BatchedRelaxedR1CSSNARK, snark::RelaxedR1CSSNARK};
 use crate::traits::circuit_supernova::{
   EnforcingStepCircuit, StepCircuit, TrivialSecondaryCircuit, TrivialTestCircuit,
 };
 use crate::traits::snark::default_ck_hint;
 use crate::{bellpepper::test_shape_cs::TestShapeCS, gadgets::utils::alloc_one};
+use abomonation::Abomonation;
 use bellpepper_core::num::AllocatedNum;
 use bellpepper_core::{ConstraintSystem, SynthesisError};
 use core::marker::PhantomData;
 use ff::Field;
 use ff::PrimeField;
 use std::fmt::Write;
+use std::sync::Arc;
 use tap::TapOptional;
 
-use super::{utils::get_selector_vec_from_index, *};
+use super::{
+  rom::{ProgramROM, ROMCircuit},
+  snark::CompressedSNARK,
+  trace::{Divergence, ExecutionTrace, ReplayableCircuit},
+  utils::get_selector_vec_from_index,
+  *,
+};
 
 #[derive(Clone, Debug, Default)]
 struct CubicCircuit<F: PrimeField> {
@@ -243,7 +253,11 @@ fn print_constraints_name_on_error_index<E1, E2, C1, C2>(
   C2: EnforcingStepCircuit<E2::Scalar>,
 {
   match err {
-    SuperNovaError::UnSatIndex(msg, index) if *msg == "r_primary" => {
+    SuperNovaError::UnSatIndex {
+      instance: msg,
+      constraint: index,
+      ..
+    } if *msg == "r_primary" => {
       let circuit_primary: SuperNovaAugmentedCircuit<'_, E2, C1> = SuperNovaAugmentedCircuit::new(
         &pp.augmented_circuit_params_primary,
         None,
@@ -257,7 +271,11 @@ fn print_constraints_name_on_error_index<E1, E2, C1, C2>(
         .get(*index)
         .tap_some(|constraint| debug!("{msg} failed at constraint {}", constraint.3));
     }
-    SuperNovaError::UnSatIndex(msg, index) if *msg == "r_secondary" || *msg == "l_secondary" => {
+    SuperNovaError::UnSatIndex {
+      instance: msg,
+      constraint: index,
+      ..
+    } if *msg == "r_secondary" || *msg == "l_secondary" => {
       let circuit_secondary: SuperNovaAugmentedCircuit<'_, E1, C2> = SuperNovaAugmentedCircuit::new(
         &pp.augmented_circuit_params_secondary,
         None,
@@ -465,6 +483,501 @@ fn test_trivial_nivc() {
   test_trivial_nivc_with::<PallasEngine, VestaEngine>();
 }
 
+// A secondary circuit doing actual work on the secondary curve: it counts how many times each of
+// the two primary circuits of `TestROM` was executed, dispatching on the index of the primary
+// circuit it receives as its program counter.
+#[derive(Clone, Debug, Default)]
+struct OpcodeCountingCircuit<F: PrimeField> {
+  _p: PhantomData<F>,
+}
+
+impl<F> StepCircuit<F> for OpcodeCountingCircuit<F>
+where
+  F: PrimeField,
+{
+  fn arity(&self) -> usize {
+    2 // number of executions of OPCODE_0, number of executions of OPCODE_1
+  }
+
+  fn circuit_index(&self) -> usize {
+    0
+  }
+
+  fn synthesize<CS: ConstraintSystem<F>>(
+    &self,
+    cs: &mut CS,
+    pc: Option<&AllocatedNum<F>>,
+    z: &[AllocatedNum<F>],
+  ) -> Result<(Option<AllocatedNum<F>>, Vec<AllocatedNum<F>>), SynthesisError> {
+    // the primary circuit index is range checked to {OPCODE_0, OPCODE_1} by the augmented circuit
+    let opcode = pc.ok_or(SynthesisError::AssignmentMissing)?;
+
+    let count_0 = AllocatedNum::alloc(cs.namespace(|| "count_0"), || {
+      Ok(
+        z[0].get_value().ok_or(SynthesisError::AssignmentMissing)? + F::ONE
+          - opcode
+            .get_value()
+            .ok_or(SynthesisError::AssignmentMissing)?,
+      )
+    })?;
+    cs.enforce(
+      || "count_0 = z[0] + 1 - opcode",
+      |lc| lc + z[0].get_variable() + CS::one() - opcode.get_variable(),
+      |lc| lc + CS::one(),
+      |lc| lc + count_0.get_variable(),
+    );
+
+    let count_1 = AllocatedNum::alloc(cs.namespace(|| "count_1"), || {
+      Ok(
+        z[1].get_value().ok_or(SynthesisError::AssignmentMissing)?
+          + opcode
+            .get_value()
+            .ok_or(SynthesisError::AssignmentMissing)?,
+      )
+    })?;
+    cs.enforce(
+      || "count_1 = z[1] + opcode",
+      |lc| lc + z[1].get_variable() + opcode.get_variable(),
+      |lc| lc + CS::one(),
+      |lc| lc + count_1.get_variable(),
+    );
+
+    Ok((None, vec![count_0, count_1]))
+  }
+}
+
+impl<E1, E2>
+  NonUniformCircuit<E1, E2, TestROMCircuit<E1::Scalar>, OpcodeCountingCircuit<E2::Scalar>>
+  for TestROM<E1, E2, OpcodeCountingCircuit<E2::Scalar>>
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  fn num_circuits(&self) -> usize {
+    2
+  }
+
+  fn primary_circuit(&self, circuit_index: usize) -> TestROMCircuit<E1::Scalar> {
+    match circuit_index {
+      0 => TestROMCircuit::Cubic(CubicCircuit::new(circuit_index, self.rom.len())),
+      1 => TestROMCircuit::Square(SquareCircuit::new(circuit_index, self.rom.len())),
+      _ => panic!("unsupported primary circuit index"),
+    }
+  }
+
+  fn secondary_circuit(&self) -> OpcodeCountingCircuit<E2::Scalar> {
+    Default::default()
+  }
+
+  fn initial_circuit_index(&self) -> usize {
+    self.rom[0]
+  }
+}
+
+fn test_nivc_secondary_circuit_with<E1, E2>()
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  let rom = vec![
+    OPCODE_1, OPCODE_1, OPCODE_0, OPCODE_0, OPCODE_1, OPCODE_1, OPCODE_0, OPCODE_1,
+  ];
+
+  let test_rom = TestROM::<E1, E2, OpcodeCountingCircuit<E2::Scalar>>::new(rom);
+
+  let pp = PublicParams::setup(&test_rom, &*default_ck_hint(), &*default_ck_hint());
+
+  let mut z0_primary = vec![<E1 as Engine>::Scalar::ONE];
+  z0_primary.push(<E1 as Engine>::Scalar::ZERO); // rom_index = 0
+  z0_primary.extend(
+    test_rom
+      .rom
+      .iter()
+      .map(|opcode| <E1 as Engine>::Scalar::from(*opcode as u64)),
+  );
+  let z0_secondary = vec![<E2 as Engine>::Scalar::ZERO; 2];
+
+  let circuit_secondary = test_rom.secondary_circuit();
+  let mut recursive_snark = RecursiveSNARK::new(
+    &pp,
+    &test_rom,
+    &test_rom.primary_circuit(test_rom.rom[0]),
+    &circuit_secondary,
+    &z0_primary,
+    &z0_secondary,
+  )
+  .unwrap();
+
+  for &op_code in test_rom.rom.iter() {
+    let circuit_primary = test_rom.primary_circuit(op_code);
+    recursive_snark
+      .prove_step(&pp, &circuit_primary, &circuit_secondary)
+      .unwrap();
+    recursive_snark
+      .verify(&pp, &z0_primary, &z0_secondary)
+      .unwrap();
+  }
+
+  // the secondary circuit ran once along each primary step
+  assert_eq!(
+    recursive_snark.zi_secondary,
+    vec![
+      <E2 as Engine>::Scalar::from(3u64),
+      <E2 as Engine>::Scalar::from(5u64)
+    ]
+  );
+}
+
+#[test]
+fn test_nivc_secondary_circuit() {
+  test_nivc_secondary_circuit_with::<PallasEngine, VestaEngine>();
+  test_nivc_secondary_circuit_with::<Bn256Engine, GrumpkinEngine>();
+}
+
+// A step circuit of a program run from a `ProgramROM`: the circuit OPCODE_0 cubes its input and
+// the circuit OPCODE_1 squares it, both continuing at the next address.
+#[derive(Clone, Debug)]
+struct ROMStepCircuit<F: PrimeField> {
+  circuit_index: usize,
+  _p: PhantomData<F>,
+}
+
+impl<F> StepCircuit<F> for ROMStepCircuit<F>
+where
+  F: PrimeField,
+{
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn circuit_index(&self) -> usize {
+    self.circuit_index
+  }
+
+  fn synthesize<CS: ConstraintSystem<F>>(
+    &self,
+    cs: &mut CS,
+    pc: Option<&AllocatedNum<F>>,
+    z: &[AllocatedNum<F>],
+  ) -> Result<(Option<AllocatedNum<F>>, Vec<AllocatedNum<F>>), SynthesisError> {
+    // the program counter of a step run from a ROM is its address
+    let address = pc.ok_or(SynthesisError::AssignmentMissing)?;
+
+    let x = &z[0];
+    let x_sq = x.square(cs.namespace(|| "x_sq"))?;
+    let y = match self.circuit_index {
+      OPCODE_0 => x_sq.mul(cs.namespace(|| "x_cu"), x)?,
+      _ => x_sq,
+    };
+
+    let next_address = AllocatedNum::alloc(cs.namespace(|| "next_address"), || {
+      Ok(
+        address
+          .get_value()
+          .ok_or(SynthesisError::AssignmentMissing)?
+          + F::ONE,
+      )
+    })?;
+    cs.enforce(
+      || "next_address = address + 1",
+      |lc| lc + address.get_variable() + CS::one(),
+      |lc| lc + CS::one(),
+      |lc| lc + next_address.get_variable(),
+    );
+
+    Ok((Some(next_address), vec![y]))
+  }
+}
+
+struct ROMProgram<E1, E2>
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  rom: Arc<ProgramROM<E2>>,
+  _p: PhantomData<E1>,
+}
+
+impl<E1, E2>
+  NonUniformCircuit<
+    E1,
+    E2,
+    ROMCircuit<E2, ROMStepCircuit<E1::Scalar>>,
+    TrivialSecondaryCircuit<E2::Scalar>,
+  > for ROMProgram<E1, E2>
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  fn num_circuits(&self) -> usize {
+    2
+  }
+
+  fn primary_circuit(&self, circuit_index: usize) -> ROMCircuit<E2, ROMStepCircuit<E1::Scalar>> {
+    ROMCircuit::new(
+      &self.rom,
+      ROMStepCircuit {
+        circuit_index,
+        _p: PhantomData,
+      },
+    )
+  }
+
+  fn secondary_circuit(&self) -> TrivialSecondaryCircuit<E2::Scalar> {
+    Default::default()
+  }
+
+  fn initial_circuit_index(&self) -> usize {
+    self.rom.program()[0]
+  }
+}
+
+type ROMPublicParams<E1, E2> = PublicParams<
+  E1,
+  E2,
+  ROMCircuit<E2, ROMStepCircuit<<E1 as Engine>::Scalar>>,
+  TrivialSecondaryCircuit<<E2 as Engine>::Scalar>,
+>;
+
+// Proves the execution of `rom` at `addresses`, returning the public parameters, the proof and
+// the initial primary and secondary inputs
+fn prove_rom_program<E1, E2>(
+  rom: &Arc<ProgramROM<E2>>,
+  addresses: &[usize],
+) -> (
+  ROMPublicParams<E1, E2>,
+  RecursiveSNARK<E1, E2>,
+  Vec<E1::Scalar>,
+  Vec<E2::Scalar>,
+)
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  let test_program = ROMProgram::<E1, E2> {
+    rom: rom.clone(),
+    _p: PhantomData,
+  };
+  let pp = PublicParams::setup(&test_program, &*default_ck_hint(), &*default_ck_hint());
+
+  let mut z0_primary = vec![<E1 as Engine>::Scalar::from(2u64)];
+  z0_primary.extend(rom.initial_state(addresses).unwrap());
+  let z0_secondary = vec![<E2 as Engine>::Scalar::ONE];
+
+  let circuit_secondary = test_program.secondary_circuit();
+  let mut recursive_snark = RecursiveSNARK::new(
+    &pp,
+    &test_program,
+    &test_program.primary_circuit(test_program.initial_circuit_index()),
+    &circuit_secondary,
+    &z0_primary,
+    &z0_secondary,
+  )
+  .unwrap();
+
+  for &address in addresses {
+    let circuit_primary = test_program.primary_circuit(rom.program()[address]);
+    recursive_snark
+      .prove_step(&pp, &circuit_primary, &circuit_secondary)
+      .unwrap();
+    recursive_snark
+      .verify(&pp, &z0_primary, &z0_secondary)
+      .unwrap();
+  }
+
+  (pp, recursive_snark, z0_primary, z0_secondary)
+}
+
+fn test_nivc_rom_with<E1, E2>()
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  let program = vec![OPCODE_1, OPCODE_0, OPCODE_0, OPCODE_1, OPCODE_1, OPCODE_0];
+  let rom = Arc::new(ProgramROM::<E2>::new(program.clone()));
+  let addresses = (0..program.len()).collect::<Vec<_>>();
+  let multiplicities = rom.multiplicities(&addresses).unwrap();
+
+  let (_, recursive_snark, z0_primary, _) = prove_rom_program::<E1, E2>(&rom, &addresses);
+  let zn_primary = recursive_snark.zi_primary;
+  assert!(rom
+    .verify(&z0_primary[1..], &zn_primary[1..], &multiplicities)
+    .is_ok());
+
+  // multiplicities that do not match the reads are rejected
+  let mut bad_multiplicities = multiplicities.clone();
+  bad_multiplicities[0] += 1;
+  bad_multiplicities[1] -= 1;
+  assert!(rom
+    .verify(&z0_primary[1..], &zn_primary[1..], &bad_multiplicities)
+    .is_err());
+
+  // the execution of another program is rejected
+  let mut other_program = program;
+  other_program[2] = OPCODE_1;
+  let other_rom = Arc::new(ProgramROM::<E2>::new(other_program));
+  let (_, recursive_snark, z0_primary, _) = prove_rom_program::<E1, E2>(&other_rom, &addresses);
+  let zn_primary = recursive_snark.zi_primary;
+  assert!(rom
+    .verify(&z0_primary[1..], &zn_primary[1..], &multiplicities)
+    .is_err());
+}
+
+#[test]
+fn test_nivc_rom() {
+  test_nivc_rom_with::<PallasEngine, VestaEngine>();
+  test_nivc_rom_with::<Bn256Engine, GrumpkinEngine>();
+}
+
+fn test_nivc_rom_compression_with<E1, E2>()
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+  <E1::Scalar as PrimeField>::Repr: Abomonation,
+  <E2::Scalar as PrimeField>::Repr: Abomonation,
+{
+  type S1<E> = BatchedRelaxedR1CSSNARK<E, ipa_pc::EvaluationEngine<E>>;
+  type S2<E> = RelaxedR1CSSNARK<E, ipa_pc::EvaluationEngine<E>>;
+
+  let program = vec![OPCODE_1, OPCODE_0, OPCODE_0, OPCODE_1, OPCODE_1, OPCODE_0];
+  let rom = Arc::new(ProgramROM::<E2>::new(program.clone()));
+  let addresses = (0..program.len()).collect::<Vec<_>>();
+  let multiplicities = rom.multiplicities(&addresses).unwrap();
+
+  let (pp, recursive_snark, z0_primary, z0_secondary) =
+    prove_rom_program::<E1, E2>(&rom, &addresses);
+  let (pk, vk) = CompressedSNARK::<_, _, _, _, S1<E1>, S2<E2>>::setup_with_rom(&pp, &rom).unwrap();
+
+  let compressed_snark =
+    CompressedSNARK::prove_with_rom(&pp, &pk, &recursive_snark, &multiplicities).unwrap();
+  assert!(compressed_snark
+    .verify(&pp, &vk, &z0_primary, &z0_secondary)
+    .is_ok());
+
+  // the ROM check cannot be skipped: proofs without multiplicities, or with multiplicities that
+  // do not match the reads, are rejected
+  let compressed_snark = CompressedSNARK::prove(&pp, &pk, &recursive_snark).unwrap();
+  assert!(compressed_snark
+    .verify(&pp, &vk, &z0_primary, &z0_secondary)
+    .is_err());
+
+  let mut bad_multiplicities = multiplicities;
+  bad_multiplicities[0] += 1;
+  bad_multiplicities[1] -= 1;
+  let compressed_snark =
+    CompressedSNARK::prove_with_rom(&pp, &pk, &recursive_snark, &bad_multiplicities).unwrap();
+  assert!(compressed_snark
+    .verify(&pp, &vk, &z0_primary, &z0_secondary)
+    .is_err());
+}
+
+#[test]
+fn test_nivc_rom_compression() {
+  test_nivc_rom_compression_with::<PallasEngine, VestaEngine>();
+  test_nivc_rom_compression_with::<Bn256Engine, GrumpkinEngine>();
+}
+
+fn test_nivc_typed_errors_with<E1, E2>()
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  let rom = vec![OPCODE_1, OPCODE_0, OPCODE_1];
+  let test_rom = TestROM::<E1, E2, TrivialSecondaryCircuit<E2::Scalar>>::new(rom);
+
+  let pp = PublicParams::setup(&test_rom, &*default_ck_hint(), &*default_ck_hint());
+
+  let mut z0_primary = vec![<E1 as Engine>::Scalar::ONE];
+  z0_primary.push(<E1 as Engine>::Scalar::ZERO); // rom_index = 0
+  z0_primary.extend(
+    test_rom
+      .rom
+      .iter()
+      .map(|opcode| <E1 as Engine>::Scalar::from(*opcode as u64)),
+  );
+  let z0_secondary = vec![<E2 as Engine>::Scalar::ONE];
+  let circuit_secondary = test_rom.secondary_circuit();
+
+  // the initial input must match the arity of every circuit
+  let res = RecursiveSNARK::new(
+    &pp,
+    &test_rom,
+    &test_rom.primary_circuit(OPCODE_1),
+    &circuit_secondary,
+    &z0_primary[1..],
+    &z0_secondary,
+  );
+  assert_eq!(
+    res.err(),
+    Some(SuperNovaError::ArityMismatch {
+      circuit_index: Some(0),
+      arity: z0_primary.len(),
+      len: z0_primary.len() - 1,
+    })
+  );
+
+  // the first circuit must be the one selected by the initial program counter
+  let res = RecursiveSNARK::new(
+    &pp,
+    &test_rom,
+    &test_rom.primary_circuit(OPCODE_0),
+    &circuit_secondary,
+    &z0_primary,
+    &z0_secondary,
+  );
+  assert_eq!(
+    res.err(),
+    Some(SuperNovaError::CircuitIndexMismatch {
+      step: 0,
+      program_counter: OPCODE_1,
+      circuit_index: OPCODE_0,
+    })
+  );
+
+  let mut recursive_snark = RecursiveSNARK::new(
+    &pp,
+    &test_rom,
+    &test_rom.primary_circuit(OPCODE_1),
+    &circuit_secondary,
+    &z0_primary,
+    &z0_secondary,
+  )
+  .unwrap();
+  recursive_snark
+    .prove_step(&pp, &test_rom.primary_circuit(OPCODE_1), &circuit_secondary)
+    .unwrap();
+
+  // the circuit of each step must be the one selected by the program counter
+  let res =
+    recursive_snark.prove_step(&pp, &test_rom.primary_circuit(OPCODE_1), &circuit_secondary);
+  assert_eq!(
+    res,
+    Err(SuperNovaError::CircuitIndexMismatch {
+      step: 1,
+      program_counter: OPCODE_0,
+      circuit_index: OPCODE_1,
+    })
+  );
+
+  // the program counter must select one of the circuits
+  recursive_snark.program_counter = <E1 as Engine>::Scalar::from(2u64);
+  let res =
+    recursive_snark.prove_step(&pp, &test_rom.primary_circuit(OPCODE_0), &circuit_secondary);
+  assert!(matches!(
+    res,
+    Err(SuperNovaError::InvalidProgramCounter {
+      step: 1,
+      num_circuits: 2,
+      ..
+    })
+  ));
+}
+
+#[test]
+fn test_nivc_typed_errors() {
+  test_nivc_typed_errors_with::<PallasEngine, VestaEngine>();
+}
+
 // In the following we use 1 to refer to the primary, and 2 to refer to the secondary circuit
 fn test_recursive_circuit_with<E1, E2>(
   primary_params: &SuperNovaAugmentedCircuitParams,
@@ -906,3 +1419,98 @@ fn test_nivc_nondet() {
   test_nivc_nondet_with::<Bn256Engine, GrumpkinEngine>();
   test_nivc_nondet_with::<Secp256k1Engine, Secq256k1Engine>();
 }
+
+impl<E1, E2>
+  ReplayableCircuit<
+    E1,
+    E2,
+    RootCheckingCircuit<E1::Scalar>,
+    TrivialSecondaryCircuit<E1::Base>,
+    Option<E1::Scalar>,
+  > for RootCheckingCircuit<E1::Scalar>
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  fn primary_circuit_with_advice(&self, circuit_index: usize, advice: &Option<E1::Scalar>) -> Self {
+    match circuit_index {
+      0 => Self::Cube(CubeRootCheckingCircuit { y: *advice }),
+      1 => Self::Fifth(FifthRootCheckingCircuit { y: *advice }),
+      _ => unreachable!(),
+    }
+  }
+}
+
+fn test_nivc_prove_trace_with<E1, E2>()
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  let num_steps = 3;
+
+  // run the computation natively, recording the trace
+  let (z0_primary, roots) = RootCheckingCircuit::<E1::Scalar>::new(num_steps);
+  let z0_secondary = vec![<E2 as Engine>::Scalar::ZERO];
+
+  let mut trace = ExecutionTrace::new();
+  let mut z = z0_primary.clone();
+  for root in &roots {
+    trace.push(root.circuit_index(), z, root.get_y());
+    z = vec![root.get_y().unwrap()];
+  }
+  // the last executed circuit is a cube root, which jumps to the fifth root
+  trace.finish(<E1 as Engine>::Scalar::ONE, z.clone());
+
+  let pp = PublicParams::<
+    E1,
+    E2,
+    RootCheckingCircuit<<E1 as Engine>::Scalar>,
+    TrivialSecondaryCircuit<<E2 as Engine>::Scalar>,
+  >::setup(&roots[0], &*default_ck_hint(), &*default_ck_hint());
+
+  let recursive_snark =
+    RecursiveSNARK::<E1, E2>::prove_trace(&pp, &roots[0], &trace, &z0_secondary).unwrap();
+  let (zn_primary, _) = recursive_snark
+    .verify(&pp, &z0_primary, &z0_secondary)
+    .unwrap();
+  assert_eq!(zn_primary, z);
+
+  // corrupt the recorded input of the third step: the second transition should be reported
+  let mut bad_trace = ExecutionTrace::new();
+  for (i, step) in trace.steps().iter().enumerate() {
+    let z = if i == 2 {
+      vec![step.z[0] + <E1 as Engine>::Scalar::ONE]
+    } else {
+      step.z.clone()
+    };
+    bad_trace.push(step.pc, z, step.advice);
+  }
+  let res = RecursiveSNARK::<E1, E2>::prove_trace(&pp, &roots[0], &bad_trace, &z0_secondary);
+  assert!(matches!(
+    res,
+    Err(SuperNovaError::TraceDivergence {
+      step: 1,
+      circuit_index: 1,
+      divergence: Divergence::Output,
+    })
+  ));
+
+  // a trace starting from the wrong circuit is rejected before proving
+  let mut bad_trace = ExecutionTrace::new();
+  bad_trace.push(1, z0_primary, roots[1].get_y());
+  let res = RecursiveSNARK::<E1, E2>::prove_trace(&pp, &roots[0], &bad_trace, &z0_secondary);
+  assert!(matches!(
+    res,
+    Err(SuperNovaError::TraceDivergence {
+      step: 0,
+      circuit_index: 1,
+      divergence: Divergence::ProgramCounter,
+    })
+  ));
+}
+
+#[test]
+fn test_nivc_prove_trace() {
+  test_nivc_prove_trace_with::<PallasEngine, VestaEngine>();
+  test_nivc_prove_trace_with::<Bn256Engine, GrumpkinEngine>();
+}
@@ -0,0 +1,222 @@
+//! This module defines a recorded execution trace of a non-uniform computation, and a prover that
+//! replays such a trace through the circuits of a [`NonUniformCircuit`].
+//!
+//! A VM will typically run a program natively first, recording at each step the program counter,
+//! the primary input `z_i` and whatever non-deterministic advice the step consumed. The trace is
+//! then handed to [`RecursiveSNARK::prove_trace`], which synthesizes
+//! `F_{pc_i}(z_i)` for each recorded step, and checks that the program counter and output produced
+//! by the circuit match the next recorded state.
+use ff::PrimeField;
+use serde::{Deserialize, Serialize};
+
+use super::{error::SuperNovaError, NonUniformCircuit, PublicParams, RecursiveSNARK};
+use crate::{
+  errors::NovaError,
+  traits::{circuit_supernova::StepCircuit, Engine},
+};
+
+/// A single step of a recorded execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep<F: PrimeField, A> {
+  /// The program counter (i.e. the index of the circuit) this step was executed with
+  pub pc: usize,
+  /// The primary input `z_i` of this step
+  pub z: Vec<F>,
+  /// The non-deterministic advice consumed by this step
+  pub advice: A,
+}
+
+/// The state reached by a recorded execution after its last step.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceOutput<F: PrimeField> {
+  /// The program counter returned by the last step
+  pub pc: F,
+  /// The primary output of the last step
+  pub z: Vec<F>,
+}
+
+/// A recorded execution of a non-uniform computation, made of a list of steps and an optional
+/// final state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionTrace<F: PrimeField, A> {
+  steps: Vec<TraceStep<F, A>>,
+  output: Option<TraceOutput<F>>,
+}
+
+impl<F: PrimeField, A> Default for ExecutionTrace<F, A> {
+  fn default() -> Self {
+    Self {
+      steps: Vec::new(),
+      output: None,
+    }
+  }
+}
+
+impl<F: PrimeField, A> ExecutionTrace<F, A> {
+  /// Create a new, empty `ExecutionTrace`
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Record a step executed with program counter `pc` on input `z`, consuming `advice`
+  pub fn push(&mut self, pc: usize, z: Vec<F>, advice: A) {
+    self.steps.push(TraceStep { pc, z, advice });
+  }
+
+  /// Record the program counter and output reached after the last step.
+  /// When present, the output of the last replayed step is checked against it.
+  pub fn finish(&mut self, pc: F, z: Vec<F>) {
+    self.output = Some(TraceOutput { pc, z });
+  }
+
+  /// The recorded steps
+  pub fn steps(&self) -> &[TraceStep<F, A>] {
+    &self.steps
+  }
+
+  /// The recorded final state, if any
+  pub fn output(&self) -> Option<&TraceOutput<F>> {
+    self.output.as_ref()
+  }
+
+  /// The number of recorded steps
+  pub fn len(&self) -> usize {
+    self.steps.len()
+  }
+
+  /// Returns `true` if no step was recorded
+  pub fn is_empty(&self) -> bool {
+    self.steps.is_empty()
+  }
+}
+
+/// Describes which part of a recorded transition the replayed circuit disagreed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Divergence {
+  /// The program counter returned by the circuit differs from the recorded one
+  ProgramCounter,
+  /// The primary output of the circuit differs from the recorded one
+  Output,
+}
+
+/// A [`NonUniformCircuit`] whose primary circuits can be loaded with the advice recorded in an
+/// [`ExecutionTrace`].
+///
+/// This is implemented for every `NonUniformCircuit` with the trivial advice `()`, in which case
+/// the circuits are obtained with [`NonUniformCircuit::primary_circuit`].
+pub trait ReplayableCircuit<E1, E2, C1, C2, A>: NonUniformCircuit<E1, E2, C1, C2>
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+  C1: StepCircuit<E1::Scalar>,
+  C2: StepCircuit<E2::Scalar>,
+{
+  /// Return the primary circuit at `circuit_index`, loaded with the `advice` of a recorded step.
+  fn primary_circuit_with_advice(&self, circuit_index: usize, advice: &A) -> C1;
+}
+
+impl<E1, E2, C1, C2, T> ReplayableCircuit<E1, E2, C1, C2, ()> for T
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+  C1: StepCircuit<E1::Scalar>,
+  C2: StepCircuit<E2::Scalar>,
+  T: NonUniformCircuit<E1, E2, C1, C2>,
+{
+  fn primary_circuit_with_advice(&self, circuit_index: usize, _advice: &()) -> C1 {
+    self.primary_circuit(circuit_index)
+  }
+}
+
+impl<E1, E2> RecursiveSNARK<E1, E2>
+where
+  E1: Engine<Base = <E2 as Engine>::Scalar>,
+  E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+  /// Prove a recorded [`ExecutionTrace`], by replaying each of its steps through the primary
+  /// circuit obtained from `non_uniform_circuit` for the recorded program counter.
+  ///
+  /// The initial primary input is the input of the first recorded step. After each step, the
+  /// program counter and output of the circuit are compared with the next recorded step (or the
+  /// final state of the trace, if any), and the first mismatch is reported as a
+  /// [`SuperNovaError::TraceDivergence`] carrying the index of the step and of its circuit.
+  pub fn prove_trace<NC, C1, C2, A>(
+    pp: &PublicParams<E1, E2, C1, C2>,
+    non_uniform_circuit: &NC,
+    trace: &ExecutionTrace<E1::Scalar, A>,
+    z0_secondary: &[E2::Scalar],
+  ) -> Result<Self, SuperNovaError>
+  where
+    NC: ReplayableCircuit<E1, E2, C1, C2, A>,
+    C1: StepCircuit<E1::Scalar>,
+    C2: StepCircuit<E2::Scalar>,
+  {
+    let first = trace
+      .steps
+      .first()
+      .ok_or(SuperNovaError::NovaError(NovaError::InvalidNumSteps))?;
+
+    if first.pc != non_uniform_circuit.initial_circuit_index() {
+      return Err(SuperNovaError::TraceDivergence {
+        step: 0,
+        circuit_index: first.pc,
+        divergence: Divergence::ProgramCounter,
+      });
+    }
+
+    let c_secondary = non_uniform_circuit.secondary_circuit();
+    let mut recursive_snark: Option<Self> = None;
+
+    for (step, recorded) in trace.steps.iter().enumerate() {
+      let c_primary =
+        non_uniform_circuit.primary_circuit_with_advice(recorded.pc, &recorded.advice);
+      if c_primary.circuit_index() != recorded.pc {
+        return Err(SuperNovaError::TraceDivergence {
+          step,
+          circuit_index: recorded.pc,
+          divergence: Divergence::ProgramCounter,
+        });
+      }
+
+      let mut snark = match recursive_snark.take() {
+        Some(snark) => snark,
+        None => Self::new(
+          pp,
+          non_uniform_circuit,
+          &c_primary,
+          &c_secondary,
+          &recorded.z,
+          z0_secondary,
+        )?,
+      };
+      snark.prove_step(pp, &c_primary, &c_secondary)?;
+
+      // compare the transition with the next recorded state
+      let expected = match trace.steps.get(step + 1) {
+        Some(next) => Some((E1::Scalar::from(next.pc as u64), &next.z)),
+        None => trace.output.as_ref().map(|output| (output.pc, &output.z)),
+      };
+      if let Some((pc, z)) = expected {
+        if snark.program_counter != pc {
+          return Err(SuperNovaError::TraceDivergence {
+            step,
+            circuit_index: recorded.pc,
+            divergence: Divergence::ProgramCounter,
+          });
+        }
+        if &snark.zi_primary != z {
+          return Err(SuperNovaError::TraceDivergence {
+            step,
+            circuit_index: recorded.pc,
+            divergence: Divergence::Output,
+          });
+        }
+      }
+
+      recursive_snark = Some(snark);
+    }
+
+    // the trace is non-empty, so at least one step was proven
+    Ok(recursive_snark.expect("recursive snark missing"))
+  }
+}
@@ -17,6 +17,10 @@ pub trait StepCircuit<F: PrimeField>: Send + Sync + Clone {
 
   /// Synthesize the circuit for a computation step and return variable
   /// that corresponds to the output of the step `pc_{i+1}` and `z_{i+1}`
+  ///
+  /// When used as the secondary circuit of a `NonUniformCircuit`, `pc` is the index of the primary
+  /// circuit executed at the same step, which lets the secondary circuit dispatch on it, and the
+  /// returned program counter is ignored.
   fn synthesize<CS: ConstraintSystem<F>>(
     &self,
     cs: &mut CS,
@@ -82,9 +86,8 @@ where
   }
 }
 
-/// A trivial step circuit that simply returns the input, for use on the secondary circuit when implementing NIVC.
-/// NOTE: This should not be needed. The secondary circuit doesn't need the program counter at all.
-/// Ideally, the need this fills could be met by `traits::circuit::TrivialTestCircuit` (or equivalent).
+/// A trivial step circuit that simply returns the input, for use on the secondary circuit when implementing NIVC
+/// without any computation on the secondary curve.
 #[derive(Clone, Debug, Default)]
 pub struct TrivialSecondaryCircuit<F: PrimeField> {
   _p: PhantomData<F>,
@@ -105,10 +108,9 @@ where
   fn synthesize<CS: ConstraintSystem<F>>(
     &self,
     _cs: &mut CS,
-    program_counter: Option<&AllocatedNum<F>>,
+    _pc: Option<&AllocatedNum<F>>,
     z: &[AllocatedNum<F>],
   ) -> Result<(Option<AllocatedNum<F>>, Vec<AllocatedNum<F>>), SynthesisError> {
-    assert!(program_counter.is_none());
     assert_eq!(z.len(), 1, "Arity of trivial step circuit should be 1");
     Ok((None, z.to_vec()))
   }
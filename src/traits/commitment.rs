@@ -18,6 +18,11 @@ impl<T, Rhs, Output> ScalarMul<Rhs, Output> for T where T: Mul<Rhs, Output = Out
 {}
 
 /// This trait defines the behavior of the commitment
+///
+/// Commitments must be additively homomorphic, since folding and Spartan's batched openings
+/// combine them linearly, and must map to curve points, since the augmented circuit absorbs
+/// their coordinates. Schemes whose commitments are not, such as the Merkle roots of Basefold,
+/// implement `pcs::PCSEngineTrait` instead, for SNARKs that prove circuits directly.
 pub trait CommitmentTrait<E: Engine>:
   Clone
   + Copy
@@ -85,4 +90,41 @@ pub trait CommitmentEngineTrait<E: Engine>: Clone + Send + Sync {
 
   /// Commits to the provided vector using the provided generators
   fn commit(ck: &Self::CommitmentKey, v: &[E::Scalar]) -> Self::Commitment;
+
+  /// Commits to the provided vector of scalars that fit in a `u64`, such as bits or small
+  /// integers, using the provided generators
+  ///
+  /// The commitment is the same as that of the vector of the corresponding field elements. By
+  /// default, it is computed as such: engines override this when they can commit faster to small
+  /// scalars.
+  fn commit_small(ck: &Self::CommitmentKey, v: &[u64]) -> Self::Commitment {
+    let v = v.iter().map(|&v| E::Scalar::from(v)).collect::<Vec<_>>();
+    Self::commit(ck, &v)
+  }
+
+  /// Precomputes tables in the commitment key, for windows of `window_size` bits, that make
+  /// subsequent commitments with it faster at the cost of memory
+  ///
+  /// Commitments are the same with or without tables. By default, engines have no use for them,
+  /// and leave the key unchanged.
+  fn precompute_tables(
+    _ck: &mut Self::CommitmentKey,
+    _window_size: usize,
+  ) -> Result<(), NovaError> {
+    Ok(())
+  }
+}
+
+/// A trait for commitment engines that can also produce hiding commitments, which are the
+/// commitments of `CommitmentEngineTrait` blinded by a multiple of a generator that is
+/// independent of the commitment key
+pub trait HidingCommitmentEngineTrait<E: Engine>: CommitmentEngineTrait<E> {
+  /// Commits to the provided vector using the provided generators, hiding it with the blind `r`
+  ///
+  /// With a zero blind, the commitment is the same as that of `commit`.
+  fn commit_with_blind(
+    ck: &Self::CommitmentKey,
+    v: &[E::Scalar],
+    r: &E::Scalar,
+  ) -> Self::Commitment;
 }
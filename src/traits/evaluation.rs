@@ -19,6 +19,9 @@ pub trait EvaluationEngineTrait<E: Engine>: Clone + Send + Sync {
   /// A type that holds the evaluation argument
   type EvaluationArgument: Clone + Send + Sync + Serialize + for<'de> Deserialize<'de>;
 
+  /// A type that holds the argument of the evaluations of several polynomials
+  type BatchEvaluationArgument: Clone + Send + Sync + Serialize + for<'de> Deserialize<'de>;
+
   /// A method to perform any additional setup needed to produce proofs of evaluations
   fn setup(
     ck: &<<E as Engine>::CE as CommitmentEngineTrait<E>>::CommitmentKey,
@@ -44,4 +47,50 @@ pub trait EvaluationEngineTrait<E: Engine>: Clone + Send + Sync {
     eval: &E::Scalar,
     arg: &Self::EvaluationArgument,
   ) -> Result<(), NovaError>;
+
+  /// A method to prove the evaluations of several multilinear polynomials, each at its own
+  /// point, with a single argument. Engines without a native multi-point opening can implement
+  /// it with `spartan::BatchEvalArgument`
+  fn prove_batch(
+    ck: &<<E as Engine>::CE as CommitmentEngineTrait<E>>::CommitmentKey,
+    pk: &Self::ProverKey,
+    transcript: &mut E::TE,
+    comms: &[<<E as Engine>::CE as CommitmentEngineTrait<E>>::Commitment],
+    polys: &[Vec<E::Scalar>],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+  ) -> Result<Self::BatchEvaluationArgument, NovaError>;
+
+  /// A method to verify the purported evaluations of several multilinear polynomials, each at
+  /// its own point
+  fn verify_batch(
+    vk: &Self::VerifierKey,
+    transcript: &mut E::TE,
+    comms: &[<<E as Engine>::CE as CommitmentEngineTrait<E>>::Commitment],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+    arg: &Self::BatchEvaluationArgument,
+  ) -> Result<(), NovaError>;
+
+  /// A method to verify several purported evaluations at once, each with its own transcript.
+  /// The default implementation verifies them one by one; engines may override it
+  /// to combine their final checks with a random linear combination.
+  fn batch_verify(
+    vk: &Self::VerifierKey,
+    transcripts: &mut [E::TE],
+    comms: &[<<E as Engine>::CE as CommitmentEngineTrait<E>>::Commitment],
+    points: &[Vec<E::Scalar>],
+    evals: &[E::Scalar],
+    args: &[&Self::EvaluationArgument],
+  ) -> Result<(), NovaError> {
+    let n = transcripts.len();
+    if comms.len() != n || points.len() != n || evals.len() != n || args.len() != n {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    for (i, transcript) in transcripts.iter_mut().enumerate() {
+      Self::verify(vk, transcript, &comms[i], &points[i], &evals[i], args[i])?;
+    }
+    Ok(())
+  }
 }
@@ -2,8 +2,9 @@
 use crate::errors::NovaError;
 use abomonation::Abomonation;
 use bellpepper_core::{boolean::AllocatedBit, num::AllocatedNum, ConstraintSystem, SynthesisError};
-use core::fmt::Debug;
-use ff::{PrimeField, PrimeFieldBits};
+use bitvec::field::BitField as _;
+use core::{cmp::Ordering, fmt::Debug};
+use ff::{Field, PrimeField, PrimeFieldBits};
 use num_bigint::BigInt;
 use serde::{Deserialize, Serialize};
 
@@ -62,6 +63,9 @@ pub trait AbsorbInROTrait<E: Engine> {
 }
 
 /// A helper trait that defines the behavior of a hash function that we use as an RO
+///
+/// Scalars can be absorbed and challenges squeezed in any order: each squeeze hashes the
+/// internal state, which then only holds the hash, so that challenges squeezed in a row differ.
 pub trait ROTrait<Base: PrimeField, Scalar> {
   /// The circuit alter ego of this trait impl - this constrains it to use the same constants
   type CircuitRO: ROCircuitTrait<Base, Constants = Self::Constants>;
@@ -77,13 +81,16 @@ pub trait ROTrait<Base: PrimeField, Scalar> {
     + Abomonation;
 
   /// Initializes the hash function
-  fn new(constants: Self::Constants, num_absorbs: usize) -> Self;
+  fn new(constants: Self::Constants) -> Self;
 
   /// Adds a scalar to the internal state
   fn absorb(&mut self, e: Base);
 
   /// Returns a challenge of `num_bits` by hashing the internal state
-  fn squeeze(&mut self, num_bits: usize) -> Scalar;
+  ///
+  /// Returns an error if nothing was absorbed, or if `num_bits` exceeds the capacity of the
+  /// fields.
+  fn squeeze(&mut self, num_bits: usize) -> Result<Scalar, NovaError>;
 }
 
 /// A helper trait that defines the behavior of a hash function that we use as an RO in the circuit model
@@ -102,12 +109,14 @@ pub trait ROCircuitTrait<Base: PrimeField> {
     + Abomonation;
 
   /// Initializes the hash function
-  fn new(constants: Self::Constants, num_absorbs: usize) -> Self;
+  fn new(constants: Self::Constants) -> Self;
 
   /// Adds a scalar to the internal state
   fn absorb(&mut self, e: &AllocatedNum<Base>);
 
-  /// Returns a challenge of `num_bits` by hashing the internal state
+  /// Returns a challenge of `num_bits` by hashing the internal state, as `ROTrait::squeeze`
+  ///
+  /// Returns an error if nothing was absorbed, or if `num_bits` exceeds the capacity of the field.
   fn squeeze<CS: ConstraintSystem<Base>>(
     &mut self,
     cs: CS,
@@ -127,10 +136,66 @@ pub type ROConstantsCircuit<E> =
 pub trait TranscriptReprTrait<G: Group>: Send + Sync {
   /// returns a byte representation of self to be added to the transcript
   fn to_transcript_bytes(&self) -> Vec<u8>;
+
+  /// returns a representation of self as scalars, to be added to transcripts that operate over
+  /// the scalar field, such as algebraic hashes; by default, the byte representation is packed
+  /// into scalars
+  fn to_transcript_scalars(&self) -> Vec<G::Scalar> {
+    bytes_to_scalars(&self.to_transcript_bytes())
+  }
+}
+
+/// Packs bytes into scalars, in little-endian chunks that fit in the capacity of the field
+pub(crate) fn bytes_to_scalars<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+  let chunk_size = (F::CAPACITY / 8) as usize;
+  bytes
+    .chunks(chunk_size)
+    .map(|chunk| {
+      chunk.iter().rev().fold(F::ZERO, |acc, byte| {
+        acc * F::from(256) + F::from(u64::from(*byte))
+      })
+    })
+    .collect()
+}
+
+/// Represents an element of the prime field `F` as scalars of the prime field `S`: a single one,
+/// holding the same integer, if the modulus of `F` is at most that of `S`, and its packed byte
+/// representation otherwise
+pub(crate) fn field_to_transcript_scalars<F, S>(f: &F) -> Vec<S>
+where
+  F: PrimeFieldBits,
+  S: PrimeFieldBits,
+{
+  // the moduli differ in length unless the fields do, so they are only read when the lengths tie
+  let fits = match F::NUM_BITS.cmp(&S::NUM_BITS) {
+    Ordering::Less => true,
+    Ordering::Greater => false,
+    Ordering::Equal => {
+      let (p_f, p_s) = (F::char_le_bits(), S::char_le_bits());
+      let n = F::NUM_BITS as usize;
+      p_f[..n]
+        .iter()
+        .by_vals()
+        .rev()
+        .cmp(p_s[..n].iter().by_vals().rev())
+        .is_le()
+    }
+  };
+
+  if fits {
+    // the integer is below the modulus of `S`, so it is rebuilt from its 64-bit limbs
+    let two_64 = S::from(u64::MAX) + S::ONE;
+    let bits = f.to_le_bits();
+    vec![bits.chunks(64).rev().fold(S::ZERO, |acc, limb| {
+      acc * two_64 + S::from(limb.load_le::<u64>())
+    })]
+  } else {
+    bytes_to_scalars(f.to_repr().as_ref())
+  }
 }
 
 /// This trait defines the behavior of a transcript engine compatible with Spartan
-pub trait TranscriptEngineTrait<E: Engine>: Send + Sync {
+pub trait TranscriptEngineTrait<E: Engine>: Clone + Send + Sync {
   /// initializes the transcript
   fn new(label: &'static [u8]) -> Self;
 
@@ -157,9 +222,17 @@ impl<G: Group, T: TranscriptReprTrait<G>> TranscriptReprTrait<G> for &[T] {
       .flat_map(|t| t.to_transcript_bytes())
       .collect::<Vec<u8>>()
   }
+
+  fn to_transcript_scalars(&self) -> Vec<G::Scalar> {
+    self
+      .iter()
+      .flat_map(|t| t.to_transcript_scalars())
+      .collect::<Vec<G::Scalar>>()
+  }
 }
 
 pub mod circuit;
 pub mod circuit_supernova;
 pub mod evaluation;
+pub mod pcs;
 pub mod snark;
@@ -0,0 +1,64 @@
+//! This module defines a trait for polynomial commitment schemes whose commitments are not those
+//! of a commitment engine: they need not be single group elements, nor be additively homomorphic,
+//! e.g. the Merkle roots of codewords, or one group element per row of a matrix. Such commitments
+//! cannot be folded, so these schemes serve SNARKs that prove circuits directly, such as
+//! `spartan::pcs_snark::R1CSSNARK`.
+use crate::{
+  errors::NovaError,
+  traits::{Engine, TranscriptReprTrait},
+};
+use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
+
+/// A trait that defines a multilinear polynomial commitment scheme, where a vector of size N is
+/// treated as a multilinear polynomial in log N variables
+pub trait PCSEngineTrait<E: Engine>: Clone + Send + Sync {
+  /// A type that holds the key to commit to polynomials and to prove their evaluations
+  type CommitmentKey: Clone + Send + Sync + Serialize + for<'de> Deserialize<'de>;
+
+  /// A type that holds the verifier key
+  type VerifierKey: Clone + Send + Sync + Serialize + for<'de> Deserialize<'de>;
+
+  /// A type that holds a commitment to a polynomial
+  type Commitment: Clone
+    + Debug
+    + PartialEq
+    + Eq
+    + Send
+    + Sync
+    + TranscriptReprTrait<E::GE>
+    + Serialize
+    + for<'de> Deserialize<'de>;
+
+  /// A type that holds the evaluation argument
+  type EvaluationArgument: Clone + Send + Sync + Serialize + for<'de> Deserialize<'de>;
+
+  /// Produces the keys for polynomials of up to `n` evaluations
+  fn setup(
+    label: &'static [u8],
+    n: usize,
+  ) -> Result<(Self::CommitmentKey, Self::VerifierKey), NovaError>;
+
+  /// Commits to a polynomial given by its evaluations, whose number must be a power of two
+  fn commit(ck: &Self::CommitmentKey, poly: &[E::Scalar]) -> Result<Self::Commitment, NovaError>;
+
+  /// A method to prove the evaluation of a multilinear polynomial
+  fn prove(
+    ck: &Self::CommitmentKey,
+    transcript: &mut E::TE,
+    comm: &Self::Commitment,
+    poly: &[E::Scalar],
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+  ) -> Result<Self::EvaluationArgument, NovaError>;
+
+  /// A method to verify the purported evaluation of a multilinear polynomial
+  fn verify(
+    vk: &Self::VerifierKey,
+    transcript: &mut E::TE,
+    comm: &Self::Commitment,
+    point: &[E::Scalar],
+    eval: &E::Scalar,
+    arg: &Self::EvaluationArgument,
+  ) -> Result<(), NovaError>;
+}
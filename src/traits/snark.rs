@@ -18,6 +18,16 @@ pub fn default_ck_hint<E: Engine>() -> Box<dyn for<'a> Fn(&'a R1CSShape<E>) -> u
   Box::new(|_shape: &R1CSShape<E>| 0)
 }
 
+/// A store in which setups persist preprocessed data that is expensive to produce, such as the
+/// Spark representation of an `R1CSShape` and the commitments to it, for later setups to reuse
+pub trait SetupCache: Send + Sync {
+  /// Returns the bytes stored under `key`, if any
+  fn load(&self, key: &str) -> Option<Vec<u8>>;
+
+  /// Stores `bytes` under `key`, replacing the bytes previously stored under it
+  fn store(&self, key: &str, bytes: &[u8]) -> Result<(), NovaError>;
+}
+
 /// A trait that defines the behavior of a `zkSNARK`
 pub trait RelaxedR1CSSNARKTrait<E: Engine>:
   Send + Sync + Serialize + for<'de> Deserialize<'de>
@@ -48,6 +58,17 @@ pub trait RelaxedR1CSSNARKTrait<E: Engine>:
     S: &R1CSShape<E>,
   ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError>;
 
+  /// Produces the keys for the prover and the verifier as `setup` does, reusing the preprocessed
+  /// data that an earlier setup for the same shape and commitment key stored in `cache`, and
+  /// storing it there otherwise. The default implementation has no such data and ignores `cache`.
+  fn setup_with_cache(
+    ck: &CommitmentKey<E>,
+    S: &R1CSShape<E>,
+    _cache: &dyn SetupCache,
+  ) -> Result<(Self::ProverKey, Self::VerifierKey), NovaError> {
+    Self::setup(ck, S)
+  }
+
   /// Produces a new SNARK for a relaxed R1CS
   fn prove(
     ck: &CommitmentKey<E>,
@@ -59,6 +80,33 @@ pub trait RelaxedR1CSSNARKTrait<E: Engine>:
 
   /// Verifies a SNARK for a relaxed R1CS
   fn verify(&self, vk: &Self::VerifierKey, U: &RelaxedR1CSInstance<E>) -> Result<(), NovaError>;
+
+  /// Verifies a batch of SNARKs for the relaxed R1CS instances `U` under the same verifier key.
+  /// If a SNARK does not verify, returns `NovaError::BatchVerifyError` with its index.
+  ///
+  /// The default implementation verifies the SNARKs one by one; implementations may override it
+  /// to check the evaluation arguments of all SNARKs at once. A batch whose combined check fails
+  /// is never accepted: if no single SNARK can be blamed, they return
+  /// `NovaError::BatchRangeVerifyError` with the failing range.
+  fn batch_verify(
+    snarks: &[&Self],
+    vk: &Self::VerifierKey,
+    U: &[RelaxedR1CSInstance<E>],
+  ) -> Result<(), NovaError> {
+    if snarks.len() != U.len() {
+      return Err(NovaError::InvalidInputLength);
+    }
+
+    for (index, snark) in snarks.iter().enumerate() {
+      snark
+        .verify(vk, &U[index])
+        .map_err(|error| NovaError::BatchVerifyError {
+          index,
+          error: Box::new(error),
+        })?;
+    }
+    Ok(())
+  }
 }
 
 /// A trait that defines the behavior of a `zkSNARK` to prove knowledge of satisfying witness to batches of relaxed R1CS instances.
@@ -97,10 +145,38 @@ pub trait BatchedRelaxedR1CSSNARKTrait<E: Engine>:
     S: Vec<&R1CSShape<E>>,
     U: &[RelaxedR1CSInstance<E>],
     W: &[RelaxedR1CSWitness<E>],
+  ) -> Result<Self, NovaError> {
+    let indices = (0..U.len()).collect::<Vec<_>>();
+    Self::prove_subset(ck, pk, S, U, W, &indices)
+  }
+
+  /// Produces a new SNARK for a sub-batch of relaxed R1CS.
+  ///
+  /// `indices` is the strictly increasing list of the positions, among the shapes the keys were
+  /// produced for, of the shapes in `S`. The instances `U` and witnesses `W` correspond to `S`.
+  fn prove_subset(
+    ck: &CommitmentKey<E>,
+    pk: &Self::ProverKey,
+    S: Vec<&R1CSShape<E>>,
+    U: &[RelaxedR1CSInstance<E>],
+    W: &[RelaxedR1CSWitness<E>],
+    indices: &[usize],
   ) -> Result<Self, NovaError>;
 
   /// Verifies a SNARK for a batch of relaxed R1CS
-  fn verify(&self, vk: &Self::VerifierKey, U: &[RelaxedR1CSInstance<E>]) -> Result<(), NovaError>;
+  fn verify(&self, vk: &Self::VerifierKey, U: &[RelaxedR1CSInstance<E>]) -> Result<(), NovaError> {
+    let indices = (0..U.len()).collect::<Vec<_>>();
+    self.verify_subset(vk, U, &indices)
+  }
+
+  /// Verifies a SNARK for the sub-batch of relaxed R1CS instances `U`, whose shapes are at
+  /// positions `indices` among the shapes the keys were produced for.
+  fn verify_subset(
+    &self,
+    vk: &Self::VerifierKey,
+    U: &[RelaxedR1CSInstance<E>],
+    indices: &[usize],
+  ) -> Result<(), NovaError>;
 }
 
 /// A helper trait that defines the behavior of a verifier key of `zkSNARK`